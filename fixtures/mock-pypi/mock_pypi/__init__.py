@@ -0,0 +1 @@
+"""Mock package to test PyPI registry support."""
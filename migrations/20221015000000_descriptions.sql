@@ -0,0 +1,3 @@
+-- Human readable descriptions as plain text or markdown
+ALTER TABLE namespaces ADD COLUMN description TEXT;
+ALTER TABLE packages ADD COLUMN description TEXT;
@@ -0,0 +1,6 @@
+-- Time a version was yanked, versions yanked before this
+-- column existed are treated as yanked now
+ALTER TABLE versions ADD COLUMN yanked_at TEXT;
+UPDATE versions SET yanked_at = datetime('now') WHERE yanked IS NOT NULL;
+-- Time the artifact was removed from storage
+ALTER TABLE versions ADD COLUMN purged_at TEXT;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS versions_checksum_idx ON versions(checksum);
@@ -0,0 +1,3 @@
+-- Registry kind of the package archive, NULL for versions
+-- published before the kind was recorded
+ALTER TABLE versions ADD COLUMN kind TEXT;
@@ -0,0 +1,3 @@
+-- Registry kind for packages in the namespace, NULL
+-- uses the kind configured for the registry
+ALTER TABLE namespaces ADD COLUMN kind TEXT;
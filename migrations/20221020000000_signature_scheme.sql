@@ -0,0 +1,3 @@
+-- Scheme used to sign the package archive, versions published
+-- before the scheme was recorded use the default scheme
+ALTER TABLE versions ADD COLUMN signature_scheme TEXT NOT NULL DEFAULT 'secp256k1';
@@ -0,0 +1,12 @@
+-- Keys trusted to publish a package, when a package has
+-- trusted keys only those keys may publish to the package
+CREATE TABLE IF NOT EXISTS package_trusted_keys
+(
+    package_id            INTEGER             NOT NULL,
+    publisher_id          INTEGER             NOT NULL,
+    created_at            TEXT                NOT NULL,
+
+    PRIMARY KEY (package_id, publisher_id),
+    FOREIGN KEY (package_id) REFERENCES packages (package_id),
+    FOREIGN KEY (publisher_id) REFERENCES publishers (publisher_id)
+);
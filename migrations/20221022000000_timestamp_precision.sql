@@ -0,0 +1,10 @@
+-- Store timestamps as ISO 8601 in UTC with millisecond precision
+UPDATE publishers SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at);
+UPDATE namespaces SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at);
+UPDATE packages SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at);
+UPDATE versions SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at);
+UPDATE versions SET yanked_at = strftime('%Y-%m-%dT%H:%M:%fZ', yanked_at)
+    WHERE yanked_at IS NOT NULL;
+UPDATE versions SET purged_at = strftime('%Y-%m-%dT%H:%M:%fZ', purged_at)
+    WHERE purged_at IS NOT NULL;
+UPDATE package_trusted_keys SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at);
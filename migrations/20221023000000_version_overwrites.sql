@@ -0,0 +1,19 @@
+-- History of artifacts replaced by a forced publish
+CREATE TABLE IF NOT EXISTS version_overwrites
+(
+    overwrite_id          INTEGER PRIMARY KEY NOT NULL,
+    version_id            INTEGER             NOT NULL,
+    -- Publisher that replaced the artifact
+    publisher_id          INTEGER             NOT NULL,
+    created_at            TEXT                NOT NULL,
+
+    -- Previous artifact for the version
+    content_id            TEXT,
+    pointer_id            TEXT                NOT NULL,
+    signature             BLOB(65)            NOT NULL,
+    signature_scheme      TEXT                NOT NULL,
+    checksum              BLOB(32)            NOT NULL,
+
+    FOREIGN KEY (version_id) REFERENCES versions (version_id),
+    FOREIGN KEY (publisher_id) REFERENCES publishers (publisher_id)
+);
@@ -0,0 +1,22 @@
+-- Published version content is immutable, the only permitted
+-- change is a forced publish which must first record the
+-- previous artifact in version_overwrites.
+--
+-- Mutable columns: yanked, yanked_at and purged_at
+CREATE TRIGGER IF NOT EXISTS versions_immutable
+BEFORE UPDATE OF
+    publisher_id, package_id, major, minor, patch, pre, build, package,
+    content_id, pointer_id, signature, signature_scheme, checksum, kind
+ON versions
+WHEN NOT EXISTS (
+    SELECT 1 FROM version_overwrites
+    WHERE overwrite_id = (
+        SELECT MAX(overwrite_id) FROM version_overwrites
+        WHERE version_id = OLD.version_id
+    )
+    AND checksum = OLD.checksum
+    AND pointer_id = OLD.pointer_id
+)
+BEGIN
+    SELECT RAISE(ABORT, 'published version content is immutable');
+END;
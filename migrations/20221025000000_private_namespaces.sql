@@ -0,0 +1,3 @@
+-- Downloads from a private namespace must be signed
+-- by a user of the namespace
+ALTER TABLE namespaces ADD COLUMN private INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,25 @@
+-- Object keys for the artifact in each storage layer as a
+-- JSON array, NULL for versions published before the keys
+-- were recorded
+ALTER TABLE versions ADD COLUMN objects TEXT;
+
+-- Object keys are part of the published content
+DROP TRIGGER IF EXISTS versions_immutable;
+CREATE TRIGGER versions_immutable
+BEFORE UPDATE OF
+    publisher_id, package_id, major, minor, patch, pre, build, package,
+    content_id, pointer_id, signature, signature_scheme, checksum, kind,
+    objects
+ON versions
+WHEN NOT EXISTS (
+    SELECT 1 FROM version_overwrites
+    WHERE overwrite_id = (
+        SELECT MAX(overwrite_id) FROM version_overwrites
+        WHERE version_id = OLD.version_id
+    )
+    AND checksum = OLD.checksum
+    AND pointer_id = OLD.pointer_id
+)
+BEGIN
+    SELECT RAISE(ABORT, 'published version content is immutable');
+END;
@@ -0,0 +1,3 @@
+-- Deprecation notice for a namespace, packages may still be
+-- published but clients warn when resolving packages
+ALTER TABLE namespaces ADD COLUMN deprecated TEXT;
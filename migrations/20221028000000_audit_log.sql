@@ -0,0 +1,12 @@
+-- Actions taken by registry administrators, the detail
+-- of each action is stored as JSON
+CREATE TABLE IF NOT EXISTS audit_log
+(
+    audit_id              INTEGER PRIMARY KEY NOT NULL,
+    publisher_id          INTEGER             NOT NULL,
+    created_at            TEXT                NOT NULL,
+    action                TEXT                NOT NULL,
+    detail                TEXT                NOT NULL,
+
+    FOREIGN KEY (publisher_id) REFERENCES publishers (publisher_id)
+);
@@ -0,0 +1,9 @@
+-- Namespace affected by an audited action so the log
+-- may be filtered by namespace
+ALTER TABLE audit_log ADD COLUMN namespace TEXT;
+
+UPDATE audit_log SET namespace = json_extract(detail, '$.namespace');
+
+CREATE INDEX IF NOT EXISTS audit_log_created_at_idx ON audit_log(created_at);
+CREATE INDEX IF NOT EXISTS audit_log_publisher_id_idx ON audit_log(publisher_id);
+CREATE INDEX IF NOT EXISTS audit_log_namespace_idx ON audit_log(namespace);
@@ -0,0 +1,40 @@
+-- Dependencies declared in the manifest of each version
+CREATE TABLE IF NOT EXISTS version_dependencies
+(
+    dependency_id         INTEGER PRIMARY KEY NOT NULL,
+    version_id            INTEGER             NOT NULL,
+    name                  TEXT                NOT NULL,
+    requirement           TEXT                NOT NULL,
+
+    FOREIGN KEY (version_id) REFERENCES versions (version_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS version_dependencies_name
+    ON version_dependencies (name);
+
+-- Index the dependencies of versions published before the
+-- index existed, a crate dependency declared as a table may
+-- be renamed and may not have a version
+INSERT INTO version_dependencies ( version_id, name, requirement )
+SELECT
+    versions.version_id,
+    CASE
+        WHEN dependencies.type = 'object'
+        THEN COALESCE(
+            json_extract(dependencies.value, '$.package'),
+            dependencies.key)
+        ELSE dependencies.key
+    END,
+    CASE
+        WHEN dependencies.type = 'object'
+        THEN COALESCE(json_extract(dependencies.value, '$.version'), '*')
+        ELSE dependencies.value
+    END
+FROM versions, json_each(versions.package, '$.dependencies') AS dependencies
+WHERE json_valid(versions.package)
+    AND COALESCE(versions.kind, '') != 'oci'
+    AND (
+        dependencies.type = 'text'
+        OR (dependencies.type = 'object' AND versions.kind = 'cargo')
+    );
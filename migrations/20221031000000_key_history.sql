@@ -0,0 +1,13 @@
+-- Addresses a publisher used before rotating to a new key,
+-- created_at is when the key was rotated out
+CREATE TABLE IF NOT EXISTS key_history
+(
+    key_id                INTEGER PRIMARY KEY NOT NULL,
+    publisher_id          INTEGER             NOT NULL,
+    address               BLOB(20)            NOT NULL UNIQUE,
+    created_at            TEXT                NOT NULL,
+
+    FOREIGN KEY (publisher_id) REFERENCES publishers (publisher_id)
+);
+CREATE INDEX IF NOT EXISTS key_history_publisher_idx
+    ON key_history (publisher_id);
@@ -0,0 +1,4 @@
+-- Number of times the artifact for a version was downloaded,
+-- the count is mutable so it is not part of the immutable
+-- content of a version
+ALTER TABLE versions ADD COLUMN downloads INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,22 @@
+-- The pointer identifier and object keys locate the artifact
+-- in the storage layers and change when a package is moved to
+-- another namespace; the content is protected by the checksum
+-- and signature so they are no longer immutable
+DROP TRIGGER IF EXISTS versions_immutable;
+CREATE TRIGGER versions_immutable
+BEFORE UPDATE OF
+    publisher_id, package_id, major, minor, patch, pre, build, package,
+    content_id, signature, signature_scheme, checksum, kind, size
+ON versions
+WHEN NOT EXISTS (
+    SELECT 1 FROM version_overwrites
+    WHERE overwrite_id = (
+        SELECT MAX(overwrite_id) FROM version_overwrites
+        WHERE version_id = OLD.version_id
+    )
+    AND checksum = OLD.checksum
+    AND pointer_id = OLD.pointer_id
+)
+BEGIN
+    SELECT RAISE(ABORT, 'published version content is immutable');
+END;
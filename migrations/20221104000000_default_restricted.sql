@@ -0,0 +1,3 @@
+-- Users added to the namespace without package restrictions
+-- may not publish to any package until access is granted
+ALTER TABLE namespaces ADD COLUMN default_restricted INTEGER NOT NULL DEFAULT 0;
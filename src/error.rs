@@ -3,6 +3,10 @@ use thiserror::Error;
 /// Errors thrown by the executable library.
 #[derive(Debug, Error)]
 pub enum Error {
+    /// Error generated when clearing the deprecation notice of a package.
+    #[error("deprecation notice can only be cleared for a namespace")]
+    ClearPackageDeprecation,
+
     /// Error generated by the core library.
     #[error(transparent)]
     Core(#[from] ipfs_registry_core::Error),
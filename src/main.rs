@@ -2,30 +2,60 @@ use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 use mime::Mime;
-use semver::VersionReq;
+use semver::{Version, VersionReq};
+use serde::Serialize;
 use serde_json::json;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use url::Url;
 use web3_address::ethereum::Address;
 
-use ipfs_registry::Result;
+use ipfs_registry::{Error, Result};
+use ipfs_registry_client::{default_cache_dir, find_keystore, CheckStatus};
 use ipfs_registry_core::{
-    AnyRef, Namespace, PackageKey, PackageName, PathRef,
+    AnyRef, Namespace, PackageKey, PackageName, PathRef, RegistryKind,
 };
 use ipfs_registry_database::{
-    default_limit, Pager, SortOrder, VersionIncludes,
+    default_limit, AuditFilter, NamespaceAction, Pager, SortOrder,
+    VersionIncludes,
 };
 
-/// Print an ok response to stdout.
-fn ok_response() -> Result<()> {
-    serde_json::to_writer_pretty(std::io::stdout(), &json!({"ok": true}))?;
+/// Print a JSON document to stdout.
+///
+/// Output is pretty printed unless compact output is requested.
+fn print_json<T: Serialize>(value: &T, compact: bool) -> Result<()> {
+    if compact {
+        serde_json::to_writer(std::io::stdout(), value)?;
+    } else {
+        serde_json::to_writer_pretty(std::io::stdout(), value)?;
+    }
     Ok(())
 }
 
+/// Print an ok response to stdout.
+fn ok_response(compact: bool) -> Result<()> {
+    print_json(&json!({"ok": true}), compact)
+}
+
+/// Parse an RFC 3339 date and time argument.
+fn parse_date_time(
+    value: &str,
+) -> std::result::Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(value, &Rfc3339)
+}
+
 /// Signed package registry server.
 #[derive(Parser, Debug)]
 #[clap(name = "ipkg", author, version, about, long_about = None)]
 struct Cli {
+    /// Address of the keystore to use when the key is a directory.
+    #[clap(long, global = true, env = "IPKG_ADDRESS", hide_env = true)]
+    address: Option<Address>,
+
+    /// Print minified JSON rather than pretty printed JSON.
+    #[clap(long, global = true)]
+    compact: bool,
+
     #[clap(subcommand)]
     command: Command,
 }
@@ -45,7 +75,7 @@ enum Command {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -61,7 +91,7 @@ enum Command {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -71,6 +101,14 @@ enum Command {
         )]
         key: PathBuf,
 
+        /// Solve a proof of work with the given difficulty.
+        #[clap(short, long)]
+        difficulty: Option<u32>,
+
+        /// Registry kind for packages in the namespace.
+        #[clap(long)]
+        kind: Option<RegistryKind>,
+
         /// Namespace to register.
         namespace: Namespace,
     },
@@ -88,7 +126,7 @@ enum Command {
         #[clap(short, long, default_value = "application/gzip")]
         mime: Mime,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -98,6 +136,19 @@ enum Command {
         )]
         key: PathBuf,
 
+        /// Sign the checksum of the file rather than the file bytes.
+        #[clap(long)]
+        sign_checksum: bool,
+
+        /// Replace an existing version within the overwrite window.
+        #[clap(long)]
+        force: bool,
+
+        /// Include the package meta data, file size and README
+        /// in the receipt.
+        #[clap(long)]
+        verbose: bool,
+
         /// File to publish.
         #[clap(parse(from_os_str))]
         file: PathBuf,
@@ -108,13 +159,82 @@ enum Command {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
+        /// Keystore file or directory to sign the request
+        /// for a package in a private namespace.
+        #[clap(short, long, parse(from_os_str))]
+        key: Option<PathBuf>,
+
+        /// Fetch a pointer using the path route.
+        #[clap(long)]
+        by_path: bool,
+
+        /// Directory for the artifact cache.
+        #[clap(
+            long,
+            parse(from_os_str),
+            env = "IPKG_CACHE_DIR",
+            hide_env = true
+        )]
+        cache_dir: Option<PathBuf>,
+
+        /// Always download the artifact, bypassing the cache.
+        #[clap(long)]
+        no_cache: bool,
+
         /// Package identifier.
-        id: PackageKey,
+        id: AnyRef,
 
         /// Write package to file.
         #[clap(parse(from_os_str))]
         file: PathBuf,
     },
+    /// Download all versions of a package as a tar archive.
+    Bundle {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory to sign the request
+        /// for a package in a private namespace.
+        #[clap(short, long, parse(from_os_str))]
+        key: Option<PathBuf>,
+
+        /// Only include versions matching a semver range.
+        #[clap(short, long)]
+        range: Option<VersionReq>,
+
+        /// Package path.
+        path: PathRef,
+
+        /// Write bundle to file.
+        #[clap(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Publish the versions in a bundle to a namespace and
+    /// replay the yank and deprecation state of the bundle.
+    Mirror {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Namespace for the package.
+        #[clap(short, long)]
+        namespace: Namespace,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Bundle file to mirror.
+        #[clap(parse(from_os_str))]
+        file: PathBuf,
+    },
     /// Manage namespace users.
     User {
         #[clap(subcommand)]
@@ -126,7 +246,7 @@ enum Command {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -142,13 +262,56 @@ enum Command {
         /// Reason for yanking the version.
         message: Option<String>,
     },
-    /// Deprecate a package.
+    /// Restore a yanked package version.
+    Unyank {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Package identifier.
+        id: PackageKey,
+    },
+    /// Create a signed URL to download a package version.
+    SignUrl {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Number of seconds until the URL expires, defaults
+        /// to the maximum allowed by the server.
+        #[clap(short, long)]
+        expires_in: Option<u64>,
+
+        /// Package identifier.
+        id: PackageKey,
+    },
+    /// Deprecate a namespace or package.
     Deprecate {
         /// Server URL.
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -158,12 +321,136 @@ enum Command {
         )]
         key: PathBuf,
 
-        /// Package path.
+        /// Remove the deprecation notice for a namespace.
+        #[clap(long)]
+        clear: bool,
+
+        /// Namespace or package path.
         path: PathRef,
 
-        /// Reason for yanking the version.
+        /// Deprecation notice.
         message: Option<String>,
     },
+    /// Set the description for a namespace or package.
+    Describe {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Namespace or package path.
+        path: PathRef,
+
+        /// Description, omit to remove the description.
+        description: Option<String>,
+    },
+    /// Require downloads from a namespace to be signed
+    /// by a user of the namespace.
+    Private {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Allow unsigned downloads from the namespace.
+        #[clap(long)]
+        disable: bool,
+
+        /// Target namespace.
+        namespace: Namespace,
+    },
+    /// Require users of a namespace without package restrictions
+    /// to be granted access before publishing.
+    Restricted {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Allow users without package restrictions to publish
+        /// to every package.
+        #[clap(long)]
+        disable: bool,
+
+        /// Target namespace.
+        namespace: Namespace,
+    },
+    /// Transfer a package to another namespace.
+    Transfer {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Keystore file for the owner of the target namespace,
+        /// defaults to the signing key.
+        #[clap(long, parse(from_os_str))]
+        target_key: Option<PathBuf>,
+
+        /// Package path.
+        path: PathRef,
+
+        /// Target namespace.
+        target: Namespace,
+    },
+    /// Transfer ownership of a namespace to another publisher.
+    TransferNamespace {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Target namespace.
+        namespace: Namespace,
+
+        /// Address of the new owner.
+        owner: Address,
+    },
     /// Get information about a specific package version.
     Get {
         /// Server URL.
@@ -174,9 +461,70 @@ enum Command {
         #[clap(long)]
         latest: bool,
 
+        /// Fetch the highest version that satisfies a range.
+        #[clap(long, requires = "latest")]
+        range: Option<VersionReq>,
+
         /// Identifier for a namespace, package or version.
         target: AnyRef,
     },
+    /// Print the manifest for a package version.
+    Manifest {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Package identifier.
+        id: PackageKey,
+    },
+    /// Compare the manifests of two versions of a package.
+    Diff {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Package path.
+        path: PathRef,
+
+        /// Version to compare from.
+        from: Version,
+
+        /// Version to compare to.
+        to: Version,
+    },
+    /// Check the packages in a lockfile are available.
+    ///
+    /// Exits with a non-zero status code when any package is
+    /// missing, yanked or deprecated.
+    Check {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Print the report as JSON.
+        #[clap(long)]
+        json: bool,
+
+        /// Lockfile with one package identifier per line.
+        #[clap(short, long, parse(from_os_str))]
+        lockfile: PathBuf,
+    },
+    /// Verify the integrity of a version without saving it.
+    ///
+    /// Exits with a non-zero status code when the checksum or
+    /// signature does not match the version record.
+    Verify {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Print the report as JSON.
+        #[clap(long)]
+        json: bool,
+
+        /// Package identifier.
+        id: PackageKey,
+    },
     /// List packages and versions.
     #[clap(alias = "ls")]
     List {
@@ -188,40 +536,260 @@ enum Command {
         #[clap(short, long)]
         offset: Option<i64>,
 
-        /// Number of records per page.
+        /// Number of records per page.
+        #[clap(short, long)]
+        limit: Option<i64>,
+
+        /// Sort order.
+        #[clap(long)]
+        sort: Option<SortOrder>,
+
+        /// Cursor from a previous page, takes precedence over offset.
+        #[clap(long, conflicts_with = "range")]
+        cursor: Option<String>,
+
+        /// For each package fetch the latest version.
+        #[clap(long)]
+        latest: bool,
+
+        /// For each package count the versions.
+        #[clap(long, conflicts_with = "latest")]
+        count: bool,
+
+        /// Range to match when fetching versions.
+        #[clap(short, long)]
+        range: Option<VersionReq>,
+
+        /// Exclude yanked versions.
+        #[clap(long)]
+        exclude_yanked: bool,
+
+        /// Path to a namespace or package.
+        path: PathRef,
+    },
+    /// Search packages in all namespaces by name prefix.
+    Search {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Offset for pagination.
+        #[clap(short, long)]
+        offset: Option<i64>,
+
+        /// Number of records per page.
+        #[clap(short, long)]
+        limit: Option<i64>,
+
+        /// Sort order.
+        #[clap(long)]
+        sort: Option<SortOrder>,
+
+        /// Package name prefix.
+        query: String,
+    },
+    /// Start a server.
+    #[clap(
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Server {
+        #[clap(subcommand)]
+        cmd: Option<Server>,
+
+        /// Bind to host:port.
+        #[clap(short, long, default_value = "127.0.0.1:9060")]
+        bind: String,
+
+        /// Config file to load.
+        #[clap(short, long, parse(from_os_str), required = true)]
+        config: Option<PathBuf>,
+    },
+}
+
+/// Subcommands for server maintenance.
+#[derive(Subcommand, Debug)]
+enum Server {
+    /// Recompute confusable skeletons for namespaces and packages.
+    Reindex {
+        /// Config file to load.
+        #[clap(short, long, parse(from_os_str))]
+        config: PathBuf,
+    },
+    /// Remove packages that do not have any versions.
+    Cleanup {
+        /// Config file to load.
+        #[clap(short, long, parse(from_os_str))]
+        config: PathBuf,
+
+        /// Report the packages without removing them.
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Rename or merge a colliding namespace using an
+    /// administrator key.
+    Resolve {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Rename the namespace.
+        #[clap(
+            long,
+            conflicts_with = "merge",
+            required_unless_present = "merge"
+        )]
+        rename: Option<Namespace>,
+
+        /// Move the packages into another namespace and
+        /// remove the namespace.
+        #[clap(long)]
+        merge: Option<Namespace>,
+
+        /// Report the changes without applying them.
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Namespace to resolve.
+        namespace: Namespace,
+    },
+    /// Rotate the key of a publisher using an administrator key.
+    RotateKey {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Current address of the publisher.
+        from: Address,
+
+        /// New address for the publisher.
+        to: Address,
+    },
+    /// List versions signed by keys that have been rotated out
+    /// using an administrator key.
+    RotatedSignatures {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Offset for pagination.
+        #[clap(short, long)]
+        offset: Option<i64>,
+
+        /// Limit for pagination.
+        #[clap(short, long)]
+        limit: Option<i64>,
+
+        /// Sort order.
+        #[clap(long)]
+        sort: Option<SortOrder>,
+    },
+    /// List entries in the audit log using an administrator key.
+    Audit {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Only include entries for this action.
+        #[clap(long)]
+        action: Option<String>,
+
+        /// Only include entries affecting this namespace.
+        #[clap(long)]
+        namespace: Option<Namespace>,
+
+        /// Only include entries for actions taken by this address.
+        #[clap(long)]
+        publisher: Option<Address>,
+
+        /// Only include entries recorded at or after this
+        /// RFC 3339 date and time.
+        #[clap(long, parse(try_from_str = parse_date_time))]
+        since: Option<OffsetDateTime>,
+
+        /// Only include entries recorded before this
+        /// RFC 3339 date and time.
+        #[clap(long, parse(try_from_str = parse_date_time))]
+        until: Option<OffsetDateTime>,
+
+        /// Offset for pagination.
+        #[clap(short, long)]
+        offset: Option<i64>,
+
+        /// Limit for pagination.
         #[clap(short, long)]
         limit: Option<i64>,
 
         /// Sort order.
         #[clap(long)]
         sort: Option<SortOrder>,
-
-        /// For each package fetch the latest version.
-        #[clap(long)]
-        latest: bool,
-
-        /// Range to match when fetching versions.
-        #[clap(short, long)]
-        range: Option<VersionReq>,
-
-        /// Path to a namespace or package.
-        path: PathRef,
-    },
-    /// Start a server.
-    Server {
-        /// Bind to host:port.
-        #[clap(short, long, default_value = "127.0.0.1:9060")]
-        bind: String,
-
-        /// Config file to load.
-        #[clap(short, long, parse(from_os_str))]
-        config: PathBuf,
     },
 }
 
 /// Subcommands for users.
 #[derive(Subcommand, Debug)]
 enum User {
+    /// List namespace users and their package restrictions.
+    List {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Target namespace.
+        #[clap(short, long)]
+        namespace: Namespace,
+    },
+
     /// Add user access to a namespace.
     Add {
         /// Server URL.
@@ -236,7 +804,7 @@ enum User {
         #[clap(short, long)]
         package: Option<PackageName>,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -260,7 +828,7 @@ enum User {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -284,7 +852,7 @@ enum User {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -311,7 +879,7 @@ enum User {
         #[clap(short, long, default_value = "http://127.0.0.1:9060")]
         server: Url,
 
-        /// Keystore for the signing key.
+        /// Keystore file or directory for the signing key.
         #[clap(
             short,
             long,
@@ -331,49 +899,186 @@ enum User {
         /// Revoke access to target package.
         package: PackageName,
     },
+
+    /// Trust a key to publish a package.
+    ///
+    /// When a package has trusted keys only those keys
+    /// may publish to the package.
+    Trust {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Target namespace.
+        #[clap(short, long)]
+        namespace: Namespace,
+
+        /// Address of the user.
+        user: Address,
+
+        /// Trust the user for target package.
+        package: PackageName,
+    },
+
+    /// Remove a trusted key for a package.
+    Untrust {
+        /// Server URL.
+        #[clap(short, long, default_value = "http://127.0.0.1:9060")]
+        server: Url,
+
+        /// Keystore file or directory for the signing key.
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            env = "IPKG_KEYSTORE",
+            hide_env = true
+        )]
+        key: PathBuf,
+
+        /// Target namespace.
+        #[clap(short, long)]
+        namespace: Namespace,
+
+        /// Address of the user.
+        user: Address,
+
+        /// Remove trust for target package.
+        package: PackageName,
+    },
 }
 
 /// Run the program.
 async fn run() -> Result<()> {
     let args = Cli::parse();
+    let address = args.address;
+    let compact = args.compact;
 
     match args.command {
         Command::Keygen { dir } => {
             let address = ipfs_registry_client::keygen(dir).await?;
-            serde_json::to_writer_pretty(std::io::stdout(), &address)?;
+            print_json(&address, compact)?;
         }
         Command::Signup { server, key } => {
+            let key = find_keystore(key, address.as_ref())?;
             let doc = ipfs_registry_client::signup(server, key).await?;
-            serde_json::to_writer_pretty(std::io::stdout(), &doc)?;
+            print_json(&doc, compact)?;
         }
         Command::Register {
             server,
             key,
+            difficulty,
+            kind,
             namespace,
         } => {
-            let doc = ipfs_registry_client::register(server, key, namespace)
-                .await?;
-            serde_json::to_writer_pretty(std::io::stdout(), &doc)?;
+            let key = find_keystore(key, address.as_ref())?;
+            let doc = ipfs_registry_client::register(
+                server, key, namespace, kind, difficulty,
+            )
+            .await?;
+            print_json(&doc, compact)?;
         }
         Command::Publish {
             server,
             namespace,
             mime,
             key,
+            sign_checksum,
+            force,
+            verbose,
             file,
         } => {
+            let key = find_keystore(key, address.as_ref())?;
             let doc = ipfs_registry_client::publish(
-                server, namespace, mime, key, file,
+                server,
+                namespace,
+                mime,
+                key,
+                file,
+                sign_checksum,
+                force,
+                verbose,
+            )
+            .await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Fetch {
+            server,
+            key,
+            by_path,
+            cache_dir,
+            no_cache,
+            id,
+            file,
+        } => {
+            let key = key
+                .map(|key| find_keystore(key, address.as_ref()))
+                .transpose()?;
+            let cache = if no_cache {
+                None
+            } else {
+                cache_dir.or_else(default_cache_dir)
+            };
+            let file = ipfs_registry_client::fetch(
+                server, id, file, by_path, key, cache,
             )
             .await?;
-            serde_json::to_writer_pretty(std::io::stdout(), &doc)?;
+            let size = file.metadata()?.len();
+            tracing::info!(file = ?file, size = ?size);
         }
-        Command::Fetch { server, id, file } => {
-            let file = ipfs_registry_client::fetch(server, id, file).await?;
+        Command::Bundle {
+            server,
+            key,
+            range,
+            path,
+            file,
+        } => {
+            let key = key
+                .map(|key| find_keystore(key, address.as_ref()))
+                .transpose()?;
+            let (namespace, package): (Namespace, PackageName) =
+                path.try_into()?;
+            let file = ipfs_registry_client::bundle(
+                server, namespace, package, range, file, key,
+            )
+            .await?;
             let size = file.metadata()?.len();
             tracing::info!(file = ?file, size = ?size);
         }
+        Command::Mirror {
+            server,
+            namespace,
+            key,
+            file,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let doc =
+                ipfs_registry_client::mirror(server, key, namespace, file)
+                    .await?;
+            print_json(&doc, compact)?;
+        }
         Command::User { cmd } => match cmd {
+            User::List {
+                server,
+                key,
+                namespace,
+            } => {
+                let key = find_keystore(key, address.as_ref())?;
+                let doc =
+                    ipfs_registry_client::list_access(server, key, namespace)
+                        .await?;
+                print_json(&doc, compact)?;
+            }
             User::Add {
                 server,
                 key,
@@ -382,11 +1087,12 @@ async fn run() -> Result<()> {
                 admin,
                 package,
             } => {
+                let key = find_keystore(key, address.as_ref())?;
                 ipfs_registry_client::add_user(
                     server, key, namespace, user, admin, package,
                 )
                 .await?;
-                ok_response()?;
+                ok_response(compact)?;
             }
             User::Remove {
                 server,
@@ -394,11 +1100,12 @@ async fn run() -> Result<()> {
                 namespace,
                 user,
             } => {
+                let key = find_keystore(key, address.as_ref())?;
                 ipfs_registry_client::remove_user(
                     server, key, namespace, user,
                 )
                 .await?;
-                ok_response()?;
+                ok_response(compact)?;
             }
             User::Grant {
                 server,
@@ -407,11 +1114,12 @@ async fn run() -> Result<()> {
                 package,
                 user,
             } => {
+                let key = find_keystore(key, address.as_ref())?;
                 ipfs_registry_client::access_control(
                     server, key, namespace, package, user, true,
                 )
                 .await?;
-                ok_response()?;
+                ok_response(compact)?;
             }
             User::Revoke {
                 server,
@@ -420,11 +1128,40 @@ async fn run() -> Result<()> {
                 package,
                 user,
             } => {
+                let key = find_keystore(key, address.as_ref())?;
                 ipfs_registry_client::access_control(
                     server, key, namespace, package, user, false,
                 )
                 .await?;
-                ok_response()?;
+                ok_response(compact)?;
+            }
+            User::Trust {
+                server,
+                key,
+                namespace,
+                package,
+                user,
+            } => {
+                let key = find_keystore(key, address.as_ref())?;
+                ipfs_registry_client::trusted_key(
+                    server, key, namespace, package, user, true,
+                )
+                .await?;
+                ok_response(compact)?;
+            }
+            User::Untrust {
+                server,
+                key,
+                namespace,
+                package,
+                user,
+            } => {
+                let key = find_keystore(key, address.as_ref())?;
+                ipfs_registry_client::trusted_key(
+                    server, key, namespace, package, user, false,
+                )
+                .await?;
+                ok_response(compact)?;
             }
         },
         Command::Yank {
@@ -433,33 +1170,153 @@ async fn run() -> Result<()> {
             id,
             message,
         } => {
+            let key = find_keystore(key, address.as_ref())?;
             let message = message.unwrap_or(String::new());
             ipfs_registry_client::yank(server, key, id, message).await?;
-            ok_response()?;
+            ok_response(compact)?;
+        }
+        Command::Unyank { server, key, id } => {
+            let key = find_keystore(key, address.as_ref())?;
+            ipfs_registry_client::unyank(server, key, id).await?;
+            ok_response(compact)?;
+        }
+        Command::SignUrl {
+            server,
+            key,
+            expires_in,
+            id,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let signed =
+                ipfs_registry_client::sign_url(server, key, id, expires_in)
+                    .await?;
+            print_json(&signed, compact)?;
         }
         Command::Deprecate {
             server,
             key,
+            clear,
             path,
             message,
         } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let message = message.unwrap_or(String::new());
+            if path.package().is_some() {
+                if clear {
+                    return Err(Error::ClearPackageDeprecation);
+                }
+                let (namespace, package): (Namespace, PackageName) =
+                    path.try_into()?;
+                ipfs_registry_client::deprecate(
+                    server, key, namespace, package, message,
+                )
+                .await?;
+                ok_response(compact)?;
+            } else {
+                let namespace = path.namespace().clone();
+                let doc = if clear {
+                    ipfs_registry_client::undeprecate_namespace(
+                        server, key, namespace,
+                    )
+                    .await?
+                } else {
+                    ipfs_registry_client::deprecate_namespace(
+                        server, key, namespace, message,
+                    )
+                    .await?
+                };
+                print_json(&doc, compact)?;
+            }
+        }
+        Command::Describe {
+            server,
+            key,
+            path,
+            description,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let description = description.unwrap_or_default();
+            let doc = ipfs_registry_client::describe(
+                server,
+                key,
+                path,
+                description,
+            )
+            .await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Private {
+            server,
+            key,
+            disable,
+            namespace,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let doc = ipfs_registry_client::set_private(
+                server, key, namespace, !disable,
+            )
+            .await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Restricted {
+            server,
+            key,
+            disable,
+            namespace,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let doc = ipfs_registry_client::set_default_restricted(
+                server, key, namespace, !disable,
+            )
+            .await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Transfer {
+            server,
+            key,
+            target_key,
+            path,
+            target,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
             let (namespace, package): (Namespace, PackageName) =
                 path.try_into()?;
-            let message = message.unwrap_or(String::new());
-            ipfs_registry_client::deprecate(
-                server, key, namespace, package, message,
+            let doc = ipfs_registry_client::transfer(
+                server, key, target_key, namespace, package, target,
+            )
+            .await?;
+            print_json(&doc, compact)?;
+        }
+        Command::TransferNamespace {
+            server,
+            key,
+            namespace,
+            owner,
+        } => {
+            let key = find_keystore(key, address.as_ref())?;
+            let doc = ipfs_registry_client::transfer_namespace(
+                server, key, namespace, owner,
             )
             .await?;
-            ok_response()?;
+            print_json(&doc, compact)?;
         }
         Command::Get {
             server,
             latest,
+            range,
             target,
         } => {
+            let is_package = matches!(&target, AnyRef::Path(path) if path.package().is_some() && path.version().is_none());
+            if range.is_some() && !is_package {
+                tracing::warn!(
+                    "argument --range is ignored when target is not a package"
+                );
+            }
+
             let doc =
-                ipfs_registry_client::get(server, target, latest).await?;
-            serde_json::to_writer_pretty(std::io::stdout(), &doc)?;
+                ipfs_registry_client::get(server, target, latest, range)
+                    .await?;
+            print_json(&doc, compact)?;
         }
         Command::List {
             server,
@@ -467,8 +1324,11 @@ async fn run() -> Result<()> {
             offset,
             limit,
             sort,
+            cursor,
             latest,
+            count,
             range,
+            exclude_yanked,
         } => {
             if latest && path.package().is_some() {
                 tracing::warn!(
@@ -476,6 +1336,12 @@ async fn run() -> Result<()> {
                 );
             }
 
+            if count && path.package().is_some() {
+                tracing::warn!(
+                    "argument --count is ignored when listing versions"
+                );
+            }
+
             if range.is_some() && path.package().is_none() {
                 tracing::warn!(
                     "argument --range is ignored when listing packages"
@@ -486,17 +1352,204 @@ async fn run() -> Result<()> {
                 offset: offset.unwrap_or_default(),
                 limit: limit.unwrap_or_else(default_limit),
                 sort: sort.unwrap_or_default(),
+                cursor,
+            };
+            let include = if latest {
+                Some(VersionIncludes::Latest)
+            } else {
+                count.then_some(VersionIncludes::Count)
             };
-            let include = latest.then_some(VersionIncludes::Latest);
             let doc = ipfs_registry_client::list(
-                server, path, pager, include, range,
+                server,
+                path,
+                pager,
+                include,
+                range,
+                !exclude_yanked,
+            )
+            .await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Search {
+            server,
+            offset,
+            limit,
+            sort,
+            query,
+        } => {
+            let pager = Pager {
+                offset: offset.unwrap_or_default(),
+                limit: limit.unwrap_or_else(default_limit),
+                sort: sort.unwrap_or_default(),
+                cursor: None,
+            };
+            let doc =
+                ipfs_registry_client::search(server, query, pager).await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Manifest { server, id } => {
+            let doc = ipfs_registry_client::manifest(server, id).await?;
+            print_json(&doc, compact)?;
+        }
+        Command::Diff {
+            server,
+            path,
+            from,
+            to,
+        } => {
+            let (namespace, package): (Namespace, PackageName) =
+                path.try_into()?;
+            let doc = ipfs_registry_client::diff(
+                server, namespace, package, from, to,
             )
             .await?;
-            serde_json::to_writer_pretty(std::io::stdout(), &doc)?;
+            print_json(&doc, compact)?;
+        }
+        Command::Check {
+            server,
+            json,
+            lockfile,
+        } => {
+            let report =
+                ipfs_registry_client::check(server, lockfile).await?;
+            if json {
+                print_json(&report, compact)?;
+            } else {
+                for entry in report.entries.iter() {
+                    if entry.status != CheckStatus::Ok {
+                        println!("{} {}", entry.status, entry.id);
+                    }
+                }
+            }
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
         }
-        Command::Server { bind, config } => {
-            ipfs_registry_server::start(bind, config).await?;
+        Command::Verify { server, json, id } => {
+            let report = ipfs_registry_client::verify(server, id).await?;
+            if json {
+                print_json(&report, compact)?;
+            } else {
+                let status = |ok: bool| if ok { "pass" } else { "fail" };
+                println!("checksum {}", status(report.checksum));
+                println!("signature {}", status(report.signature));
+                if let Some(message) = &report.message {
+                    println!("{}", message);
+                }
+            }
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
         }
+        Command::Server { cmd, bind, config } => match cmd {
+            Some(Server::Reindex { config }) => {
+                let report = ipfs_registry_server::reindex(config).await?;
+                for collision in report.collisions.iter() {
+                    tracing::warn!(
+                        skeleton = %collision.skeleton,
+                        names = ?collision.names,
+                        "collision");
+                }
+                print_json(&report, compact)?;
+            }
+            Some(Server::Cleanup { config, dry_run }) => {
+                let report =
+                    ipfs_registry_server::cleanup(config, dry_run).await?;
+                for package in report.packages.iter() {
+                    tracing::warn!(
+                        namespace = %package.namespace,
+                        package = %package.name,
+                        "empty package");
+                }
+                print_json(&report, compact)?;
+            }
+            Some(Server::Resolve {
+                server,
+                key,
+                rename,
+                merge,
+                dry_run,
+                namespace,
+            }) => {
+                let key = find_keystore(key, address.as_ref())?;
+                let action = rename
+                    .map(NamespaceAction::Rename)
+                    .or_else(|| merge.map(NamespaceAction::Merge))
+                    .expect("rename or merge is required");
+                let report = ipfs_registry_client::resolve_namespace(
+                    server, key, namespace, action, dry_run,
+                )
+                .await?;
+                print_json(&report, compact)?;
+            }
+            Some(Server::RotateKey {
+                server,
+                key,
+                from,
+                to,
+            }) => {
+                let key = find_keystore(key, address.as_ref())?;
+                let record =
+                    ipfs_registry_client::rotate_key(server, key, from, to)
+                        .await?;
+                print_json(&record, compact)?;
+            }
+            Some(Server::RotatedSignatures {
+                server,
+                key,
+                offset,
+                limit,
+                sort,
+            }) => {
+                let key = find_keystore(key, address.as_ref())?;
+                let pager = Pager {
+                    offset: offset.unwrap_or_default(),
+                    limit: limit.unwrap_or_else(default_limit),
+                    sort: sort.unwrap_or_default(),
+                    cursor: None,
+                };
+                let doc = ipfs_registry_client::rotated_signatures(
+                    server, key, pager,
+                )
+                .await?;
+                print_json(&doc, compact)?;
+            }
+            Some(Server::Audit {
+                server,
+                key,
+                action,
+                namespace,
+                publisher,
+                since,
+                until,
+                offset,
+                limit,
+                sort,
+            }) => {
+                let key = find_keystore(key, address.as_ref())?;
+                let filter = AuditFilter {
+                    action,
+                    namespace,
+                    address: publisher,
+                    since,
+                    until,
+                };
+                let pager = Pager {
+                    offset: offset.unwrap_or_default(),
+                    limit: limit.unwrap_or_else(default_limit),
+                    sort: sort.unwrap_or_default(),
+                    cursor: None,
+                };
+                let doc =
+                    ipfs_registry_client::audit(server, key, filter, pager)
+                        .await?;
+                print_json(&doc, compact)?;
+            }
+            None => {
+                let config = config.expect("config file is required");
+                ipfs_registry_server::start(bind, config).await?;
+            }
+        },
     }
 
     Ok(())
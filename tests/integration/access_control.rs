@@ -50,7 +50,7 @@ async fn integration_access_control() -> Result<()> {
 
     // Create a namespace
     let namespace_id =
-        NamespaceModel::insert(&pool, &namespace, publisher_id).await?;
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
 
     assert!(namespace_id > 0);
 
@@ -118,6 +118,7 @@ async fn integration_access_control() -> Result<()> {
         &namespace_record,
         &address,
         &pointer,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -130,6 +131,7 @@ async fn integration_access_control() -> Result<()> {
         &namespace_record,
         &address,
         &pointer,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -141,6 +143,7 @@ async fn integration_access_control() -> Result<()> {
         &namespace_record,
         &address,
         &pointer,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -243,6 +246,64 @@ async fn integration_access_control() -> Result<()> {
     .await
     .is_ok());
 
+    // Only the owner can restrict users by default
+    let result = NamespaceModel::set_default_restricted(
+        &pool,
+        &administrator_address,
+        &namespace,
+        true,
+    )
+    .await;
+    assert_unauthorized(result);
+
+    let restricted_ns = NamespaceModel::set_default_restricted(
+        &pool, &address, &namespace, true,
+    )
+    .await?;
+    assert!(restricted_ns.default_restricted);
+
+    // When users are restricted by default an authorized user
+    // without package restrictions is denied
+    let result = PackageModel::can_publish_package(
+        &pool,
+        &authorized_address,
+        &restricted_ns,
+        &mock_package,
+        Some(&Version::new(2, 0, 0)),
+    )
+    .await;
+    assert_unauthorized(result);
+
+    // The owner and administrators are not restricted by default
+    for owner_or_admin in [&address, &administrator_address] {
+        assert!(PackageModel::can_publish_package(
+            &pool,
+            owner_or_admin,
+            &restricted_ns,
+            &mock_package,
+            Some(&Version::new(2, 0, 0)),
+        )
+        .await
+        .is_ok());
+    }
+
+    // Restricted user can still publish to the granted package
+    assert!(PackageModel::can_publish_package(
+        &pool,
+        &restricted_address,
+        &restricted_ns,
+        &mock_package,
+        Some(&Version::new(2, 0, 0)),
+    )
+    .await
+    .is_ok());
+
+    let ns = NamespaceModel::set_default_restricted(
+        &pool, &address, &namespace, false,
+    )
+    .await?;
+    assert!(!ns.default_restricted);
+
     // Administrator can publish to the private package
     assert!(PackageModel::can_publish_package(
         &pool,
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use k256::ecdsa::{recoverable, signature::Signer};
+use serial_test::serial;
+use std::collections::HashSet;
+use time::OffsetDateTime;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{
+    expiring_message, Namespace, X_SIGNATURE, X_SIGNATURE_EXPIRES,
+};
+use ipfs_registry_database::{
+    AuditFilter, NamespaceAction, Pager, ResolveNamespace,
+};
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_audit_log() -> Result<()> {
+    let (admin_key, admin_address) = new_signing_key();
+    let (other_key, other_address) = new_signing_key();
+    let (signing_key, _) = new_signing_key();
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        admins: Some(HashSet::from([admin_address, other_address])),
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let first = Namespace::new_unchecked("first-namespace");
+    let second = Namespace::new_unchecked("second-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &first).await?;
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        second.clone(),
+        None,
+    )
+    .await?;
+    RegistryClient::signup(server_url.clone(), admin_key.clone()).await?;
+    RegistryClient::signup(server_url.clone(), other_key.clone()).await?;
+
+    // Each administrator renames a namespace
+    for (key, namespace, name) in [
+        (&admin_key, &first, "first-renamed"),
+        (&other_key, &second, "second-renamed"),
+    ] {
+        RegistryClient::resolve_namespace(
+            server_url.clone(),
+            key.clone(),
+            ResolveNamespace {
+                namespace: namespace.clone(),
+                action: NamespaceAction::Rename(Namespace::new_unchecked(
+                    name,
+                )),
+                dry_run: false,
+            },
+        )
+        .await?;
+    }
+
+    let audit = |filter: AuditFilter| {
+        RegistryClient::audit(
+            server_url.clone(),
+            admin_key.clone(),
+            filter,
+            Default::default(),
+        )
+    };
+
+    let records = audit(Default::default()).await?;
+    assert_eq!(2, records.count);
+
+    // Filter by action
+    let records = audit(AuditFilter {
+        action: Some(String::from("resolve_namespace")),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(2, records.count);
+    let records = audit(AuditFilter {
+        action: Some(String::from("yank")),
+        ..Default::default()
+    })
+    .await?;
+    assert!(records.is_empty());
+
+    // Filter by address
+    let records = audit(AuditFilter {
+        address: Some(other_address),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(1, records.count);
+    assert_eq!(other_address, records.records[0].address);
+    assert_eq!(Some(second), records.records[0].namespace);
+
+    // Pagination keeps the total count
+    let records = RegistryClient::audit(
+        server_url.clone(),
+        admin_key.clone(),
+        Default::default(),
+        Pager {
+            offset: 0,
+            limit: 1,
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(1, records.len());
+    assert_eq!(2, records.count);
+
+    // Only administrators may read the audit log
+    let result = RegistryClient::audit(
+        server_url.clone(),
+        signing_key,
+        Default::default(),
+        Default::default(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let list_audit = |message: &[u8], expires: Option<i64>| {
+        let url = server_url.join("api/admin/audit?action=yank").unwrap();
+        let signature: recoverable::Signature = admin_key.sign(message);
+        let mut request = Request::get(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature));
+        if let Some(expires) = expires {
+            request =
+                request.header(X_SIGNATURE_EXPIRES, expires.to_string());
+        }
+        Client::new().request(request.body(Body::empty()).unwrap())
+    };
+    let expires = OffsetDateTime::now_utc().unix_timestamp() + 60;
+
+    // Signature is bound to the query string
+    let message =
+        expiring_message(b"audit:action=resolve_namespace", expires);
+    let response = list_audit(&message, Some(expires)).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+    // Signature is bound to the endpoint and expiry
+    let message = expiring_message(b"action=yank", expires);
+    let response = list_audit(&message, Some(expires)).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    let response = list_audit(b"audit:action=yank", None).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    let expired = expires - 120;
+    let message = expiring_message(b"audit:action=yank", expired);
+    let response = list_audit(&message, Some(expired)).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+    let message = expiring_message(b"audit:action=yank", expires);
+    let response = list_audit(&message, Some(expires)).await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+use ipfs_registry_server::config::RegistryConfig;
+
+use k256::ecdsa::SigningKey;
+
+/// Get the response code for a failed publish.
+fn response_code<T>(result: ipfs_registry_client::Result<T>) -> Option<u16> {
+    if let Err(ipfs_registry_client::Error::ResponseCode(code)) = result {
+        Some(code)
+    } else {
+        None
+    }
+}
+
+fn auto_create_config() -> RegistryConfig {
+    RegistryConfig {
+        auto_create_namespace: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_auto_create_namespace() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(auto_create_config()))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, address) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    // Unregistered publishers do not create namespaces
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await;
+    assert_eq!(Some(404), response_code(result));
+
+    RegistryClient::signup(server_url.clone(), signing_key.clone()).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await?;
+
+    let record = RegistryClient::get_namespace(server_url, namespace).await?;
+    assert_eq!(address, record.owner);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_auto_create_namespace_disabled() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    RegistryClient::signup(server_url.clone(), signing_key.clone()).await?;
+
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await;
+    assert_eq!(Some(404), response_code(result));
+
+    let result = RegistryClient::get_namespace(server_url, namespace).await;
+    assert_eq!(Some(404), response_code(result));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_auto_create_namespace_collision() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(auto_create_config()))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (owner_key, owner) = new_signing_key();
+    let other_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &owner_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), other_key.clone()).await?;
+
+    // Existing namespace owned by another publisher is not taken over
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        other_key,
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await;
+    assert_eq!(Some(401), response_code(result));
+
+    let record = RegistryClient::get_namespace(server_url, namespace).await?;
+    assert_eq!(owner, record.owner);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_auto_create_namespace_concurrent() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(auto_create_config()))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let keys = [new_signing_key(), new_signing_key()];
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    for (key, _) in &keys {
+        RegistryClient::signup(server_url.clone(), key.clone()).await?;
+    }
+
+    // Publishers racing to create the namespace
+    let publish = |key: &SigningKey| {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            file.clone(),
+        )
+    };
+    let (first, second) =
+        tokio::join!(publish(&keys[0].0), publish(&keys[1].0));
+
+    // One publisher creates the namespace and the other is
+    // denied access rather than failing on the collision
+    let record = RegistryClient::get_namespace(server_url, namespace).await?;
+    let (created, denied) = if record.owner == keys[0].1 {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    assert!(created.is_ok());
+    assert_eq!(Some(401), response_code(denied));
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use semver::Version;
+use sqlx::SqlitePool;
+
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_database::{
+    Error, NamespaceModel, PackageModel, PublisherModel,
+};
+
+#[tokio::test]
+#[serial]
+async fn integration_build_metadata() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+
+    let first: Version = "1.0.0+a".parse()?;
+    let second: Version = "1.0.0+b".parse()?;
+
+    PackageModel::can_publish_package(
+        &pool,
+        &address,
+        &namespace_record,
+        &package,
+        Some(&first),
+    )
+    .await?;
+    PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &mock_pointer(Some(first.clone()))?,
+        &[],
+    )
+    .await?;
+
+    // Same version with different build metadata is a conflict
+    let result = PackageModel::can_publish_package(
+        &pool,
+        &address,
+        &namespace_record,
+        &package,
+        Some(&second),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::PackageExists(_, _, _))));
+
+    // Lookup ignores build metadata
+    let id = PackageKey::Pointer(namespace.clone(), package.clone(), second);
+    let (_, _, record) =
+        PackageModel::find_by_key(&pool, &id, Default::default()).await?;
+    assert_eq!(Some(first.clone()), record.map(|r| r.version));
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    let (_, _, record) =
+        PackageModel::find_by_key(&pool, &id, Default::default()).await?;
+    assert_eq!(Some(first.clone()), record.map(|r| r.version));
+
+    // Latest agrees with lookup
+    let latest = PackageModel::find_latest_by_name(
+        &pool, &namespace, &package, false, true,
+    )
+    .await?;
+    assert_eq!(Some(first), latest.map(|r| r.version));
+
+    // Next version is ahead regardless of build metadata
+    let next: Version = "1.0.1+a".parse()?;
+    assert!(PackageModel::can_publish_package(
+        &pool,
+        &address,
+        &namespace_record,
+        &package,
+        Some(&next),
+    )
+    .await
+    .is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use k256::ecdsa::SigningKey;
+use semver::VersionReq;
+use serial_test::serial;
+use std::{io::Read, path::PathBuf};
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_database::VersionRecord;
+use tempfile::NamedTempFile;
+
+use crate::test_utils::*;
+
+/// Read the entry names and manifest from a bundle archive.
+fn read_bundle(file: &PathBuf) -> Result<(Vec<String>, Vec<VersionRecord>)> {
+    let mut names = Vec::new();
+    let mut records = Vec::new();
+    let mut archive = tar::Archive::new(std::fs::File::open(file)?);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if name == "versions.json" {
+            let mut buffer = Vec::new();
+            entry.read_to_end(&mut buffer)?;
+            records = serde_json::from_slice(&buffer)?;
+        }
+        names.push(name);
+    }
+    Ok((names, records))
+}
+
+/// Get a path for a bundle file that does not exist yet.
+fn bundle_path() -> Result<PathBuf> {
+    let tmp = NamedTempFile::new()?;
+    let output = tmp.path().to_path_buf();
+    std::fs::remove_file(&output)?;
+    Ok(output)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_bundle() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    let output = RegistryClient::fetch_bundle(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        None,
+        bundle_path()?,
+    )
+    .await?;
+
+    let (names, records) = read_bundle(&output)?;
+    assert_eq!(
+        vec![
+            "versions.json".to_owned(),
+            "mock-package-1.0.0.tgz".to_owned()
+        ],
+        names
+    );
+    assert_eq!(1, records.len());
+
+    // Artifact in the bundle matches the published file
+    let mut archive = tar::Archive::new(std::fs::File::open(&output)?);
+    let mut entry = archive.entries()?.nth(1).unwrap()?;
+    let mut buffer = Vec::new();
+    entry.read_to_end(&mut buffer)?;
+    assert_eq!(std::fs::read(&file)?, buffer);
+
+    // Range that does not match any versions
+    let output = RegistryClient::fetch_bundle(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        Some(VersionReq::parse(">=2.0.0")?),
+        bundle_path()?,
+    )
+    .await?;
+
+    let (names, records) = read_bundle(&output)?;
+    assert_eq!(vec!["versions.json".to_owned()], names);
+    assert!(records.is_empty());
+
+    // Pre-release range that the version query over matches
+    let output = RegistryClient::fetch_bundle(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        Some(VersionReq::parse("=1.0.0-alpha")?),
+        bundle_path()?,
+    )
+    .await?;
+
+    let (names, records) = read_bundle(&output)?;
+    assert_eq!(vec!["versions.json".to_owned()], names);
+    assert!(records.is_empty());
+
+    // Unknown package
+    let result = RegistryClient::fetch_bundle(
+        server_url,
+        namespace,
+        PackageName::new_unchecked("unknown-package"),
+        None,
+        bundle_path()?,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(404))
+    ));
+
+    Ok(())
+}
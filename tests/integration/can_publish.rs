@@ -0,0 +1,247 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request};
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName, X_SIGNATURE};
+use ipfs_registry_database::{
+    PublishCheck, PublishDenied, PublishPermission,
+};
+
+use k256::ecdsa::{recoverable, signature::Signer};
+
+fn allowed(permission: PublishPermission) -> PublishCheck {
+    PublishCheck {
+        allowed: true,
+        permission: Some(permission),
+        reason: None,
+    }
+}
+
+fn denied(reason: PublishDenied) -> PublishCheck {
+    PublishCheck {
+        allowed: false,
+        permission: None,
+        reason: Some(reason),
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_can_publish() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, address) = new_signing_key();
+    let (authorized_key, authorized_address) = new_signing_key();
+    let (restricted_key, restricted_address) = new_signing_key();
+    let (unauthorized_key, unauthorized_address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let other_package = PackageName::new_unchecked("denied-extension");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    for key in [&authorized_key, &restricted_key, &unauthorized_key] {
+        RegistryClient::signup(server_url.clone(), key.clone()).await?;
+    }
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/denied-extension-1.0.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        authorized_address,
+        false,
+        None,
+    )
+    .await?;
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        restricted_address,
+        false,
+        Some(package.clone()),
+    )
+    .await?;
+
+    let check = |key, package: &PackageName, address, version| {
+        RegistryClient::can_publish(
+            server_url.clone(),
+            key,
+            namespace.clone(),
+            package.clone(),
+            address,
+            version,
+        )
+    };
+
+    // Owner
+    assert_eq!(
+        allowed(PublishPermission::Owner),
+        check(signing_key.clone(), &package, address, None).await?
+    );
+
+    // Authorized user checking their own address
+    assert_eq!(
+        allowed(PublishPermission::Publisher),
+        check(authorized_key.clone(), &package, authorized_address, None)
+            .await?
+    );
+
+    // Restricted user for granted and other packages
+    assert_eq!(
+        allowed(PublishPermission::Restricted),
+        check(restricted_key.clone(), &package, restricted_address, None)
+            .await?
+    );
+    assert_eq!(
+        denied(PublishDenied::NotGranted),
+        check(
+            restricted_key.clone(),
+            &other_package,
+            restricted_address,
+            None
+        )
+        .await?
+    );
+
+    // Owner checking an address that is not a user
+    assert_eq!(
+        denied(PublishDenied::NotMember),
+        check(signing_key.clone(), &package, unauthorized_address, None)
+            .await?
+    );
+
+    // Version checks are applied when a version is given
+    assert_eq!(
+        denied(PublishDenied::VersionExists),
+        check(
+            authorized_key.clone(),
+            &package,
+            authorized_address,
+            Some(Version::new(1, 0, 0))
+        )
+        .await?
+    );
+    assert_eq!(
+        denied(PublishDenied::VersionNotAhead),
+        check(
+            authorized_key.clone(),
+            &package,
+            authorized_address,
+            Some(Version::new(0, 1, 0))
+        )
+        .await?
+    );
+    assert_eq!(
+        allowed(PublishPermission::Publisher),
+        check(
+            authorized_key.clone(),
+            &package,
+            authorized_address,
+            Some(Version::new(1, 1, 0))
+        )
+        .await?
+    );
+
+    // Only the owner can restrict users by default
+    let result = RegistryClient::set_default_restricted(
+        server_url.clone(),
+        authorized_key.clone(),
+        namespace.clone(),
+        true,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Users without restrictions must be granted access when the
+    // namespace restricts users by default
+    let record = RegistryClient::set_default_restricted(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        true,
+    )
+    .await?;
+    assert!(record.default_restricted);
+    assert_eq!(
+        denied(PublishDenied::NotGranted),
+        check(authorized_key.clone(), &package, authorized_address, None)
+            .await?
+    );
+    assert_eq!(
+        allowed(PublishPermission::Restricted),
+        check(restricted_key.clone(), &package, restricted_address, None)
+            .await?
+    );
+
+    let record = RegistryClient::set_default_restricted(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        false,
+    )
+    .await?;
+    assert!(!record.default_restricted);
+    assert_eq!(
+        allowed(PublishPermission::Publisher),
+        check(authorized_key.clone(), &package, authorized_address, None)
+            .await?
+    );
+
+    // Users that are not administrators cannot check other addresses
+    let result =
+        check(authorized_key, &package, restricted_address, None).await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let result =
+        check(unauthorized_key, &package, authorized_address, None).await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Signatures for the address alone, as for adding a user,
+    // or for another package are rejected
+    let mut url = server_url.join(&format!(
+        "api/package/{}/{}/can-publish",
+        namespace, package
+    ))?;
+    url.query_pairs_mut()
+        .append_pair("address", &restricted_address.to_string());
+    let other_message = format!(
+        "can-publish:{}/{}/{}",
+        namespace, other_package, restricted_address
+    );
+    for message in [restricted_address.as_ref(), other_message.as_bytes()] {
+        let signature: recoverable::Signature = restricted_key.sign(message);
+        let request = Request::get(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature))
+            .body(Body::empty())?;
+        let response = Client::new().request(request).await?;
+        assert!(!response.status().is_success());
+    }
+
+    Ok(())
+}
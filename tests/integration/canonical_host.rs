@@ -0,0 +1,117 @@
+use anyhow::Result;
+use hyper::{
+    header::{HOST, LOCATION},
+    Body, Client, Method, Request, StatusCode,
+};
+use semver::Version;
+use serial_test::serial;
+
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+
+use ipfs_registry_server::config::CanonicalHostConfig;
+
+use crate::test_utils::*;
+
+const CANONICAL: &str = "http://localhost:9009";
+const MIRROR: &str = "mirror.example.com";
+
+/// Send a request with the given host header.
+async fn request(
+    method: Method,
+    path: &str,
+    host: &str,
+) -> Result<(StatusCode, Option<String>)> {
+    let url = server().join(path)?;
+    let request = Request::builder()
+        .method(method)
+        .uri(url.as_str())
+        .header(HOST, host)
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    let location = response
+        .headers()
+        .get(LOCATION)
+        .map(|value| value.to_str().unwrap().to_owned());
+    Ok((response.status(), location))
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_canonical_host() -> Result<()> {
+    let mut config = default_server_config();
+    config.canonical_host = Some(CanonicalHostConfig {
+        url: CANONICAL.parse()?,
+        metadata_only: false,
+    });
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let path = "api/package/mock-namespace/mock-package/versions?limit=5";
+
+    // Mismatched host is redirected
+    let (status, location) = request(Method::GET, path, MIRROR).await?;
+    assert_eq!(StatusCode::PERMANENT_REDIRECT, status);
+    assert_eq!(Some(format!("{}/{}", CANONICAL, path)), location);
+
+    let (status, _) = request(Method::POST, "api/signup", MIRROR).await?;
+    assert_eq!(StatusCode::PERMANENT_REDIRECT, status);
+
+    // Identity, health and metrics endpoints are never redirected
+    for path in ["api", "api/health", "api/metrics"] {
+        let (status, location) = request(Method::GET, path, MIRROR).await?;
+        assert_eq!(StatusCode::OK, status);
+        assert!(location.is_none());
+    }
+
+    // Canonical host is not redirected
+    let (status, location) =
+        request(Method::GET, path, "localhost:9009").await?;
+    assert_ne!(StatusCode::PERMANENT_REDIRECT, status);
+    assert!(location.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_canonical_host_metadata_only() -> Result<()> {
+    let mut config = default_server_config();
+    config.canonical_host = Some(CanonicalHostConfig {
+        url: CANONICAL.parse()?,
+        metadata_only: true,
+    });
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let path = "api/package/mock-namespace/mock-package/versions";
+    let (status, _) = request(Method::GET, path, MIRROR).await?;
+    assert_eq!(StatusCode::PERMANENT_REDIRECT, status);
+
+    // Downloads and writes are not redirected
+    let key = PackageKey::Pointer(
+        Namespace::new_unchecked("mock-namespace"),
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    );
+    let path = format!("api/package?id={}", key);
+    let (status, _) = request(Method::GET, &path, MIRROR).await?;
+    assert_ne!(StatusCode::PERMANENT_REDIRECT, status);
+
+    let path = "api/package/mock-namespace/mock-package/1.0.0/download";
+    let (status, _) = request(Method::GET, path, MIRROR).await?;
+    assert_ne!(StatusCode::PERMANENT_REDIRECT, status);
+
+    let (status, _) = request(Method::POST, "api/signup", MIRROR).await?;
+    assert_ne!(StatusCode::PERMANENT_REDIRECT, status);
+
+    for path in ["api/health", "api/metrics"] {
+        let (status, _) = request(Method::GET, path, MIRROR).await?;
+        assert_eq!(StatusCode::OK, status);
+    }
+
+    Ok(())
+}
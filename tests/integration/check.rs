@@ -0,0 +1,102 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{CheckStatus, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+
+use k256::ecdsa::SigningKey;
+
+#[tokio::test]
+#[serial]
+async fn integration_check_lockfile() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let key = |version: Version| {
+        PackageKey::Pointer(namespace.clone(), package.clone(), version)
+    };
+
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        key(Version::new(1, 1, 0)),
+        String::from("mock yank message"),
+    )
+    .await?;
+
+    let dir = tempfile::tempdir()?;
+    let lockfile = dir.path().join("ipkg.lock");
+    std::fs::write(
+        &lockfile,
+        format!(
+            "# mock lockfile\n{}\n\n{}\n{}\n",
+            key(Version::new(1, 0, 0)),
+            key(Version::new(1, 1, 0)),
+            key(Version::new(2, 0, 0)),
+        ),
+    )?;
+
+    let report =
+        ipfs_registry_client::check(server_url.clone(), lockfile.clone())
+            .await?;
+    let statuses: Vec<CheckStatus> =
+        report.entries.iter().map(|entry| entry.status).collect();
+    assert_eq!(
+        vec![CheckStatus::Ok, CheckStatus::Yanked, CheckStatus::Missing],
+        statuses
+    );
+    assert_eq!(
+        Some(String::from("mock yank message")),
+        report.entries[1].message
+    );
+    assert!(!report.is_ok());
+
+    // Deprecated packages are reported for available versions
+    RegistryClient::deprecate(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        String::from("mock deprecation"),
+    )
+    .await?;
+
+    std::fs::write(&lockfile, format!("{}\n", key(Version::new(1, 0, 0))))?;
+    let report = ipfs_registry_client::check(server_url, lockfile).await?;
+    assert_eq!(CheckStatus::Deprecated, report.entries[0].status);
+    assert_eq!(
+        Some(String::from("mock deprecation")),
+        report.entries[0].message
+    );
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use serial_test::serial;
+use sha3::{Digest, Sha3_256};
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::Namespace;
+
+use k256::ecdsa::SigningKey;
+
+#[tokio::test]
+#[serial]
+async fn integration_find_by_checksum() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let checksum: [u8; 32] = Sha3_256::digest(std::fs::read(&file)?)
+        .as_slice()
+        .try_into()?;
+    let records =
+        RegistryClient::find_by_checksum(server_url.clone(), checksum)
+            .await?;
+    assert_eq!(1, records.len());
+    let record = records.first().unwrap();
+    assert_eq!(Version::new(1, 0, 0), record.version);
+    assert_eq!(checksum, record.checksum);
+
+    // Unknown checksum is not found
+    let checksum: [u8; 32] =
+        Sha3_256::digest(b"mock-unknown").as_slice().try_into()?;
+    let result =
+        RegistryClient::find_by_checksum(server_url.clone(), checksum).await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
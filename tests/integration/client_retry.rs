@@ -0,0 +1,130 @@
+use anyhow::Result;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use semver::Version;
+use serial_test::serial;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use url::Url;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use tempfile::NamedTempFile;
+
+use crate::test_utils::*;
+
+/// Spawn a server that responds with 429 TOO MANY REQUESTS
+/// until the number of rate limited responses is exhausted.
+fn spawn_rate_limited(
+    limited: usize,
+    retry_after: u64,
+    counter: Arc<AtomicUsize>,
+) -> Result<Url> {
+    let make_service = make_service_fn(move |_| {
+        let counter = Arc::clone(&counter);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_req: Request<Body>| {
+                let count = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let response = if count < limited {
+                        Response::builder()
+                            .status(StatusCode::TOO_MANY_REQUESTS)
+                            .header("retry-after", retry_after.to_string())
+                            .body(Body::empty())
+                    } else {
+                        Response::builder()
+                            .status(StatusCode::OK)
+                            .body(Body::from("mock-artifact"))
+                    };
+                    Ok::<_, Infallible>(response.unwrap())
+                }
+            }))
+        }
+    });
+
+    let addr: SocketAddr = ([127, 0, 0, 1], 0).into();
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    let url = Url::parse(&format!("http://{}", server.local_addr()))?;
+    tokio::spawn(server);
+    Ok(url)
+}
+
+fn mock_key() -> PackageKey {
+    PackageKey::Pointer(
+        Namespace::new_unchecked("mock-namespace"),
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_client_retry() -> Result<()> {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let server_url = spawn_rate_limited(1, 1, Arc::clone(&counter))?;
+
+    let tmp = NamedTempFile::new()?;
+    let output = tmp.path().to_path_buf();
+    std::fs::remove_file(&output)?;
+
+    // Idempotent request is retried after the rate limit
+    let result =
+        RegistryClient::fetch_file(server_url, mock_key(), output).await?;
+    assert_eq!(b"mock-artifact".to_vec(), std::fs::read(result)?);
+    assert_eq!(2, counter.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_client_retry_after_limit() -> Result<()> {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let server_url = spawn_rate_limited(1, 3600, Arc::clone(&counter))?;
+
+    let tmp = NamedTempFile::new()?;
+    let output = tmp.path().to_path_buf();
+    std::fs::remove_file(&output)?;
+
+    // Wait is longer than the maximum so the request is not retried
+    let result =
+        RegistryClient::fetch_file(server_url, mock_key(), output).await;
+    assert!(matches!(result, Err(Error::RateLimited(3600))));
+    assert_eq!(1, counter.load(Ordering::SeqCst));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_client_rate_limited_publish() -> Result<()> {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let server_url = spawn_rate_limited(1, 1, Arc::clone(&counter))?;
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    // Publish is not retried and reports the suggested wait
+    let result = RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        Namespace::new_unchecked("mock-namespace"),
+        mime,
+        file,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::RateLimited(1))));
+    assert_eq!(1, counter.load(Ordering::SeqCst));
+
+    Ok(())
+}
@@ -1,11 +1,13 @@
 use anyhow::Result;
+use hyper::{body::to_bytes, Body, Client, Request, StatusCode};
+use serde_json::Value;
 use serial_test::serial;
 
 use crate::test_utils::*;
 
-use ipfs_registry_core::Namespace;
+use ipfs_registry_core::{Namespace, X_SIGNATURE};
 
-use k256::ecdsa::SigningKey;
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
 
 #[tokio::test]
 #[serial]
@@ -40,3 +42,52 @@ async fn integration_confusable_namespace() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_confusable_namespace_conflict() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    // 03BF GREEK SMALL LETTER OMICRO at index 1
+    let confusable = Namespace::new_unchecked("mοck-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let register = |name: Namespace| {
+        let signature: recoverable::Signature =
+            signing_key.sign(name.as_bytes());
+        let url = server_url.join(&format!("api/register/{}", name));
+        async move {
+            let request = Request::post(url?.as_str())
+                .header(X_SIGNATURE, base64::encode(signature))
+                .body(Body::empty())?;
+            let response = Client::new().request(request).await?;
+            assert_eq!(StatusCode::CONFLICT, response.status());
+            let body = to_bytes(response.into_body()).await?;
+            Ok::<_, anyhow::Error>(serde_json::from_slice::<Value>(&body)?)
+        }
+    };
+
+    let exact = register(namespace.clone()).await?;
+    assert_eq!("exists", exact["error"]);
+    assert_eq!("mock-namespace", exact["namespace"]);
+    assert_eq!("namespace mock-namespace already exists", exact["message"]);
+
+    let similar = register(confusable).await?;
+    assert_eq!("confusable", similar["error"]);
+    assert_eq!("mock-namespace", similar["namespace"]);
+    assert_eq!(
+        "namespace mοck-namespace is confusable with existing namespace mock-namespace",
+        similar["message"]
+    );
+
+    Ok(())
+}
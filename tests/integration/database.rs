@@ -3,12 +3,17 @@ use serial_test::serial;
 
 use crate::test_utils::*;
 
-use semver::Version;
+use semver::{Version, VersionReq};
 use sqlx::SqlitePool;
+use time::{
+    format_description::well_known::Rfc3339, macros::datetime, OffsetDateTime,
+};
 
-use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, StoredObject};
 use ipfs_registry_database::{
-    Error, NamespaceModel, PackageModel, PublisherModel, VersionIncludes,
+    AuditFilter, AuditModel, Error, MaintenanceModel, NamespaceAction,
+    NamespaceModel, PackageModel, Pager, PublisherModel, ResolveNamespace,
+    TransferSigners, VersionIncludes, VersionLocation,
 };
 
 #[tokio::test]
@@ -36,7 +41,7 @@ async fn integration_database() -> Result<()> {
 
     // Create a namespace
     let namespace_id =
-        NamespaceModel::insert(&pool, &namespace, publisher_id).await?;
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
 
     assert!(namespace_id > 0);
 
@@ -83,6 +88,7 @@ async fn integration_database() -> Result<()> {
         &namespace_record,
         &address,
         &pointer,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -94,6 +100,7 @@ async fn integration_database() -> Result<()> {
         &namespace_record,
         &authorized_address,
         &mock_pointer(Some(Version::new(1, 0, 1)))?,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -206,6 +213,7 @@ async fn integration_database() -> Result<()> {
         &namespace,
         &mock_package,
         &Default::default(),
+        true,
     )
     .await?;
 
@@ -216,6 +224,7 @@ async fn integration_database() -> Result<()> {
         &namespace,
         &Default::default(),
         VersionIncludes::Latest,
+        true,
     )
     .await?;
 
@@ -232,3 +241,1301 @@ async fn integration_database() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_database_timestamps() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let (_, legacy_address) = new_signing_key();
+
+    // Stored timestamp is ISO 8601 with millisecond precision
+    let record = PublisherModel::insert_fetch(&pool, &address).await?;
+    let stored: String = sqlx::query_scalar(
+        "SELECT created_at FROM publishers WHERE publisher_id = ?",
+    )
+    .bind(record.publisher_id)
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(24, stored.len());
+    assert_eq!(Some('T'), stored.chars().nth(10));
+    assert!(stored.ends_with('Z'));
+    assert_eq!(record.created_at, OffsetDateTime::parse(&stored, &Rfc3339)?);
+
+    // Timestamps without sub-second precision are assumed to be UTC
+    sqlx::query(
+        "INSERT INTO publishers ( address, created_at ) VALUES ( ?, ? )",
+    )
+    .bind(legacy_address.as_ref())
+    .bind("2022-09-11 08:28:17")
+    .execute(&pool)
+    .await?;
+    let record = PublisherModel::find_by_address(&pool, &legacy_address)
+        .await?
+        .unwrap();
+    assert_eq!(datetime!(2022-09-11 08:28:17 UTC), record.created_at);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_reindex() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    // Rename the namespace leaving a stale skeleton
+    let renamed = Namespace::new_unchecked("renamed-namespace");
+    sqlx::query(
+        "UPDATE namespaces SET name = ?, skeleton = ? WHERE namespace_id = ?",
+    )
+    .bind(renamed.as_str())
+    .bind("corrupt")
+    .bind(namespace_id)
+    .execute(&pool)
+    .await?;
+    assert!(NamespaceModel::find_by_name(&pool, &renamed)
+        .await?
+        .is_none());
+
+    // Confusable packages, one with a corrupted skeleton
+    let package = PackageName::new_unchecked("mock-package");
+    let confusable = PackageName::new_unchecked("mοck-package");
+    for (name, skeleton) in [
+        (&package, package.skeleton()),
+        (&confusable, String::from("corrupt")),
+    ] {
+        sqlx::query(
+            r#"
+                INSERT INTO packages ( namespace_id, name, skeleton, created_at )
+                VALUES ( ?, ?, ?, '2022-10-01T00:00:00.000Z' )
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(name.as_str())
+        .bind(skeleton)
+        .execute(&pool)
+        .await?;
+    }
+
+    let report = MaintenanceModel::reindex_skeletons(&pool).await?;
+    assert_eq!(1, report.namespaces);
+    assert_eq!(1, report.packages);
+    assert_eq!(1, report.collisions.len());
+    assert_eq!(package.skeleton(), report.collisions[0].skeleton);
+    assert_eq!(
+        vec![
+            format!("{}/{}", renamed, package),
+            format!("{}/{}", renamed, confusable),
+        ],
+        report.collisions[0].names
+    );
+
+    let record = NamespaceModel::find_by_name(&pool, &renamed).await?;
+    assert!(record.is_some());
+    let skeleton: String =
+        sqlx::query_scalar("SELECT skeleton FROM packages WHERE name = ?")
+            .bind(confusable.as_str())
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(package.skeleton(), skeleton);
+
+    // Running again is a no-op
+    let report = MaintenanceModel::reindex_skeletons(&pool).await?;
+    assert_eq!(0, report.namespaces);
+    assert_eq!(0, report.packages);
+    assert!(report.collisions.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_resolve_namespace() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let (_, unknown_address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let confusable = Namespace::new_unchecked("mοck-namespace");
+    let third = Namespace::new_unchecked("third-namespace");
+    let resolved = Namespace::new_unchecked("resolved-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let other = PackageName::new_unchecked("other-package");
+
+    let namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+    let third_id =
+        NamespaceModel::insert(&pool, &third, publisher_id, None).await?;
+
+    // Names with the same skeleton are rejected by the unique constraint
+    let result =
+        NamespaceModel::insert(&pool, &confusable, publisher_id, None).await;
+    assert!(matches!(result, Err(Error::NamespaceExists(_))));
+
+    // Confusable namespace registered under an older skeleton algorithm
+    let confusable_id = sqlx::query(
+        r#"
+            INSERT INTO namespaces ( name, skeleton, publisher_id, created_at )
+            VALUES ( ?, 'corrupt', ?, '2022-10-01T00:00:00.000Z' )
+        "#,
+    )
+    .bind(confusable.as_str())
+    .bind(publisher_id)
+    .execute(&pool)
+    .await?
+    .last_insert_rowid();
+
+    for (namespace_id, name) in [
+        (confusable_id, &package),
+        (namespace_id, &other),
+        (third_id, &package),
+    ] {
+        sqlx::query(
+            r#"
+                INSERT INTO packages ( namespace_id, name, skeleton, created_at )
+                VALUES ( ?, ?, ?, '2022-10-01T00:00:00.000Z' )
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(name.as_str())
+        .bind(name.skeleton())
+        .execute(&pool)
+        .await?;
+    }
+
+    let report = MaintenanceModel::reindex_skeletons(&pool).await?;
+    assert_eq!(1, report.collisions.len());
+
+    let resolve = |name: &Namespace, action: NamespaceAction, dry_run| {
+        let request = ResolveNamespace {
+            namespace: name.clone(),
+            action,
+            dry_run,
+        };
+        let pool = pool.clone();
+        async move {
+            MaintenanceModel::resolve_namespace(
+                &pool,
+                &address,
+                &request,
+                &[],
+            )
+            .await
+        }
+    };
+    let namespace_exists = |name: &Namespace| {
+        let pool = pool.clone();
+        let name = name.clone();
+        async move {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM namespaces WHERE name = ?",
+            )
+            .bind(name.as_str())
+            .fetch_one(&pool)
+            .await
+            .map(|count| count == 1)
+        }
+    };
+
+    // Unknown callers are rejected
+    let request = ResolveNamespace {
+        namespace: confusable.clone(),
+        action: NamespaceAction::Rename(resolved.clone()),
+        dry_run: false,
+    };
+    let result = MaintenanceModel::resolve_namespace(
+        &pool,
+        &unknown_address,
+        &request,
+        &[],
+    )
+    .await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    // Dry run reports the packages without making changes
+    let report =
+        resolve(&confusable, NamespaceAction::Merge(namespace.clone()), true)
+            .await?;
+    assert!(report.dry_run);
+    assert_eq!(vec![package.clone()], report.packages);
+    assert!(namespace_exists(&confusable).await?);
+    assert!(AuditModel::list(
+        &pool,
+        &Default::default(),
+        &Default::default()
+    )
+    .await?
+    .is_empty());
+
+    // Rename to a name that is already taken
+    let result =
+        resolve(&confusable, NamespaceAction::Rename(third.clone()), false)
+            .await;
+    assert!(matches!(result, Err(Error::NamespaceExists(_))));
+
+    // Rename the colliding namespace
+    resolve(
+        &confusable,
+        NamespaceAction::Rename(resolved.clone()),
+        false,
+    )
+    .await?;
+    assert!(!namespace_exists(&confusable).await?);
+    let record = NamespaceModel::find_by_name(&pool, &resolved).await?;
+    assert_eq!(Some(resolved.clone()), record.map(|r| r.name));
+
+    // Merge into a namespace with a conflicting package
+    let result =
+        resolve(&resolved, NamespaceAction::Merge(third.clone()), false)
+            .await;
+    assert!(matches!(result, Err(Error::PackageNameExists(_, _))));
+
+    let result =
+        resolve(&namespace, NamespaceAction::Merge(namespace.clone()), false)
+            .await;
+    assert!(matches!(result, Err(Error::MergeSelf(_))));
+
+    // Merge the packages into the surviving namespace
+    let report =
+        resolve(&resolved, NamespaceAction::Merge(namespace.clone()), false)
+            .await?;
+    assert_eq!(vec![package.clone()], report.packages);
+    assert!(!namespace_exists(&resolved).await?);
+    assert!(PackageModel::find_by_name(&pool, namespace_id, &package)
+        .await?
+        .is_some());
+
+    // Only applied actions are recorded
+    let records =
+        AuditModel::list(&pool, &Default::default(), &Default::default())
+            .await?
+            .records;
+    assert_eq!(2, records.len());
+    assert!(records
+        .iter()
+        .all(|r| r.action == "resolve_namespace" && r.address == address));
+    assert_eq!(
+        serde_json::json!({"rename": "resolved-namespace"}),
+        records[0].detail["action"]
+    );
+    assert_eq!(
+        serde_json::json!({"merge": "mock-namespace"}),
+        records[1].detail["action"]
+    );
+
+    let report = MaintenanceModel::reindex_skeletons(&pool).await?;
+    assert!(report.collisions.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_audit_filters() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let (_, other_address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let other_id = PublisherModel::insert(&pool, &other_address).await?;
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let other = Namespace::new_unchecked("other-namespace");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+    NamespaceModel::insert(&pool, &other, other_id, None).await?;
+
+    for (address, namespace) in
+        [(&address, &namespace), (&other_address, &other)]
+    {
+        let (publisher_record, namespace_record) =
+            NamespaceModel::can_access_namespace(&pool, address, namespace)
+                .await?;
+        let mut pointer = mock_pointer(None)?;
+        pointer.definition.artifact.namespace = namespace.clone();
+        PackageModel::insert(
+            &pool,
+            &publisher_record,
+            &namespace_record,
+            address,
+            &pointer,
+            &[],
+        )
+        .await?;
+    }
+
+    let package = PackageName::new_unchecked("mock-package");
+    let key = |namespace: &Namespace| {
+        PackageKey::Pointer(
+            namespace.clone(),
+            package.clone(),
+            Version::new(1, 0, 0),
+        )
+    };
+
+    PackageModel::yank(&pool, &address, &key(&namespace), "broken").await?;
+    PackageModel::yank(&pool, &other_address, &key(&other), "broken").await?;
+    PackageModel::unyank(&pool, &other_address, &key(&other)).await?;
+    NamespaceModel::set_description(&pool, &address, &namespace, "Mock")
+        .await?;
+
+    let list = |filter: AuditFilter| {
+        let pool = pool.clone();
+        async move { AuditModel::list(&pool, &filter, &Default::default()).await }
+    };
+
+    let records = list(Default::default()).await?;
+    assert_eq!(4, records.count);
+
+    // Filter by action
+    let records = list(AuditFilter {
+        action: Some(String::from("yank")),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(2, records.count);
+    assert!(records.records.iter().all(|r| r.action == "yank"));
+    assert_eq!(
+        serde_json::json!({
+            "package": "mock-package",
+            "version": "1.0.0",
+            "message": "broken",
+        }),
+        records.records[0].detail
+    );
+
+    // Filter by address
+    let records = list(AuditFilter {
+        address: Some(other_address),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(2, records.count);
+    assert!(records.records.iter().all(|r| r.address == other_address));
+    assert_eq!("unyank", records.records[1].action);
+
+    // Yanks in a namespace during the last week
+    let now = OffsetDateTime::now_utc();
+    let records = list(AuditFilter {
+        action: Some(String::from("yank")),
+        namespace: Some(namespace.clone()),
+        since: Some(now - time::Duration::weeks(1)),
+        until: Some(now + time::Duration::minutes(1)),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(1, records.count);
+    assert_eq!(address, records.records[0].address);
+    assert_eq!(Some(namespace.clone()), records.records[0].namespace);
+
+    // Nothing recorded after the range
+    let records = list(AuditFilter {
+        action: Some(String::from("yank")),
+        since: Some(now + time::Duration::minutes(1)),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(0, records.count);
+
+    // Count includes records outside the page
+    let records = AuditModel::list(
+        &pool,
+        &AuditFilter {
+            action: Some(String::from("yank")),
+            ..Default::default()
+        },
+        &Pager {
+            offset: 0,
+            limit: 1,
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(1, records.len());
+    assert_eq!(2, records.count);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_immutable_versions() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    let pointer = mock_pointer(None)?;
+    let version_id = PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &pointer,
+        &[],
+    )
+    .await?;
+
+    // Published content cannot be changed
+    for column in [
+        "package",
+        "content_id",
+        "signature",
+        "signature_scheme",
+        "checksum",
+        "major",
+        "pre",
+        "kind",
+        "size",
+    ] {
+        let result = sqlx::query(&format!(
+            "UPDATE versions SET {} = {} WHERE version_id = ?",
+            column, column
+        ))
+        .bind(version_id)
+        .execute(&pool)
+        .await;
+        let message = result.err().map(|e| e.to_string()).unwrap_or_default();
+        assert!(message.contains("immutable"), "{}: {}", column, message);
+    }
+
+    // Mutable columns, the pointer and objects locate the artifact
+    sqlx::query(
+        r#"
+            UPDATE versions
+            SET yanked = 'mock', yanked_at = '2022-10-01T00:00:00.000Z',
+                purged_at = '2022-10-01T00:00:00.000Z',
+                pointer_id = pointer_id, objects = objects
+            WHERE version_id = ?
+        "#,
+    )
+    .bind(version_id)
+    .execute(&pool)
+    .await?;
+
+    // Overwrite records the previous artifact so is permitted
+    let mut replacement = mock_pointer(None)?;
+    replacement.definition.checksum = [0; 32];
+    let (_, version_record) = PackageModel::find_by_name_version(
+        &pool,
+        namespace_id,
+        &PackageName::new_unchecked("mock-package"),
+        &Version::new(1, 0, 0),
+    )
+    .await?;
+    PackageModel::overwrite(
+        &pool,
+        &publisher_record,
+        &version_record.unwrap(),
+        &replacement,
+        &[],
+    )
+    .await?;
+
+    // An overwrite does not permit later changes
+    let result = sqlx::query(
+        "UPDATE versions SET checksum = checksum WHERE version_id = ?",
+    )
+    .bind(version_id)
+    .execute(&pool)
+    .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_version_objects() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    let pointer = mock_pointer(None)?;
+    let objects = [
+        StoredObject {
+            layer: 0,
+            key: "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb".parse()?,
+        },
+        StoredObject {
+            layer: 1,
+            key: "mock-namespace/mock-package/1.0.0/mock-package-1.0.0.tgz"
+                .parse()?,
+        },
+    ];
+    PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &pointer,
+        &objects[..1],
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace,
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    );
+    let (_, _, record) =
+        PackageModel::find_by_key(&pool, &id, Default::default()).await?;
+    let record = record.unwrap();
+    assert_eq!(1, record.objects.len());
+
+    // Object stored by a write retry after the publish
+    PackageModel::add_version_object(&pool, &record.pointer_id, &objects[1])
+        .await?;
+    PackageModel::add_version_object(&pool, "unknown-pointer", &objects[1])
+        .await?;
+
+    let (_, _, record) =
+        PackageModel::find_by_key(&pool, &id, Default::default()).await?;
+    let record = record.unwrap();
+    assert_eq!(2, record.objects.len());
+    for (expected, stored) in objects.iter().zip(record.objects.iter()) {
+        assert_eq!(expected.layer, stored.layer);
+        assert_eq!(expected.key.to_string(), stored.key.to_string());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_shallow_namespace() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let (_, user_address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    PublisherModel::insert(&pool, &user_address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+    NamespaceModel::add_user(
+        &pool,
+        &namespace,
+        &address,
+        &user_address,
+        false,
+        vec![],
+    )
+    .await?;
+
+    let record = NamespaceModel::find_by_name(&pool, &namespace)
+        .await?
+        .unwrap();
+    assert_eq!(1, record.publishers.len());
+
+    let shallow = NamespaceModel::find_by_name_shallow(&pool, &namespace)
+        .await?
+        .unwrap();
+    assert_eq!(record.namespace_id, shallow.namespace_id);
+    assert_eq!(address, shallow.owner);
+    assert!(shallow.publishers.is_empty());
+
+    assert!(NamespaceModel::find_by_name_shallow(
+        &pool,
+        &Namespace::new_unchecked("unknown-namespace")
+    )
+    .await?
+    .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_version_order() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    for version in
+        ["1.0.0", "1.0.0-beta", "0.9.0", "1.0.0-alpha", "1.1.0-rc.1"]
+    {
+        PackageModel::insert(
+            &pool,
+            &publisher_record,
+            &namespace_record,
+            &address,
+            &mock_pointer(Some(version.parse()?))?,
+            &[],
+        )
+        .await?;
+    }
+
+    let expected: Vec<Version> =
+        ["0.9.0", "1.0.0-alpha", "1.0.0-beta", "1.0.0", "1.1.0-rc.1"]
+            .into_iter()
+            .map(|v| v.parse())
+            .collect::<std::result::Result<_, _>>()?;
+    let mut reversed = expected.clone();
+    reversed.reverse();
+
+    for (sort, expected) in [("asc", &expected), ("desc", &reversed)] {
+        let pager = Pager {
+            sort: sort.parse()?,
+            ..Default::default()
+        };
+
+        let listed = PackageModel::list_versions(
+            &pool, &namespace, &package, &pager, true,
+        )
+        .await?;
+        let listed: Vec<Version> =
+            listed.records.into_iter().map(|r| r.version).collect();
+        assert_eq!(expected, &listed);
+
+        let found = PackageModel::find_versions(
+            &pool,
+            &namespace,
+            &package,
+            &VersionReq::parse(">=0")?,
+            &pager,
+            true,
+        )
+        .await?;
+        let found: Vec<Version> =
+            found.records.into_iter().map(|r| r.version).collect();
+        assert_eq!(expected, &found);
+    }
+
+    // Latest agrees with the first descending version
+    let latest = PackageModel::find_latest_by_name(
+        &pool, &namespace, &package, true, true,
+    )
+    .await?
+    .unwrap();
+    assert_eq!(reversed[0], latest.version);
+
+    let latest = PackageModel::find_latest_by_name(
+        &pool, &namespace, &package, false, true,
+    )
+    .await?
+    .unwrap();
+    assert_eq!(Version::new(1, 0, 0), latest.version);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_version_count() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    let mut version_ids = Vec::new();
+    for version in ["1.0.0", "1.1.0", "1.2.0"] {
+        version_ids.push(
+            PackageModel::insert(
+                &pool,
+                &publisher_record,
+                &namespace_record,
+                &address,
+                &mock_pointer(Some(version.parse()?))?,
+                &[],
+            )
+            .await?,
+        );
+    }
+    sqlx::query("UPDATE versions SET yanked = 'mock' WHERE version_id = ?")
+        .bind(version_ids[0])
+        .execute(&pool)
+        .await?;
+
+    // Package without any versions
+    sqlx::query(
+        r#"
+            INSERT INTO packages ( namespace_id, name, skeleton, created_at )
+            VALUES ( ?, 'empty-package', 'empty-package', '2022-10-01T00:00:00.000Z' )
+        "#,
+    )
+    .bind(namespace_id)
+    .execute(&pool)
+    .await?;
+
+    for (include_yanked, expected) in [(true, 3), (false, 2)] {
+        let packages = PackageModel::list_packages(
+            &pool,
+            &namespace,
+            &Default::default(),
+            VersionIncludes::Count,
+            include_yanked,
+        )
+        .await?;
+        assert_eq!(2, packages.count);
+
+        let counts: Vec<(String, i64, usize)> = packages
+            .records
+            .iter()
+            .map(|p| {
+                (
+                    p.name.to_string(),
+                    p.versions.count,
+                    p.versions.records.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (String::from("empty-package"), 0, 0),
+                (String::from("mock-package"), expected, 0),
+            ],
+            counts
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_empty_packages() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+
+    // Failed first version insert does not leave a package
+    sqlx::query(
+        r#"
+            CREATE TRIGGER versions_insert_failure BEFORE INSERT ON versions
+            BEGIN
+                SELECT RAISE(ABORT, 'injected failure');
+            END
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+    let result = PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &mock_pointer(None)?,
+        &[],
+    )
+    .await;
+    assert!(result.is_err());
+    let packages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM packages")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(0, packages);
+
+    sqlx::query("DROP TRIGGER versions_insert_failure")
+        .execute(&pool)
+        .await?;
+    PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &mock_pointer(None)?,
+        &[],
+    )
+    .await?;
+    assert!(PackageModel::find_empty_packages(&pool).await?.is_empty());
+
+    // Package left without versions by an earlier release
+    let empty_id = sqlx::query(
+        r#"
+            INSERT INTO packages ( namespace_id, name, skeleton, created_at )
+            VALUES ( ?, 'empty-package', 'empty-package', '2022-10-01T00:00:00.000Z' )
+        "#,
+    )
+    .bind(namespace_id)
+    .execute(&pool)
+    .await?
+    .last_insert_rowid();
+    sqlx::query(
+        "INSERT INTO publisher_restrictions ( publisher_id, package_id ) VALUES ( ?, ? )",
+    )
+    .bind(publisher_id)
+    .bind(empty_id)
+    .execute(&pool)
+    .await?;
+
+    let empty = PackageModel::find_empty_packages(&pool).await?;
+    assert_eq!(1, empty.len());
+    assert_eq!(namespace, empty[0].namespace);
+    assert_eq!("empty-package", empty[0].name.as_str());
+
+    // Dry run reports without removing
+    let report = MaintenanceModel::remove_empty_packages(&pool, true).await?;
+    assert!(report.dry_run);
+    assert_eq!(1, report.packages.len());
+    assert_eq!(1, PackageModel::find_empty_packages(&pool).await?.len());
+
+    let report =
+        MaintenanceModel::remove_empty_packages(&pool, false).await?;
+    assert!(!report.dry_run);
+    assert_eq!(1, report.packages.len());
+    assert!(PackageModel::find_empty_packages(&pool).await?.is_empty());
+    let restrictions: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM publisher_restrictions")
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(0, restrictions);
+
+    let packages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM packages")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(1, packages);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_list_cursor() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let insert_package = |name: &'static str| {
+        sqlx::query(
+            r#"
+                INSERT INTO packages ( namespace_id, name, skeleton, created_at )
+                VALUES ( ?, ?, ?, '2022-10-01T00:00:00.000Z' )
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(name)
+        .bind(name)
+        .execute(&pool)
+    };
+
+    for name in ["pkg-b", "pkg-d", "pkg-f"] {
+        insert_package(name).await?;
+    }
+
+    let list_packages = |cursor: Option<String>| {
+        let pager = Pager {
+            limit: 2,
+            cursor,
+            ..Default::default()
+        };
+        let pool = pool.clone();
+        let namespace = namespace.clone();
+        async move {
+            PackageModel::list_packages(
+                &pool,
+                &namespace,
+                &pager,
+                VersionIncludes::None,
+                true,
+            )
+            .await
+        }
+    };
+
+    let page = list_packages(None).await?;
+    let names: Vec<String> =
+        page.records.iter().map(|p| p.name.to_string()).collect();
+    assert_eq!(vec!["pkg-b", "pkg-d"], names);
+    assert!(page.next_cursor.is_some());
+
+    // Rows inserted before and after the cursor
+    for name in ["pkg-a", "pkg-e"] {
+        insert_package(name).await?;
+    }
+
+    let page = list_packages(page.next_cursor).await?;
+    let names: Vec<String> =
+        page.records.iter().map(|p| p.name.to_string()).collect();
+    assert_eq!(vec!["pkg-e", "pkg-f"], names);
+    assert_eq!(5, page.count);
+
+    let page = list_packages(page.next_cursor).await?;
+    assert!(page.records.is_empty());
+    assert!(page.next_cursor.is_none());
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    let insert_version = |version: &'static str| {
+        let pool = pool.clone();
+        let publisher_record = publisher_record.clone();
+        let namespace_record = namespace_record.clone();
+        async move {
+            PackageModel::insert(
+                &pool,
+                &publisher_record,
+                &namespace_record,
+                &address,
+                &mock_pointer(Some(version.parse()?))?,
+                &[],
+            )
+            .await?;
+            Ok::<_, anyhow::Error>(())
+        }
+    };
+
+    for version in ["1.0.0", "1.2.0", "1.4.0"] {
+        insert_version(version).await?;
+    }
+
+    let list_versions = |cursor: Option<String>| {
+        let pager = Pager {
+            limit: 2,
+            sort: "desc".parse().unwrap(),
+            cursor,
+            ..Default::default()
+        };
+        let pool = pool.clone();
+        let namespace = namespace.clone();
+        let package = package.clone();
+        async move {
+            PackageModel::list_versions(
+                &pool, &namespace, &package, &pager, true,
+            )
+            .await
+        }
+    };
+
+    let page = list_versions(None).await?;
+    let versions: Vec<String> =
+        page.records.iter().map(|v| v.version.to_string()).collect();
+    assert_eq!(vec!["1.4.0", "1.2.0"], versions);
+
+    for version in ["1.5.0", "1.1.0", "1.0.0-beta"] {
+        insert_version(version).await?;
+    }
+
+    let page = list_versions(page.next_cursor).await?;
+    let versions: Vec<String> =
+        page.records.iter().map(|v| v.version.to_string()).collect();
+    assert_eq!(vec!["1.1.0", "1.0.0"], versions);
+    assert_eq!(6, page.count);
+
+    let page = list_versions(page.next_cursor).await?;
+    let versions: Vec<String> =
+        page.records.iter().map(|v| v.version.to_string()).collect();
+    assert_eq!(vec!["1.0.0-beta"], versions);
+    assert!(page.next_cursor.is_none());
+
+    // Cursor for a different list is rejected
+    let cursor = list_packages(None).await?.next_cursor;
+    let result = list_versions(cursor).await;
+    assert!(matches!(result, Err(Error::InvalidCursor(_))));
+
+    let result = list_versions(Some(String::from("mock-cursor"))).await;
+    assert!(matches!(result, Err(Error::InvalidCursor(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_transfer_ownership() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let (_, owner_address) = new_signing_key();
+    let (_, unknown_address) = new_signing_key();
+
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let owner_id = PublisherModel::insert(&pool, &owner_address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &mock_pointer(None)?,
+        &[],
+    )
+    .await?;
+
+    let package = PackageName::new_unchecked("mock-package");
+    NamespaceModel::add_user(
+        &pool,
+        &namespace,
+        &address,
+        &owner_address,
+        false,
+        vec![&package],
+    )
+    .await?;
+
+    let result = NamespaceModel::transfer_ownership(
+        &pool,
+        &namespace,
+        &address,
+        &unknown_address,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::UnknownPublisher(_))));
+
+    let result = NamespaceModel::transfer_ownership(
+        &pool,
+        &namespace,
+        &owner_address,
+        &owner_address,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+
+    let record = NamespaceModel::transfer_ownership(
+        &pool,
+        &namespace,
+        &address,
+        &owner_address,
+    )
+    .await?;
+    assert_eq!(owner_address, record.owner);
+    assert!(record.publishers.is_empty());
+    assert!(!record.has_user(&address));
+
+    let restrictions = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM publisher_restrictions WHERE publisher_id = ?",
+    )
+    .bind(owner_id)
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(0, restrictions);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_transfer_package() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let target = Namespace::new_unchecked("mock-target");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+    let target_id =
+        NamespaceModel::insert(&pool, &target, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    let version_id = PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &mock_pointer(None)?,
+        &[],
+    )
+    .await?;
+
+    let package = PackageName::new_unchecked("mock-package");
+    let location = VersionLocation {
+        version_id,
+        pointer_id: "mock-pointer".to_string(),
+        objects: vec![],
+    };
+    let (_, other_address) = new_signing_key();
+    PublisherModel::insert(&pool, &other_address).await?;
+    let signers = TransferSigners::Owners {
+        from: address,
+        to: address,
+    };
+
+    // Both signers must own their namespace
+    for invalid in [
+        TransferSigners::Owners {
+            from: other_address,
+            to: address,
+        },
+        TransferSigners::Owners {
+            from: address,
+            to: other_address,
+        },
+    ] {
+        let result = PackageModel::transfer(
+            &pool,
+            &invalid,
+            &namespace,
+            &package,
+            &target,
+            std::slice::from_ref(&location),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+    }
+
+    PackageModel::transfer(
+        &pool,
+        &signers,
+        &namespace,
+        &package,
+        &target,
+        &[location],
+    )
+    .await?;
+
+    // Version is stored under the pointer for the target
+    let (_, version_record) = PackageModel::find_by_name_version(
+        &pool,
+        target_id,
+        &package,
+        &Version::new(1, 0, 0),
+    )
+    .await?;
+    assert_eq!("mock-pointer", version_record.unwrap().pointer_id);
+
+    let records =
+        AuditModel::list(&pool, &Default::default(), &Default::default())
+            .await?
+            .records;
+    assert_eq!(1, records.len());
+    assert_eq!("transfer_package", records[0].action);
+    assert_eq!(Some(namespace.clone()), records[0].namespace);
+    assert_eq!(
+        serde_json::json!({
+            "package": "mock-package",
+            "from": "mock-namespace",
+            "to": "mock-target",
+        }),
+        records[0].detail
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_database_description_audit() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+    PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &mock_pointer(None)?,
+        &[],
+    )
+    .await?;
+
+    let package = PackageName::new_unchecked("mock-package");
+    NamespaceModel::set_description(
+        &pool,
+        &address,
+        &namespace,
+        "Mock namespace",
+    )
+    .await?;
+    PackageModel::set_description(&pool, &address, &namespace, &package, "")
+        .await?;
+
+    let records = AuditModel::list(
+        &pool,
+        &AuditFilter {
+            action: Some(String::from("set_description")),
+            ..Default::default()
+        },
+        &Default::default(),
+    )
+    .await?
+    .records;
+    assert_eq!(2, records.len());
+    assert!(records
+        .iter()
+        .all(|r| r.namespace.as_ref() == Some(&namespace)));
+    assert_eq!(
+        serde_json::json!({"description": "Mock namespace"}),
+        records[0].detail
+    );
+    assert_eq!(
+        serde_json::json!({"package": "mock-package", "description": null}),
+        records[1].detail
+    );
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_dependents() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let mock_package = PackageName::new_unchecked("mock-package");
+    let mock_dependent = PackageName::new_unchecked("mock-dependent");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-dependent-1.0.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let dependents = RegistryClient::dependents(
+        server_url.clone(),
+        namespace.clone(),
+        mock_package.clone(),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(1, dependents.count);
+    let record = dependents.records.first().unwrap();
+    assert_eq!(namespace, record.namespace);
+    assert_eq!(mock_dependent, record.package);
+    assert_eq!(Version::new(1, 0, 0), record.version);
+    assert_eq!("^1.0.0", record.range);
+
+    // Nothing depends on the dependent
+    let dependents = RegistryClient::dependents(
+        server_url.clone(),
+        namespace.clone(),
+        mock_dependent.clone(),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(0, dependents.count);
+
+    // Yanked versions are not dependents
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        PackageKey::Pointer(
+            namespace.clone(),
+            mock_dependent.clone(),
+            Version::new(1, 0, 0),
+        ),
+        String::new(),
+    )
+    .await?;
+    let dependents = RegistryClient::dependents(
+        server_url.clone(),
+        namespace.clone(),
+        mock_package.clone(),
+        Default::default(),
+    )
+    .await?;
+    assert!(dependents.records.is_empty());
+
+    // Unknown package
+    let result = RegistryClient::dependents(
+        server_url.clone(),
+        namespace.clone(),
+        PackageName::new_unchecked("mock-unknown"),
+        Default::default(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request};
+use k256::ecdsa::{recoverable, signature::Signer};
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{CheckStatus, Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, X_SIGNATURE};
+
+#[tokio::test]
+#[serial]
+async fn integration_deprecate_namespace() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (admin_key, admin_address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let message = String::from("mock deprecation notice");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), admin_key.clone()).await?;
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        admin_address,
+        true,
+        None,
+    )
+    .await?;
+
+    // Only the owner may deprecate the namespace
+    let result = RegistryClient::deprecate_namespace(
+        server_url.clone(),
+        admin_key.clone(),
+        namespace.clone(),
+        message.clone(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let record = RegistryClient::deprecate_namespace(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        message.clone(),
+    )
+    .await?;
+    assert_eq!(Some(&message), record.deprecated.as_ref());
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(Some(&message), record.deprecated.as_ref());
+
+    // Publishing to a deprecated namespace is allowed
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Packages in the namespace are reported as deprecated
+    let dir = tempfile::tempdir()?;
+    let lockfile = dir.path().join("ipkg.lock");
+    std::fs::write(
+        &lockfile,
+        format!(
+            "{}\n",
+            PackageKey::Pointer(
+                namespace.clone(),
+                package,
+                Version::new(1, 0, 0)
+            )
+        ),
+    )?;
+    let report =
+        ipfs_registry_client::check(server_url.clone(), lockfile.clone())
+            .await?;
+    assert_eq!(CheckStatus::Deprecated, report.entries[0].status);
+    assert_eq!(Some(&message), report.entries[0].message.as_ref());
+
+    // Signature made to register the namespace replayed
+    // to clear the deprecation notice
+    let url =
+        server_url.join(&format!("api/namespace/{}/deprecate", namespace))?;
+    let signature: recoverable::Signature =
+        signing_key.sign(namespace.as_bytes());
+    let request = Request::delete(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert!(!response.status().is_success());
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(Some(&message), record.deprecated.as_ref());
+
+    // Only the owner may clear the deprecation notice
+    let result = RegistryClient::undeprecate_namespace(
+        server_url.clone(),
+        admin_key,
+        namespace.clone(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let record = RegistryClient::undeprecate_namespace(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+    )
+    .await?;
+    assert!(record.deprecated.is_none());
+
+    let report = ipfs_registry_client::check(server_url, lockfile).await?;
+    assert_eq!(CheckStatus::Ok, report.entries[0].status);
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request};
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::{Error as ClientError, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName, X_SIGNATURE};
+use ipfs_registry_database::MAX_DESCRIPTION_LENGTH;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_description() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let other_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), other_key.clone()).await?;
+
+    // Namespace description before any version is published
+    let record = RegistryClient::set_namespace_description(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        String::from("Mock *namespace*"),
+    )
+    .await?;
+    assert_eq!(Some("Mock *namespace*"), record.description.as_deref());
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(Some("Mock *namespace*"), record.description.as_deref());
+
+    // Signature for another namespace of the owner replayed
+    // against this namespace
+    let url = server_url.join(&format!("api/namespace/{}", namespace))?;
+    let signature: recoverable::Signature =
+        signing_key.sign(b"mock-other:Replayed");
+    let request = Request::put(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .body(Body::from("Replayed"))?;
+    let response = Client::new().request(request).await?;
+    assert!(!response.status().is_success());
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(Some("Mock *namespace*"), record.description.as_deref());
+
+    // Users outside the namespace cannot change the description
+    let result = RegistryClient::set_namespace_description(
+        server_url.clone(),
+        other_key.clone(),
+        namespace.clone(),
+        String::from("Hijacked"),
+    )
+    .await;
+    assert!(matches!(result, Err(ClientError::ResponseCode(401))));
+
+    // Description is too long
+    let result = RegistryClient::set_namespace_description(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        "a".repeat(MAX_DESCRIPTION_LENGTH + 1),
+    )
+    .await;
+    assert!(matches!(result, Err(ClientError::ResponseCode(422))));
+
+    // Package must exist
+    let result = RegistryClient::set_package_description(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        String::from("Mock package"),
+    )
+    .await;
+    assert!(matches!(result, Err(ClientError::ResponseCode(404))));
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    let record = RegistryClient::set_package_description(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        String::from("Mock package"),
+    )
+    .await?;
+    assert_eq!(Some("Mock package"), record.description.as_deref());
+
+    let record = RegistryClient::get_package(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+    )
+    .await?;
+    assert_eq!(Some("Mock package"), record.description.as_deref());
+
+    // Empty description removes the description
+    let record = RegistryClient::set_package_description(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        package,
+        String::new(),
+    )
+    .await?;
+    assert!(record.description.is_none());
+
+    Ok(())
+}
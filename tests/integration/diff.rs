@@ -0,0 +1,103 @@
+use anyhow::Result;
+use semver::Version;
+use serde_json::json;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{FieldChange, Namespace, PackageName};
+
+use k256::ecdsa::SigningKey;
+
+#[tokio::test]
+#[serial]
+async fn integration_diff() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let diff = RegistryClient::diff(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+        Version::new(1, 1, 0),
+    )
+    .await?;
+
+    assert_eq!(Version::new(1, 0, 0), diff.from);
+    assert_eq!(Version::new(1, 1, 0), diff.to);
+    assert_eq!(
+        Some(&json!("^7.0.0")),
+        diff.added.get("dependencies.semver")
+    );
+    assert!(diff.removed.is_empty());
+    assert_eq!(
+        Some(&FieldChange {
+            from: json!("1.0.0"),
+            to: json!("1.1.0"),
+        }),
+        diff.changed.get("version")
+    );
+    assert_eq!(1, diff.changed.len());
+
+    // Missing versions and package
+    for (package, from, to) in [
+        (
+            package.clone(),
+            Version::new(1, 0, 0),
+            Version::new(2, 0, 0),
+        ),
+        (
+            package.clone(),
+            Version::new(2, 0, 0),
+            Version::new(1, 0, 0),
+        ),
+        (
+            PackageName::new_unchecked("mock-missing"),
+            Version::new(1, 0, 0),
+            Version::new(1, 1, 0),
+        ),
+    ] {
+        let result = RegistryClient::diff(
+            server_url.clone(),
+            namespace.clone(),
+            package,
+            from,
+            to,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(ipfs_registry_client::Error::ResponseCode(404))
+        ));
+    }
+
+    Ok(())
+}
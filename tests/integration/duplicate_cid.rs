@@ -0,0 +1,80 @@
+use anyhow::Result;
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use semver::Version;
+use sqlx::SqlitePool;
+
+use ipfs_registry_core::{Namespace, ObjectKey, PackageKey, PackageName};
+use ipfs_registry_database::{
+    DuplicateCid, Error, NamespaceModel, PackageModel, PublisherModel,
+};
+
+#[tokio::test]
+#[serial]
+async fn integration_duplicate_cid() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let _namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+
+    // Publish identical content under two package names
+    let first = PackageName::new_unchecked("mock-package");
+    let second = PackageName::new_unchecked("mock-other");
+    let mut cid = None;
+    for name in [&first, &second] {
+        let mut pointer = mock_pointer(Some(Version::new(1, 0, 0)))?;
+        pointer.definition.artifact.package.name = name.clone();
+        cid = pointer.definition.objects.iter().find_map(|o| {
+            if let ObjectKey::Cid(value) = o {
+                Some(*value)
+            } else {
+                None
+            }
+        });
+        PackageModel::insert(
+            &pool,
+            &publisher_record,
+            &namespace_record,
+            &address,
+            &pointer,
+            &[],
+        )
+        .await?;
+    }
+
+    let cid = cid.unwrap();
+    let id = PackageKey::Cid(cid);
+
+    let versions = PackageModel::find_by_cid(&pool, &cid).await?;
+    assert_eq!(2, versions.len());
+
+    let (_, package, _) =
+        PackageModel::find_by_key(&pool, &id, DuplicateCid::Latest).await?;
+    assert_eq!(second, package.unwrap().name);
+
+    let (_, package, _) =
+        PackageModel::find_by_key(&pool, &id, DuplicateCid::Earliest).await?;
+    assert_eq!(first, package.unwrap().name);
+
+    let result =
+        PackageModel::find_by_key(&pool, &id, DuplicateCid::Reject).await;
+    assert!(matches!(result, Err(Error::AmbiguousCid(_, 2))));
+
+    // Yanking shared content is ambiguous
+    let result = PackageModel::yank(&pool, &address, &id, "mock yank").await;
+    assert!(matches!(result, Err(Error::AmbiguousCid(_, 2))));
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
 use anyhow::Result;
+use hyper::{header::CONTENT_DISPOSITION, Client, StatusCode};
 use k256::ecdsa::SigningKey;
 use semver::Version;
 use serial_test::serial;
 use std::path::PathBuf;
 
-use ipfs_registry_client::RegistryClient;
+use ipfs_registry_client::{Error, RegistryClient};
 use ipfs_registry_core::{Namespace, PackageKey, PackageName};
 use tempfile::NamedTempFile;
 
@@ -61,3 +62,67 @@ async fn integration_fetch_pointer() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_path() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    let dir = tempfile::tempdir()?;
+    let output = dir.path().join("package.tgz");
+
+    let result = RegistryClient::fetch_file_by_path(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+        output.clone(),
+    )
+    .await?;
+    assert_eq!(output, result);
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&output)?);
+
+    // Path route sets the same headers as the query string route
+    let url = server_url
+        .join("api/package/mock-namespace/mock-package/1.0.0/download")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        "attachment; filename=\"mock-package-1.0.0.tgz\"",
+        response.headers().get(CONTENT_DISPOSITION).unwrap()
+    );
+
+    let result = RegistryClient::fetch_file_by_path(
+        server_url,
+        namespace,
+        package,
+        Version::new(2, 0, 0),
+        dir.path().join("missing.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
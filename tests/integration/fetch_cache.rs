@@ -0,0 +1,141 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{fetch, ArtifactCache, RegistryClient};
+use ipfs_registry_core::{AnyRef, Namespace, PackageKey, PackageName};
+use ipfs_registry_server::config::{FetchLimitConfig, RegistryConfig};
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_cache() -> Result<()> {
+    // Only one download is allowed so a second successful
+    // fetch must be served from the cache
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        fetch_limit: Some(FetchLimitConfig {
+            requests: 1,
+            window: 3600,
+        }),
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace,
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    );
+
+    let dir = tempfile::tempdir()?;
+    let cache_dir = dir.path().join("cache");
+    let cache = ArtifactCache::new(cache_dir.clone());
+
+    // First fetch downloads and populates the cache
+    let first = dir.path().join("first.tgz");
+    fetch(
+        server_url.clone(),
+        AnyRef::Key(id.clone()),
+        first.clone(),
+        false,
+        None,
+        Some(cache_dir.clone()),
+    )
+    .await?;
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&first)?);
+    assert!(cache.path(&receipt.checksum).exists());
+
+    // Second fetch is copied from the cache
+    let second = dir.path().join("second.tgz");
+    fetch(
+        server_url.clone(),
+        AnyRef::Key(id.clone()),
+        second.clone(),
+        false,
+        None,
+        Some(cache_dir),
+    )
+    .await?;
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&second)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_cache_corrupt() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace,
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    );
+
+    let dir = tempfile::tempdir()?;
+    let cache_dir = dir.path().join("cache");
+    let cache = ArtifactCache::new(cache_dir.clone());
+
+    // Entry that does not match the checksum
+    std::fs::create_dir_all(&cache_dir)?;
+    std::fs::write(cache.path(&receipt.checksum), b"corrupt")?;
+
+    // Corrupt entry is replaced by a fresh download
+    let output = dir.path().join("package.tgz");
+    fetch(
+        server_url,
+        AnyRef::Key(id),
+        output.clone(),
+        false,
+        None,
+        Some(cache_dir),
+    )
+    .await?;
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&output)?);
+    assert_eq!(
+        std::fs::read(&file)?,
+        std::fs::read(cache.path(&receipt.checksum))?
+    );
+
+    Ok(())
+}
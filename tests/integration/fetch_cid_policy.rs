@@ -0,0 +1,53 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use serial_test::serial;
+use std::collections::HashSet;
+
+use crate::test_utils::*;
+
+use ipfs_registry_server::config::{CidConfig, RegistryConfig};
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_cid_policy() -> Result<()> {
+    // Only accept CIDv1 dag-pb identifiers
+    let config = registry_server_config(RegistryConfig {
+        cid: Some(CidConfig {
+            versions: Some(HashSet::from([1])),
+            codecs: Some(HashSet::from([0x70])),
+        }),
+        ..Default::default()
+    });
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    for (cid, status) in [
+        // CIDv0 is rejected
+        (
+            "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb",
+            StatusCode::BAD_REQUEST,
+        ),
+        // CIDv1 with the raw codec is rejected
+        (
+            "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy",
+            StatusCode::BAD_REQUEST,
+        ),
+        // CIDv1 dag-pb is accepted but not published
+        (
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            StatusCode::NOT_FOUND,
+        ),
+    ] {
+        let url =
+            server_url.join(&format!("api/package?id=/ipfs/{}", cid))?;
+        let request = Request::get(url.as_str()).body(Body::empty())?;
+        let response = Client::new().request(request).await?;
+        assert_eq!(status, response.status());
+    }
+
+    Ok(())
+}
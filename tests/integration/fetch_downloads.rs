@@ -0,0 +1,84 @@
+use anyhow::Result;
+use hyper::Client;
+use k256::ecdsa::SigningKey;
+use serial_test::serial;
+use std::{path::PathBuf, time::Duration};
+use url::Url;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey};
+
+use crate::test_utils::*;
+
+/// Wait for the spawned download count to be written.
+async fn wait_for_downloads(
+    server_url: &Url,
+    id: &PackageKey,
+    expected: i64,
+) -> Result<i64> {
+    let mut downloads = 0;
+    for _ in 0..50 {
+        let record =
+            RegistryClient::exact_version(server_url.clone(), id.clone())
+                .await?;
+        downloads = record.downloads;
+        if downloads == expected {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    Ok(downloads)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_downloads() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let id: PackageKey = "mock-namespace/mock-package/1.0.0".parse()?;
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        file,
+    )
+    .await?;
+
+    let record =
+        RegistryClient::exact_version(server_url.clone(), id.clone()).await?;
+    assert_eq!(0, record.downloads);
+
+    let mut url = server_url.join("api/package")?;
+    url.query_pairs_mut().append_pair("id", &id.to_string());
+
+    for expected in 1..=2 {
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert!(response.status().is_success());
+        assert_eq!(
+            expected,
+            wait_for_downloads(&server_url, &id, expected).await?
+        );
+    }
+
+    // Versions in a bundle are counted as downloads
+    let bundle =
+        server_url.join("api/package/mock-namespace/mock-package/bundle")?;
+    let response = Client::new().get(bundle.as_str().parse()?).await?;
+    assert!(response.status().is_success());
+    hyper::body::to_bytes(response.into_body()).await?;
+    assert_eq!(3, wait_for_downloads(&server_url, &id, 3).await?);
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use hyper::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    Client,
+};
+use k256::ecdsa::SigningKey;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, RegistryKind};
+use ipfs_registry_server::config::RegistryConfig;
+
+use crate::test_utils::*;
+
+/// Publish a package, verify the recorded kind and return the
+/// content type and content disposition for the fetch response.
+async fn publish_fetch(
+    kind: RegistryKind,
+    file: &str,
+    id: &str,
+) -> Result<(String, String)> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        kind,
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from(file),
+    )
+    .await?;
+    assert_eq!(kind, receipt.artifact.kind);
+
+    // Kind is persisted on the version record
+    let record =
+        RegistryClient::exact_version(server_url.clone(), id.parse()?)
+            .await?;
+    assert_eq!(Some(kind), record.kind);
+
+    let mut url = server_url.join("api/package")?;
+    url.query_pairs_mut().append_pair("id", id);
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert!(response.status().is_success());
+
+    let headers = response.headers();
+    Ok((
+        headers.get(CONTENT_TYPE).unwrap().to_str()?.to_owned(),
+        headers
+            .get(CONTENT_DISPOSITION)
+            .unwrap()
+            .to_str()?
+            .to_owned(),
+    ))
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_kind_npm() -> Result<()> {
+    let (content_type, disposition) = publish_fetch(
+        RegistryKind::Npm,
+        "fixtures/mock-package-1.0.0.tgz",
+        "mock-namespace/mock-package/1.0.0",
+    )
+    .await?;
+    assert_eq!("application/gzip", content_type);
+    assert_eq!(
+        r#"attachment; filename="mock-package-1.0.0.tgz""#,
+        disposition
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_kind_cargo() -> Result<()> {
+    let (content_type, disposition) = publish_fetch(
+        RegistryKind::Cargo,
+        "fixtures/mock-crate-1.0.0.crate",
+        "mock-namespace/mock-crate/1.0.0",
+    )
+    .await?;
+    assert_eq!("application/gzip", content_type);
+    assert_eq!(
+        r#"attachment; filename="mock-crate-1.0.0.crate""#,
+        disposition
+    );
+    Ok(())
+}
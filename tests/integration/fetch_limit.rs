@@ -0,0 +1,97 @@
+use anyhow::Result;
+use hyper::{header::RETRY_AFTER, Body, Client, Request, StatusCode};
+use k256::ecdsa::SigningKey;
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_server::config::{FetchLimitConfig, RegistryConfig};
+
+use crate::test_utils::*;
+
+/// Fetch a package archive without retrying.
+async fn fetch_status(namespace: &Namespace) -> Result<(StatusCode, bool)> {
+    let key = PackageKey::Pointer(
+        namespace.clone(),
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    );
+    let mut url = server().join("api/package")?;
+    url.query_pairs_mut().append_pair("id", &key.to_string());
+    let request = Request::get(url.as_str()).body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    let retry_after = response.headers().contains_key(RETRY_AFTER);
+    Ok((response.status(), retry_after))
+}
+
+/// Fetch a package bundle without retrying.
+async fn bundle_status(namespace: &Namespace) -> Result<StatusCode> {
+    let url = server()
+        .join(&format!("api/package/{}/mock-package/bundle", namespace))?;
+    let request = Request::get(url.as_str()).body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    Ok(response.status())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_limit() -> Result<()> {
+    let registry_config = RegistryConfig {
+        fetch_limit: Some(FetchLimitConfig {
+            requests: 3,
+            window: 60,
+        }),
+        ..Default::default()
+    };
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(registry_config))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+    let mime: mime::Mime = "application/gzip".parse()?;
+
+    // Publish the same package to two namespaces so they
+    // are tracked as different packages
+    let hot = Namespace::new_unchecked("mock-hot");
+    let cold = Namespace::new_unchecked("mock-cold");
+    for namespace in [&hot, &cold] {
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        prepare_mock_namespace(&server_url, &signing_key, namespace).await?;
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key,
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+        )
+        .await?;
+    }
+
+    // Hammer one package until it is throttled
+    for _ in 0..3 {
+        let (status, _) = fetch_status(&hot).await?;
+        assert_eq!(StatusCode::OK, status);
+    }
+    let (status, retry_after) = fetch_status(&hot).await?;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, status);
+    assert!(retry_after);
+
+    // Bundles of a throttled package are also throttled
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, bundle_status(&hot).await?);
+
+    // Other package is not throttled
+    let (status, _) = fetch_status(&cold).await?;
+    assert_eq!(StatusCode::OK, status);
+
+    // A bundle counts towards the limit for the package
+    assert_eq!(StatusCode::OK, bundle_status(&cold).await?);
+    let (status, _) = fetch_status(&cold).await?;
+    assert_eq!(StatusCode::OK, status);
+    let (status, _) = fetch_status(&cold).await?;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, status);
+
+    Ok(())
+}
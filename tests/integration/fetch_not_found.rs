@@ -5,7 +5,7 @@ use semver::Version;
 use tempfile::NamedTempFile;
 
 use ipfs_registry_client::fetch;
-use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_core::{AnyRef, Namespace, PackageKey, PackageName};
 
 use crate::test_utils::*;
 
@@ -30,7 +30,15 @@ async fn integration_fetch_not_found() -> Result<()> {
         Version::new(1, 0, 0),
     );
 
-    let result = fetch(server_url, key, output.clone()).await;
+    let result = fetch(
+        server_url,
+        AnyRef::Key(key),
+        output.clone(),
+        false,
+        None,
+        None,
+    )
+    .await;
 
     assert!(result.is_err());
 
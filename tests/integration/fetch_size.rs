@@ -0,0 +1,66 @@
+use anyhow::Result;
+use hyper::{header::CONTENT_LENGTH, Body, Client, Method, Request};
+use k256::ecdsa::SigningKey;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_fetch_size() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let size = std::fs::metadata(&file)?.len();
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let id = "mock-namespace/mock-package/1.0.0";
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        file,
+    )
+    .await?;
+
+    // Size is persisted on the version record
+    let record =
+        RegistryClient::exact_version(server_url.clone(), id.parse()?)
+            .await?;
+    assert_eq!(Some(size), record.size);
+
+    let mut url = server_url.join("api/package")?;
+    url.query_pairs_mut().append_pair("id", id);
+
+    for method in [Method::GET, Method::HEAD] {
+        let request = Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .body(Body::empty())?;
+        let response = Client::new().request(request).await?;
+        assert!(response.status().is_success());
+
+        let length: u64 = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .unwrap()
+            .to_str()?
+            .parse()?;
+        assert_eq!(size, length);
+    }
+
+    Ok(())
+}
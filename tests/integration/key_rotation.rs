@@ -0,0 +1,223 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use k256::ecdsa::{recoverable, signature::Signer};
+use serial_test::serial;
+use sqlx::SqlitePool;
+use std::{collections::HashSet, path::PathBuf};
+use tempfile::NamedTempFile;
+use time::OffsetDateTime;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{
+    expiring_message, Namespace, PackageKey, X_SIGNATURE, X_SIGNATURE_EXPIRES,
+};
+use ipfs_registry_database::{MaintenanceModel, PublisherModel, RotateKey};
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_key_rotation() -> Result<()> {
+    let (admin_key, admin_address) = new_signing_key();
+    let (old_key, old_address) = new_signing_key();
+    let (new_key, new_address) = new_signing_key();
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        admins: Some(HashSet::from([admin_address])),
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &old_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), admin_key.clone()).await?;
+
+    let old_receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        old_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Only administrators may rotate keys
+    let result = RegistryClient::rotate_key(
+        server_url.clone(),
+        old_key.clone(),
+        RotateKey {
+            from: old_address,
+            to: new_address,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let record = RegistryClient::rotate_key(
+        server_url.clone(),
+        admin_key.clone(),
+        RotateKey {
+            from: old_address,
+            to: new_address,
+        },
+    )
+    .await?;
+    assert_eq!(new_address, record.address);
+
+    // Previous key is no longer a publisher
+    let result = RegistryClient::rotate_key(
+        server_url.clone(),
+        admin_key.clone(),
+        RotateKey {
+            from: old_address,
+            to: new_address,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    // Keys of other publishers cannot be rotated to
+    let result = RegistryClient::rotate_key(
+        server_url.clone(),
+        admin_key.clone(),
+        RotateKey {
+            from: new_address,
+            to: admin_address,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(409))));
+
+    // The new key publishes to the namespace
+    let new_receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        new_key,
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await?;
+
+    // Versions signed before and after the rotation are verified
+    for receipt in [&old_receipt, &new_receipt] {
+        let tmp = NamedTempFile::new()?;
+        let output = tmp.path().to_path_buf();
+        std::fs::remove_file(&output)?;
+        let key = PackageKey::Pointer(
+            receipt.artifact.namespace.clone(),
+            receipt.artifact.package.name.clone(),
+            receipt.artifact.package.version.clone(),
+        );
+        RegistryClient::fetch_file(server_url.clone(), key, output).await?;
+    }
+
+    // Only the version signed by the rotated key is listed
+    let records = RegistryClient::rotated_signatures(
+        server_url.clone(),
+        admin_key.clone(),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(1, records.count);
+    let record = &records.records[0];
+    assert_eq!(old_receipt.artifact.package.version, record.version);
+    assert_eq!(new_address, record.publisher);
+    assert_eq!(old_address, record.address);
+
+    let result = RegistryClient::rotated_signatures(
+        server_url.clone(),
+        old_key,
+        Default::default(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Signatures without the endpoint name, such as for the audit
+    // log, or without an expiry are rejected
+    let expires = OffsetDateTime::now_utc().unix_timestamp() + 60;
+    let url = server_url.join("api/admin/signatures/rotated")?;
+    for (message, expires) in [
+        (expiring_message(b"audit:", expires), Some(expires)),
+        (expiring_message(b"", expires), Some(expires)),
+        (b"rotated-signatures:".to_vec(), None),
+    ] {
+        let signature: recoverable::Signature = admin_key.sign(&message);
+        let mut request = Request::get(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature));
+        if let Some(expires) = expires {
+            request =
+                request.header(X_SIGNATURE_EXPIRES, expires.to_string());
+        }
+        let response =
+            Client::new().request(request.body(Body::empty())?).await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_key_rotation_signing_keys() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, admin_address) = new_signing_key();
+    let (_, first_address) = new_signing_key();
+    let (_, second_address) = new_signing_key();
+    let (_, third_address) = new_signing_key();
+    let (_, other_address) = new_signing_key();
+
+    PublisherModel::insert(&pool, &admin_address).await?;
+    let publisher_id = PublisherModel::insert(&pool, &first_address).await?;
+    PublisherModel::insert(&pool, &other_address).await?;
+
+    for (from, to) in [
+        (first_address, second_address),
+        (second_address, third_address),
+    ] {
+        MaintenanceModel::rotate_key(
+            &pool,
+            &admin_address,
+            &RotateKey { from, to },
+        )
+        .await?;
+    }
+
+    // Current and rotated keys belong to the publisher
+    for address in [first_address, second_address, third_address] {
+        assert!(
+            PublisherModel::is_signing_key(&pool, publisher_id, &address)
+                .await?
+        );
+    }
+
+    // Keys of other publishers do not
+    assert!(
+        !PublisherModel::is_signing_key(&pool, publisher_id, &other_address)
+            .await?
+    );
+
+    // Rotated keys cannot be reused
+    let result = MaintenanceModel::rotate_key(
+        &pool,
+        &admin_address,
+        &RotateKey {
+            from: other_address,
+            to: first_address,
+        },
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_database::Error::PublisherKeyExists(_))
+    ));
+
+    Ok(())
+}
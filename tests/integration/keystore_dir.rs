@@ -0,0 +1,64 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::find_keystore;
+use web3_address::ethereum::Address;
+
+/// Write a keystore file in the same way as keygen.
+fn write_keystore(dir: &Path) -> Result<Address> {
+    let (signing_key, address) = new_signing_key();
+    let keystore = web3_keystore::encrypt(
+        &mut rand::thread_rng(),
+        signing_key.to_bytes(),
+        "mock-password",
+        Some(address.to_string()),
+    )?;
+    let buffer = serde_json::to_vec_pretty(&keystore)?;
+    std::fs::write(dir.join(format!("{}.json", address)), buffer)?;
+    Ok(address)
+}
+
+#[test]
+fn integration_keystore_dir_one() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let address = write_keystore(dir.path())?;
+
+    // Non-keystore files are ignored
+    std::fs::write(dir.path().join("notes.txt"), "mock")?;
+
+    let file = find_keystore(dir.path().to_path_buf(), None)?;
+    assert_eq!(dir.path().join(format!("{}.json", address)), file);
+
+    // File paths are returned unchanged
+    assert_eq!(file.clone(), find_keystore(file, None)?);
+
+    Ok(())
+}
+
+#[test]
+fn integration_keystore_dir_many() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let first = write_keystore(dir.path())?;
+    let second = write_keystore(dir.path())?;
+
+    let file = find_keystore(dir.path().to_path_buf(), Some(&first))?;
+    assert_eq!(dir.path().join(format!("{}.json", first)), file);
+
+    let file = find_keystore(dir.path().to_path_buf(), Some(&second))?;
+    assert_eq!(dir.path().join(format!("{}.json", second)), file);
+
+    // Unknown address is an error
+    let (_, unknown) = new_signing_key();
+    assert!(find_keystore(dir.path().to_path_buf(), Some(&unknown)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn integration_keystore_dir_empty() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    assert!(find_keystore(dir.path().to_path_buf(), None).is_err());
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anyhow::Result;
+use k256::ecdsa::SigningKey;
+use semver::{Version, VersionReq};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::{Error as ClientError, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName};
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_latest_range() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in ["mock-package-1.0.0.tgz", "mock-package-1.1.0.tgz"] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from("fixtures").join(file),
+        )
+        .await?;
+    }
+
+    // Overall latest
+    let record = RegistryClient::latest_version(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        None,
+    )
+    .await?;
+    assert_eq!(Version::new(1, 1, 0), record.version);
+
+    // Highest version satisfying the range
+    let record = RegistryClient::latest_version(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        Some(VersionReq::parse("^1")?),
+    )
+    .await?;
+    assert_eq!(Version::new(1, 1, 0), record.version);
+
+    let record = RegistryClient::latest_version(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        Some(VersionReq::parse("~1.0")?),
+    )
+    .await?;
+    assert_eq!(Version::new(1, 0, 0), record.version);
+
+    // No version satisfies the range
+    let result = RegistryClient::latest_version(
+        server_url,
+        namespace,
+        package,
+        Some(VersionReq::parse("^2")?),
+    )
+    .await;
+    assert!(matches!(result, Err(ClientError::ResponseCode(404))));
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use serial_test::serial;
+use sqlx::SqlitePool;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+    time::Duration,
+};
+
+use crate::test_utils::*;
+
+use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_database::{
+    NamespaceModel, PackageModel, PublisherModel, VersionIncludes,
+};
+
+static QUERIES: AtomicUsize = AtomicUsize::new(0);
+static LOGGER: Once = Once::new();
+
+/// Counts the statements executed by the SQL library.
+struct QueryCounter;
+
+impl log::Log for QueryCounter {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "sqlx::query"
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            QUERIES.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Number of statements executed once pending statements are counted.
+///
+/// Statements are logged by the connection worker when they
+/// complete which may be after the query has returned.
+async fn query_count() -> usize {
+    LOGGER.call_once(|| {
+        log::set_logger(&QueryCounter).expect("failed to set logger");
+        log::set_max_level(log::LevelFilter::Info);
+    });
+    let mut count = QUERIES.load(Ordering::SeqCst);
+    loop {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let current = QUERIES.load(Ordering::SeqCst);
+        if current == count {
+            return count;
+        }
+        count = current;
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_list_latest() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+
+    let mut yanked = Vec::new();
+    for (name, version, yank) in [
+        ("alpha", "1.0.0", false),
+        ("alpha", "1.1.0", false),
+        ("alpha", "2.0.0-beta.1", false),
+        ("beta", "1.0.0", false),
+        ("beta", "1.2.0", true),
+        ("gamma", "1.0.0", true),
+    ] {
+        let mut pointer = mock_pointer(Some(version.parse()?))?;
+        pointer.definition.artifact.package.name =
+            PackageName::new_unchecked(name);
+        let version_id = PackageModel::insert(
+            &pool,
+            &publisher_record,
+            &namespace_record,
+            &address,
+            &pointer,
+            &[],
+        )
+        .await?;
+        if yank {
+            yanked.push(version_id);
+        }
+    }
+    for version_id in yanked {
+        sqlx::query(
+            "UPDATE versions SET yanked = 'mock' WHERE version_id = ?",
+        )
+        .bind(version_id)
+        .execute(&pool)
+        .await?;
+    }
+
+    for include_yanked in [true, false] {
+        // Expected output finds the latest version for each package
+        let mut expected = PackageModel::list_packages(
+            &pool,
+            &namespace,
+            &Default::default(),
+            VersionIncludes::None,
+            include_yanked,
+        )
+        .await?;
+        for package in expected.records.iter_mut() {
+            if let Some(latest) = PackageModel::find_latest(
+                &pool,
+                package,
+                false,
+                include_yanked,
+            )
+            .await?
+            {
+                package.versions.count = latest.count;
+                package.versions.records = vec![latest];
+            }
+        }
+
+        let before = query_count().await;
+        let packages = PackageModel::list_packages(
+            &pool,
+            &namespace,
+            &Default::default(),
+            VersionIncludes::Latest,
+            include_yanked,
+        )
+        .await?;
+
+        // Namespace lookup and a single query for the packages
+        assert_eq!(2, query_count().await - before);
+
+        assert_eq!(3, packages.count);
+        assert_eq!(expected.count, packages.count);
+        assert_eq!(
+            serde_json::to_value(&expected.records)?,
+            serde_json::to_value(&packages.records)?
+        );
+
+        let latest: Vec<(String, Option<String>)> = packages
+            .records
+            .iter()
+            .map(|p| {
+                (
+                    p.name.to_string(),
+                    p.versions.records.first().map(|v| v.version.to_string()),
+                )
+            })
+            .collect();
+        let (beta, gamma) = if include_yanked {
+            ("1.2.0", Some(String::from("1.0.0")))
+        } else {
+            ("1.0.0", None)
+        };
+        assert_eq!(
+            vec![
+                (String::from("alpha"), Some(String::from("1.1.0"))),
+                (String::from("beta"), Some(String::from(beta))),
+                (String::from("gamma"), gamma),
+            ],
+            latest
+        );
+    }
+
+    Ok(())
+}
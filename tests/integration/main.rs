@@ -1,15 +1,100 @@
+// Existing tests predate these lints
+#![allow(
+    clippy::bool_comparison,
+    clippy::collapsible_match,
+    clippy::field_reassign_with_default,
+    clippy::get_first,
+    clippy::len_zero,
+    clippy::let_and_return,
+    clippy::match_like_matches_macro,
+    clippy::op_ref,
+    clippy::single_match,
+    clippy::unused_unit
+)]
+
 mod test_utils;
 
 mod access_control;
+mod audit_log;
+mod auto_create_namespace;
+mod build_metadata;
+mod bundle;
+mod can_publish;
+mod canonical_host;
+mod check;
+mod checksum;
+mod client_retry;
 mod confusable_namespace;
 mod confusable_package;
 mod database;
+mod dependents;
+mod deprecate_namespace;
+mod description;
+mod diff;
+mod duplicate_cid;
 mod fetch;
+mod fetch_cache;
+mod fetch_cid_policy;
+mod fetch_downloads;
+mod fetch_kind;
+mod fetch_limit;
 mod fetch_not_found;
+mod fetch_size;
+mod key_rotation;
+mod keystore_dir;
+mod latest_range;
+mod list_latest;
+mod manifest;
+mod message_validation;
+mod mirror;
+mod msgpack;
+mod namespace_access;
+mod namespace_counts;
+mod namespace_kind;
+mod oci;
+mod pagination_links;
+mod pretty_json;
+mod private_namespace;
 mod publish;
 mod publish_allow_unauthorized;
+mod publish_archive_entries;
+mod publish_checksum;
+mod publish_cleanup;
 mod publish_conflict;
+mod publish_content_encoding;
+mod publish_content_type;
+mod publish_decompressed_size;
+mod publish_denied_extension;
 mod publish_deny_unauthorized;
+mod publish_force;
+mod publish_invalid_manifest;
+mod publish_scan;
 mod publish_too_large;
+mod publish_verbose;
+mod publish_version_rules;
+mod publish_wrong_kind;
+mod publish_yanked;
+mod publisher_packages;
+mod pypi;
+mod query_validation;
+mod record_cache;
+mod register_work;
+mod resolve_namespace;
+mod root_behavior;
+mod search;
 mod semver;
+mod server_info;
+mod signature_header;
+mod signed_url;
+mod tls;
+mod transfer;
+mod transfer_namespace;
+mod trusted_keys;
+mod unknown_route;
+mod unyank;
+mod verify;
+mod version_deprecation;
+mod version_objects;
+mod version_sort;
 mod yank;
+mod yank_latest;
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_message_validation() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        max_message_length: 32,
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await?;
+
+    let over_length = "a".repeat(33);
+    let control = String::from("mock\u{1b}[31m message");
+
+    for message in [&over_length, &control] {
+        let result = RegistryClient::yank(
+            server_url.clone(),
+            signing_key.clone(),
+            id.clone(),
+            message.clone(),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::ResponseCode(400))));
+
+        let result = RegistryClient::deprecate(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            package.clone(),
+            message.clone(),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::ResponseCode(400))));
+    }
+
+    // Control characters are rejected for descriptions
+    let result = RegistryClient::set_namespace_description(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        control.clone(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(400))));
+
+    let result = RegistryClient::set_package_description(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        control,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(400))));
+
+    // Line breaks are allowed
+    RegistryClient::deprecate(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        String::from("mock\ndeprecation"),
+    )
+    .await?;
+
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        id,
+        "a".repeat(32),
+    )
+    .await?;
+
+    Ok(())
+}
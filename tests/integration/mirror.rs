@@ -0,0 +1,113 @@
+use anyhow::Result;
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+
+#[tokio::test]
+#[serial]
+async fn integration_mirror() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (mirror_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let mirror = Namespace::new_unchecked("mock-mirror");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    prepare_mock_namespace(&server_url, &mirror_key, &mirror).await?;
+
+    for file in ["mock-package-1.0.0.tgz", "mock-package-1.1.0.tgz"] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from("fixtures").join(file),
+        )
+        .await?;
+    }
+
+    let yanked = Version::new(1, 0, 0);
+    let live = Version::new(1, 1, 0);
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        PackageKey::Pointer(
+            namespace.clone(),
+            package.clone(),
+            yanked.clone(),
+        ),
+        String::from("mock yank message"),
+    )
+    .await?;
+    RegistryClient::deprecate(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        package.clone(),
+        String::from("mock deprecation message"),
+    )
+    .await?;
+
+    // Export the package with its yank and deprecation state
+    let tmp = NamedTempFile::new()?;
+    let output = tmp.path().to_path_buf();
+    std::fs::remove_file(&output)?;
+    let bundle = RegistryClient::fetch_bundle(
+        server_url.clone(),
+        namespace,
+        package.clone(),
+        None,
+        output,
+    )
+    .await?;
+
+    // Mirror the bundle into another namespace
+    let report = ipfs_registry_client::mirror_bundle(
+        server_url.clone(),
+        mirror_key,
+        mirror.clone(),
+        bundle,
+    )
+    .await?;
+    assert_eq!(vec![yanked.clone(), live.clone()], report.published);
+    assert_eq!(vec![yanked.clone()], report.yanked);
+    assert!(report.skipped.is_empty());
+
+    // Mirror reproduces the yank and deprecation state
+    let doc = RegistryClient::exact_version(
+        server_url.clone(),
+        PackageKey::Pointer(mirror.clone(), package.clone(), yanked),
+    )
+    .await?;
+    assert_eq!(Some(String::from("mock yank message")), doc.yanked);
+
+    let doc = RegistryClient::exact_version(
+        server_url.clone(),
+        PackageKey::Pointer(mirror.clone(), package.clone(), live),
+    )
+    .await?;
+    assert!(doc.yanked.is_none());
+
+    let record =
+        RegistryClient::get_package(server_url, mirror, package).await?;
+    assert_eq!(
+        Some(String::from("mock deprecation message")),
+        record.deprecated
+    );
+
+    Ok(())
+}
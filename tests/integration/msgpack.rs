@@ -0,0 +1,105 @@
+use anyhow::Result;
+use hyper::{header::ACCEPT, Body, Client, Request};
+use k256::ecdsa::SigningKey;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageName, MSGPACK_MIME};
+use ipfs_registry_server::config::RegistryConfig;
+
+use crate::test_utils::*;
+
+/// Get the content type for a latest version request
+/// that accepts MessagePack.
+async fn latest_content_type(
+    namespace: &Namespace,
+    package: &PackageName,
+) -> Result<String> {
+    let url = server()
+        .join(&format!("api/package/{}/{}/latest", namespace, package))?;
+    let request = Request::get(url.as_str())
+        .header(ACCEPT, MSGPACK_MIME)
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert!(response.status().is_success());
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .map(|value| value.to_str().unwrap().to_owned())
+        .unwrap_or_default();
+    Ok(content_type)
+}
+
+async fn publish_mock_package(namespace: &Namespace) -> Result<SigningKey> {
+    let server_url = server();
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    prepare_mock_namespace(&server_url, &signing_key, namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url,
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await?;
+
+    Ok(signing_key)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_msgpack() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    publish_mock_package(&namespace).await?;
+
+    let content_type = latest_content_type(&namespace, &package).await?;
+    assert_eq!(MSGPACK_MIME, content_type);
+
+    // Client decodes the MessagePack response
+    let record =
+        RegistryClient::latest_version(server(), namespace, package, None)
+            .await?;
+    assert_eq!(1, record.version.major);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_msgpack_disabled() -> Result<()> {
+    let registry_config = RegistryConfig {
+        msgpack: false,
+        ..Default::default()
+    };
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(registry_config))?;
+    let _ = rx.await?;
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    publish_mock_package(&namespace).await?;
+
+    let content_type = latest_content_type(&namespace, &package).await?;
+    assert_eq!("application/json", content_type);
+
+    // Client falls back to JSON
+    let record =
+        RegistryClient::latest_version(server(), namespace, package, None)
+            .await?;
+    assert_eq!(1, record.version.major);
+
+    Ok(())
+}
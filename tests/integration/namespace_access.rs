@@ -0,0 +1,179 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use serial_test::serial;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{
+    expiring_message, Namespace, PackageName, X_SIGNATURE,
+    X_SIGNATURE_EXPIRES,
+};
+use ipfs_registry_database::UserAccess;
+
+use k256::ecdsa::{recoverable, signature::Signer};
+
+#[tokio::test]
+#[serial]
+async fn integration_namespace_access() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, address) = new_signing_key();
+    let (admin_key, admin_address) = new_signing_key();
+    let (user_key, user_address) = new_signing_key();
+    let (restricted_key, restricted_address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let mock_package = PackageName::new_unchecked("mock-package");
+    let other_package = PackageName::new_unchecked("denied-extension");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    for key in [&admin_key, &user_key, &restricted_key] {
+        RegistryClient::signup(server_url.clone(), key.clone()).await?;
+    }
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/denied-extension-1.0.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        admin_address,
+        true,
+        None,
+    )
+    .await?;
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        user_address,
+        false,
+        None,
+    )
+    .await?;
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        restricted_address,
+        false,
+        Some(mock_package.clone()),
+    )
+    .await?;
+
+    RegistryClient::access_control(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        other_package.clone(),
+        restricted_address,
+        true,
+    )
+    .await?;
+
+    let access = RegistryClient::list_access(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+    )
+    .await?;
+
+    assert_eq!(namespace, access.name);
+    assert_eq!(address, access.owner);
+    assert_eq!(
+        vec![
+            UserAccess {
+                address: admin_address,
+                administrator: true,
+                restrictions: vec![],
+            },
+            UserAccess {
+                address: user_address,
+                administrator: false,
+                restrictions: vec![],
+            },
+            UserAccess {
+                address: restricted_address,
+                administrator: false,
+                restrictions: vec![other_package, mock_package],
+            },
+        ],
+        access.users
+    );
+
+    let list_access = |message: Vec<u8>, expires: Option<i64>| {
+        let url = server_url
+            .join(&format!("api/namespace/{}/access", namespace))
+            .unwrap();
+        let signature: recoverable::Signature = signing_key.sign(&message);
+        let mut request = Request::get(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature));
+        if let Some(expires) = expires {
+            request =
+                request.header(X_SIGNATURE_EXPIRES, expires.to_string());
+        }
+        Client::new().request(request.body(Body::empty()).unwrap())
+    };
+
+    // Registration signature over the bare namespace is rejected
+    let response = list_access(namespace.as_bytes().to_vec(), None).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+    // Signature must expire within the maximum lifetime
+    let message = format!("access:{}", namespace);
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    for expires in [now - 1, now + 3600] {
+        let response = list_access(
+            expiring_message(message.as_bytes(), expires),
+            Some(expires),
+        )
+        .await?;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    }
+
+    // Expiry header must match the signed expiry
+    let response = list_access(
+        expiring_message(message.as_bytes(), now + 60),
+        Some(now + 120),
+    )
+    .await?;
+    assert!(!response.status().is_success());
+
+    // Only the owner may view the access list
+    let result =
+        RegistryClient::list_access(server_url.clone(), admin_key, namespace)
+            .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let result = RegistryClient::list_access(
+        server_url,
+        signing_key,
+        Namespace::new_unchecked("missing-namespace"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_namespace_counts() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(Some(0), record.package_count);
+    assert_eq!(Some(0), record.version_count);
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+        "fixtures/mock-readme-1.0.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(Some(2), record.package_count);
+    assert_eq!(Some(3), record.version_count);
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use k256::ecdsa::SigningKey;
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, RegistryKind};
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_namespace_kind() -> Result<()> {
+    // Spawn the server, default kind is npm
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let crates = Namespace::new_unchecked("mock-crates");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    let record = RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        crates.clone(),
+        Some(RegistryKind::Cargo),
+    )
+    .await?;
+    assert_eq!(Some(RegistryKind::Cargo), record.kind);
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(None, record.kind);
+
+    // Publish an npm package to the default namespace
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+    assert_eq!(RegistryKind::Npm, receipt.artifact.kind);
+
+    // Publish a crate to the cargo namespace
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        crates.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-crate-1.0.0.crate"),
+    )
+    .await?;
+    assert_eq!(RegistryKind::Cargo, receipt.artifact.kind);
+
+    let record = RegistryClient::exact_version(
+        server_url.clone(),
+        PackageKey::Pointer(
+            crates.clone(),
+            PackageName::new_unchecked("mock-crate"),
+            Version::new(1, 0, 0),
+        ),
+    )
+    .await?;
+    assert_eq!(Some(RegistryKind::Cargo), record.kind);
+
+    // An npm package cannot be read as a crate
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        crates.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(400))));
+
+    Ok(())
+}
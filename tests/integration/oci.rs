@@ -0,0 +1,184 @@
+use anyhow::Result;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use k256::ecdsa::SigningKey;
+use serde_json::Value;
+use serial_test::serial;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tempfile::NamedTempFile;
+use url::Url;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey, RegistryKind};
+use ipfs_registry_server::config::{
+    LayerConfig, RegistryConfig, ServerConfig, StorageConfig,
+};
+
+use crate::test_utils::*;
+
+const REPOSITORY: &str = "mock/artifacts";
+
+/// Blobs and manifests stored by the mock OCI registry.
+#[derive(Default)]
+struct MockRegistry {
+    blobs: HashMap<String, Vec<u8>>,
+    manifests: HashMap<String, Vec<u8>>,
+}
+
+async fn handle(
+    registry: Arc<Mutex<MockRegistry>>,
+    req: Request<Body>,
+) -> Response<Body> {
+    let prefix = format!("/v2/{}/", REPOSITORY);
+    let method = req.method().clone();
+    let path = req.uri().path().to_owned();
+    let query = req.uri().query().unwrap_or_default().to_owned();
+    let path = match path.strip_prefix(&prefix) {
+        Some(path) => path.to_owned(),
+        None => return status(StatusCode::NOT_FOUND),
+    };
+
+    match (method, path.as_str()) {
+        (Method::POST, "blobs/uploads/") => Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header(
+                "location",
+                format!("{}blobs/uploads/mock-upload", prefix),
+            )
+            .body(Body::empty())
+            .unwrap(),
+        (Method::PUT, "blobs/uploads/mock-upload") => {
+            let digest = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("digest="))
+                .map(|digest| digest.replace("%3A", ":"))
+                .unwrap_or_default();
+            let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            registry.lock().unwrap().blobs.insert(digest, body.to_vec());
+            status(StatusCode::CREATED)
+        }
+        (Method::PUT, path) if path.starts_with("manifests/") => {
+            let tag = path.trim_start_matches("manifests/").to_owned();
+            let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            registry
+                .lock()
+                .unwrap()
+                .manifests
+                .insert(tag, body.to_vec());
+            status(StatusCode::CREATED)
+        }
+        (Method::GET, path) if path.starts_with("blobs/") => {
+            let digest = path.trim_start_matches("blobs/");
+            match registry.lock().unwrap().blobs.get(digest) {
+                Some(blob) => Response::new(Body::from(blob.clone())),
+                None => status(StatusCode::NOT_FOUND),
+            }
+        }
+        _ => status(StatusCode::NOT_FOUND),
+    }
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(code)
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Spawn a mock OCI registry.
+fn spawn_registry(registry: Arc<Mutex<MockRegistry>>) -> Result<Url> {
+    let make_service = make_service_fn(move |_| {
+        let registry = Arc::clone(&registry);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let registry = Arc::clone(&registry);
+                async move { Ok::<_, Infallible>(handle(registry, req).await) }
+            }))
+        }
+    });
+
+    let addr: SocketAddr = ([127, 0, 0, 1], 0).into();
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    let url = Url::parse(&format!("http://{}", server.local_addr()))?;
+    tokio::spawn(server);
+    Ok(url)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_oci() -> Result<()> {
+    let registry = Arc::new(Mutex::new(MockRegistry::default()));
+    let registry_url = spawn_registry(Arc::clone(&registry))?;
+
+    let layer = LayerConfig::Oci {
+        registry: registry_url,
+        repository: REPOSITORY.to_owned(),
+        credentials: None,
+    };
+    let storage: StorageConfig = layer.into();
+    let mut config = ServerConfig::new(storage);
+    config.registry = RegistryConfig {
+        kind: RegistryKind::Oci,
+        validate_manifest: true,
+        ..Default::default()
+    };
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-oci-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    // Digest is returned as a content identifier
+    assert!(matches!(receipt.key, Some(PackageKey::Cid(_))));
+
+    // Manifest is tagged with the pointer id and references the blob
+    let manifest = {
+        let registry = registry.lock().unwrap();
+        let manifest = registry
+            .manifests
+            .get(&receipt.artifact.pointer_id())
+            .cloned()
+            .unwrap();
+        assert_eq!(2, registry.blobs.len());
+        manifest
+    };
+    let manifest: Value = serde_json::from_slice(&manifest)?;
+    let digest = manifest["layers"][0]["digest"].as_str().unwrap();
+    assert!(registry.lock().unwrap().blobs.contains_key(digest));
+
+    // Fetch uses the content identifier
+    let tmp = NamedTempFile::new()?;
+    let output = tmp.path().to_path_buf();
+    std::fs::remove_file(&output)?;
+
+    RegistryClient::fetch_file(server_url, receipt.id, output.clone())
+        .await?;
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&output)?);
+
+    Ok(())
+}
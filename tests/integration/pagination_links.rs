@@ -0,0 +1,134 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, header::LINK, Client, Response};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+use ipfs_registry_database::{ResultSet, VersionRecord};
+
+use k256::ecdsa::SigningKey;
+
+#[tokio::test]
+#[serial]
+async fn integration_pagination_links() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+        "fixtures/mock-package-1.2.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    // Middle page keeps other query parameters
+    let path = "/api/package/mock-namespace/mock-package/versions";
+    let url =
+        server_url.join(&format!("{}?sort=desc&offset=1&limit=1", path))?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert!(response.status().is_success());
+
+    let links = response
+        .headers()
+        .get(LINK)
+        .map(|value| value.to_str().unwrap().to_owned());
+    let expected = format!(
+        concat!(
+            r#"<{path}?sort=desc&offset=0&limit=1>; rel="first", "#,
+            r#"<{path}?sort=desc&offset=0&limit=1>; rel="prev", "#,
+            r#"<{path}?sort=desc&offset=2&limit=1>; rel="next", "#,
+            r#"<{path}?sort=desc&offset=2&limit=1>; rel="last""#,
+        ),
+        path = path
+    );
+    assert_eq!(Some(expected), links);
+
+    // Count is the total for the package
+    let path = "/api/package/mock-namespace/packages";
+    let url = server_url.join(&format!("{}?limit=1", path))?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    let links = response
+        .headers()
+        .get(LINK)
+        .map(|value| value.to_str().unwrap().to_owned());
+    let expected = format!(
+        concat!(
+            r#"<{path}?offset=0&limit=1>; rel="first", "#,
+            r#"<{path}?offset=0&limit=1>; rel="last""#,
+        ),
+        path = path
+    );
+    assert_eq!(Some(expected), links);
+
+    // Following the next link in cursor mode visits every version
+    let path = "/api/package/mock-namespace/mock-package/versions";
+    let url = server_url.join(&format!("{}?limit=1", path))?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    let page = read_versions(response).await?;
+    let mut versions: Vec<String> = page
+        .records
+        .iter()
+        .map(|record| record.version.to_string())
+        .collect();
+
+    let mut url = server_url.join(&format!(
+        "{}?limit=1&cursor={}",
+        path,
+        page.next_cursor.unwrap()
+    ))?;
+    loop {
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert!(response.status().is_success());
+        let links = response.headers().get(LINK).unwrap().to_str()?;
+        assert!(!links.contains(r#"rel="prev""#));
+        assert!(!links.contains(r#"rel="last""#));
+        let next = link_target(links, "next");
+        let page = read_versions(response).await?;
+        versions.extend(
+            page.records.iter().map(|record| record.version.to_string()),
+        );
+        match next {
+            Some(next) => url = server_url.join(&next)?,
+            None => break,
+        }
+    }
+    assert_eq!(vec!["1.2.0", "1.1.0", "1.0.0"], versions);
+
+    Ok(())
+}
+
+async fn read_versions(
+    response: Response<hyper::Body>,
+) -> Result<ResultSet<VersionRecord>> {
+    let body = to_bytes(response.into_body()).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+fn link_target(links: &str, rel: &str) -> Option<String> {
+    let suffix = format!(r#">; rel="{}""#, rel);
+    links.split(", ").find_map(|link| {
+        link.strip_suffix(&suffix)
+            .and_then(|link| link.strip_prefix('<'))
+            .map(|target| target.to_owned())
+    })
+}
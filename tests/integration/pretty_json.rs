@@ -0,0 +1,71 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, header::CONTENT_TYPE, Client};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+
+use k256::ecdsa::SigningKey;
+
+/// Get the content type and body for a request path.
+async fn get_json(path: &str) -> Result<(String, String)> {
+    let url = server().join(path)?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert!(response.status().is_success());
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_owned())
+        .unwrap_or_default();
+    let body = to_bytes(response.into_body()).await?;
+    Ok((content_type, String::from_utf8(body.to_vec())?))
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_pretty_json() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    for path in [
+        "api/package/mock-namespace/mock-package/latest",
+        "api/package/mock-namespace/mock-package/versions",
+    ] {
+        // Minified by default
+        let (content_type, compact) = get_json(path).await?;
+        assert_eq!("application/json", content_type);
+        assert!(!compact.contains('\n'));
+
+        let (content_type, pretty) =
+            get_json(&format!("{}?pretty=true", path)).await?;
+        assert_eq!("application/json", content_type);
+        assert!(pretty.contains('\n'));
+
+        let compact: serde_json::Value = serde_json::from_str(&compact)?;
+        let pretty: serde_json::Value = serde_json::from_str(&pretty)?;
+        assert_eq!(compact, pretty);
+    }
+
+    Ok(())
+}
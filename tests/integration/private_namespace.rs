@@ -0,0 +1,255 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{
+    download_message, Namespace, PackageKey, PackageName, X_SIGNATURE,
+    X_SIGNATURE_EXPIRES,
+};
+
+/// Download a package with a signature over the key and the
+/// signed expiry, sending the given expiry header.
+async fn fetch_status(
+    signing_key: &SigningKey,
+    key: &PackageKey,
+    signed_expires: i64,
+    expires: Option<i64>,
+) -> Result<StatusCode> {
+    let message =
+        download_message(key.to_string().as_bytes(), signed_expires);
+    fetch_signed(signing_key.sign(&message), key, expires).await
+}
+
+/// Download a package with a signature sending the given
+/// expiry header.
+async fn fetch_signed(
+    signature: recoverable::Signature,
+    key: &PackageKey,
+    expires: Option<i64>,
+) -> Result<StatusCode> {
+    let mut url = server().join("api/package")?;
+    url.query_pairs_mut().append_pair("id", &key.to_string());
+    let mut request = Request::get(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature));
+    if let Some(expires) = expires {
+        request = request.header(X_SIGNATURE_EXPIRES, expires.to_string());
+    }
+    let response =
+        Client::new().request(request.body(Body::empty())?).await?;
+    Ok(response.status())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_private_namespace() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (member_key, member_address) = new_signing_key();
+    let (outsider_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let version = Version::new(1, 0, 0);
+    let key = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        version.clone(),
+    );
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    for key in [&member_key, &outsider_key] {
+        RegistryClient::signup(server_url.clone(), key.clone()).await?;
+    }
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        member_address,
+        false,
+        None,
+    )
+    .await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Only administrators may change the visibility
+    let result = RegistryClient::set_private(
+        server_url.clone(),
+        member_key.clone(),
+        namespace.clone(),
+        true,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let record = RegistryClient::set_private(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        true,
+    )
+    .await?;
+    assert!(record.private);
+
+    let dir = tempfile::tempdir()?;
+    let output = |name: &str| dir.path().join(name);
+
+    // Unsigned downloads are denied
+    let result = RegistryClient::fetch_file(
+        server_url.clone(),
+        key.clone(),
+        output("unsigned.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let result = RegistryClient::fetch_bundle(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+        None,
+        output("unsigned.tar"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Downloads signed by a non-member are denied
+    let result = RegistryClient::fetch_file_signed(
+        server_url.clone(),
+        outsider_key.clone(),
+        key.clone(),
+        output("outsider.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let result = RegistryClient::fetch_file_by_path_signed(
+        server_url.clone(),
+        outsider_key,
+        namespace.clone(),
+        package.clone(),
+        version.clone(),
+        output("outsider-path.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Downloads signed by the owner or a member are allowed
+    RegistryClient::fetch_file_signed(
+        server_url.clone(),
+        signing_key.clone(),
+        key.clone(),
+        output("owner.tgz"),
+    )
+    .await?;
+
+    RegistryClient::fetch_file_by_path_signed(
+        server_url.clone(),
+        member_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        version,
+        output("member-path.tgz"),
+    )
+    .await?;
+
+    RegistryClient::fetch_bundle_signed(
+        server_url.clone(),
+        member_key.clone(),
+        namespace.clone(),
+        package,
+        None,
+        output("member.tar"),
+    )
+    .await?;
+
+    // Signatures are bound to an expiry that must be sent,
+    // must not have passed and must be within the lifetime
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    assert_eq!(
+        StatusCode::OK,
+        fetch_status(&member_key, &key, now + 60, Some(now + 60)).await?
+    );
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        fetch_status(&member_key, &key, now + 60, None).await?
+    );
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        fetch_status(&member_key, &key, now - 1, Some(now - 1)).await?
+    );
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        fetch_status(&member_key, &key, now + 3600, Some(now + 3600)).await?
+    );
+
+    // Expiry header does not match the signed expiry
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        fetch_status(&member_key, &key, now + 60, Some(now + 120)).await?
+    );
+
+    // Signature without the download tag has the same bytes
+    // as the message for a package deprecation
+    let message = format!("{}\n{}", key, now + 60);
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        fetch_signed(
+            member_key.sign(message.as_bytes()),
+            &key,
+            Some(now + 60)
+        )
+        .await?
+    );
+
+    // Signature over the bare namespace replayed to make
+    // the namespace public
+    let url =
+        server_url.join(&format!("api/namespace/{}/private", namespace))?;
+    let signature: recoverable::Signature =
+        signing_key.sign(namespace.as_bytes());
+    let request = Request::delete(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert!(!response.status().is_success());
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert!(record.private);
+
+    // Public namespaces do not require a signature
+    let record = RegistryClient::set_private(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        false,
+    )
+    .await?;
+    assert!(!record.private);
+
+    RegistryClient::fetch_file(server_url, key, output("public.tgz")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::Namespace;
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_archive_entries() -> Result<()> {
+    let file = PathBuf::from("fixtures/entry-bomb-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    let registry_config = RegistryConfig {
+        max_archive_entries: Some(1024),
+        ..Default::default()
+    };
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(registry_config))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(413))));
+
+    // Package within the limit is accepted
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let receipt = RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        file,
+    )
+    .await?;
+    assert_eq!(1, receipt.artifact.package.version.major);
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use semver::Version;
+use serial_test::serial;
+use sha3::{Digest, Sha3_256};
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{
+    Namespace, PackageKey, PackageName, SignatureScheme, X_CHECKSUM,
+    X_SIGNATURE,
+};
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_checksum() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let receipt = RegistryClient::publish_file_checksum(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    assert_eq!(
+        PackageName::new_unchecked("mock-package"),
+        receipt.artifact.package.name
+    );
+    assert_eq!(Version::new(1, 0, 0), receipt.artifact.package.version);
+
+    let expected = Sha3_256::digest(std::fs::read(&file)?);
+    assert_eq!(expected.as_slice(), receipt.checksum.as_slice());
+
+    // Package signed over the checksum can be fetched
+    let tmp = NamedTempFile::new()?;
+    let output = tmp.path().to_path_buf();
+    std::fs::remove_file(&output)?;
+
+    let key = PackageKey::Pointer(
+        receipt.artifact.namespace.clone(),
+        receipt.artifact.package.name.clone(),
+        receipt.artifact.package.version.clone(),
+    );
+    RegistryClient::fetch_file(
+        server_url.clone(),
+        key.clone(),
+        output.clone(),
+    )
+    .await?;
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&output)?);
+
+    // Version records the scheme used for the signature
+    let record =
+        RegistryClient::exact_version(server_url.clone(), key.clone())
+            .await?;
+    assert_eq!(SignatureScheme::Secp256k1Checksum, record.signature_scheme);
+
+    // Signature verifies using the recorded scheme
+    let report = ipfs_registry_client::verify(server_url, key).await?;
+    assert!(report.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_checksum_mismatch() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let body = std::fs::read("fixtures/mock-package-1.0.0.tgz")?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Sign a checksum that does not match the body
+    let checksum = Sha3_256::digest(b"mock-checksum-mismatch");
+    let signature: recoverable::Signature =
+        signing_key.sign(checksum.as_slice());
+
+    let url = server_url.join(&format!("api/package/{}", namespace))?;
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .header(X_CHECKSUM, hex::encode(checksum))
+        .header("content-type", "application/gzip")
+        .body(Body::from(body))?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    Ok(())
+}
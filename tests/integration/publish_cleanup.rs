@@ -0,0 +1,90 @@
+use anyhow::Result;
+use serial_test::serial;
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::Namespace;
+use ipfs_registry_database::PackageModel;
+use ipfs_registry_server::config::{LayerConfig, ServerConfig};
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_cleanup() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let storage = dir.path().join("storage");
+    std::fs::create_dir(&storage)?;
+
+    // File database so the test can inject a failure
+    let url = format!(
+        "sqlite://{}?mode=rwc",
+        dir.path().join("registry.db").display()
+    );
+    let pool = SqlitePool::connect(&url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    // Spawn the server
+    let mut config = ServerConfig::new(
+        LayerConfig::File {
+            directory: storage.clone(),
+        }
+        .into(),
+    );
+    config.database.url = url;
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Recording the version fails after the artifact is stored
+    sqlx::query(
+        r#"
+            CREATE TRIGGER versions_insert_failure BEFORE INSERT ON versions
+            BEGIN
+                SELECT RAISE(ABORT, 'injected failure');
+            END
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(500))));
+
+    // Stored artifact was removed
+    assert_eq!(0, std::fs::read_dir(&storage)?.count());
+
+    // Package was not recorded without a version
+    assert!(PackageModel::find_empty_packages(&pool).await?.is_empty());
+
+    // Publish succeeds once the failure is resolved
+    sqlx::query("DROP TRIGGER versions_insert_failure")
+        .execute(&pool)
+        .await?;
+    RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+    assert_eq!(1, std::fs::read_dir(&storage)?.count());
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use flate2::{write::GzEncoder, Compression};
+use hyper::{header::CONTENT_ENCODING, Body, Client, Request, StatusCode};
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use semver::Version;
+use serial_test::serial;
+use sha3::{Digest, Sha3_256};
+use std::io::Write;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{
+    Namespace, PackageKey, PackageName, Receipt, X_SIGNATURE,
+};
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_content_encoding() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = std::fs::read("fixtures/mock-package-1.0.0.tgz")?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Signature is over the decoded payload
+    let signature: recoverable::Signature = signing_key.sign(&file);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&file)?;
+    let body = encoder.finish()?;
+
+    let url = server_url.join(&format!("api/package/{}", namespace))?;
+
+    // Unsupported encodings are rejected
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .header(CONTENT_ENCODING, "br")
+        .header("content-type", "application/gzip")
+        .body(Body::from(body.clone()))?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::UNSUPPORTED_MEDIA_TYPE, response.status());
+
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .header(CONTENT_ENCODING, "gzip")
+        .header("content-type", "application/gzip")
+        .body(Body::from(body))?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let receipt: Receipt = serde_json::from_slice(&body)?;
+    let expected = Sha3_256::digest(&file);
+    assert_eq!(expected.as_slice(), receipt.checksum.as_slice());
+
+    // Stored artifact is the decoded payload
+    let key = PackageKey::Pointer(namespace, package, Version::new(1, 0, 0));
+    let dir = tempfile::tempdir()?;
+    let output = dir.path().join("package.tgz");
+    RegistryClient::fetch_file(server_url, key, output.clone()).await?;
+    assert_eq!(file, std::fs::read(&output)?);
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::Namespace;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_content_type() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Wrong type is rejected, parameters or not
+    for mime in ["application/json", "application/x-tar; charset=binary"] {
+        let result = RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.parse()?,
+            PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::ResponseCode(400))));
+    }
+
+    // Parameters are ignored when matching the type
+    for (mime, file) in [
+        ("application/gzip", "fixtures/mock-package-1.0.0.tgz"),
+        (
+            "application/gzip; charset=binary",
+            "fixtures/mock-package-1.1.0.tgz",
+        ),
+        (
+            "Application/GZIP; boundary=mock",
+            "fixtures/mock-package-1.2.0.tgz",
+        ),
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.parse()?,
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::{collections::HashSet, path::PathBuf};
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_database::AuditFilter;
+use ipfs_registry_server::config::RegistryConfig;
+
+use k256::ecdsa::SigningKey;
+
+fn status_code(result: ipfs_registry_client::Result<impl Sized>) -> u16 {
+    if let Err(ipfs_registry_client::Error::ResponseCode(code)) = result {
+        code
+    } else {
+        0
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_force() -> Result<()> {
+    let (signing_key, address) = new_signing_key();
+
+    // Spawn the server
+    let config = registry_server_config(RegistryConfig {
+        allow_force_window: Some(3600),
+        admins: Some(HashSet::from([address])),
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let replacement = PathBuf::from("fixtures/mock-package-force-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let original = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await?;
+
+    // Regular publish is still a conflict
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        replacement.clone(),
+    )
+    .await;
+    assert_eq!(409, status_code(result));
+
+    // Force publish within the window replaces the artifact
+    let receipt = RegistryClient::publish_file_force(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        replacement,
+        false,
+    )
+    .await?;
+    assert_eq!(original.id, receipt.id);
+    assert_ne!(original.checksum, receipt.checksum);
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        PackageName::new_unchecked("mock-package"),
+        Version::new(1, 0, 0),
+    );
+    let record =
+        RegistryClient::exact_version(server_url.clone(), id).await?;
+    assert_eq!(receipt.checksum, record.checksum);
+
+    // Overwrite is recorded in the audit log
+    let records = RegistryClient::audit(
+        server_url.clone(),
+        signing_key.clone(),
+        AuditFilter {
+            action: Some(String::from("overwrite_version")),
+            ..Default::default()
+        },
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(1, records.count);
+    assert_eq!(
+        hex::encode(original.checksum),
+        records.records[0].detail["previous_checksum"]
+    );
+
+    // Force publish of a new version behaves as a regular publish
+    let receipt = RegistryClient::publish_file_force(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+        false,
+    )
+    .await?;
+    assert_eq!(Version::new(1, 1, 0), receipt.artifact.package.version);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_force_window_expired() -> Result<()> {
+    // Spawn the server
+    let config = registry_server_config(RegistryConfig {
+        allow_force_window: Some(0),
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    let result = RegistryClient::publish_file_force(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-force-1.0.0.tgz"),
+        false,
+    )
+    .await;
+    assert_eq!(409, status_code(result));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_force_disabled() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let result = RegistryClient::publish_file_force(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+        false,
+    )
+    .await;
+    assert_eq!(403, status_code(result));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_force_immutable() -> Result<()> {
+    // Spawn the server
+    let config = registry_server_config(RegistryConfig {
+        allow_force_window: Some(3600),
+        immutable_versions: true,
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    let result = RegistryClient::publish_file_force(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-force-1.0.0.tgz"),
+        false,
+    )
+    .await;
+    assert_eq!(403, status_code(result));
+
+    Ok(())
+}
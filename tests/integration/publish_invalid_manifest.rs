@@ -0,0 +1,82 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use serde_json::Value;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, X_SIGNATURE};
+use ipfs_registry_server::config::RegistryConfig;
+
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_invalid_manifest() -> Result<()> {
+    let file = PathBuf::from("fixtures/invalid-manifest-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let registry_config = RegistryConfig {
+        validate_manifest: true,
+        ..Default::default()
+    };
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(registry_config))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Response names the missing field
+    let body = std::fs::read(&file)?;
+    let signature: recoverable::Signature = signing_key.sign(&body);
+    let url = server_url.join(&format!("api/package/{}", namespace))?;
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .header("content-type", mime.as_ref())
+        .body(Body::from(body))?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+    let body: Value =
+        serde_json::from_slice(&hyper::body::to_bytes(response).await?)?;
+    assert_eq!("license", body["field"]);
+    assert_eq!(
+        "manifest field license is missing or invalid",
+        body["message"]
+    );
+
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(422))
+    ));
+
+    // Valid manifest is accepted
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let receipt = RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        file,
+    )
+    .await?;
+    assert_eq!(1, receipt.artifact.package.version.major);
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_server::config::RegistryConfig;
+use semver::Version;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_scan() -> Result<()> {
+    // Spawn the server with a fake scanner that rejects
+    // archives containing a marker string
+    let config = registry_server_config(RegistryConfig {
+        scan_command: Some(
+            "if gzip -dc | grep -q IPKG-SCAN-TEST-THREAT; \
+            then echo threat; exit 1; fi"
+                .to_owned(),
+        ),
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Clean package is accepted
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Package with a threat is rejected
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/scan-threat-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(422))));
+
+    // Rejected package was not stored
+    let id = PackageKey::Pointer(
+        namespace,
+        PackageName::new_unchecked("scan-threat"),
+        Version::new(1, 0, 0),
+    );
+    let result = RegistryClient::exact_version(server_url, id).await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_scan_failure() -> Result<()> {
+    // Scanner that fails to run does not accept packages
+    let config = registry_server_config(RegistryConfig {
+        scan_command: Some("exit 2".to_owned()),
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let result = RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(500))));
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_verbose() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    // Lean receipt by default
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+    assert!(receipt.package.is_none());
+    assert!(receipt.size.is_none());
+    assert!(receipt.readme.is_none());
+
+    // Verbose receipt includes the details of the package
+    let file = PathBuf::from("fixtures/mock-readme-1.0.0.tgz");
+    let receipt = RegistryClient::publish_file_verbose(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+        false,
+    )
+    .await?;
+    let package = receipt.package.unwrap();
+    assert_eq!("mock-readme", package["name"]);
+    assert_eq!("Mock package with a README", package["description"]);
+    assert_eq!(Some(std::fs::metadata(&file)?.len()), receipt.size);
+    assert_eq!(
+        Some("# mock-readme\n\nMock package with a README.\n"),
+        receipt.readme.as_deref()
+    );
+
+    // Packages without a README omit the field
+    let file = PathBuf::from("fixtures/mock-package-1.1.0.tgz");
+    let receipt = RegistryClient::publish_file_verbose(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        file.clone(),
+        true,
+    )
+    .await?;
+    assert_eq!(Some(std::fs::metadata(&file)?.len()), receipt.size);
+    assert!(receipt.package.is_some());
+    assert!(receipt.readme.is_none());
+
+    Ok(())
+}
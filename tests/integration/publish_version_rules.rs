@@ -0,0 +1,83 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::Namespace;
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_version_rules() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        forbid_zero_version: true,
+        require_no_build_metadata: true,
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let publish = |file: &str| {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+    };
+
+    // Zero version is rejected
+    let result = publish("fixtures/mock-package-0.0.0.tgz").await;
+    assert!(matches!(result, Err(Error::ResponseCode(422))));
+
+    // Build metadata is rejected
+    let result = publish("fixtures/mock-package-1.0.0+build.1.tgz").await;
+    assert!(matches!(result, Err(Error::ResponseCode(422))));
+
+    publish("fixtures/mock-package-1.0.0.tgz").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_version_rules_disabled() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-0.0.0.tgz",
+        "fixtures/mock-package-1.0.0+build.1.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, RegistryKind};
+use ipfs_registry_server::config::RegistryConfig;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_wrong_kind() -> Result<()> {
+    // Spawn the server, default kind is npm
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        reject_wrong_kind: true,
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let gzip: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let crates = Namespace::new_unchecked("mock-crates");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        crates.clone(),
+        Some(RegistryKind::Cargo),
+    )
+    .await?;
+
+    // A crate cannot be published to an npm namespace
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        gzip.clone(),
+        PathBuf::from("fixtures/mock-crate-1.0.0.crate"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(415))));
+
+    // An npm package cannot be published to a cargo namespace
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        crates.clone(),
+        gzip.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(415))));
+
+    // An OCI artifact cannot be published to an npm namespace
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        gzip.clone(),
+        PathBuf::from("fixtures/mock-oci-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(415))));
+
+    // Content type for another kind is rejected
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        RegistryKind::Oci.mime_type().parse()?,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(415))));
+
+    // Matching kinds are accepted
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        gzip.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+    assert_eq!(RegistryKind::Npm, receipt.artifact.kind);
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        crates.clone(),
+        gzip.clone(),
+        PathBuf::from("fixtures/mock-crate-1.0.0.crate"),
+    )
+    .await?;
+    assert_eq!(RegistryKind::Cargo, receipt.artifact.kind);
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use serde_json::Value;
+use serial_test::serial;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, X_SIGNATURE};
+use ipfs_registry_database::PublishDenied;
+use ipfs_registry_server::config::RegistryConfig;
+
+/// Publish a file and return the status and body of the response.
+async fn publish_response(
+    server_url: &Url,
+    signing_key: &SigningKey,
+    namespace: &Namespace,
+    file: &Path,
+) -> Result<(StatusCode, Vec<u8>)> {
+    let body = std::fs::read(file)?;
+    let signature: recoverable::Signature = signing_key.sign(&body);
+    let url = server_url.join(&format!("api/package/{}", namespace))?;
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .header("content-type", "application/gzip")
+        .body(Body::from(body))?;
+    let response = Client::new().request(request).await?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response).await?;
+    Ok((status, body.to_vec()))
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_yanked() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let version = Version::new(1, 0, 0);
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await?;
+
+    // Republishing a version that was not yanked
+    let (status, body) =
+        publish_response(&server_url, &signing_key, &namespace, &file)
+            .await?;
+    assert_eq!(StatusCode::CONFLICT, status);
+    assert!(body.is_empty());
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        version.clone(),
+    );
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+        String::from("mock yank message"),
+    )
+    .await?;
+
+    // Republishing a yanked version names the reason
+    let (status, body) =
+        publish_response(&server_url, &signing_key, &namespace, &file)
+            .await?;
+    assert_eq!(StatusCode::CONFLICT, status);
+    let body: Value = serde_json::from_slice(&body)?;
+    assert_eq!("version-yanked", body["reason"]);
+    assert_eq!(
+        "version mock-namespace/mock-package/1.0.0 was yanked, choose a new version",
+        body["message"]
+    );
+
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(409))));
+
+    let check = RegistryClient::can_publish(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        address,
+        Some(version.clone()),
+    )
+    .await?;
+    assert!(!check.allowed);
+    assert_eq!(Some(PublishDenied::VersionYanked), check.reason);
+
+    // Version is still yanked
+    let doc = RegistryClient::exact_version(server_url, id).await?;
+    assert!(doc.yanked.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_yanked_overwrite() -> Result<()> {
+    // Spawn the server
+    let config = registry_server_config(RegistryConfig {
+        allow_yanked_overwrite: true,
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let other = PathBuf::from("fixtures/mock-package-1.1.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for path in [&file, &other] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            path.clone(),
+        )
+        .await?;
+    }
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+        String::from("mock yank message"),
+    )
+    .await?;
+
+    // Yanked version can be published again
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await?;
+
+    let doc = RegistryClient::exact_version(server_url.clone(), id).await?;
+    assert!(doc.yanked.is_none());
+
+    // Replaced version is no longer yanked so it is a conflict
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(409))));
+
+    // Versions that were never yanked cannot be overwritten
+    let result = RegistryClient::publish_file(
+        server_url,
+        signing_key,
+        namespace,
+        mime,
+        other,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(409))));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_publish_yanked_overwrite_failure() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    // File database so the test can inject a failure
+    let url = format!(
+        "sqlite://{}?mode=rwc",
+        dir.path().join("registry.db").display()
+    );
+    let pool = SqlitePool::connect(&url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    // Spawn the server
+    let mut config = registry_server_config(RegistryConfig {
+        allow_yanked_overwrite: true,
+        ..Default::default()
+    });
+    config.database.url = url;
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package,
+        Version::new(1, 0, 0),
+    );
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+        String::from("mock yank message"),
+    )
+    .await?;
+
+    // Recording the overwrite fails after the replacement is stored
+    sqlx::query(
+        r#"
+            CREATE TRIGGER overwrites_insert_failure
+            BEFORE INSERT ON version_overwrites
+            BEGIN
+                SELECT RAISE(ABORT, 'injected failure');
+            END
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-force-1.0.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(500))));
+
+    // Version is still yanked and serves the previous artifact
+    let doc =
+        RegistryClient::exact_version(server_url.clone(), id.clone()).await?;
+    assert!(doc.yanked.is_some());
+    let data = RegistryClient::fetch_bytes(server_url, id).await?;
+    assert_eq!(std::fs::read(&file)?, data);
+
+    Ok(())
+}
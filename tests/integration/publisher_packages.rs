@@ -0,0 +1,117 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_database::{Pager, PublishPermission};
+
+#[tokio::test]
+#[serial]
+async fn integration_publisher_packages() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await?;
+
+    let (admin_key, admin_address) = new_signing_key();
+    let (member_key, member_address) = new_signing_key();
+    let (restricted_key, restricted_address) = new_signing_key();
+    let (stranger_key, stranger_address) = new_signing_key();
+
+    for key in [&admin_key, &member_key, &restricted_key, &stranger_key] {
+        RegistryClient::signup(server_url.clone(), key.clone()).await?;
+    }
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        admin_address,
+        true,
+        None,
+    )
+    .await?;
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        member_address,
+        false,
+        None,
+    )
+    .await?;
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        restricted_address,
+        false,
+        Some(package.clone()),
+    )
+    .await?;
+
+    for (address, permission) in [
+        (address, PublishPermission::Owner),
+        (admin_address, PublishPermission::Administrator),
+        (member_address, PublishPermission::Publisher),
+        (restricted_address, PublishPermission::Restricted),
+    ] {
+        let result = RegistryClient::publisher_packages(
+            server_url.clone(),
+            &address,
+            Pager::default(),
+        )
+        .await?;
+        assert_eq!(1, result.count);
+        let record = result.records.first().unwrap();
+        assert_eq!(namespace, record.namespace);
+        assert_eq!(package, record.package);
+        assert_eq!(permission, record.permission);
+    }
+
+    // Publisher without any namespace access has no packages
+    let result = RegistryClient::publisher_packages(
+        server_url.clone(),
+        &stranger_address,
+        Pager::default(),
+    )
+    .await?;
+    assert_eq!(0, result.count);
+    assert!(result.records.is_empty());
+
+    // Unknown publisher is not found
+    let (_, unknown_address) = new_signing_key();
+    let result = RegistryClient::publisher_packages(
+        server_url.clone(),
+        &unknown_address,
+        Pager::default(),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use hyper::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    Client,
+};
+use k256::ecdsa::SigningKey;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageName, RegistryKind};
+use ipfs_registry_server::config::RegistryConfig;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_pypi() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        kind: RegistryKind::Pypi,
+        validate_manifest: true,
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let wheels = Namespace::new_unchecked("mock-wheels");
+    let sources = Namespace::new_unchecked("mock-sources");
+
+    prepare_mock_namespace(&server_url, &signing_key, &wheels).await?;
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        sources.clone(),
+        None,
+    )
+    .await?;
+
+    let files = [
+        (
+            &wheels,
+            "application/zip",
+            "fixtures/Mock_Pypi-1.0.0-py3-none-any.whl",
+            "mock-pypi-1.0.0.whl",
+        ),
+        (
+            &sources,
+            "application/gzip",
+            "fixtures/Mock_Pypi-1.0.0.tar.gz",
+            "mock-pypi-1.0.0.tar.gz",
+        ),
+    ];
+
+    for (namespace, mime, file, file_name) in files {
+        let receipt = RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.parse()?,
+            PathBuf::from(file),
+        )
+        .await?;
+
+        // Package name is normalized
+        assert_eq!(
+            PackageName::new_unchecked("mock-pypi"),
+            receipt.artifact.package.name
+        );
+        assert_eq!(RegistryKind::Pypi, receipt.artifact.kind);
+
+        let id = format!("{}/mock-pypi/1.0.0", namespace);
+        let mut url = server_url.join("api/package")?;
+        url.query_pairs_mut().append_pair("id", &id);
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert!(response.status().is_success());
+
+        let headers = response.headers();
+        assert_eq!(mime, headers.get(CONTENT_TYPE).unwrap().to_str()?);
+        assert_eq!(
+            format!("attachment; filename=\"{}\"", file_name),
+            headers.get(CONTENT_DISPOSITION).unwrap().to_str()?
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, Client, StatusCode};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+
+use k256::ecdsa::SigningKey;
+
+#[tokio::test]
+#[serial]
+async fn integration_query_validation() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    let packages = "/api/package/mock-namespace/packages";
+    let versions = "/api/package/mock-namespace/mock-package/versions";
+    let latest = "/api/package/mock-namespace/mock-package/latest";
+
+    // Omitted parameters use the defaults
+    for path in [packages, versions, latest] {
+        let url = server_url.join(path)?;
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    // Present but invalid parameters are rejected
+    for (path, query, message) in [
+        (packages, "sort=random", "unknown variant `random`"),
+        (packages, "sort=", "unknown variant ``"),
+        (packages, "limit=abc", "invalid digit"),
+        (packages, "limit=0", "limit must be greater than zero"),
+        (packages, "offset=-1", "offset must not be negative"),
+        (versions, "range=garbage", "unexpected character 'g'"),
+        (versions, "sort=random", "unknown variant `random`"),
+        (latest, "range=garbage", "unexpected character 'g'"),
+        (
+            "/api/publisher/0x1fc770ac21067a04f83101ebf19a670db9e3eb21/packages",
+            "limit=-1",
+            "limit must be greater than zero",
+        ),
+    ] {
+        let url = server_url.join(&format!("{}?{}", path, query))?;
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = to_bytes(response.into_body()).await?;
+        let body = std::str::from_utf8(&body)?;
+        assert!(body.contains(message), "{}", body);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_record_cache() -> Result<()> {
+    // Spawn the server
+    let config = registry_server_config(RegistryConfig {
+        cache_ttl: Some(3600),
+        ..Default::default()
+    });
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (member_key, member_address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), member_key.clone()).await?;
+
+    let publish = |file: &str| {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            member_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+    };
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        member_address,
+        false,
+        None,
+    )
+    .await?;
+
+    // Caches the namespace with the member
+    publish("fixtures/mock-package-1.0.0.tgz").await?;
+
+    // Removed member is denied
+    RegistryClient::remove_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        member_address,
+    )
+    .await?;
+    let result = publish("fixtures/mock-package-1.1.0.tgz").await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Member added again is allowed
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        member_address,
+        false,
+        None,
+    )
+    .await?;
+    publish("fixtures/mock-package-1.1.0.tgz").await?;
+
+    // Restricted member cannot create a new package
+    RegistryClient::access_control(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        member_address,
+        true,
+    )
+    .await?;
+    let result = publish("fixtures/denied-extension-1.0.0.tgz").await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+    publish("fixtures/mock-package-1.2.0.tgz").await?;
+
+    // Revoking the restriction lifts it
+    RegistryClient::access_control(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package,
+        member_address,
+        false,
+    )
+    .await?;
+    publish("fixtures/denied-extension-1.0.0.tgz").await?;
+
+    Ok(())
+}
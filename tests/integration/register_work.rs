@@ -0,0 +1,72 @@
+use anyhow::Result;
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{solve_work, verify_work, Namespace};
+
+use k256::ecdsa::SigningKey;
+
+#[tokio::test]
+#[serial]
+async fn integration_register_work() -> Result<()> {
+    let difficulty = 8;
+    let mut config = default_server_config();
+    config.namespace_difficulty = Some(difficulty);
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    RegistryClient::signup(server_url.clone(), signing_key.clone()).await?;
+
+    // Missing proof of work
+    let result = RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        None,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(401))
+    ));
+
+    // Proof of work is not difficult enough
+    assert!(!verify_work(
+        &namespace,
+        solve_work(&namespace, 0),
+        difficulty
+    ));
+    let result = RegistryClient::register_with_work(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        None,
+        0,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(401))
+    ));
+
+    let record = RegistryClient::register_with_work(
+        server_url,
+        signing_key,
+        namespace.clone(),
+        None,
+        difficulty,
+    )
+    .await?;
+    assert_eq!(namespace, record.name);
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::{collections::HashSet, path::PathBuf};
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_database::{NamespaceAction, ResolveNamespace};
+use ipfs_registry_server::config::RegistryConfig;
+
+#[tokio::test]
+#[serial]
+async fn integration_resolve_namespace() -> Result<()> {
+    let (admin_key, admin_address) = new_signing_key();
+    let (signing_key, _) = new_signing_key();
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        admins: Some(HashSet::from([admin_address])),
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let target = Namespace::new_unchecked("other-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        target.clone(),
+        None,
+    )
+    .await?;
+    RegistryClient::signup(server_url.clone(), admin_key.clone()).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        file.clone(),
+    )
+    .await?;
+
+    let merge = |dry_run| ResolveNamespace {
+        namespace: namespace.clone(),
+        action: NamespaceAction::Merge(target.clone()),
+        dry_run,
+    };
+
+    // Namespace owner is not an administrator
+    let result = RegistryClient::resolve_namespace(
+        server_url.clone(),
+        signing_key.clone(),
+        merge(false),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Dry run leaves the namespace in place
+    let report = RegistryClient::resolve_namespace(
+        server_url.clone(),
+        admin_key.clone(),
+        merge(true),
+    )
+    .await?;
+    assert!(report.dry_run);
+    assert_eq!(vec![package.clone()], report.packages);
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    RegistryClient::exact_version(server_url.clone(), id).await?;
+
+    let report = RegistryClient::resolve_namespace(
+        server_url.clone(),
+        admin_key,
+        merge(false),
+    )
+    .await?;
+    assert!(!report.dry_run);
+
+    // Package is served from the surviving namespace
+    let result =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    // Register the removed namespace again and publish the same
+    // version with different content
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        None,
+    )
+    .await?;
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace,
+        "application/gzip".parse()?,
+        PathBuf::from("fixtures/mock-package-force-1.0.0.tgz"),
+    )
+    .await?;
+
+    let dir = tempfile::tempdir()?;
+    let output = dir.path().join("package.tgz");
+    let id = PackageKey::Pointer(target, package, Version::new(1, 0, 0));
+    RegistryClient::fetch_file(server_url, id, output.clone()).await?;
+    assert_eq!(std::fs::read(&file)?, std::fs::read(&output)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_resolve_namespace_disabled() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let result = RegistryClient::resolve_namespace(
+        server_url,
+        signing_key,
+        ResolveNamespace {
+            namespace: namespace.clone(),
+            action: NamespaceAction::Rename(Namespace::new_unchecked(
+                "renamed-namespace",
+            )),
+            dry_run: false,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(403))));
+
+    Ok(())
+}
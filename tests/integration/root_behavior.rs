@@ -0,0 +1,98 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, header::LOCATION, Client, StatusCode};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_server::config::RootBehavior;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_root_behavior_info() -> Result<()> {
+    // Spawn the server, default is the server information
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let response = Client::new().get(server_url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let body = to_bytes(response.into_body()).await?;
+    let root: Value = serde_json::from_slice(&body)?;
+
+    let url = server_url.join("api")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    let body = to_bytes(response.into_body()).await?;
+    let api: Value = serde_json::from_slice(&body)?;
+    assert_eq!(api, root);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_root_behavior_redirect() -> Result<()> {
+    let mut config = default_server_config();
+    config.root_behavior = RootBehavior::Redirect;
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let response = Client::new().get(server_url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::TEMPORARY_REDIRECT, response.status());
+    assert_eq!("/api", response.headers().get(LOCATION).unwrap());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_root_behavior_static() -> Result<()> {
+    let mut config = default_server_config();
+    config.root_behavior =
+        RootBehavior::Static(PathBuf::from("fixtures/landing"));
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    // Landing page is served for the root path
+    let expected = std::fs::read("fixtures/landing/index.html")?;
+    for path in ["", "index.html"] {
+        let url = server_url.join(path)?;
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert_eq!(StatusCode::OK, response.status());
+        let body = to_bytes(response.into_body()).await?;
+        assert_eq!(expected, body.to_vec());
+    }
+
+    // Missing file
+    let url = server_url.join("missing.html")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    // Unknown API routes are not served from the directory
+    let url = server_url.join("api/unknown/route")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+    let body = to_bytes(response.into_body()).await?;
+    let body: Value = serde_json::from_slice(&body)?;
+    assert_eq!(
+        json!({"error": "not_found", "path": "/api/unknown/route"}),
+        body
+    );
+
+    // API is still available
+    let url = server_url.join("api")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    Ok(())
+}
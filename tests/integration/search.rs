@@ -0,0 +1,129 @@
+use anyhow::Result;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::Namespace;
+use ipfs_registry_database::Pager;
+
+#[tokio::test]
+#[serial]
+async fn integration_search() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-dependent-1.0.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let results = RegistryClient::search(
+        server_url.clone(),
+        String::from("mock"),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(2, results.count);
+    let names: Vec<String> =
+        results.records.iter().map(|p| p.name.to_string()).collect();
+    assert_eq!(vec!["mock-dependent", "mock-package"], names);
+
+    let results = RegistryClient::search(
+        server_url.clone(),
+        String::from("mock-pack"),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(1, results.records.len());
+    let record = results.records.first().unwrap();
+    assert_eq!(Some(namespace.clone()), record.namespace);
+    assert_eq!("mock-package", record.name.as_str());
+
+    // Match must be a prefix
+    let results = RegistryClient::search(
+        server_url.clone(),
+        String::from("package"),
+        Default::default(),
+    )
+    .await?;
+    assert!(results.records.is_empty());
+
+    // Pagination
+    let results = RegistryClient::search(
+        server_url.clone(),
+        String::from("mock"),
+        Pager {
+            offset: 1,
+            limit: 1,
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(2, results.count);
+    assert_eq!("mock-package", results.records[0].name.as_str());
+
+    // Packages in a private namespace are not listed
+    RegistryClient::set_private(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        true,
+    )
+    .await?;
+    let results = RegistryClient::search(
+        server_url.clone(),
+        String::from("mock"),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(0, results.count);
+    assert!(results.records.is_empty());
+
+    RegistryClient::set_private(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        false,
+    )
+    .await?;
+    let results = RegistryClient::search(
+        server_url.clone(),
+        String::from("mock"),
+        Default::default(),
+    )
+    .await?;
+    assert_eq!(2, results.count);
+
+    // Invalid queries are rejected
+    for query in ["m%", "mo", "mock\n"] {
+        let result = RegistryClient::search(
+            server_url.clone(),
+            String::from(query),
+            Default::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(Error::ResponseCode(400))));
+    }
+
+    Ok(())
+}
@@ -24,7 +24,7 @@ async fn integration_semver() -> Result<()> {
     // Create a namespace
     let namespace = Namespace::new_unchecked("mock-namespace");
     let _namespace_id =
-        NamespaceModel::insert(&pool, &namespace, publisher_id).await?;
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
 
     let mock_package = PackageName::new_unchecked("mock-package");
 
@@ -58,6 +58,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &dev_release_1,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -67,6 +68,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &dev_release_2,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -76,6 +78,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &dev_release_3,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -86,6 +89,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &first_release,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -96,6 +100,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &patch_release,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -106,6 +111,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &point_release,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -116,6 +122,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &next_pre_release_1,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -126,6 +133,7 @@ async fn integration_semver() -> Result<()> {
         &namespace_record,
         &address,
         &next_pre_release_2,
+        &[],
     )
     .await?;
     assert!(result > 0);
@@ -139,6 +147,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -152,6 +161,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -166,6 +176,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -181,6 +192,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -196,6 +208,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -210,6 +223,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -224,6 +238,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -239,6 +254,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -254,6 +270,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -270,6 +287,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -286,6 +304,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -303,6 +322,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
@@ -320,6 +340,7 @@ async fn integration_semver() -> Result<()> {
         &mock_package,
         &request,
         &Default::default(),
+        true,
     )
     .await?;
     let mut versions = versions.records;
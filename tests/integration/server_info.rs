@@ -0,0 +1,64 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, Client, StatusCode};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use ipfs_registry_core::RegistryKind;
+use ipfs_registry_server::config::RegistryConfig;
+
+async fn server_info() -> Result<Value> {
+    let url = server().join("api")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let body = to_bytes(response.into_body()).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_server_info() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let info = server_info().await?;
+    assert_eq!(json!("integration-test"), info["name"]);
+    assert_eq!(json!("0.0.0"), info["version"]);
+    assert!(info["build"]["rustc"]
+        .as_str()
+        .map(|rustc| rustc.starts_with("rustc "))
+        .unwrap_or(false));
+    assert_eq!(
+        json!({
+            "webhooks": false,
+            "tls": false,
+            "msgpack": true,
+            "signed_urls": false,
+            "kind": "npm",
+            "kinds": ["npm", "cargo", "oci", "pypi"],
+        }),
+        info["features"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_server_info_features() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        kind: RegistryKind::Cargo,
+        msgpack: false,
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let info = server_info().await?;
+    assert_eq!(json!(false), info["features"]["msgpack"]);
+    assert_eq!(json!("cargo"), info["features"]["kind"]);
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, Body, Client, Request, StatusCode};
+use serde_json::Value;
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use ipfs_registry_core::X_SIGNATURE;
+
+#[tokio::test]
+#[serial]
+async fn integration_signature_header() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+    let url = server_url.join("api/signup")?;
+
+    for (signature, error) in [
+        (None, "missing_signature"),
+        (Some("not base64!".to_owned()), "invalid_signature_encoding"),
+        (Some(base64::encode([0u8; 64])), "invalid_signature_length"),
+    ] {
+        let mut request = Request::post(url.as_str());
+        if let Some(signature) = signature {
+            request = request.header(X_SIGNATURE, signature);
+        }
+        let request = request.body(Body::empty())?;
+        let response = Client::new().request(request).await?;
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body = to_bytes(response.into_body()).await?;
+        let body: Value = serde_json::from_slice(&body)?;
+        assert_eq!(error, body["error"]);
+        assert!(body["message"].is_string());
+    }
+
+    // Optional signatures are still rejected when malformed
+    let url = server_url
+        .join("api/package?id=mock-namespace/mock-package/1.0.0")?;
+    let request = Request::get(url.as_str())
+        .header(X_SIGNATURE, base64::encode([0u8; 64]))
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let request = Request::get(url.as_str()).body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, Body, Client, Request, StatusCode};
+use serial_test::serial;
+use std::path::PathBuf;
+use url::Url;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, X_SIGNATURE};
+use ipfs_registry_server::config::SignedUrlConfig;
+
+use k256::ecdsa::{recoverable, signature::Signer};
+
+async fn get(url: &Url) -> Result<(StatusCode, Vec<u8>)> {
+    let request = Request::get(url.as_str()).body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    let status = response.status();
+    let body = to_bytes(response.into_body()).await?;
+    Ok((status, body.to_vec()))
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_signed_url() -> Result<()> {
+    let mut config = default_server_config();
+    config.signed_urls = Some(SignedUrlConfig {
+        secret: String::from("mock-secret-for-signed-download-urls"),
+        max_expires_in: 60,
+    });
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (outsider_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), outsider_key.clone()).await?;
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        file.clone(),
+    )
+    .await?;
+    RegistryClient::set_private(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        true,
+    )
+    .await?;
+
+    let id = receipt.id.clone();
+
+    // Only users of a private namespace may sign URLs
+    let result = RegistryClient::sign_url(
+        server_url.clone(),
+        outsider_key,
+        id.clone(),
+        None,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Expiry must not exceed the configured maximum
+    let result = RegistryClient::sign_url(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+        Some(61),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(400))));
+
+    // Valid URL downloads without a signature
+    let signed = RegistryClient::sign_url(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+        None,
+    )
+    .await?;
+    let url = server_url.join(signed.url.trim_start_matches('/'))?;
+    let (status, body) = get(&url).await?;
+    assert_eq!(StatusCode::OK, status);
+    assert_eq!(std::fs::read(&file)?, body);
+
+    let query = |url: &Url, name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+            .unwrap()
+    };
+    let expires = query(&url, "expires");
+    let token = query(&url, "token");
+    assert_eq!(signed.expires.to_string(), expires);
+
+    let with_query = |pairs: &[(&str, &str)]| -> Result<Url> {
+        let mut url = server_url.join("api/package")?;
+        url.query_pairs_mut().extend_pairs(pairs);
+        Ok(url)
+    };
+    let id_str = id.to_string();
+
+    // Tampered URLs are forbidden
+    let mut tampered_token = token.clone();
+    let last = if tampered_token.pop() == Some('0') {
+        '1'
+    } else {
+        '0'
+    };
+    tampered_token.push(last);
+    let later = (signed.expires + 1).to_string();
+    let other: PackageKey = "mock-namespace/mock-package/1.1.0".parse()?;
+    let other = other.to_string();
+    for pairs in [
+        vec![
+            ("id", id_str.as_str()),
+            ("expires", expires.as_str()),
+            ("token", tampered_token.as_str()),
+        ],
+        vec![
+            ("id", id_str.as_str()),
+            ("expires", later.as_str()),
+            ("token", token.as_str()),
+        ],
+        vec![
+            ("id", other.as_str()),
+            ("expires", expires.as_str()),
+            ("token", token.as_str()),
+        ],
+    ] {
+        let (status, _) = get(&with_query(&pairs)?).await?;
+        assert_eq!(StatusCode::FORBIDDEN, status);
+    }
+
+    // Incomplete signed URLs are rejected
+    let (status, _) = get(&with_query(&[
+        ("id", id_str.as_str()),
+        ("expires", expires.as_str()),
+    ])?)
+    .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+
+    // Plain downloads still require a signature
+    let (status, _) = get(&with_query(&[("id", id_str.as_str())])?).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, status);
+
+    let post = |path: &str, pairs: &[(&str, &str)], signature| {
+        let mut url = server_url.join(path).unwrap();
+        url.query_pairs_mut().extend_pairs(pairs);
+        let request = Request::post(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature))
+            .body(Body::empty())
+            .unwrap();
+        Client::new().request(request)
+    };
+    let signature: recoverable::Signature =
+        signing_key.sign(&id.signed_message("sign-url", b"1"));
+
+    // Signature is bound to the requested expiry
+    let response = post(
+        "api/package/version/sign-url",
+        &[("id", id_str.as_str()), ("expires_in", "60")],
+        signature,
+    )
+    .await?;
+    assert!(!response.status().is_success());
+
+    // Signature cannot be replayed to yank or unyank the version
+    for path in ["api/package/yank", "api/package/unyank"] {
+        let response =
+            post(path, &[("id", id_str.as_str())], signature).await?;
+        assert!(!response.status().is_success());
+    }
+    let doc =
+        RegistryClient::exact_version(server_url.clone(), id.clone()).await?;
+    assert!(doc.yanked.is_none());
+
+    // Expired URLs are forbidden
+    let signed = RegistryClient::sign_url(
+        server_url.clone(),
+        signing_key,
+        id,
+        Some(1),
+    )
+    .await?;
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    let url = server_url.join(signed.url.trim_start_matches('/'))?;
+    let (status, _) = get(&url).await?;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+
+    Ok(())
+}
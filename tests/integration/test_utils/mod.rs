@@ -38,19 +38,14 @@ impl MockServer {
         tracing::info!("start mock server {:#?}", addr);
 
         let layers = build_layers(&config)?;
-
-        let state = Arc::new(
-            State::new(
-                config,
-                ServerInfo {
-                    name: String::from("integration-test"),
-                    version: String::from("0.0.0"),
-                },
-                layers,
-            )
-            .await?,
+        let info = ServerInfo::new(
+            String::from("integration-test"),
+            String::from("0.0.0"),
+            &config,
         );
 
+        let state = Arc::new(State::new(config, info, layers).await?);
+
         Server.start(addr, state, self.handle.clone()).await?;
         Ok(())
     }
@@ -144,6 +139,7 @@ pub async fn prepare_mock_namespace(
         server.clone(),
         key.clone(),
         namespace.clone(),
+        None,
     )
     .await?;
 
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use hyper::{
+    header::{LOCATION, STRICT_TRANSPORT_SECURITY},
+    Body, Client, Method, Request, StatusCode,
+};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_server::config::{HstsConfig, TlsConfig};
+
+use crate::test_utils::*;
+
+const SECURE: &str = "https://localhost:9009";
+const PLAINTEXT: &str = "http://localhost:9010";
+
+#[tokio::test]
+#[serial]
+async fn integration_tls_hsts() -> Result<()> {
+    let mut config = default_server_config();
+    config.tls = Some(TlsConfig {
+        cert: PathBuf::from("fixtures/tls/cert.pem"),
+        key: PathBuf::from("fixtures/tls/key.pem"),
+        hsts: Some(HstsConfig {
+            include_subdomains: true,
+            ..Default::default()
+        }),
+        redirect: Some("127.0.0.1:9010".parse()?),
+    });
+
+    // Spawn the server
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    // Responses over TLS include the HSTS header
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()?;
+    let response = client.get(format!("{}/api", SECURE)).send().await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let hsts = response
+        .headers()
+        .get(STRICT_TRANSPORT_SECURITY)
+        .map(|value| value.to_str().unwrap().to_owned());
+    assert_eq!(
+        Some(String::from("max-age=31536000; includeSubDomains")),
+        hsts
+    );
+
+    // Plaintext reads are redirected to HTTPS
+    let path = "/api/package/mock-namespace/mock-package?limit=5";
+    let response = Client::new()
+        .get(format!("{}{}", PLAINTEXT, path).parse()?)
+        .await?;
+    assert_eq!(StatusCode::PERMANENT_REDIRECT, response.status());
+    let location = response
+        .headers()
+        .get(LOCATION)
+        .map(|value| value.to_str().unwrap().to_owned());
+    assert_eq!(Some(format!("{}{}", SECURE, path)), location);
+
+    // Plaintext writes are rejected
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("{}/api/signup", PLAINTEXT))
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request, StatusCode};
+use serial_test::serial;
+use std::{collections::HashSet, path::PathBuf};
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, X_SIGNATURE};
+use ipfs_registry_server::config::RegistryConfig;
+use semver::Version;
+
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+
+#[tokio::test]
+#[serial]
+async fn integration_transfer() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+    let other_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let target = Namespace::new_unchecked("mock-target");
+    let other = Namespace::new_unchecked("mock-other");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        target.clone(),
+        None,
+    )
+    .await?;
+    prepare_mock_namespace(&server_url, &other_key, &other).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        file.clone(),
+    )
+    .await?;
+
+    // Caller does not own the target namespace
+    let result = RegistryClient::transfer(
+        server_url.clone(),
+        signing_key.clone(),
+        None,
+        namespace.clone(),
+        package.clone(),
+        other.clone(),
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(401))
+    ));
+
+    let transfer = |signature: recoverable::Signature| {
+        let url = server_url
+            .join(&format!(
+                "api/package/{}/{}/transfer/{}",
+                namespace, package, target
+            ))
+            .unwrap();
+        let request = Request::post(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature))
+            .body(Body::empty())
+            .unwrap();
+        Client::new().request(request)
+    };
+
+    // Signature for another package replayed against this package
+    let signature: recoverable::Signature = signing_key.sign(
+        format!("{}/mock-other-package/{}", namespace, target).as_bytes(),
+    );
+    let response = transfer(signature).await?;
+    assert!(!response.status().is_success());
+
+    // Signature over the target alone
+    let signature: recoverable::Signature =
+        signing_key.sign(target.as_bytes());
+    let response = transfer(signature).await?;
+    assert!(!response.status().is_success());
+
+    // Signature of the source owner without the target owner
+    let signature: recoverable::Signature = signing_key
+        .sign(format!("{}/{}/{}", namespace, package, target).as_bytes());
+    let response = transfer(signature).await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+    let record = RegistryClient::transfer(
+        server_url.clone(),
+        signing_key.clone(),
+        None,
+        namespace.clone(),
+        package.clone(),
+        target.clone(),
+    )
+    .await?;
+    assert_eq!(package, record.name);
+
+    // Package is no longer in the source namespace
+    let result = RegistryClient::get_package(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(404))
+    ));
+
+    // Versions are resolved using the target namespace
+    let versions = RegistryClient::latest_version(
+        server_url.clone(),
+        target.clone(),
+        package.clone(),
+        None,
+    )
+    .await?;
+    assert_eq!(1, versions.version.major);
+
+    // Publish the same version with different content to the
+    // source and attempt to transfer into the namespace that
+    // already has the name
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/mock-package-force-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Transferred artifact is not replaced by the new publish
+    let id = PackageKey::Pointer(
+        target.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    let data = RegistryClient::fetch_bytes(server_url.clone(), id).await?;
+    assert_eq!(std::fs::read(&file)?, data);
+
+    let result = RegistryClient::transfer(
+        server_url.clone(),
+        signing_key.clone(),
+        None,
+        namespace.clone(),
+        package.clone(),
+        target,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(409))
+    ));
+
+    // Owners of different namespaces both sign the transfer
+    let record = RegistryClient::transfer(
+        server_url,
+        signing_key,
+        Some(other_key),
+        namespace,
+        package.clone(),
+        other,
+    )
+    .await?;
+    assert_eq!(package, record.name);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_transfer_administrator() -> Result<()> {
+    let (admin_key, admin_address) = new_signing_key();
+    let (signing_key, _) = new_signing_key();
+    let (other_key, _) = new_signing_key();
+
+    // Spawn the server
+    let (rx, _handle) = spawn(registry_server_config(RegistryConfig {
+        admins: Some(HashSet::from([admin_address])),
+        ..Default::default()
+    }))?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let other = Namespace::new_unchecked("mock-other");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    prepare_mock_namespace(&server_url, &other_key, &other).await?;
+    RegistryClient::signup(server_url.clone(), admin_key.clone()).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        "application/gzip".parse()?,
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Registry administrator signs for both namespaces
+    let record = RegistryClient::transfer(
+        server_url.clone(),
+        admin_key,
+        None,
+        namespace,
+        package.clone(),
+        other.clone(),
+    )
+    .await?;
+    assert_eq!(package, record.name);
+
+    let record =
+        RegistryClient::get_package(server_url, other, package.clone())
+            .await?;
+    assert_eq!(package, record.name);
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName, X_SIGNATURE};
+
+use k256::ecdsa::{recoverable, signature::Signer};
+
+#[tokio::test]
+#[serial]
+async fn integration_transfer_namespace() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, address) = new_signing_key();
+    let (owner_key, owner_address) = new_signing_key();
+    let (admin_key, admin_address) = new_signing_key();
+    let (_, unknown_address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), owner_key.clone()).await?;
+    RegistryClient::signup(server_url.clone(), admin_key.clone()).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // New owner is an existing user restricted to a package
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        owner_address,
+        false,
+        Some(package),
+    )
+    .await?;
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        admin_address,
+        true,
+        None,
+    )
+    .await?;
+
+    // Only the owner can transfer the namespace
+    let result = RegistryClient::transfer_namespace(
+        server_url.clone(),
+        admin_key,
+        namespace.clone(),
+        admin_address,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // New owner must be a registered publisher
+    let result = RegistryClient::transfer_namespace(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        unknown_address,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    // Signature for another namespace of the owner replayed
+    // against this namespace
+    let other = Namespace::new_unchecked("mock-other");
+    RegistryClient::register(
+        server_url.clone(),
+        signing_key.clone(),
+        other.clone(),
+        None,
+    )
+    .await?;
+    let url = server_url.join(&format!(
+        "api/namespace/{}/transfer/{}",
+        namespace, owner_address
+    ))?;
+    let signature: recoverable::Signature =
+        signing_key.sign(format!("{}/{}", other, owner_address).as_bytes());
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert!(!response.status().is_success());
+
+    let record = RegistryClient::transfer_namespace(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        owner_address,
+    )
+    .await?;
+    assert_eq!(owner_address, record.owner);
+    let users: Vec<_> = record.publishers.iter().map(|u| u.address).collect();
+    assert_eq!(vec![admin_address], users);
+
+    let record =
+        RegistryClient::get_namespace(server_url.clone(), namespace.clone())
+            .await?;
+    assert_eq!(owner_address, record.owner);
+
+    // Previous owner keeps no access to the namespace
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let result = RegistryClient::transfer_namespace(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        address,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // New owner is no longer restricted
+    RegistryClient::publish_file(
+        server_url,
+        owner_key,
+        namespace,
+        mime,
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use anyhow::Result;
+use hyper::{Body, Client, Method, Request};
+use k256::ecdsa::{recoverable, signature::Signer};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageName, X_SIGNATURE};
+use ipfs_registry_database::Pager;
+
+#[tokio::test]
+#[serial]
+async fn integration_trusted_keys() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (admin_key, admin_address) = new_signing_key();
+    let (member_key, member_address) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for key in [&admin_key, &member_key] {
+        RegistryClient::signup(server_url.clone(), key.clone()).await?;
+    }
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        admin_address,
+        true,
+        None,
+    )
+    .await?;
+
+    RegistryClient::add_user(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        member_address,
+        false,
+        None,
+    )
+    .await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    // Members cannot modify the trusted keys
+    let result = RegistryClient::trusted_key(
+        server_url.clone(),
+        member_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        member_address,
+        true,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    let url = server_url.join(&format!(
+        "api/package/{}/{}/trusted/{}",
+        namespace, package, admin_address
+    ))?;
+    let send = |method: Method, message: String| {
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let request = Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature))
+            .body(Body::empty())
+            .unwrap();
+        Client::new().request(request)
+    };
+
+    // Signature for another package replayed against this package
+    let response = send(
+        Method::POST,
+        format!("add-trusted-key:{}/mock-other/{}", namespace, admin_address),
+    )
+    .await?;
+    assert!(!response.status().is_success());
+
+    let keys = RegistryClient::trusted_keys(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+    )
+    .await?;
+    assert!(keys.is_empty());
+
+    // Trust the administrator key
+    RegistryClient::trusted_key(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        admin_address,
+        true,
+    )
+    .await?;
+
+    // Key is already trusted
+    let result = RegistryClient::trusted_key(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        admin_address,
+        true,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(409))));
+
+    let keys = RegistryClient::trusted_keys(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+    )
+    .await?;
+    assert_eq!(1, keys.len());
+    assert_eq!(admin_address, keys.first().unwrap().address);
+
+    // Signature for adding the key replayed to remove it
+    let response = send(
+        Method::DELETE,
+        format!(
+            "add-trusted-key:{}/{}/{}",
+            namespace, package, admin_address
+        ),
+    )
+    .await?;
+    assert!(!response.status().is_success());
+
+    let keys = RegistryClient::trusted_keys(
+        server_url.clone(),
+        namespace.clone(),
+        package.clone(),
+    )
+    .await?;
+    assert_eq!(1, keys.len());
+
+    // Member without trusted key status is denied
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        member_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Trusted keys also apply to the namespace owner
+    let result = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    // Package is not listed for the member
+    let result = RegistryClient::publisher_packages(
+        server_url.clone(),
+        &member_address,
+        Pager::default(),
+    )
+    .await?;
+    assert_eq!(0, result.count);
+
+    // Trusted key can publish
+    RegistryClient::publish_file(
+        server_url.clone(),
+        admin_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.1.0.tgz"),
+    )
+    .await?;
+
+    // Remove the trusted key
+    RegistryClient::trusted_key(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        admin_address,
+        false,
+    )
+    .await?;
+
+    // Key is no longer trusted
+    let result = RegistryClient::trusted_key(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        package.clone(),
+        admin_address,
+        false,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::ResponseCode(409))));
+
+    // Without trusted keys the namespace permissions apply
+    RegistryClient::publish_file(
+        server_url.clone(),
+        member_key,
+        namespace.clone(),
+        mime,
+        PathBuf::from("fixtures/mock-package-1.2.0.tgz"),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use hyper::{
+    body::to_bytes, header::ALLOW, Body, Client, Method, Request, StatusCode,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_unknown_route() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let url = server_url.join("api/unknown/route")?;
+    let response = Client::new().get(url.as_str().parse()?).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    let body = to_bytes(response.into_body()).await?;
+    let body: Value = serde_json::from_slice(&body)?;
+    assert_eq!(
+        json!({"error": "not_found", "path": "/api/unknown/route"}),
+        body
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_method_not_allowed() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let url = server_url.join("api/signup")?;
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri(url.as_str())
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert_eq!(StatusCode::METHOD_NOT_ALLOWED, response.status());
+    assert_eq!("POST", response.headers().get(ALLOW).unwrap());
+
+    let body = to_bytes(response.into_body()).await?;
+    let body: Value = serde_json::from_slice(&body)?;
+    assert_eq!(
+        json!({"error": "method_not_allowed", "path": "/api/signup"}),
+        body
+    );
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use hyper::{Body, Client, Request};
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, X_SIGNATURE};
+
+use k256::ecdsa::{recoverable, signature::Signer};
+
+#[tokio::test]
+#[serial]
+async fn integration_unyank() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let (other_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let version = Version::new(1, 0, 0);
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+    RegistryClient::signup(server_url.clone(), other_key.clone()).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(namespace, package, version);
+    RegistryClient::yank(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+        String::from("mock yank message"),
+    )
+    .await?;
+
+    // Signature for a yank with an empty message replayed to unyank
+    let signature: recoverable::Signature =
+        signing_key.sign(&id.signed_message("yank", &[]));
+    let mut url = server_url.join("api/package/unyank")?;
+    url.query_pairs_mut().append_pair("id", &id.to_string());
+    let request = Request::post(url.as_str())
+        .header(X_SIGNATURE, base64::encode(signature))
+        .body(Body::empty())?;
+    let response = Client::new().request(request).await?;
+    assert!(!response.status().is_success());
+    let doc =
+        RegistryClient::exact_version(server_url.clone(), id.clone()).await?;
+    assert!(doc.yanked.is_some());
+
+    // Only publishers for the package may restore a version
+    let result =
+        RegistryClient::unyank(server_url.clone(), other_key, id.clone())
+            .await;
+    assert!(matches!(result, Err(Error::ResponseCode(401))));
+
+    RegistryClient::unyank(
+        server_url.clone(),
+        signing_key.clone(),
+        id.clone(),
+    )
+    .await?;
+    let doc =
+        RegistryClient::exact_version(server_url.clone(), id.clone()).await?;
+    assert!(doc.yanked.is_none());
+
+    // Restoring a version that is not yanked is a no-op
+    RegistryClient::unyank(server_url.clone(), signing_key, id.clone())
+        .await?;
+    let doc = RegistryClient::exact_version(server_url, id).await?;
+    assert!(doc.yanked.is_none());
+
+    Ok(())
+}
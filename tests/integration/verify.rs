@@ -0,0 +1,111 @@
+use anyhow::Result;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use serial_test::serial;
+use std::{convert::Infallible, net::SocketAddr, path::PathBuf};
+use url::Url;
+
+use crate::test_utils::*;
+use semver::Version;
+
+use ipfs_registry_client::{Error, RegistryClient};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+use web3_address::ethereum::Address;
+
+/// Spawn a server that responds with a version record
+/// and an artifact.
+fn spawn_mirror(record: String, artifact: Vec<u8>) -> Result<Url> {
+    let make_service = make_service_fn(move |_| {
+        let record = record.clone();
+        let artifact = artifact.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let response = if req.uri().path() == "/api/package/version" {
+                    Response::builder()
+                        .header("content-type", "application/json")
+                        .body(Body::from(record.clone()))
+                } else {
+                    Response::builder().body(Body::from(artifact.clone()))
+                };
+                async move { Ok::<_, Infallible>(response.unwrap()) }
+            }))
+        }
+    });
+
+    let addr: SocketAddr = ([127, 0, 0, 1], 0).into();
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    let url = Url::parse(&format!("http://{}", server.local_addr()))?;
+    tokio::spawn(server);
+    Ok(url)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_verify() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key.clone(),
+        namespace.clone(),
+        mime.clone(),
+        PathBuf::from("fixtures/mock-package-1.0.0.tgz"),
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    let report =
+        ipfs_registry_client::verify(server_url.clone(), id.clone()).await?;
+    assert!(report.checksum);
+    assert!(report.signature);
+    assert!(report.is_ok());
+
+    // Artifact signed again by a key that is not the publisher
+    let mut record =
+        RegistryClient::exact_version(server_url.clone(), id.clone()).await?;
+    let address: Address = signing_key.verifying_key().into();
+    assert_eq!(vec![address], record.signers);
+
+    let artifact =
+        RegistryClient::fetch_bytes(server_url.clone(), id.clone()).await?;
+    let (other_key, _) = new_signing_key();
+    let signature: recoverable::Signature = other_key.sign(
+        record
+            .signature_scheme
+            .message(&artifact, record.checksum.as_slice()),
+    );
+    record.signature = signature.as_ref().try_into()?;
+    let mirror_url = spawn_mirror(serde_json::to_string(&record)?, artifact)?;
+
+    let report = ipfs_registry_client::verify(mirror_url, id).await?;
+    assert!(report.checksum);
+    assert!(!report.signature);
+    assert!(!report.is_ok());
+
+    // Missing versions are an error rather than a report
+    let id = PackageKey::Pointer(namespace, package, Version::new(2, 0, 0));
+    let result = ipfs_registry_client::verify(server_url, id).await;
+    assert!(matches!(result, Err(Error::ResponseCode(404))));
+
+    Ok(())
+}
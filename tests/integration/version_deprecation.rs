@@ -0,0 +1,106 @@
+use anyhow::Result;
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use semver::{Version, VersionReq};
+use sqlx::SqlitePool;
+
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_database::{NamespaceModel, PackageModel, PublisherModel};
+
+#[tokio::test]
+#[serial]
+async fn integration_version_deprecation() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let _namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let mock_package = PackageName::new_unchecked("mock-package");
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+
+    let pointer = mock_pointer(Some(Version::new(1, 0, 0)))?;
+    PackageModel::insert(
+        &pool,
+        &publisher_record,
+        &namespace_record,
+        &address,
+        &pointer,
+        &[],
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        mock_package.clone(),
+        Version::new(1, 0, 0),
+    );
+
+    // Not deprecated yet
+    let (_, _, version) =
+        PackageModel::find_by_key(&pool, &id, Default::default()).await?;
+    assert!(version.unwrap().deprecated.is_none());
+
+    let message = "mock deprecation message";
+    PackageModel::deprecate(
+        &pool,
+        &address,
+        &namespace,
+        &mock_package,
+        message,
+    )
+    .await?;
+
+    // Exact version
+    let (_, _, version) =
+        PackageModel::find_by_key(&pool, &id, Default::default()).await?;
+    assert_eq!(Some(message), version.unwrap().deprecated.as_deref());
+
+    // List versions
+    let versions = PackageModel::list_versions(
+        &pool,
+        &namespace,
+        &mock_package,
+        &Default::default(),
+        false,
+    )
+    .await?;
+    let version = versions.records.first().unwrap();
+    assert_eq!(Some(message), version.deprecated.as_deref());
+
+    // Resolve a range
+    let version = PackageModel::find_best_match(
+        &pool,
+        &namespace,
+        &mock_package,
+        &VersionReq::parse("^1")?,
+        false,
+    )
+    .await?
+    .unwrap();
+    assert_eq!(Some(message), version.deprecated.as_deref());
+
+    // Latest version
+    let version = PackageModel::find_latest_by_name(
+        &pool,
+        &namespace,
+        &mock_package,
+        false,
+        false,
+    )
+    .await?
+    .unwrap();
+    assert_eq!(Some(message), version.deprecated.as_deref());
+
+    Ok(())
+}
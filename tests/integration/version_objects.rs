@@ -0,0 +1,59 @@
+use anyhow::Result;
+use k256::ecdsa::SigningKey;
+use semver::Version;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+
+use crate::test_utils::*;
+
+#[tokio::test]
+#[serial]
+async fn integration_version_objects() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let file = PathBuf::from("fixtures/mock-package-1.0.0.tgz");
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let signing_key = SigningKey::random(&mut rand::thread_rng());
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    let receipt = RegistryClient::publish_file(
+        server_url.clone(),
+        signing_key,
+        namespace.clone(),
+        mime,
+        file,
+    )
+    .await?;
+
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    let objects =
+        RegistryClient::version_objects(server_url.clone(), id).await?;
+    assert_eq!(1, objects.len());
+    assert_eq!(0, objects[0].layer);
+    assert_eq!(receipt.artifact.pointer_id(), objects[0].key.to_string());
+
+    // Version does not exist
+    let id = PackageKey::Pointer(namespace, package, Version::new(2, 0, 0));
+    let result = RegistryClient::version_objects(server_url, id).await;
+    assert!(matches!(
+        result,
+        Err(ipfs_registry_client::Error::ResponseCode(404))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use hyper::{body::to_bytes, Client};
+use serde_json::Value;
+use serial_test::serial;
+use std::path::PathBuf;
+
+use crate::test_utils::*;
+
+use ipfs_registry_client::RegistryClient;
+use ipfs_registry_core::Namespace;
+use ipfs_registry_database::SortOrder;
+use ipfs_registry_server::config::{RegistryConfig, ServerConfig};
+
+/// Publish versions and return the versions listed for a query.
+async fn list_versions(
+    config: ServerConfig,
+    queries: &[&str],
+) -> Result<Vec<Vec<String>>> {
+    let (rx, _handle) = spawn(config)?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+    let namespace = Namespace::new_unchecked("mock-namespace");
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let path = "api/package/mock-namespace/mock-package/versions";
+    let mut results = Vec::new();
+    for query in queries {
+        let url = server_url.join(&format!("{}?{}", path, query))?;
+        let response = Client::new().get(url.as_str().parse()?).await?;
+        assert!(response.status().is_success());
+        let body = to_bytes(response.into_body()).await?;
+        let doc: Value = serde_json::from_slice(&body)?;
+        let versions = doc["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["version"].as_str().unwrap().to_owned())
+            .collect();
+        results.push(versions);
+    }
+    Ok(results)
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_version_sort_default() -> Result<()> {
+    let results =
+        list_versions(default_server_config(), &["", "sort=asc"]).await?;
+
+    // Newest first when the sort is omitted
+    assert_eq!(vec!["1.1.0", "1.0.0"], results[0]);
+    // Explicit sort overrides the default
+    assert_eq!(vec!["1.0.0", "1.1.0"], results[1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn integration_version_sort_configured() -> Result<()> {
+    let config = registry_server_config(RegistryConfig {
+        default_version_sort: SortOrder::Asc,
+        ..Default::default()
+    });
+    let results = list_versions(config, &["", "sort=desc"]).await?;
+
+    assert_eq!(vec!["1.0.0", "1.1.0"], results[0]);
+    assert_eq!(vec!["1.1.0", "1.0.0"], results[1]);
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use hyper::{Body, Client, Request};
 use serial_test::serial;
 use std::path::PathBuf;
 
@@ -6,9 +7,9 @@ use crate::test_utils::*;
 use semver::Version;
 
 use ipfs_registry_client::RegistryClient;
-use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_core::{Namespace, PackageKey, PackageName, X_SIGNATURE};
 
-use k256::ecdsa::SigningKey;
+use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
 
 #[tokio::test]
 #[serial]
@@ -59,3 +60,86 @@ async fn integration_yank() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn integration_yank_signed_id() -> Result<()> {
+    // Spawn the server
+    let (rx, _handle) = spawn(default_server_config())?;
+    let _ = rx.await?;
+
+    let server_url = server();
+
+    let mime: mime::Mime = "application/gzip".parse()?;
+    let (signing_key, _) = new_signing_key();
+
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let package = PackageName::new_unchecked("mock-package");
+    let message = "mock yank message";
+
+    prepare_mock_namespace(&server_url, &signing_key, &namespace).await?;
+
+    for file in [
+        "fixtures/mock-package-1.0.0.tgz",
+        "fixtures/mock-package-1.1.0.tgz",
+    ] {
+        RegistryClient::publish_file(
+            server_url.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime.clone(),
+            PathBuf::from(file),
+        )
+        .await?;
+    }
+
+    let signed = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 0, 0),
+    );
+    let target = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        Version::new(1, 1, 0),
+    );
+
+    let yank = |id: &PackageKey, signature: recoverable::Signature| {
+        let mut url = server_url.join("api/package/yank").unwrap();
+        url.query_pairs_mut().append_pair("id", &id.to_string());
+        let request = Request::post(url.as_str())
+            .header(X_SIGNATURE, base64::encode(signature))
+            .body(Body::from(message))
+            .unwrap();
+        Client::new().request(request)
+    };
+
+    // Signature for one version replayed against another
+    let signature: recoverable::Signature =
+        signing_key.sign(&signed.signed_message("yank", message.as_bytes()));
+    let response = yank(&target, signature).await?;
+    assert!(!response.status().is_success());
+
+    // Signature over the body alone
+    let signature: recoverable::Signature =
+        signing_key.sign(message.as_bytes());
+    let response = yank(&target, signature).await?;
+    assert!(!response.status().is_success());
+
+    for id in [signed, target.clone()] {
+        let doc =
+            RegistryClient::exact_version(server_url.clone(), id).await?;
+        assert!(doc.yanked.is_none());
+    }
+
+    // Signature that includes the target version
+    let signature: recoverable::Signature =
+        signing_key.sign(&target.signed_message("yank", message.as_bytes()));
+    let response = yank(&target, signature).await?;
+    assert!(response.status().is_success());
+
+    let doc = RegistryClient::exact_version(server_url, target).await?;
+    assert_eq!(Some(message.to_owned()), doc.yanked);
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use serial_test::serial;
+
+use crate::test_utils::*;
+
+use semver::{Version, VersionReq};
+use sqlx::SqlitePool;
+
+use ipfs_registry_core::{Namespace, PackageKey, PackageName};
+use ipfs_registry_database::{
+    NamespaceModel, PackageModel, PublisherModel, VersionIncludes,
+};
+
+#[tokio::test]
+#[serial]
+async fn integration_yank_latest() -> Result<()> {
+    let url = "sqlite::memory:";
+    let pool = SqlitePool::connect(url).await?;
+    sqlx::migrate!().run(&pool).await?;
+
+    let (_, address) = new_signing_key();
+
+    // Create a publisher to own the namespace
+    let publisher_id = PublisherModel::insert(&pool, &address).await?;
+
+    // Create a namespace
+    let namespace = Namespace::new_unchecked("mock-namespace");
+    let _namespace_id =
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+    let mock_package = PackageName::new_unchecked("mock-package");
+
+    let (publisher_record, namespace_record) =
+        NamespaceModel::can_access_namespace(&pool, &address, &namespace)
+            .await?;
+
+    // Publish 1.0.0 and 1.0.1
+    for version in [Version::new(1, 0, 0), Version::new(1, 0, 1)] {
+        let pointer = mock_pointer(Some(version))?;
+        PackageModel::insert(
+            &pool,
+            &publisher_record,
+            &namespace_record,
+            &address,
+            &pointer,
+            &[],
+        )
+        .await?;
+    }
+
+    // Yank the top version
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        mock_package.clone(),
+        Version::new(1, 0, 1),
+    );
+    PackageModel::yank(&pool, &address, &id, "mock yank message").await?;
+
+    // Yanked version is still latest when included
+    let latest = PackageModel::find_latest_by_name(
+        &pool,
+        &namespace,
+        &mock_package,
+        false,
+        true,
+    )
+    .await?
+    .unwrap();
+    assert_eq!(Version::new(1, 0, 1), latest.version);
+    assert!(latest.yanked.is_some());
+
+    // Next non-yanked version is latest when excluded
+    let latest = PackageModel::find_latest_by_name(
+        &pool,
+        &namespace,
+        &mock_package,
+        false,
+        false,
+    )
+    .await?
+    .unwrap();
+    assert_eq!(Version::new(1, 0, 0), latest.version);
+    assert!(latest.yanked.is_none());
+
+    let versions = PackageModel::list_versions(
+        &pool,
+        &namespace,
+        &mock_package,
+        &Default::default(),
+        false,
+    )
+    .await?;
+    assert_eq!(1, versions.records.len());
+
+    let versions = PackageModel::find_versions(
+        &pool,
+        &namespace,
+        &mock_package,
+        &VersionReq::parse(">=1.0.0")?,
+        &Default::default(),
+        false,
+    )
+    .await?;
+    assert_eq!(1, versions.records.len());
+
+    let packages = PackageModel::list_packages(
+        &pool,
+        &namespace,
+        &Default::default(),
+        VersionIncludes::Latest,
+        false,
+    )
+    .await?;
+    let package = packages.records.first().unwrap();
+    let version = package.versions.records.first().unwrap();
+    assert_eq!(Version::new(1, 0, 0), version.version);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Content addressed cache of downloaded artifacts.
+use sha3::{Digest, Sha3_256};
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// Environment variable for the cache directory.
+pub const CACHE_DIR_ENV: &str = "IPKG_CACHE_DIR";
+
+/// Default directory for the artifact cache.
+///
+/// Uses `IPKG_CACHE_DIR` when set otherwise an `ipkg` folder
+/// in `XDG_CACHE_HOME` or `~/.cache`.
+pub fn default_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".cache"))
+        })
+        .map(|dir| dir.join("ipkg"))
+}
+
+/// Cache of artifacts keyed by the SHA3-256 checksum.
+///
+/// Entries are verified against the checksum when read so a
+/// corrupted entry is discarded rather than returned.
+pub struct ArtifactCache {
+    dir: PathBuf,
+}
+
+impl ArtifactCache {
+    /// Create a cache in a directory.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Path to the entry for a checksum.
+    pub fn path(&self, checksum: &[u8; 32]) -> PathBuf {
+        self.dir.join(hex::encode(checksum))
+    }
+
+    /// Copy a cached artifact to file.
+    ///
+    /// Returns `false` when there is no entry or the entry does
+    /// not match the checksum in which case it is removed.
+    pub async fn get(
+        &self,
+        checksum: &[u8; 32],
+        file: &Path,
+    ) -> Result<bool> {
+        let path = self.path(checksum);
+        let body = match tokio::fs::read(&path).await {
+            Ok(body) => body,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(false)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if Sha3_256::digest(&body).as_slice() != checksum.as_slice() {
+            tracing::warn!(path = ?path, "cached artifact is corrupt");
+            tokio::fs::remove_file(&path).await?;
+            return Ok(false);
+        }
+
+        tokio::fs::write(file, &body).await?;
+        Ok(true)
+    }
+
+    /// Add a downloaded file to the cache.
+    ///
+    /// Returns `false` when the file does not match the checksum
+    /// and was not added.
+    pub async fn put(
+        &self,
+        checksum: &[u8; 32],
+        file: &Path,
+    ) -> Result<bool> {
+        let body = tokio::fs::read(file).await?;
+        if Sha3_256::digest(&body).as_slice() != checksum.as_slice() {
+            return Ok(false);
+        }
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        // Write to a temporary file first so concurrent readers
+        // never observe a partial entry
+        let path = self.path(checksum);
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+        tokio::fs::write(&temp, &body).await?;
+        tokio::fs::rename(&temp, &path).await?;
+        Ok(true)
+    }
+}
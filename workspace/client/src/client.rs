@@ -1,27 +1,202 @@
-use semver::VersionReq;
+use semver::{Version, VersionReq};
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 use std::{borrow::BorrowMut, path::PathBuf};
 
 use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
 use mime::Mime;
-use reqwest::Client;
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE, RETRY_AFTER},
+    Client, RequestBuilder, Response, StatusCode,
+};
+use sha3::{Digest, Sha3_256};
 
+use std::time::Duration;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tokio::io::AsyncWriteExt;
 use url::Url;
 use web3_address::ethereum::Address;
 
 use ipfs_registry_core::{
-    Namespace, PackageKey, PackageName, Receipt, WELL_KNOWN_MESSAGE,
-    X_SIGNATURE,
+    download_message, expiring_message, solve_work, Namespace, PackageKey,
+    PackageName, Receipt, RegistryKind, SignedUrl, StoredObject, VersionDiff,
+    MSGPACK_MIME, WELL_KNOWN_MESSAGE, X_CHECKSUM, X_NONCE, X_SIGNATURE,
+    X_SIGNATURE_EXPIRES, X_TARGET_SIGNATURE,
 };
 
 use ipfs_registry_database::{
-    NamespaceRecord, PackageRecord, Pager, PublisherRecord, VersionIncludes,
+    AuditFilter, AuditRecord, DependentRecord, NamespaceAccess,
+    NamespaceRecord, PackageRecord, Pager, PublishCheck,
+    PublisherPackageRecord, PublisherRecord, ResolveNamespace, ResolveReport,
+    ResultSet, RotateKey, RotatedSignatureRecord, VersionIncludes,
     VersionRecord,
 };
 
 use crate::{Error, Result};
 
+/// Environment variable for the maximum number of retries.
+const MAX_RETRIES_ENV: &str = "IPKG_MAX_RETRIES";
+
+/// Default maximum number of retries for rate limited requests.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Number of seconds a signature with an expiry is valid for;
+/// long enough to outlast the retries of a rate limited download
+/// and shorter than the maximum the server accepts to allow for
+/// clock skew.
+const SIGNATURE_LIFETIME: i64 = 240;
+
+/// Environment variable for the maximum wait before a retry.
+const MAX_RETRY_AFTER_ENV: &str = "IPKG_MAX_RETRY_AFTER";
+
+/// Default maximum wait in seconds before retrying a
+/// rate limited request.
+const DEFAULT_MAX_RETRY_AFTER: u64 = 60;
+
+/// Default wait in seconds when a rate limited response
+/// does not include a valid Retry-After header.
+const DEFAULT_RETRY_AFTER: u64 = 1;
+
+/// Get the maximum number of retries for rate limited requests.
+fn max_retries() -> u32 {
+    std::env::var(MAX_RETRIES_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_RETRIES)
+}
+
+/// Get the maximum number of seconds to wait before retrying
+/// a rate limited request.
+fn max_retry_after() -> u64 {
+    std::env::var(MAX_RETRY_AFTER_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_RETRY_AFTER)
+}
+
+/// Get the number of seconds to wait from the Retry-After header.
+fn retry_after(response: &Response) -> u64 {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+}
+
+/// Get an error for an unsuccessful response.
+fn response_error(response: &Response) -> Error {
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        Error::RateLimited(retry_after(response))
+    } else {
+        Error::ResponseCode(response.status().into())
+    }
+}
+
+/// Send an idempotent request retrying when rate limited.
+///
+/// When the server asks to wait longer than the maximum wait
+/// the request is not retried and a rate limited error is returned.
+async fn send_with_retry(request: RequestBuilder) -> Result<Response> {
+    let retries = max_retries();
+    let max_wait = max_retry_after();
+    let mut attempts = 0;
+    loop {
+        let builder = match request.try_clone() {
+            Some(builder) => builder,
+            None => return Ok(request.send().await?),
+        };
+
+        let response = builder.send().await?;
+        if response.status() == StatusCode::TOO_MANY_REQUESTS
+            && attempts < retries
+        {
+            let wait = retry_after(&response);
+            if wait > max_wait {
+                return Err(Error::RateLimited(wait));
+            }
+            tracing::debug!(wait = ?wait, "rate limited, retrying");
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+            attempts += 1;
+            continue;
+        }
+        return Ok(response);
+    }
+}
+
+/// Sign a request with a signature that expires.
+///
+/// The signature expires so it is only useful to anyone that
+/// captures the request for a short time; the message to sign
+/// is built from the expiry.
+fn sign_expiring(
+    request: RequestBuilder,
+    signing_key: &SigningKey,
+    message: impl FnOnce(i64) -> Vec<u8>,
+) -> RequestBuilder {
+    let expires =
+        OffsetDateTime::now_utc().unix_timestamp() + SIGNATURE_LIFETIME;
+    let signature: recoverable::Signature =
+        signing_key.sign(&message(expires));
+    request
+        .header(X_SIGNATURE, base64::encode(signature))
+        .header(X_SIGNATURE_EXPIRES, expires.to_string())
+}
+
+/// Sign a download request so it is authorized for a
+/// private namespace.
+fn sign_download(
+    request: RequestBuilder,
+    signing_key: &SigningKey,
+    message: &[u8],
+) -> RequestBuilder {
+    sign_expiring(request, signing_key, |expires| {
+        download_message(message, expires)
+    })
+}
+
+/// Send a download request and stream the response body to file.
+async fn download(request: RequestBuilder, file: PathBuf) -> Result<PathBuf> {
+    let mut response = send_with_retry(request).await?;
+
+    response
+        .status()
+        .is_success()
+        .then_some(())
+        .ok_or_else(|| response_error(&response))?;
+
+    let mut fd = tokio::fs::File::create(&file).await?;
+    while let Some(mut item) = response.chunk().await? {
+        fd.write_all_buf(item.borrow_mut()).await?;
+    }
+
+    fd.flush().await?;
+
+    Ok(file)
+}
+
+/// Accept header for metadata requests.
+const ACCEPT_METADATA: &str = "application/msgpack, application/json";
+
+/// Decode a metadata response using the response content type.
+async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let is_msgpack = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with(MSGPACK_MIME))
+        .unwrap_or(false);
+
+    if is_msgpack {
+        let buffer = response.bytes().await?;
+        let mut deserializer = rmp_serde::Deserializer::new(buffer.as_ref())
+            .with_human_readable();
+        Ok(T::deserialize(&mut deserializer)?)
+    } else {
+        Ok(response.json::<T>().await?)
+    }
+}
+
 /// Package registry client implementation.
 pub struct RegistryClient;
 
@@ -48,17 +223,59 @@ impl RegistryClient {
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         let record: PublisherRecord = response.json().await?;
         Ok(record)
     }
 
     /// Register a namespace.
+    ///
+    /// When a kind is given it overrides the kind configured
+    /// for the registry for packages in the namespace.
     pub async fn register(
         server: Url,
         signing_key: SigningKey,
         namespace: Namespace,
+        kind: Option<RegistryKind>,
+    ) -> Result<NamespaceRecord> {
+        RegistryClient::register_nonce(
+            server,
+            signing_key,
+            namespace,
+            kind,
+            None,
+        )
+        .await
+    }
+
+    /// Register a namespace solving the proof of work
+    /// required by the server.
+    pub async fn register_with_work(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        kind: Option<RegistryKind>,
+        difficulty: u32,
+    ) -> Result<NamespaceRecord> {
+        let nonce = solve_work(&namespace, difficulty);
+        RegistryClient::register_nonce(
+            server,
+            signing_key,
+            namespace,
+            kind,
+            Some(nonce),
+        )
+        .await
+    }
+
+    /// Register a namespace with an optional proof of work nonce.
+    async fn register_nonce(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        kind: Option<RegistryKind>,
+        nonce: Option<u64>,
     ) -> Result<NamespaceRecord> {
         let signature: recoverable::Signature =
             signing_key.sign(namespace.as_bytes());
@@ -67,17 +284,23 @@ impl RegistryClient {
         let client = Client::new();
         let url = server.join(&format!("api/register/{}", namespace))?;
 
-        let response = client
+        let mut request = client
             .post(url)
-            .header(X_SIGNATURE, base64::encode(sign_bytes))
-            .send()
-            .await?;
+            .header(X_SIGNATURE, base64::encode(sign_bytes));
+        if let Some(kind) = kind {
+            request = request.query(&[("kind", kind.to_string())]);
+        }
+        if let Some(nonce) = nonce {
+            request = request.header(X_NONCE, nonce.to_string());
+        }
+
+        let response = request.send().await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         let record: NamespaceRecord = response.json().await?;
         Ok(record)
@@ -88,32 +311,199 @@ impl RegistryClient {
         server: Url,
         key: PackageKey,
         file: PathBuf,
+    ) -> Result<PathBuf> {
+        RegistryClient::fetch_file_key(server, None, key, file).await
+    }
+
+    /// Download a package from a private namespace and write
+    /// it to file.
+    pub async fn fetch_file_signed(
+        server: Url,
+        signing_key: SigningKey,
+        key: PackageKey,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        RegistryClient::fetch_file_key(server, Some(signing_key), key, file)
+            .await
+    }
+
+    /// Download a package signing the identifier when a
+    /// signing key is given.
+    async fn fetch_file_key(
+        server: Url,
+        signing_key: Option<SigningKey>,
+        key: PackageKey,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        if file.exists() {
+            return Err(Error::FileExists(file));
+        }
+
+        let url = server.join("api/package")?;
+
+        let client = Client::new();
+        let id = key.to_string();
+        let mut request = client.get(url).query(&[("id", &id)]);
+        if let Some(signing_key) = signing_key {
+            request = sign_download(request, &signing_key, id.as_bytes());
+        }
+
+        download(request, file).await
+    }
+
+    /// Download a package version using the path route and
+    /// write it to file.
+    pub async fn fetch_file_by_path(
+        server: Url,
+        namespace: Namespace,
+        package: PackageName,
+        version: Version,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        RegistryClient::fetch_file_path(
+            server, None, namespace, package, version, file,
+        )
+        .await
+    }
+
+    /// Download a package version from a private namespace
+    /// using the path route and write it to file.
+    pub async fn fetch_file_by_path_signed(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        package: PackageName,
+        version: Version,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        RegistryClient::fetch_file_path(
+            server,
+            Some(signing_key),
+            namespace,
+            package,
+            version,
+            file,
+        )
+        .await
+    }
+
+    /// Download a package version using the path route signing
+    /// the pointer when a signing key is given.
+    async fn fetch_file_path(
+        server: Url,
+        signing_key: Option<SigningKey>,
+        namespace: Namespace,
+        package: PackageName,
+        version: Version,
+        file: PathBuf,
     ) -> Result<PathBuf> {
         if file.exists() {
             return Err(Error::FileExists(file));
         }
 
+        let url = server.join(&format!(
+            "api/package/{}/{}/{}/download",
+            namespace, package, version
+        ))?;
+
+        let client = Client::new();
+        let mut request = client.get(url);
+        if let Some(signing_key) = signing_key {
+            let key = PackageKey::Pointer(namespace, package, version);
+            request = sign_download(
+                request,
+                &signing_key,
+                key.to_string().as_bytes(),
+            );
+        }
+
+        download(request, file).await
+    }
+
+    /// Download a package into memory.
+    pub async fn fetch_bytes(
+        server: Url,
+        key: PackageKey,
+    ) -> Result<Vec<u8>> {
         let url = server.join("api/package")?;
 
         let client = Client::new();
         let request = client.get(url).query(&[("id", key.to_string())]);
 
-        let mut response = request.send().await?;
+        let response = send_with_retry(request).await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Download all versions of a package as a tar archive and
+    /// write it to file.
+    pub async fn fetch_bundle(
+        server: Url,
+        namespace: Namespace,
+        package: PackageName,
+        range: Option<VersionReq>,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        RegistryClient::fetch_bundle_key(
+            server, None, namespace, package, range, file,
+        )
+        .await
+    }
+
+    /// Download all versions of a package from a private
+    /// namespace as a tar archive and write it to file.
+    pub async fn fetch_bundle_signed(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        package: PackageName,
+        range: Option<VersionReq>,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        RegistryClient::fetch_bundle_key(
+            server,
+            Some(signing_key),
+            namespace,
+            package,
+            range,
+            file,
+        )
+        .await
+    }
 
-        let mut fd = tokio::fs::File::create(&file).await?;
-        while let Some(mut item) = response.chunk().await? {
-            fd.write_all_buf(item.borrow_mut()).await?;
+    /// Download a bundle signing the package path when a
+    /// signing key is given.
+    async fn fetch_bundle_key(
+        server: Url,
+        signing_key: Option<SigningKey>,
+        namespace: Namespace,
+        package: PackageName,
+        range: Option<VersionReq>,
+        file: PathBuf,
+    ) -> Result<PathBuf> {
+        if file.exists() {
+            return Err(Error::FileExists(file));
         }
 
-        fd.flush().await?;
+        let path = format!("{}/{}", namespace, package);
+        let url = server.join(&format!("api/package/{}/bundle", path))?;
+
+        let client = Client::new();
+        let mut request = client.get(url);
+        if let Some(range) = range {
+            request = request.query(&[("range", range.to_string())]);
+        }
+        if let Some(signing_key) = signing_key {
+            request = sign_download(request, &signing_key, path.as_bytes());
+        }
 
-        Ok(file)
+        download(request, file).await
     }
 
     /// Publish a package file with the given signing key.
@@ -123,31 +513,167 @@ impl RegistryClient {
         namespace: Namespace,
         mime: Mime,
         file: PathBuf,
+    ) -> Result<Receipt> {
+        RegistryClient::publish_signed(
+            server,
+            signing_key,
+            namespace,
+            mime,
+            file,
+            false,
+            false,
+            false,
+        )
+        .await
+    }
+
+    /// Publish a package file signing the checksum of the file
+    /// rather than the file bytes.
+    pub async fn publish_file_checksum(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        mime: Mime,
+        file: PathBuf,
+    ) -> Result<Receipt> {
+        RegistryClient::publish_signed(
+            server,
+            signing_key,
+            namespace,
+            mime,
+            file,
+            true,
+            false,
+            false,
+        )
+        .await
+    }
+
+    /// Force publish a package file replacing an existing version
+    /// that is still within the server's overwrite window.
+    pub async fn publish_file_force(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        mime: Mime,
+        file: PathBuf,
+        sign_checksum: bool,
+    ) -> Result<Receipt> {
+        RegistryClient::publish_signed(
+            server,
+            signing_key,
+            namespace,
+            mime,
+            file,
+            sign_checksum,
+            true,
+            false,
+        )
+        .await
+    }
+
+    /// Publish a package file and include the package meta data,
+    /// file size and README in the receipt.
+    pub async fn publish_file_verbose(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        mime: Mime,
+        file: PathBuf,
+        sign_checksum: bool,
+    ) -> Result<Receipt> {
+        RegistryClient::publish_signed(
+            server,
+            signing_key,
+            namespace,
+            mime,
+            file,
+            sign_checksum,
+            false,
+            true,
+        )
+        .await
+    }
+
+    /// Publish a package file signing either the file bytes
+    /// or the checksum of the file.
+    ///
+    /// When `verbose` is set the receipt includes the package
+    /// meta data, file size and README.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn publish_signed(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        mime: Mime,
+        file: PathBuf,
+        sign_checksum: bool,
+        force: bool,
+        verbose: bool,
     ) -> Result<Receipt> {
         if !file.is_file() {
             return Err(Error::NotFile(file));
         }
 
         let body = std::fs::read(file)?;
-        let signature: recoverable::Signature = signing_key.sign(&body);
+        RegistryClient::publish_body(
+            server,
+            signing_key,
+            namespace,
+            mime,
+            body,
+            sign_checksum,
+            force,
+            verbose,
+        )
+        .await
+    }
+
+    /// Publish the bytes of a package archive signing either the
+    /// bytes or the checksum of the bytes.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn publish_body(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        mime: Mime,
+        body: Vec<u8>,
+        sign_checksum: bool,
+        force: bool,
+        verbose: bool,
+    ) -> Result<Receipt> {
+        let checksum = Sha3_256::digest(&body);
+        let signature: recoverable::Signature = if sign_checksum {
+            signing_key.sign(checksum.as_slice())
+        } else {
+            signing_key.sign(&body)
+        };
         let sign_bytes = &signature;
 
         let client = Client::new();
         let url = server.join(&format!("api/package/{}", namespace))?;
 
-        let response = client
+        let mut request = client
             .post(url)
             .header(X_SIGNATURE, base64::encode(sign_bytes))
-            .header("content-type", mime.to_string())
-            .body(body)
-            .send()
-            .await?;
+            .header("content-type", mime.to_string());
+        if sign_checksum {
+            request = request.header(X_CHECKSUM, hex::encode(checksum));
+        }
+        if force {
+            request = request.query(&[("force", "true")]);
+        }
+        if verbose {
+            request = request.query(&[("verbose", "true")]);
+        }
+
+        let response = request.body(body).send().await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         let doc: Receipt = response.json().await?;
         Ok(doc)
@@ -189,11 +715,38 @@ impl RegistryClient {
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         Ok(())
     }
 
+    /// Get the access control list for a namespace.
+    pub async fn list_access(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+    ) -> Result<NamespaceAccess> {
+        let client = Client::new();
+        let url =
+            server.join(&format!("api/namespace/{}/access", namespace))?;
+
+        let message = format!("access:{}", namespace);
+        let request = sign_expiring(
+            client.get(url).header(ACCEPT, ACCEPT_METADATA),
+            &signing_key,
+            |expires| expiring_message(message.as_bytes(), expires),
+        );
+        let response = send_with_retry(request).await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<NamespaceAccess>(response).await
+    }
+
     /// Remove a user from a namespace.
     pub async fn remove_user(
         server: Url,
@@ -219,7 +772,7 @@ impl RegistryClient {
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         Ok(())
     }
@@ -258,33 +811,43 @@ impl RegistryClient {
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         Ok(())
     }
 
-    /// Deprecate a package.
-    pub async fn deprecate(
+    /// Add or remove a trusted key for a package.
+    pub async fn trusted_key(
         server: Url,
         signing_key: SigningKey,
         namespace: Namespace,
         package: PackageName,
-        body: String,
+        key: Address,
+        trust: bool,
     ) -> Result<()> {
+        let action = if trust { "add" } else { "remove" };
+        let message = format!(
+            "{}-trusted-key:{}/{}/{}",
+            action, namespace, package, key
+        );
         let signature: recoverable::Signature =
-            signing_key.sign(body.as_bytes());
+            signing_key.sign(message.as_bytes());
         let sign_bytes = &signature;
 
         let client = Client::new();
         let url = server.join(&format!(
-            "api/package/{}/{}/deprecate",
-            namespace, package
+            "api/package/{}/{}/trusted/{}",
+            namespace, package, key
         ))?;
 
-        let response = client
-            .post(url)
+        let builder = if trust {
+            client.post(url)
+        } else {
+            client.delete(url)
+        };
+
+        let response = builder
             .header(X_SIGNATURE, base64::encode(sign_bytes))
-            .body(body)
             .send()
             .await?;
 
@@ -292,63 +855,678 @@ impl RegistryClient {
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
         Ok(())
     }
 
-    /// Yank a version.
-    pub async fn yank(
+    /// Check whether an address can publish to a package.
+    pub async fn can_publish(
         server: Url,
         signing_key: SigningKey,
-        id: PackageKey,
-        body: String,
-    ) -> Result<()> {
+        namespace: Namespace,
+        package: PackageName,
+        address: Address,
+        version: Option<Version>,
+    ) -> Result<PublishCheck> {
+        let message =
+            format!("can-publish:{}/{}/{}", namespace, package, address);
         let signature: recoverable::Signature =
-            signing_key.sign(body.as_bytes());
+            signing_key.sign(message.as_bytes());
         let sign_bytes = &signature;
 
         let client = Client::new();
-        let url = server.join("api/package/yank")?;
+        let url = server.join(&format!(
+            "api/package/{}/{}/can-publish",
+            namespace, package
+        ))?;
 
-        let response = client
-            .post(url)
-            .query(&[("id", id.to_string())])
-            .header(X_SIGNATURE, base64::encode(sign_bytes))
-            .body(body)
-            .send()
-            .await?;
+        let mut query = vec![("address", address.to_string())];
+        if let Some(version) = version {
+            query.push(("version", version.to_string()));
+        }
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .query(&query)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .header(X_SIGNATURE, base64::encode(sign_bytes)),
+        )
+        .await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
-        Ok(())
+        decode::<PublishCheck>(response).await
     }
 
-    /// Get a namespace record.
-    pub async fn get_namespace(
+    /// List the trusted keys for a package.
+    pub async fn trusted_keys(
         server: Url,
         namespace: Namespace,
-    ) -> Result<NamespaceRecord> {
+        package: PackageName,
+    ) -> Result<Vec<PublisherRecord>> {
         let client = Client::new();
-        let url = server.join(&format!("api/package/{}", namespace))?;
+        let url = server.join(&format!(
+            "api/package/{}/{}/trusted",
+            namespace, package
+        ))?;
 
-        let response = client.get(url).send().await?;
+        let response = send_with_retry(client.get(url)).await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
-        Ok(response.json::<NamespaceRecord>().await?)
+        Ok(response.json::<Vec<PublisherRecord>>().await?)
     }
 
-    /// Get a package record.
-    pub async fn get_package(
+    /// Deprecate a package.
+    pub async fn deprecate(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        package: PackageName,
+        body: String,
+    ) -> Result<()> {
+        let signature: recoverable::Signature =
+            signing_key.sign(body.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join(&format!(
+            "api/package/{}/{}/deprecate",
+            namespace, package
+        ))?;
+
+        let response = client
+            .post(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .body(body)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(())
+    }
+
+    /// Set the description for a namespace.
+    pub async fn set_namespace_description(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        description: String,
+    ) -> Result<NamespaceRecord> {
+        let message = format!("{}:{}", namespace, description);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join(&format!("api/namespace/{}", namespace))?;
+
+        let response = client
+            .put(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .body(description)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        let record: NamespaceRecord = response.json().await?;
+        Ok(record)
+    }
+
+    /// Set whether downloads from a namespace must be signed
+    /// by a user of the namespace.
+    pub async fn set_private(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        private: bool,
+    ) -> Result<NamespaceRecord> {
+        let action = if private { "private" } else { "public" };
+        let message = format!("{}:{}", action, namespace);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url =
+            server.join(&format!("api/namespace/{}/private", namespace))?;
+
+        let request = if private {
+            client.post(url)
+        } else {
+            client.delete(url)
+        };
+
+        let response = request
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        let record: NamespaceRecord = response.json().await?;
+        Ok(record)
+    }
+
+    /// Set whether users of a namespace without package
+    /// restrictions must be granted access before publishing.
+    pub async fn set_default_restricted(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        default_restricted: bool,
+    ) -> Result<NamespaceRecord> {
+        let action = if default_restricted {
+            "restricted"
+        } else {
+            "unrestricted"
+        };
+        let message = format!("{}:{}", action, namespace);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server
+            .join(&format!("api/namespace/{}/restricted", namespace))?;
+
+        let request = if default_restricted {
+            client.post(url)
+        } else {
+            client.delete(url)
+        };
+
+        let response = request
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        let record: NamespaceRecord = response.json().await?;
+        Ok(record)
+    }
+
+    /// Deprecate a namespace.
+    pub async fn deprecate_namespace(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        body: String,
+    ) -> Result<NamespaceRecord> {
+        let message = format!("deprecate:{}:{}", namespace, body);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url =
+            server.join(&format!("api/namespace/{}/deprecate", namespace))?;
+
+        let response = client
+            .post(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .body(body)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        let record: NamespaceRecord = response.json().await?;
+        Ok(record)
+    }
+
+    /// Resolve a namespace collision by renaming the namespace
+    /// or merging it into another namespace.
+    ///
+    /// The signing key must belong to a registry administrator.
+    pub async fn resolve_namespace(
+        server: Url,
+        signing_key: SigningKey,
+        request: ResolveNamespace,
+    ) -> Result<ResolveReport> {
+        let body = serde_json::to_vec(&request)?;
+        let signature: recoverable::Signature = signing_key.sign(&body);
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join("api/admin/namespace/resolve")?;
+
+        let response = client
+            .post(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(response.json::<ResolveReport>().await?)
+    }
+
+    /// List entries in the audit log using an administrator key.
+    ///
+    /// The signature is of the endpoint name and query string
+    /// with an expiry.
+    pub async fn audit(
+        server: Url,
+        signing_key: SigningKey,
+        filter: AuditFilter,
+        pager: Pager,
+    ) -> Result<ResultSet<AuditRecord>> {
+        let mut url = server.join("api/admin/audit")?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(action) = &filter.action {
+                query.append_pair("action", action);
+            }
+            if let Some(namespace) = &filter.namespace {
+                query.append_pair("namespace", namespace.as_str());
+            }
+            if let Some(address) = &filter.address {
+                query.append_pair("address", &address.to_string());
+            }
+            if let Some(since) = &filter.since {
+                query.append_pair("since", &since.format(&Rfc3339)?);
+            }
+            if let Some(until) = &filter.until {
+                query.append_pair("until", &until.format(&Rfc3339)?);
+            }
+            query.append_pair("offset", &pager.offset.to_string());
+            query.append_pair("limit", &pager.limit.to_string());
+            query.append_pair("sort", &pager.sort.to_string());
+        }
+
+        let message = format!("audit:{}", url.query().unwrap_or_default());
+        let client = Client::new();
+        let response = sign_expiring(
+            client.get(url).header(ACCEPT, ACCEPT_METADATA),
+            &signing_key,
+            |expires| expiring_message(message.as_bytes(), expires),
+        )
+        .send()
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<ResultSet<AuditRecord>>(response).await
+    }
+
+    /// Rotate the key of a publisher using an administrator key.
+    pub async fn rotate_key(
+        server: Url,
+        signing_key: SigningKey,
+        request: RotateKey,
+    ) -> Result<PublisherRecord> {
+        let body = serde_json::to_vec(&request)?;
+        let signature: recoverable::Signature = signing_key.sign(&body);
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join("api/admin/publisher/rotate")?;
+
+        let response = client
+            .post(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(response.json::<PublisherRecord>().await?)
+    }
+
+    /// List versions signed by keys that have been rotated out
+    /// using an administrator key.
+    ///
+    /// The signature is of the endpoint name and query string
+    /// with an expiry.
+    pub async fn rotated_signatures(
+        server: Url,
+        signing_key: SigningKey,
+        pager: Pager,
+    ) -> Result<ResultSet<RotatedSignatureRecord>> {
+        let mut url = server.join("api/admin/signatures/rotated")?;
+        url.query_pairs_mut()
+            .append_pair("offset", &pager.offset.to_string())
+            .append_pair("limit", &pager.limit.to_string())
+            .append_pair("sort", &pager.sort.to_string());
+
+        let message =
+            format!("rotated-signatures:{}", url.query().unwrap_or_default());
+        let client = Client::new();
+        let response = sign_expiring(
+            client.get(url).header(ACCEPT, ACCEPT_METADATA),
+            &signing_key,
+            |expires| expiring_message(message.as_bytes(), expires),
+        )
+        .send()
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<ResultSet<RotatedSignatureRecord>>(response).await
+    }
+
+    /// Remove the deprecation notice for a namespace.
+    pub async fn undeprecate_namespace(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+    ) -> Result<NamespaceRecord> {
+        let message = format!("undeprecate:{}", namespace);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url =
+            server.join(&format!("api/namespace/{}/deprecate", namespace))?;
+
+        let response = client
+            .delete(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        let record: NamespaceRecord = response.json().await?;
+        Ok(record)
+    }
+
+    /// Set the description for a package.
+    pub async fn set_package_description(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        package: PackageName,
+        description: String,
+    ) -> Result<PackageRecord> {
+        let message = format!("{}/{}:{}", namespace, package, description);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url =
+            server.join(&format!("api/package/{}/{}", namespace, package))?;
+
+        let response = client
+            .put(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .body(description)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        let record: PackageRecord = response.json().await?;
+        Ok(record)
+    }
+
+    /// Transfer a package to another namespace.
+    ///
+    /// The signing key is for the owner of the source namespace
+    /// and the target key for the owner of the target namespace;
+    /// when no target key is given the signing key signs for both
+    /// namespaces which is also used by registry administrators.
+    pub async fn transfer(
+        server: Url,
+        signing_key: SigningKey,
+        target_key: Option<SigningKey>,
+        namespace: Namespace,
+        package: PackageName,
+        target: Namespace,
+    ) -> Result<PackageRecord> {
+        let message = format!("{}/{}/{}", namespace, package, target);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+        let target_signature: recoverable::Signature = target_key
+            .as_ref()
+            .unwrap_or(&signing_key)
+            .sign(message.as_bytes());
+
+        let client = Client::new();
+        let url = server.join(&format!(
+            "api/package/{}/{}/transfer/{}",
+            namespace, package, target
+        ))?;
+
+        let response = client
+            .post(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .header(X_TARGET_SIGNATURE, base64::encode(target_signature))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(response.json::<PackageRecord>().await?)
+    }
+
+    /// Transfer ownership of a namespace to another publisher.
+    pub async fn transfer_namespace(
+        server: Url,
+        signing_key: SigningKey,
+        namespace: Namespace,
+        owner: Address,
+    ) -> Result<NamespaceRecord> {
+        let message = format!("{}/{}", namespace, owner);
+        let signature: recoverable::Signature =
+            signing_key.sign(message.as_bytes());
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join(&format!(
+            "api/namespace/{}/transfer/{}",
+            namespace, owner
+        ))?;
+
+        let response = client
+            .post(url)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(response.json::<NamespaceRecord>().await?)
+    }
+
+    /// Yank a version.
+    pub async fn yank(
+        server: Url,
+        signing_key: SigningKey,
+        id: PackageKey,
+        body: String,
+    ) -> Result<()> {
+        let signature: recoverable::Signature =
+            signing_key.sign(&id.signed_message("yank", body.as_bytes()));
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join("api/package/yank")?;
+
+        let response = client
+            .post(url)
+            .query(&[("id", id.to_string())])
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .body(body)
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(())
+    }
+
+    /// Restore a yanked version.
+    pub async fn unyank(
+        server: Url,
+        signing_key: SigningKey,
+        id: PackageKey,
+    ) -> Result<()> {
+        let signature: recoverable::Signature =
+            signing_key.sign(&id.signed_message("unyank", &[]));
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join("api/package/unyank")?;
+
+        let response = client
+            .post(url)
+            .query(&[("id", id.to_string())])
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(())
+    }
+
+    /// Create a signed URL to download a package version without
+    /// a signature until the URL expires.
+    ///
+    /// The returned URL is relative to the server.
+    pub async fn sign_url(
+        server: Url,
+        signing_key: SigningKey,
+        id: PackageKey,
+        expires_in: Option<u64>,
+    ) -> Result<SignedUrl> {
+        let expires_in_message = expires_in
+            .map(|expires_in| expires_in.to_string())
+            .unwrap_or_default();
+        let signature: recoverable::Signature = signing_key.sign(
+            &id.signed_message("sign-url", expires_in_message.as_bytes()),
+        );
+        let sign_bytes = &signature;
+
+        let client = Client::new();
+        let url = server.join("api/package/version/sign-url")?;
+
+        let mut query = vec![("id", id.to_string())];
+        if let Some(expires_in) = expires_in {
+            query.push(("expires_in", expires_in.to_string()));
+        }
+
+        let response = client
+            .post(url)
+            .query(&query)
+            .header(X_SIGNATURE, base64::encode(sign_bytes))
+            .send()
+            .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<SignedUrl>(response).await
+    }
+
+    /// Get a namespace record.
+    pub async fn get_namespace(
+        server: Url,
+        namespace: Namespace,
+    ) -> Result<NamespaceRecord> {
+        let client = Client::new();
+        let url = server.join(&format!("api/package/{}", namespace))?;
+
+        let response =
+            send_with_retry(client.get(url).header(ACCEPT, ACCEPT_METADATA))
+                .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<NamespaceRecord>(response).await
+    }
+
+    /// Get a package record.
+    pub async fn get_package(
         server: Url,
         namespace: Namespace,
         package: PackageName,
@@ -357,15 +1535,17 @@ impl RegistryClient {
         let url =
             server.join(&format!("api/package/{}/{}", namespace, package))?;
 
-        let response = client.get(url).send().await?;
+        let response =
+            send_with_retry(client.get(url).header(ACCEPT, ACCEPT_METADATA))
+                .await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
-        Ok(response.json::<PackageRecord>().await?)
+        decode::<PackageRecord>(response).await
     }
 
     /// Get an exact version.
@@ -376,19 +1556,86 @@ impl RegistryClient {
         let client = Client::new();
         let url = server.join("api/package/version")?;
 
-        let response = client
-            .get(url)
-            .query(&[("id", id.to_string())])
-            .send()
-            .await?;
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&[("id", id.to_string())]),
+        )
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<VersionRecord>(response).await
+    }
+
+    /// Find the versions that match a package archive checksum.
+    pub async fn find_by_checksum(
+        server: Url,
+        checksum: [u8; 32],
+    ) -> Result<Vec<VersionRecord>> {
+        let client = Client::new();
+        let url = server.join("api/package/version/by-checksum")?;
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&[("sha3", hex::encode(checksum))]),
+        )
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<Vec<VersionRecord>>(response).await
+    }
+
+    /// Get the stored manifest for an exact version.
+    pub async fn manifest(server: Url, id: PackageKey) -> Result<Value> {
+        let client = Client::new();
+        let url = server.join("api/package/version/manifest")?;
+
+        let response =
+            send_with_retry(client.get(url).query(&[("id", id.to_string())]))
+                .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        Ok(response.json::<Value>().await?)
+    }
+
+    /// Get the object keys stored in each layer for an exact
+    /// version of a package.
+    pub async fn version_objects(
+        server: Url,
+        id: PackageKey,
+    ) -> Result<Vec<StoredObject>> {
+        let client = Client::new();
+        let url = server.join("api/package/version/objects")?;
+
+        let response =
+            send_with_retry(client.get(url).query(&[("id", id.to_string())]))
+                .await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
-        Ok(response.json::<VersionRecord>().await?)
+        Ok(response.json::<Vec<StoredObject>>().await?)
     }
 
     /// List packages and versions.
@@ -399,6 +1646,7 @@ impl RegistryClient {
         pager: Pager,
         include: Option<VersionIncludes>,
         range: Option<VersionReq>,
+        include_yanked: bool,
     ) -> Result<T> {
         let client = Client::new();
         let url = if let Some(package) = &package {
@@ -416,6 +1664,10 @@ impl RegistryClient {
             ("sort", pager.sort.to_string()),
         ];
 
+        if let Some(cursor) = pager.cursor {
+            query.push(("cursor", cursor));
+        }
+
         if let (Some(include), true) = (include, package.is_none()) {
             query.push(("include", include.to_string()));
         }
@@ -424,32 +1676,183 @@ impl RegistryClient {
             query.push(("range", range.to_string()));
         }
 
-        let response = client.get(url).query(&query).send().await?;
+        if !include_yanked {
+            query.push(("include_yanked", include_yanked.to_string()));
+        }
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&query),
+        )
+        .await?;
 
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
+            .ok_or_else(|| response_error(&response))?;
 
-        Ok(response.json::<T>().await?)
+        decode::<T>(response).await
+    }
+
+    /// Search packages in all namespaces by name prefix.
+    pub async fn search(
+        server: Url,
+        query: String,
+        pager: Pager,
+    ) -> Result<ResultSet<PackageRecord>> {
+        let client = Client::new();
+        let url = server.join("api/search")?;
+
+        let query = vec![
+            ("q", query),
+            ("offset", pager.offset.to_string()),
+            ("limit", pager.limit.to_string()),
+            ("sort", pager.sort.to_string()),
+        ];
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&query),
+        )
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<ResultSet<PackageRecord>>(response).await
+    }
+
+    /// List the packages a publisher is allowed to publish to.
+    pub async fn publisher_packages(
+        server: Url,
+        address: &Address,
+        pager: Pager,
+    ) -> Result<ResultSet<PublisherPackageRecord>> {
+        let client = Client::new();
+        let url =
+            server.join(&format!("api/publisher/{}/packages", address))?;
+
+        let query = vec![
+            ("offset", pager.offset.to_string()),
+            ("limit", pager.limit.to_string()),
+            ("sort", pager.sort.to_string()),
+        ];
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&query),
+        )
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<ResultSet<PublisherPackageRecord>>(response).await
+    }
+
+    /// List the versions of packages that depend on a package.
+    pub async fn dependents(
+        server: Url,
+        namespace: Namespace,
+        package: PackageName,
+        pager: Pager,
+    ) -> Result<ResultSet<DependentRecord>> {
+        let client = Client::new();
+        let url = server.join(&format!(
+            "api/package/{}/{}/dependents",
+            namespace, package
+        ))?;
+
+        let query = vec![
+            ("offset", pager.offset.to_string()),
+            ("limit", pager.limit.to_string()),
+            ("sort", pager.sort.to_string()),
+        ];
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&query),
+        )
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<ResultSet<DependentRecord>>(response).await
+    }
+
+    /// Compare the manifests of two versions of a package.
+    pub async fn diff(
+        server: Url,
+        namespace: Namespace,
+        package: PackageName,
+        from: Version,
+        to: Version,
+    ) -> Result<VersionDiff> {
+        let client = Client::new();
+        let url = server
+            .join(&format!("api/package/{}/{}/diff", namespace, package))?;
+
+        let response = send_with_retry(
+            client
+                .get(url)
+                .header(ACCEPT, ACCEPT_METADATA)
+                .query(&[("from", from.to_string()), ("to", to.to_string())]),
+        )
+        .await?;
+
+        response
+            .status()
+            .is_success()
+            .then_some(())
+            .ok_or_else(|| response_error(&response))?;
+
+        decode::<VersionDiff>(response).await
     }
 
     /// Get the latest version for a package.
+    ///
+    /// When a range is given the highest version that satisfies
+    /// the range is returned.
     pub async fn latest_version(
         server: Url,
         namespace: Namespace,
         package: PackageName,
+        range: Option<VersionReq>,
     ) -> Result<VersionRecord> {
         let client = Client::new();
         let url = server
             .join(&format!("api/package/{}/{}/latest", namespace, package))?;
-        let response = client.get(url).send().await?;
+
+        let mut request = client.get(url).header(ACCEPT, ACCEPT_METADATA);
+        if let Some(range) = range {
+            request = request.query(&[("range", range.to_string())]);
+        }
+
+        let response = send_with_retry(request).await?;
         response
             .status()
             .is_success()
             .then_some(())
-            .ok_or_else(|| Error::ResponseCode(response.status().into()))?;
-        Ok(response.json::<VersionRecord>().await?)
+            .ok_or_else(|| response_error(&response))?;
+        decode::<VersionRecord>(response).await
     }
 }
@@ -1,23 +1,39 @@
 use k256::ecdsa::SigningKey;
 use mime::Mime;
-use semver::VersionReq;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
 
 use secrecy::ExposeSecret;
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    fmt,
+    io::Read,
+    path::{Path, PathBuf},
+};
 use url::Url;
 use web3_address::ethereum::Address;
 use web3_keystore::encrypt;
 
 use ipfs_registry_core::{
-    AnyRef, Namespace, PackageKey, PackageName, PathRef, Receipt,
+    AnyRef, DefaultIdentity, Identity, Namespace, PackageKey, PackageName,
+    PathRef, Receipt, RegistryKind, SignedUrl, VersionDiff,
 };
 use ipfs_registry_database::{
-    NamespaceRecord, PackageRecord, Pager, PublisherRecord, ResultSet,
+    AuditFilter, AuditRecord, NamespaceAccess, NamespaceAction,
+    NamespaceRecord, PackageRecord, Pager, PublisherRecord, ResolveNamespace,
+    ResolveReport, ResultSet, RotateKey, RotatedSignatureRecord,
     VersionIncludes, VersionRecord,
 };
 
-use crate::{helpers, input, Error, RegistryClient, Result};
+use crate::{helpers, input, ArtifactCache, Error, RegistryClient, Result};
+
+/// Name of the manifest entry in a bundle archive.
+const BUNDLE_MANIFEST: &str = "versions.json";
+
+/// Artifacts in a bundle archive keyed by checksum.
+type BundleArtifacts = HashMap<[u8; 32], Vec<u8>>;
 
 /// Enumeration of types for a get operation.
 #[derive(Serialize, Deserialize)]
@@ -41,17 +57,123 @@ pub enum ListRecord {
     Versions(ResultSet<VersionRecord>),
 }
 
+/// Status of a package key in a lockfile check.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Version is available.
+    Ok,
+    /// Version does not exist in the registry.
+    Missing,
+    /// Version has been yanked.
+    Yanked,
+    /// Package or namespace has been deprecated.
+    Deprecated,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ok => "ok",
+                Self::Missing => "missing",
+                Self::Yanked => "yanked",
+                Self::Deprecated => "deprecated",
+            }
+        )
+    }
+}
+
+/// Result of checking a single package key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckEntry {
+    /// The package key.
+    pub id: PackageKey,
+    /// Status of the package version.
+    pub status: CheckStatus,
+    /// Yank or deprecation message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Report for a lockfile check.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// Entries in lockfile order.
+    pub entries: Vec<CheckEntry>,
+}
+
+impl CheckReport {
+    /// Determine if every entry is available.
+    pub fn is_ok(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| entry.status == CheckStatus::Ok)
+    }
+}
+
+/// Report for mirroring a bundle to a registry.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MirrorReport {
+    /// Versions that were published.
+    pub published: Vec<Version>,
+    /// Versions that were yanked after they were published.
+    pub yanked: Vec<Version>,
+    /// Versions without an artifact in the bundle.
+    pub skipped: Vec<Version>,
+    /// Deprecation message set for the package.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+}
+
+/// Report for verifying the integrity of a version.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// The package key.
+    pub id: PackageKey,
+    /// Whether the checksum of the artifact matches the version record.
+    pub checksum: bool,
+    /// Whether the signature of the version record is valid
+    /// for the artifact.
+    pub signature: bool,
+    /// Reason the artifact could not be verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl VerifyReport {
+    /// Determine if the artifact passed all integrity checks.
+    pub fn is_ok(&self) -> bool {
+        self.checksum && self.signature
+    }
+}
+
 /// Publish a package.
+#[allow(clippy::too_many_arguments)]
 pub async fn publish(
     server: Url,
     namespace: Namespace,
     mime: Mime,
     key: PathBuf,
     file: PathBuf,
+    sign_checksum: bool,
+    force: bool,
+    verbose: bool,
 ) -> Result<Receipt> {
     let signing_key = helpers::read_keystore_file(key)?;
-    RegistryClient::publish_file(server, signing_key, namespace, mime, file)
-        .await
+    RegistryClient::publish_signed(
+        server,
+        signing_key,
+        namespace,
+        mime,
+        file,
+        sign_checksum,
+        force,
+        verbose,
+    )
+    .await
 }
 
 /// Signup for publishing.
@@ -65,18 +187,286 @@ pub async fn register(
     server: Url,
     key: PathBuf,
     namespace: Namespace,
+    kind: Option<RegistryKind>,
+    difficulty: Option<u32>,
 ) -> Result<NamespaceRecord> {
     let signing_key = helpers::read_keystore_file(key)?;
-    RegistryClient::register(server, signing_key, namespace).await
+    if let Some(difficulty) = difficulty {
+        RegistryClient::register_with_work(
+            server,
+            signing_key,
+            namespace,
+            kind,
+            difficulty,
+        )
+        .await
+    } else {
+        RegistryClient::register(server, signing_key, namespace, kind).await
+    }
 }
 
 /// Download a package and write it to file.
+///
+/// When `by_path` is set pointers are fetched using the path
+/// route; content identifiers always use the query string.
+///
+/// When a keystore is given the request is signed so that
+/// packages in private namespaces may be downloaded.
+///
+/// When a cache directory is given a verified copy of the
+/// artifact is used instead of downloading it again.
 pub async fn fetch(
+    server: Url,
+    key: AnyRef,
+    file: PathBuf,
+    by_path: bool,
+    keystore: Option<PathBuf>,
+    cache: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let signing_key =
+        keystore.map(helpers::read_keystore_file).transpose()?;
+    let key: PackageKey = key.try_into()?;
+    if let PackageKey::Pointer(namespace, _, _) = &key {
+        warn_deprecated_namespace(&server, namespace).await;
+    }
+
+    let dir = match cache {
+        Some(dir) => dir,
+        None => {
+            return fetch_key(server, key, file, by_path, signing_key).await
+        }
+    };
+
+    if file.exists() {
+        return Err(Error::FileExists(file));
+    }
+
+    // Checksum from the version record addresses the cache
+    let record =
+        RegistryClient::exact_version(server.clone(), key.clone()).await?;
+    let cache = ArtifactCache::new(dir);
+    match cache.get(&record.checksum, &file).await {
+        Ok(true) => {
+            tracing::info!(id = %key, "fetched from cache");
+            return Ok(file);
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!("{}", e),
+    }
+
+    let file = fetch_key(server, key, file, by_path, signing_key).await?;
+    match cache.put(&record.checksum, &file).await {
+        Ok(true) => {}
+        Ok(false) => tracing::warn!(
+            file = ?file,
+            "downloaded artifact does not match the checksum, not cached"
+        ),
+        Err(e) => tracing::warn!("{}", e),
+    }
+    Ok(file)
+}
+
+/// Download a package signing the request when a key is given.
+async fn fetch_key(
     server: Url,
     key: PackageKey,
     file: PathBuf,
+    by_path: bool,
+    signing_key: Option<SigningKey>,
 ) -> Result<PathBuf> {
-    RegistryClient::fetch_file(server, key, file).await
+    match (key, signing_key) {
+        (PackageKey::Pointer(namespace, package, version), signing_key)
+            if by_path =>
+        {
+            if let Some(signing_key) = signing_key {
+                RegistryClient::fetch_file_by_path_signed(
+                    server,
+                    signing_key,
+                    namespace,
+                    package,
+                    version,
+                    file,
+                )
+                .await
+            } else {
+                RegistryClient::fetch_file_by_path(
+                    server, namespace, package, version, file,
+                )
+                .await
+            }
+        }
+        (key, Some(signing_key)) => {
+            RegistryClient::fetch_file_signed(server, signing_key, key, file)
+                .await
+        }
+        (key, None) => RegistryClient::fetch_file(server, key, file).await,
+    }
+}
+
+/// Download all versions of a package and write the bundle to file.
+///
+/// When a keystore is given the request is signed so that
+/// packages in private namespaces may be downloaded.
+pub async fn bundle(
+    server: Url,
+    namespace: Namespace,
+    package: PackageName,
+    range: Option<VersionReq>,
+    file: PathBuf,
+    keystore: Option<PathBuf>,
+) -> Result<PathBuf> {
+    warn_deprecated_namespace(&server, &namespace).await;
+    if let Some(keystore) = keystore {
+        let signing_key = helpers::read_keystore_file(keystore)?;
+        RegistryClient::fetch_bundle_signed(
+            server,
+            signing_key,
+            namespace,
+            package,
+            range,
+            file,
+        )
+        .await
+    } else {
+        RegistryClient::fetch_bundle(server, namespace, package, range, file)
+            .await
+    }
+}
+
+/// Publish the versions in a bundle to a namespace and replay
+/// the yank and deprecation state from the bundle manifest.
+pub async fn mirror(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    file: PathBuf,
+) -> Result<MirrorReport> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    mirror_bundle(server, signing_key, namespace, file).await
+}
+
+/// Publish the versions in a bundle to a namespace using a
+/// signing key and replay the yank and deprecation state.
+///
+/// Versions are published in semver order before any version is
+/// yanked; versions purged from the source registry do not have
+/// an artifact in the bundle so they are skipped.
+pub async fn mirror_bundle(
+    server: Url,
+    signing_key: SigningKey,
+    namespace: Namespace,
+    file: PathBuf,
+) -> Result<MirrorReport> {
+    let (mut records, mut artifacts) = read_bundle(&file)?;
+    records.sort_by(|a, b| a.version.cmp(&b.version));
+
+    let mut report: MirrorReport = Default::default();
+    let mut package = None;
+    for record in &records {
+        let body = match artifacts.remove(&record.checksum) {
+            Some(body) => body,
+            None => {
+                tracing::warn!(
+                    version = %record.version,
+                    "no artifact in bundle, skipped");
+                report.skipped.push(record.version.clone());
+                continue;
+            }
+        };
+
+        let mime: Mime =
+            record.kind.unwrap_or_default().mime_type().parse()?;
+        let receipt = RegistryClient::publish_body(
+            server.clone(),
+            signing_key.clone(),
+            namespace.clone(),
+            mime,
+            body,
+            false,
+            false,
+            false,
+        )
+        .await?;
+        package = Some(receipt.artifact.package.name);
+        report.published.push(record.version.clone());
+    }
+
+    // Nothing was published so there is no package to update
+    let package = match package {
+        Some(package) => package,
+        None => return Ok(report),
+    };
+
+    for record in &records {
+        if let Some(message) = &record.yanked {
+            if !report.published.contains(&record.version) {
+                continue;
+            }
+            let id = PackageKey::Pointer(
+                namespace.clone(),
+                package.clone(),
+                record.version.clone(),
+            );
+            RegistryClient::yank(
+                server.clone(),
+                signing_key.clone(),
+                id,
+                message.clone(),
+            )
+            .await?;
+            report.yanked.push(record.version.clone());
+        }
+    }
+
+    if let Some(message) = records.iter().find_map(|r| r.deprecated.clone()) {
+        RegistryClient::deprecate(
+            server,
+            signing_key,
+            namespace,
+            package,
+            message.clone(),
+        )
+        .await?;
+        report.deprecated = Some(message);
+    }
+
+    Ok(report)
+}
+
+/// Read the manifest and the artifacts keyed by checksum
+/// from a bundle archive.
+fn read_bundle(file: &Path) -> Result<(Vec<VersionRecord>, BundleArtifacts)> {
+    let mut records = Vec::new();
+    let mut artifacts = HashMap::new();
+    let mut archive = tar::Archive::new(std::fs::File::open(file)?);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let mut buffer = Vec::new();
+        entry.read_to_end(&mut buffer)?;
+        if entry.path()?.to_string_lossy() == BUNDLE_MANIFEST {
+            records = serde_json::from_slice(&buffer)?;
+        } else {
+            artifacts.insert(Sha3_256::digest(&buffer).into(), buffer);
+        }
+    }
+    Ok((records, artifacts))
+}
+
+/// Warn when the namespace for a package has been deprecated.
+///
+/// Errors fetching the namespace are ignored so the request
+/// for the package determines the outcome.
+async fn warn_deprecated_namespace(server: &Url, namespace: &Namespace) {
+    if let Ok(record) =
+        RegistryClient::get_namespace(server.clone(), namespace.clone()).await
+    {
+        if let Some(message) = record.deprecated {
+            tracing::warn!(
+                namespace = %namespace,
+                message = %message,
+                "namespace is deprecated");
+        }
+    }
 }
 
 /// Generate a signing key and write the result to file.
@@ -93,8 +483,7 @@ pub async fn keygen(dir: PathBuf) -> Result<Address> {
     }
 
     let key = SigningKey::random(&mut rand::thread_rng());
-    let public_key = key.verifying_key();
-    let address: Address = public_key.into();
+    let address = DefaultIdentity::address(&key)?;
 
     let keystore = encrypt(
         &mut rand::thread_rng(),
@@ -121,6 +510,29 @@ pub async fn yank(
     RegistryClient::yank(server, signing_key, id, message).await
 }
 
+/// Restore a yanked package.
+pub async fn unyank(server: Url, key: PathBuf, id: PackageKey) -> Result<()> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::unyank(server, signing_key, id).await
+}
+
+/// Create a signed download URL for a package version.
+///
+/// The URL in the result is resolved against the server URL.
+pub async fn sign_url(
+    server: Url,
+    key: PathBuf,
+    id: PackageKey,
+    expires_in: Option<u64>,
+) -> Result<SignedUrl> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    let mut signed =
+        RegistryClient::sign_url(server.clone(), signing_key, id, expires_in)
+            .await?;
+    signed.url = server.join(signed.url.trim_start_matches('/'))?.to_string();
+    Ok(signed)
+}
+
 /// Deprecate a package.
 pub async fn deprecate(
     server: Url,
@@ -140,20 +552,199 @@ pub async fn deprecate(
     .await
 }
 
+/// Deprecate a namespace.
+pub async fn deprecate_namespace(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    message: String,
+) -> Result<NamespaceRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::deprecate_namespace(
+        server,
+        signing_key,
+        namespace,
+        message,
+    )
+    .await
+}
+
+/// List entries in the audit log.
+pub async fn audit(
+    server: Url,
+    key: PathBuf,
+    filter: AuditFilter,
+    pager: Pager,
+) -> Result<ResultSet<AuditRecord>> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::audit(server, signing_key, filter, pager).await
+}
+
+/// Rotate the key of a publisher.
+pub async fn rotate_key(
+    server: Url,
+    key: PathBuf,
+    from: Address,
+    to: Address,
+) -> Result<PublisherRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::rotate_key(server, signing_key, RotateKey { from, to })
+        .await
+}
+
+/// List versions signed by keys that have been rotated out.
+pub async fn rotated_signatures(
+    server: Url,
+    key: PathBuf,
+    pager: Pager,
+) -> Result<ResultSet<RotatedSignatureRecord>> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::rotated_signatures(server, signing_key, pager).await
+}
+
+/// Resolve a namespace collision by renaming the namespace
+/// or merging it into another namespace.
+pub async fn resolve_namespace(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    action: NamespaceAction,
+    dry_run: bool,
+) -> Result<ResolveReport> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::resolve_namespace(
+        server,
+        signing_key,
+        ResolveNamespace {
+            namespace,
+            action,
+            dry_run,
+        },
+    )
+    .await
+}
+
+/// Remove the deprecation notice for a namespace.
+pub async fn undeprecate_namespace(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+) -> Result<NamespaceRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::undeprecate_namespace(server, signing_key, namespace)
+        .await
+}
+
+/// Transfer a package to another namespace.
+pub async fn transfer(
+    server: Url,
+    key: PathBuf,
+    target_key: Option<PathBuf>,
+    namespace: Namespace,
+    package: PackageName,
+    target: Namespace,
+) -> Result<PackageRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    let target_key =
+        target_key.map(helpers::read_keystore_file).transpose()?;
+    RegistryClient::transfer(
+        server,
+        signing_key,
+        target_key,
+        namespace,
+        package,
+        target,
+    )
+    .await
+}
+
+/// Transfer ownership of a namespace to another publisher.
+pub async fn transfer_namespace(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    owner: Address,
+) -> Result<NamespaceRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::transfer_namespace(server, signing_key, namespace, owner)
+        .await
+}
+
+/// Set whether downloads from a namespace must be signed.
+pub async fn set_private(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    private: bool,
+) -> Result<NamespaceRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::set_private(server, signing_key, namespace, private).await
+}
+
+/// Set whether users of a namespace without package restrictions
+/// must be granted access before publishing.
+pub async fn set_default_restricted(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    default_restricted: bool,
+) -> Result<NamespaceRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::set_default_restricted(
+        server,
+        signing_key,
+        namespace,
+        default_restricted,
+    )
+    .await
+}
+
+/// Set the description for a namespace or package.
+pub async fn describe(
+    server: Url,
+    key: PathBuf,
+    path: PathRef,
+    description: String,
+) -> Result<GetRecord> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    if let Some(package) = path.package() {
+        RegistryClient::set_package_description(
+            server,
+            signing_key,
+            path.namespace().clone(),
+            package.clone(),
+            description,
+        )
+        .await
+        .map(GetRecord::Package)
+    } else {
+        RegistryClient::set_namespace_description(
+            server,
+            signing_key,
+            path.namespace().clone(),
+            description,
+        )
+        .await
+        .map(GetRecord::Namespace)
+    }
+}
+
 /// Get a namespace, package or version.
 pub async fn get(
     server: Url,
     target: AnyRef,
     latest: bool,
+    range: Option<VersionReq>,
 ) -> Result<GetRecord> {
     match target {
-        AnyRef::Path(path) => {
+        AnyRef::Path(path) if path.version().is_none() => {
             if let Some(package) = path.package() {
                 if latest {
                     RegistryClient::latest_version(
                         server,
                         path.namespace().clone(),
                         package.clone(),
+                        range,
                     )
                     .await
                     .map(GetRecord::Version)
@@ -175,7 +766,7 @@ pub async fn get(
                 .map(GetRecord::Namespace)
             }
         }
-        AnyRef::Key(id) => RegistryClient::exact_version(server, id)
+        _ => RegistryClient::exact_version(server, target.try_into()?)
             .await
             .map(GetRecord::Version),
     }
@@ -188,25 +779,186 @@ pub async fn list(
     pager: Pager,
     include: Option<VersionIncludes>,
     range: Option<VersionReq>,
+    include_yanked: bool,
 ) -> Result<ListRecord> {
     let namespace = path.namespace().clone();
-    let package = path.package().map(|v| v.clone());
+    let package = path.package().cloned();
 
     if package.is_some() {
         RegistryClient::list::<ResultSet<VersionRecord>>(
-            server, namespace, package, pager, include, range,
+            server,
+            namespace,
+            package,
+            pager,
+            include,
+            range,
+            include_yanked,
         )
         .await
         .map(ListRecord::Versions)
     } else {
         RegistryClient::list::<ResultSet<PackageRecord>>(
-            server, namespace, package, pager, include, range,
+            server,
+            namespace,
+            package,
+            pager,
+            include,
+            range,
+            include_yanked,
         )
         .await
         .map(ListRecord::Packages)
     }
 }
 
+/// Search packages in all namespaces by name prefix.
+pub async fn search(
+    server: Url,
+    query: String,
+    pager: Pager,
+) -> Result<ResultSet<PackageRecord>> {
+    RegistryClient::search(server, query, pager).await
+}
+
+/// Get the stored manifest for an exact version.
+pub async fn manifest(server: Url, id: PackageKey) -> Result<Value> {
+    RegistryClient::manifest(server, id).await
+}
+
+/// Compare the manifests of two versions of a package.
+pub async fn diff(
+    server: Url,
+    namespace: Namespace,
+    package: PackageName,
+    from: Version,
+    to: Version,
+) -> Result<VersionDiff> {
+    RegistryClient::diff(server, namespace, package, from, to).await
+}
+
+/// Check the package keys in a lockfile are available.
+///
+/// Deprecation can only be determined for pointer keys as
+/// content identifiers do not reference a package; a package
+/// in a deprecated namespace is deprecated unless the package
+/// has its own deprecation notice.
+pub async fn check(server: Url, lockfile: PathBuf) -> Result<CheckReport> {
+    let keys = helpers::read_lockfile(lockfile)?;
+    let mut deprecations: HashMap<String, Option<String>> = HashMap::new();
+    let mut entries = Vec::with_capacity(keys.len());
+
+    for id in keys {
+        let record =
+            match RegistryClient::exact_version(server.clone(), id.clone())
+                .await
+            {
+                Ok(record) => record,
+                Err(Error::ResponseCode(404)) => {
+                    entries.push(CheckEntry {
+                        id,
+                        status: CheckStatus::Missing,
+                        message: None,
+                    });
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+        if record.yanked.is_some() {
+            entries.push(CheckEntry {
+                id,
+                status: CheckStatus::Yanked,
+                message: record.yanked,
+            });
+            continue;
+        }
+
+        let deprecated = if let PackageKey::Pointer(namespace, package, _) =
+            &id
+        {
+            let key = format!("{}/{}", namespace, package);
+            if !deprecations.contains_key(&key) {
+                let record = RegistryClient::get_package(
+                    server.clone(),
+                    namespace.clone(),
+                    package.clone(),
+                )
+                .await?;
+                deprecations.insert(key.clone(), record.deprecated);
+            }
+            let namespace_key = namespace.to_string();
+            if !deprecations.contains_key(&namespace_key) {
+                let record = RegistryClient::get_namespace(
+                    server.clone(),
+                    namespace.clone(),
+                )
+                .await?;
+                deprecations.insert(namespace_key.clone(), record.deprecated);
+            }
+            deprecations.get(&key).cloned().flatten().or_else(|| {
+                deprecations.get(&namespace_key).cloned().flatten()
+            })
+        } else {
+            None
+        };
+
+        entries.push(if deprecated.is_some() {
+            CheckEntry {
+                id,
+                status: CheckStatus::Deprecated,
+                message: deprecated,
+            }
+        } else {
+            CheckEntry {
+                id,
+                status: CheckStatus::Ok,
+                message: None,
+            }
+        });
+    }
+
+    Ok(CheckReport { entries })
+}
+
+/// Verify the integrity of a version without saving the artifact.
+pub async fn verify(server: Url, id: PackageKey) -> Result<VerifyReport> {
+    let record =
+        RegistryClient::exact_version(server.clone(), id.clone()).await?;
+
+    let body = match RegistryClient::fetch_bytes(server, id.clone()).await {
+        Ok(body) => body,
+        // Server refuses to serve artifacts that fail verification
+        Err(Error::ResponseCode(422)) => {
+            return Ok(VerifyReport {
+                id,
+                checksum: false,
+                signature: false,
+                message: Some(String::from(
+                    "server rejected the artifact as corrupt",
+                )),
+            });
+        }
+        Err(e) => return Err(e),
+    };
+
+    let digest = Sha3_256::digest(&body);
+    let checksum = digest.as_slice() == record.checksum.as_slice();
+    // Signature must be from the publisher of the version
+    let signature = helpers::verify_signature(
+        record.signature,
+        record.signature_scheme.message(&body, digest.as_slice()),
+    )
+    .map(|signer| record.signers.contains(&signer))
+    .unwrap_or(false);
+
+    Ok(VerifyReport {
+        id,
+        checksum,
+        signature,
+        message: None,
+    })
+}
+
 /// Add a user.
 pub async fn add_user(
     server: Url,
@@ -239,6 +991,16 @@ pub async fn remove_user(
     RegistryClient::remove_user(server, signing_key, namespace, user).await
 }
 
+/// List the users of a namespace.
+pub async fn list_access(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+) -> Result<NamespaceAccess> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::list_access(server, signing_key, namespace).await
+}
+
 /// Grant or revoke package access.
 pub async fn access_control(
     server: Url,
@@ -259,3 +1021,24 @@ pub async fn access_control(
     )
     .await
 }
+
+/// Add or remove a trusted key for a package.
+pub async fn trusted_key(
+    server: Url,
+    key: PathBuf,
+    namespace: Namespace,
+    package: PackageName,
+    user: Address,
+    trust: bool,
+) -> Result<()> {
+    let signing_key = helpers::read_keystore_file(key)?;
+    RegistryClient::trusted_key(
+        server,
+        signing_key,
+        namespace,
+        package,
+        user,
+        trust,
+    )
+    .await
+}
@@ -12,6 +12,14 @@ pub enum Error {
     #[error("path {0} is not a directory")]
     NotDirectory(PathBuf),
 
+    /// Error generated when a directory has no keystore files.
+    #[error("no keystore files in {0}")]
+    NoKeystore(PathBuf),
+
+    /// Error generated when a directory has no keystore for an address.
+    #[error("no keystore for address 0x{0} in {1}")]
+    KeystoreNotFound(String, PathBuf),
+
     /// Error generated when a file already exists.
     #[error("file already exists {0}")]
     FileExists(PathBuf),
@@ -24,10 +32,22 @@ pub enum Error {
     #[error("unexpected response code {0}")]
     ResponseCode(u16),
 
+    /// Error generated when the server is rate limiting requests.
+    #[error("too many requests, retry after {0} seconds")]
+    RateLimited(u64),
+
     /// Error generated by the core library.
     #[error(transparent)]
     Core(#[from] ipfs_registry_core::Error),
 
+    /// Error generated by the address library.
+    #[error(transparent)]
+    Address(#[from] web3_address::Error),
+
+    /// Error generated converting from a slice.
+    #[error(transparent)]
+    TryFromSlice(#[from] std::array::TryFromSliceError),
+
     /// Error generated by the io module.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -48,6 +68,10 @@ pub enum Error {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
+    /// Error generated decoding MessagePack.
+    #[error(transparent)]
+    MessagePack(#[from] rmp_serde::decode::Error),
+
     /// Error generate by the ECDSA library.
     #[error(transparent)]
     Ecdsa(#[from] k256::ecdsa::Error),
@@ -55,4 +79,12 @@ pub enum Error {
     /// Error generate by the readline library.
     #[error(transparent)]
     Readline(#[from] rustyline::error::ReadlineError),
+
+    /// Error generated formatting a date and time.
+    #[error(transparent)]
+    TimeFormat(#[from] time::error::Format),
+
+    /// Error generated parsing a MIME type.
+    #[error(transparent)]
+    Mime(#[from] mime::FromStrError),
 }
@@ -1,10 +1,15 @@
 use k256::ecdsa::SigningKey;
 use secrecy::ExposeSecret;
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
 
+use ipfs_registry_core::{DefaultIdentity, Identity, PackageKey};
+use web3_address::ethereum::Address;
 use web3_keystore::{decrypt, KeyStore};
 
-use crate::{input::read_password, Error, Result};
+use crate::{
+    input::{choose, read_password},
+    Error, Result,
+};
 
 const KEYSTORE_PASSWORD_ENV: &str = "IPKG_KEYSTORE_PASSWORD";
 
@@ -29,3 +34,94 @@ pub(crate) fn read_keystore_file(key: PathBuf) -> Result<SigningKey> {
     let signing_key = SigningKey::from_bytes(&key)?;
     Ok(signing_key)
 }
+
+/// Resolve the keystore file for a signing key.
+///
+/// A file path is returned unchanged. When the path is a directory
+/// the keystores it contains are candidates; if there is more than
+/// one the address is used to select a keystore, otherwise the
+/// user is prompted to choose.
+pub fn find_keystore(
+    key: PathBuf,
+    address: Option<&Address>,
+) -> Result<PathBuf> {
+    if !key.is_dir() {
+        return Ok(key);
+    }
+
+    let mut candidates = Vec::new();
+    for entry in std::fs::read_dir(&key)? {
+        let path = entry?.path();
+        if !path.is_file()
+            || path.extension().and_then(|ext| ext.to_str()) != Some("json")
+        {
+            continue;
+        }
+
+        let buffer = std::fs::read(&path)?;
+        if let Ok(keystore) = serde_json::from_slice::<KeyStore>(&buffer) {
+            let owner = keystore.address.or_else(|| {
+                path.file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .map(|stem| stem.to_owned())
+            });
+            candidates.push((owner.unwrap_or_default(), path));
+        }
+    }
+    candidates.sort_by(|a, b| a.1.cmp(&b.1));
+
+    if let Some(address) = address {
+        let address = normalize_address(&address.to_string());
+        return candidates
+            .into_iter()
+            .find(|(owner, _)| normalize_address(owner) == address)
+            .map(|(_, path)| path)
+            .ok_or_else(|| Error::KeystoreNotFound(address, key));
+    }
+
+    match candidates.len() {
+        0 => Err(Error::NoKeystore(key)),
+        1 => Ok(candidates.remove(0).1),
+        _ => {
+            let options: Vec<String> =
+                candidates.iter().map(|(owner, _)| owner.clone()).collect();
+            let index = choose("Choose a keystore: ", &options)?;
+            Ok(candidates.remove(index).1)
+        }
+    }
+}
+
+/// Normalize an address for comparison.
+fn normalize_address(address: &str) -> String {
+    address.trim_start_matches("0x").to_lowercase()
+}
+
+/// Verify a signature and recover the address of the signer
+/// using the identity system of the registry.
+pub(crate) fn verify_signature(
+    signature: [u8; 65],
+    message: &[u8],
+) -> Result<Address> {
+    Ok(DefaultIdentity::verify(&signature, message)?)
+}
+
+/// Read a lockfile into a list of package keys.
+///
+/// Each line contains a single package key; blank lines and
+/// lines beginning with `#` are ignored.
+pub(crate) fn read_lockfile(file: PathBuf) -> Result<Vec<PackageKey>> {
+    if !file.is_file() {
+        return Err(Error::NotFile(file));
+    }
+
+    let contents = std::fs::read_to_string(file)?;
+    let mut keys = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        keys.push(PackageKey::from_str(line)?);
+    }
+    Ok(keys)
+}
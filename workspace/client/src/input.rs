@@ -46,3 +46,27 @@ pub fn read_password(prompt: Option<&str>) -> Result<SecretString> {
 
     Ok(Secret::new(passwd))
 }
+
+/// Prompt the user to choose one of a list of options.
+///
+/// Returns the index of the chosen option.
+pub fn choose(prompt: &str, options: &[String]) -> Result<usize> {
+    for (index, option) in options.iter().enumerate() {
+        eprintln!("{}) {}", index + 1, option);
+    }
+
+    let mut rl = Editor::<()>::new()?;
+    rl.set_auto_add_history(false);
+    loop {
+        let line = rl.readline(prompt)?;
+        if let Ok(choice) = line.trim().parse::<usize>() {
+            if choice > 0 && choice <= options.len() {
+                return Ok(choice - 1);
+            }
+        }
+        eprintln!(
+            "invalid choice, enter a number from 1 to {}",
+            options.len()
+        );
+    }
+}
@@ -1,7 +1,13 @@
 //! Client implementation and commands.
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
+// Existing code predates these lints
+#![allow(
+    clippy::match_result_ok,
+    clippy::let_and_return
+)]
 
+mod cache;
 mod client;
 mod commands;
 mod error;
@@ -11,6 +17,8 @@ mod input;
 /// Result type for the client library.
 pub type Result<T> = std::result::Result<T, error::Error>;
 
+pub use cache::{default_cache_dir, ArtifactCache, CACHE_DIR_ENV};
 pub use client::RegistryClient;
 pub use commands::*;
 pub use error::Error;
+pub use helpers::find_keystore;
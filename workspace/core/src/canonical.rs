@@ -0,0 +1,109 @@
+//! Canonical JSON serialization for signed payloads.
+//!
+//! The canonical form has object keys sorted by their UTF-8 bytes
+//! and no whitespace between tokens; strings and numbers are
+//! written as serde_json writes them. A receiver can parse a
+//! payload and serialize it again to recover the exact bytes
+//! that were signed.
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Result;
+
+/// Serialize a value to canonical JSON.
+pub fn to_canonical_vec<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let value = serde_json::to_value(value)?;
+    let mut buffer = Vec::new();
+    write_value(&value, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Write a JSON value in canonical form.
+fn write_value(value: &Value, buffer: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            buffer.push(b'{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    buffer.push(b',');
+                }
+                serde_json::to_writer(&mut *buffer, key)?;
+                buffer.push(b':');
+                write_value(value, buffer)?;
+            }
+            buffer.push(b'}');
+        }
+        Value::Array(items) => {
+            buffer.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    buffer.push(b',');
+                }
+                write_value(item, buffer)?;
+            }
+            buffer.push(b']');
+        }
+        _ => serde_json::to_writer(&mut *buffer, value)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DefaultIdentity, Identity};
+    use anyhow::Result;
+    use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+    use serde_json::json;
+    use web3_address::ethereum::Address;
+
+    #[test]
+    fn canonical_sorted_keys() -> Result<()> {
+        let value = json!({
+            "z": 1,
+            "a": {"y": [true, null, {"c": "text", "b": 1.5}], "x": "é"},
+            "B": false
+        });
+        let buffer = to_canonical_vec(&value)?;
+        assert_eq!(
+            r#"{"B":false,"a":{"x":"é","y":[true,null,{"b":1.5,"c":"text"}]},"z":1}"#,
+            std::str::from_utf8(&buffer)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_signature_round_trip() -> Result<()> {
+        #[derive(Serialize)]
+        struct Payload {
+            event: &'static str,
+            version: &'static str,
+            namespace: &'static str,
+        }
+
+        let signing_key = SigningKey::from_bytes(&[1u8; 32])?;
+        let address: Address = signing_key.verifying_key().into();
+
+        let payload = Payload {
+            event: "publish",
+            version: "1.0.0",
+            namespace: "mock-namespace",
+        };
+        let body = to_canonical_vec(&payload)?;
+        let signature: recoverable::Signature = signing_key.sign(&body);
+
+        // Receiver parses the payload and serializes it again,
+        // whitespace and key order in the parsed document are
+        // not significant
+        let pretty = serde_json::to_vec_pretty(&payload)?;
+        let received: Value = serde_json::from_slice(&pretty)?;
+        let message = to_canonical_vec(&received)?;
+        assert_eq!(body, message);
+
+        let signer = DefaultIdentity::verify(signature.as_ref(), &message)?;
+        assert_eq!(address, signer);
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+//! Compare package manifests between versions.
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Value of a field before and after a change.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Value in the earlier version.
+    pub from: Value,
+    /// Value in the later version.
+    pub to: Value,
+}
+
+/// Differences between the manifests of two package versions.
+///
+/// Nested objects such as dependency tables are compared
+/// field by field and keys are joined with a period, for
+/// example `dependencies.lodash`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VersionDiff {
+    /// Version being compared from.
+    pub from: Version,
+    /// Version being compared to.
+    pub to: Version,
+    /// Fields only present in the later version.
+    pub added: BTreeMap<String, Value>,
+    /// Fields only present in the earlier version.
+    pub removed: BTreeMap<String, Value>,
+    /// Fields present in both versions with different values.
+    pub changed: BTreeMap<String, FieldChange>,
+}
+
+impl VersionDiff {
+    /// Compute the differences between two manifests.
+    pub fn new(
+        from: Version,
+        to: Version,
+        before: &Value,
+        after: &Value,
+    ) -> Self {
+        let mut diff = Self {
+            from,
+            to,
+            added: Default::default(),
+            removed: Default::default(),
+            changed: Default::default(),
+        };
+
+        let empty = Map::new();
+        let before = before.as_object().unwrap_or(&empty);
+        let after = after.as_object().unwrap_or(&empty);
+        diff.compare("", before, after);
+        diff
+    }
+
+    /// Determine if the manifests are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+
+    fn compare(
+        &mut self,
+        prefix: &str,
+        before: &Map<String, Value>,
+        after: &Map<String, Value>,
+    ) {
+        let empty = Map::new();
+        for (key, value) in before {
+            let path = format!("{}{}", prefix, key);
+            match after.get(key) {
+                None => match value {
+                    Value::Object(map) if !map.is_empty() => {
+                        self.compare(&format!("{}.", path), map, &empty);
+                    }
+                    _ => {
+                        self.removed.insert(path, value.clone());
+                    }
+                },
+                Some(other) => match (value, other) {
+                    (Value::Object(before), Value::Object(after)) => {
+                        self.compare(&format!("{}.", path), before, after);
+                    }
+                    _ => {
+                        if value != other {
+                            self.changed.insert(
+                                path,
+                                FieldChange {
+                                    from: value.clone(),
+                                    to: other.clone(),
+                                },
+                            );
+                        }
+                    }
+                },
+            }
+        }
+
+        for (key, value) in after {
+            if !before.contains_key(key) {
+                let path = format!("{}{}", prefix, key);
+                match value {
+                    Value::Object(map) if !map.is_empty() => {
+                        self.compare(&format!("{}.", path), &empty, map);
+                    }
+                    _ => {
+                        self.added.insert(path, value.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_manifest() {
+        let before = json!({
+            "name": "mock-package",
+            "version": "1.0.0",
+            "license": "ISC",
+            "dependencies": {
+                "lodash": "^4.17.0",
+                "left-pad": "^1.0.0"
+            }
+        });
+        let after = json!({
+            "name": "mock-package",
+            "version": "1.1.0",
+            "description": "Mock package",
+            "dependencies": {
+                "lodash": "^4.17.21",
+                "semver": "^7.0.0"
+            },
+            "devDependencies": {
+                "typescript": "^4.8.0"
+            }
+        });
+
+        let diff = VersionDiff::new(
+            Version::new(1, 0, 0),
+            Version::new(1, 1, 0),
+            &before,
+            &after,
+        );
+
+        assert_eq!(
+            Some(&json!("Mock package")),
+            diff.added.get("description")
+        );
+        assert_eq!(
+            Some(&json!("^7.0.0")),
+            diff.added.get("dependencies.semver")
+        );
+        assert_eq!(
+            Some(&json!("^4.8.0")),
+            diff.added.get("devDependencies.typescript")
+        );
+        assert_eq!(Some(&json!("ISC")), diff.removed.get("license"));
+        assert_eq!(
+            Some(&json!("^1.0.0")),
+            diff.removed.get("dependencies.left-pad")
+        );
+        assert_eq!(
+            Some(&FieldChange {
+                from: json!("^4.17.0"),
+                to: json!("^4.17.21"),
+            }),
+            diff.changed.get("dependencies.lodash")
+        );
+        assert!(diff.changed.contains_key("version"));
+        assert!(!diff.changed.contains_key("name"));
+    }
+
+    #[test]
+    fn diff_manifest_same() {
+        let value = json!({"name": "mock-package", "version": "1.0.0"});
+        let diff = VersionDiff::new(
+            Version::new(1, 0, 0),
+            Version::new(1, 0, 0),
+            &value,
+            &value,
+        );
+        assert!(diff.is_empty());
+    }
+}
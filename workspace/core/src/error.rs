@@ -1,6 +1,9 @@
+use semver::Version;
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::RegistryKind;
+
 /// Errors thrown by the core library.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -25,6 +28,60 @@ pub enum Error {
     #[error("pointer is missing a version component")]
     VersionComponent,
 
+    /// Error generated when a registry kind is not supported.
+    #[error("registry kind {0} is not supported")]
+    UnsupportedKind(String),
+
+    /// Error generated when a signature scheme is not supported.
+    #[error("signature scheme {0} is not supported")]
+    UnsupportedScheme(String),
+
+    /// Error generated when an archive entry has a file extension
+    /// that is not allowed.
+    #[error("archive entry {0} has a file extension that is not allowed")]
+    DisallowedEntry(String),
+
+    /// Error generated when a package archive is not the kind
+    /// expected by the namespace.
+    #[error("archive is a {0} package but the namespace kind is {1}")]
+    WrongKind(RegistryKind, RegistryKind),
+
+    /// Error generated when a required manifest field is missing or invalid.
+    #[error("manifest field {0} is missing or invalid")]
+    ManifestField(String),
+
+    /// Error generated when a version is 0.0.0 and zero
+    /// versions are forbidden.
+    #[error(
+        "version {0} is not allowed, versions must be greater than 0.0.0"
+    )]
+    ZeroVersion(Version),
+
+    /// Error generated when a version has build metadata and
+    /// build metadata is forbidden.
+    #[error("version {0} must not include build metadata")]
+    VersionBuildMetadata(Version),
+
+    /// Error generated when a CID does not use an allowed
+    /// version or codec.
+    #[error("CID {0} (version {1}, codec {2:#x}) is not allowed")]
+    CidNotAllowed(cid::Cid, u64, u64),
+
+    /// Error generated when a Python package version cannot be
+    /// represented as a semantic version.
+    #[error(
+        "python version {0} cannot be represented as a semantic version"
+    )]
+    PypiVersion(String),
+
+    /// Error generated when a decompressed archive exceeds the size limit.
+    #[error("decompressed archive exceeds the limit of {0} bytes")]
+    DecompressedSize(u64),
+
+    /// Error generated when an archive has too many entries.
+    #[error("archive exceeds the limit of {0} entries")]
+    ArchiveEntries(usize),
+
     /// Error generated by the io module.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -37,6 +94,10 @@ pub enum Error {
     #[error(transparent)]
     Toml(#[from] toml::de::Error),
 
+    /// Error generated by the zip library.
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+
     /// Error generated by the CID library.
     #[error(transparent)]
     Cid(#[from] cid::Error),
@@ -44,4 +105,16 @@ pub enum Error {
     /// Error generated by the semver library.
     #[error(transparent)]
     Semver(#[from] semver::Error),
+
+    /// Error generated by the address library.
+    #[error(transparent)]
+    Address(#[from] web3_address::Error),
+
+    /// Error generated converting from a slice.
+    #[error(transparent)]
+    TryFromSlice(#[from] std::array::TryFromSliceError),
+
+    /// Error generated by the ECDSA library.
+    #[error(transparent)]
+    Ecdsa(#[from] k256::ecdsa::Error),
 }
@@ -0,0 +1,79 @@
+//! Identity systems that derive the address of a signer.
+use k256::ecdsa::{recoverable, SigningKey};
+use web3_address::ethereum::Address;
+
+use crate::Result;
+
+/// Identity system used to derive addresses and verify signatures.
+///
+/// Publishers, namespace users and package signatures are keyed
+/// by the address of the identity system.
+pub trait Identity {
+    /// Address that identifies a signer.
+    type Address;
+
+    /// Key used to sign messages.
+    type SigningKey;
+
+    /// Derive the address for a signing key.
+    fn address(signing_key: &Self::SigningKey) -> Result<Self::Address>;
+
+    /// Verify a signature of a message and return the address
+    /// of the signer.
+    fn verify(signature: &[u8], message: &[u8]) -> Result<Self::Address>;
+}
+
+/// Ethereum identity using recoverable secp256k1 signatures.
+///
+/// Signatures are 65 bytes and the address of the signer is
+/// recovered from the signature and the message.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ethereum;
+
+impl Identity for Ethereum {
+    type Address = Address;
+    type SigningKey = SigningKey;
+
+    fn address(signing_key: &SigningKey) -> Result<Address> {
+        Ok(signing_key.verifying_key().into())
+    }
+
+    fn verify(signature: &[u8], message: &[u8]) -> Result<Address> {
+        let recoverable: recoverable::Signature = signature.try_into()?;
+        let public_key = recoverable.recover_verifying_key(message)?;
+        let public_key: [u8; 33] =
+            public_key.to_bytes().as_slice().try_into()?;
+        let address: Address = (&public_key).try_into()?;
+        Ok(address)
+    }
+}
+
+/// Identity system used by the registry.
+pub type DefaultIdentity = Ethereum;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use k256::ecdsa::signature::Signer;
+
+    #[test]
+    fn ethereum_identity() -> Result<()> {
+        let signing_key = SigningKey::from_bytes(&[1u8; 32])?;
+        let other_key = SigningKey::from_bytes(&[2u8; 32])?;
+        let address = Ethereum::address(&signing_key)?;
+        assert_ne!(address, Ethereum::address(&other_key)?);
+
+        let signature: recoverable::Signature =
+            signing_key.sign(b"mock-message");
+        let signature = signature.as_ref();
+        assert_eq!(address, Ethereum::verify(signature, b"mock-message")?);
+
+        // Another message recovers another address
+        assert_ne!(address, Ethereum::verify(signature, b"other-message")?);
+
+        // Signatures must be 65 bytes
+        assert!(Ethereum::verify(&signature[..64], b"mock-message").is_err());
+        Ok(())
+    }
+}
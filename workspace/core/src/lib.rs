@@ -1,25 +1,83 @@
 //! Common types and functions for the client and server.
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
+// Existing code predates these lints
+#![allow(
+    clippy::needless_borrows_for_generic_args,
+    clippy::collapsible_if,
+    clippy::redundant_closure,
+    clippy::useless_conversion
+)]
 
+mod canonical;
+mod diff;
 mod error;
+mod identity;
 mod package;
 mod tarball;
 mod validate;
+mod work;
 
+pub use canonical::to_canonical_vec;
+pub use diff::{FieldChange, VersionDiff};
 pub use error::Error;
+pub use identity::{DefaultIdentity, Ethereum, Identity};
 pub use package::{
-    AnyRef, Artifact, Definition, Namespace, ObjectKey, PackageKey,
-    PackageMeta, PackageName, PackageReader, PackageSignature, PathRef,
-    Pointer, Receipt, RegistryKind,
+    AnyRef, Artifact, Definition, Dependency, Namespace, ObjectKey,
+    PackageKey, PackageMeta, PackageName, PackageReader, PackageSignature,
+    PathRef, Pointer, Receipt, RegistryKind, SignatureScheme, SignedUrl,
+    StoredObject,
 };
+pub use tarball::ArchiveFormat;
 pub use validate::validate_id;
+pub use work::{solve_work, verify_work, MAX_DIFFICULTY};
 
 /// Result type for the core library.
 pub type Result<T> = std::result::Result<T, error::Error>;
 
+/// Message signed for a request that is only authorized until
+/// the signature expires.
+///
+/// The message is followed by a newline and the unix timestamp
+/// when the signature expires so a captured signature can only
+/// be used until it expires.
+pub fn expiring_message(message: &[u8], expires: i64) -> Vec<u8> {
+    let mut signed = message.to_vec();
+    signed.extend_from_slice(format!("\n{}", expires).as_bytes());
+    signed
+}
+
+/// Message signed to authorize a download from a private namespace.
+///
+/// The download message is prefixed with `download:` so the
+/// signature cannot be replayed to another endpoint.
+pub fn download_message(message: &[u8], expires: i64) -> Vec<u8> {
+    let mut tagged = b"download:".to_vec();
+    tagged.extend_from_slice(message);
+    expiring_message(&tagged, expires)
+}
+
 /// Name of the header used for signatures.
 pub const X_SIGNATURE: &str = "x-signature";
 
+/// Name of the header used for webhook HMAC signatures.
+pub const X_HUB_SIGNATURE: &str = "x-hub-signature-256";
+
+/// Name of the header used for publish checksums.
+pub const X_CHECKSUM: &str = "x-checksum";
+
+/// Name of the header used for proof of work nonces.
+pub const X_NONCE: &str = "x-nonce";
+
+/// Name of the header used for the signature of the target
+/// namespace owner when transferring a package.
+pub const X_TARGET_SIGNATURE: &str = "x-target-signature";
+
+/// Name of the header used for the expiry of a download signature.
+pub const X_SIGNATURE_EXPIRES: &str = "x-signature-expires";
+
+/// Media type for MessagePack encoded responses.
+pub const MSGPACK_MIME: &str = "application/msgpack";
+
 /// Well known message used for self-signing.
 pub const WELL_KNOWN_MESSAGE: &[u8] = b".ipfs-registry";
@@ -9,17 +9,55 @@ use serde::{
 use serde_json::Value;
 use serde_with::{base64::Base64, serde_as};
 use sha3::{Digest, Sha3_256};
-use std::{fmt, str::FromStr};
+use std::{collections::HashSet, fmt, str::FromStr};
 use web3_address::ethereum::Address;
 
 use crate::{
-    tarball::{decompress, read_cargo_package, read_npm_package},
+    tarball::{
+        decompress, detect_kinds, detect_zip_kinds, find_disallowed_entry,
+        find_disallowed_zip_entry, find_readme, read_cargo_package,
+        read_npm_package, read_oci_package, read_pypi_package, ArchiveFormat,
+    },
     validate::confusable_skeleton,
     validate_id, Error, Result,
 };
 
 const IPFS_DELIMITER: &str = "/ipfs/";
 
+/// Fields that must be present in an NPM manifest.
+const NPM_REQUIRED: &[&str] = &["description", "license", "main"];
+
+/// Fields that must be present in the package section of a Cargo manifest.
+const CARGO_REQUIRED: &[&str] = &["description"];
+
+/// Fields that must be present in Python core metadata.
+const PYPI_REQUIRED: &[&str] = &["summary", "license"];
+
+/// Annotations that must be present in an OCI image manifest.
+const OCI_REQUIRED: &[&str] = &[
+    "org.opencontainers.image.description",
+    "org.opencontainers.image.licenses",
+];
+
+/// Determine if a manifest has a non-empty string field.
+fn has_field(value: &Value, name: &str) -> bool {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .map(|s| !s.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Ensure a manifest has all of the given fields.
+fn require_fields(value: &Value, fields: &[&str]) -> Result<()> {
+    for name in fields {
+        if !has_field(value, name) {
+            return Err(Error::ManifestField(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
 /// Attempt to parse an IPFS CID.
 fn parse_ipfs_cid(s: &str) -> Option<Cid> {
     let hash = match s.find(IPFS_DELIMITER) {
@@ -63,6 +101,19 @@ impl FromStr for AnyRef {
     }
 }
 
+impl TryFrom<AnyRef> for PackageKey {
+    type Error = Error;
+    fn try_from(value: AnyRef) -> Result<Self> {
+        match value {
+            AnyRef::Key(key) => Ok(key),
+            AnyRef::Path(path) => {
+                let (namespace, package, version) = path.try_into()?;
+                Ok(Self::Pointer(namespace, package, version))
+            }
+        }
+    }
+}
+
 /// Reference to a namespace, package or version.
 #[derive(Debug)]
 pub struct PathRef(Namespace, Option<PackageName>, Option<Version>);
@@ -149,7 +200,9 @@ impl FromStr for PathRef {
 }
 
 /// Kinds of supported registries.
-#[derive(Default, Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(
+    Default, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum RegistryKind {
     /// NPM compatible packages.
@@ -157,6 +210,60 @@ pub enum RegistryKind {
     Npm,
     /// Rust compatible packages.
     Cargo,
+    /// OCI artifacts described by an image manifest.
+    Oci,
+    /// Python wheels and source distributions.
+    Pypi,
+}
+
+impl RegistryKind {
+    /// Every supported registry kind.
+    pub const ALL: [RegistryKind; 4] =
+        [Self::Npm, Self::Cargo, Self::Oci, Self::Pypi];
+
+    /// MIME type for package archives of this kind.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Npm => "application/gzip",
+            Self::Cargo => "application/gzip",
+            Self::Oci => "application/vnd.oci.image.layer.v1.tar+gzip",
+            Self::Pypi => ArchiveFormat::Zip.mime_type(),
+        }
+    }
+
+    /// Determine if package archives of this kind may use a MIME type.
+    ///
+    /// Python source distributions are gzip compressed tarballs
+    /// so Python packages also accept `application/gzip`.
+    pub fn accepts_mime(&self, mime: &str) -> bool {
+        mime == self.mime_type()
+            || (*self == Self::Pypi
+                && mime == ArchiveFormat::TarGzip.mime_type())
+    }
+
+    /// File extension for package archives of this kind.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Npm => "tgz",
+            Self::Cargo => "crate",
+            Self::Oci => "tgz",
+            Self::Pypi => "whl",
+        }
+    }
+}
+
+impl FromStr for RegistryKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "npm" => Ok(Self::Npm),
+            "cargo" => Ok(Self::Cargo),
+            "oci" => Ok(Self::Oci),
+            "pypi" => Ok(Self::Pypi),
+            _ => Err(Error::UnsupportedKind(s.to_owned())),
+        }
+    }
 }
 
 impl fmt::Display for RegistryKind {
@@ -167,12 +274,14 @@ impl fmt::Display for RegistryKind {
             match self {
                 Self::Npm => "npm",
                 Self::Cargo => "cargo",
+                Self::Oci => "oci",
+                Self::Pypi => "pypi",
             }
         )
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Identifier(String);
 
 impl Identifier {
@@ -229,6 +338,21 @@ pub enum PackageKey {
     Pointer(Namespace, PackageName, Version),
 }
 
+impl PackageKey {
+    /// Message signed for a request that mutates the version
+    /// identified by this key.
+    ///
+    /// The action and the canonical identifier are each followed
+    /// by a newline and then the request body so a signature cannot
+    /// be replayed against a different version by changing the query
+    /// string or against a different endpoint for the same version.
+    pub fn signed_message(&self, action: &str, body: &[u8]) -> Vec<u8> {
+        let mut message = format!("{}\n{}\n", action, self).into_bytes();
+        message.extend_from_slice(body);
+        message
+    }
+}
+
 impl fmt::Display for PackageKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -376,6 +500,15 @@ impl<'de> Deserialize<'de> for ObjectKey {
     }
 }
 
+/// Object key for an artifact in a storage layer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredObject {
+    /// Index of the storage layer in the server configuration.
+    pub layer: usize,
+    /// Key for the artifact in the storage layer.
+    pub key: ObjectKey,
+}
+
 /// Meta data extracted from an archive.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PackageMeta {
@@ -385,11 +518,23 @@ pub struct PackageMeta {
     pub version: Version,
 }
 
+/// Dependency declared in a package manifest.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Name of the dependency as declared in the manifest.
+    pub name: String,
+    /// Version range required for the dependency.
+    pub range: String,
+}
+
 /// Package meta data with namespace context.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Artifact {
     /// The kind of registry.
-    #[serde(skip)]
+    ///
+    /// Documents written before the kind was serialized
+    /// default to NPM.
+    #[serde(default)]
     pub kind: RegistryKind,
     /// Organization namespace.
     pub namespace: Namespace,
@@ -425,6 +570,65 @@ pub struct Definition {
         deserialize_with = "hex::serde::deserialize"
     )]
     pub checksum: [u8; 32],
+    /// Size of the package file in bytes.
+    ///
+    /// Pointers created before the size was recorded
+    /// do not have a size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+/// Schemes used to sign package archives.
+#[derive(
+    Default, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureScheme {
+    /// Recoverable secp256k1 signature of the archive file bytes.
+    #[default]
+    Secp256k1,
+    /// Recoverable secp256k1 signature of the SHA3-256
+    /// checksum of the archive file.
+    Secp256k1Checksum,
+}
+
+impl SignatureScheme {
+    /// Select the message that was signed for this scheme.
+    pub fn message<'a>(
+        &self,
+        body: &'a [u8],
+        checksum: &'a [u8],
+    ) -> &'a [u8] {
+        match self {
+            Self::Secp256k1 => body,
+            Self::Secp256k1Checksum => checksum,
+        }
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "secp256k1" => Ok(Self::Secp256k1),
+            "secp256k1-checksum" => Ok(Self::Secp256k1Checksum),
+            _ => Err(Error::UnsupportedScheme(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Secp256k1 => "secp256k1",
+                Self::Secp256k1Checksum => "secp256k1-checksum",
+            }
+        )
+    }
 }
 
 /// Package signature and address of the verifying key.
@@ -433,9 +637,15 @@ pub struct Definition {
 pub struct PackageSignature {
     /// Address of the signer.
     pub signer: Address,
-    /// Signature of the archive file bytes.
+    /// Signature of the archive.
     #[serde_as(as = "Base64")]
     pub value: [u8; 65],
+    /// Scheme used to create the signature.
+    ///
+    /// Signatures created before the scheme was recorded
+    /// use the default scheme.
+    #[serde(default)]
+    pub scheme: SignatureScheme,
 }
 
 /// Package definition with raw meta data extracted from the archive file.
@@ -463,6 +673,27 @@ pub struct Receipt {
         deserialize_with = "hex::serde::deserialize"
     )]
     pub checksum: [u8; 32],
+    /// Package meta data read from the manifest, only included
+    /// in a verbose receipt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<Value>,
+    /// Size of the package file in bytes, only included in
+    /// a verbose receipt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// README read from the package, only included in a verbose
+    /// receipt when the package has a README.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme: Option<String>,
+}
+
+/// Download URL that does not require a signature until it expires.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedUrl {
+    /// URL relative to the server.
+    pub url: String,
+    /// Unix timestamp in seconds when the URL expires.
+    pub expires: i64,
 }
 
 /// Read a descriptor from a package.
@@ -470,23 +701,226 @@ pub struct PackageReader;
 
 impl PackageReader {
     /// Read a descriptor from file content.
+    ///
+    /// The container format of the archive is determined
+    /// by the MIME type.
     pub fn read(
         kind: RegistryKind,
+        mime: &str,
+        buffer: &[u8],
+    ) -> Result<(PackageMeta, Value)> {
+        PackageReader::read_with_limit(kind, mime, buffer, None, None)
+    }
+
+    /// Read a descriptor from file content aborting if the
+    /// decompressed size of the archive or the number of entries
+    /// in the archive exceeds a limit.
+    pub fn read_with_limit(
+        kind: RegistryKind,
+        mime: &str,
         buffer: &[u8],
+        limit: Option<u64>,
+        max_entries: Option<usize>,
     ) -> Result<(PackageMeta, Value)> {
         match kind {
             RegistryKind::Npm => {
-                let contents = decompress(buffer)?;
-                let (descriptor, buffer) = read_npm_package(&contents)?;
+                let contents = decompress(buffer, limit)?;
+                let (descriptor, buffer) =
+                    read_npm_package(&contents, max_entries)?;
                 let value: Value = serde_json::from_slice(buffer)?;
                 Ok((descriptor, value))
             }
             RegistryKind::Cargo => {
-                let contents = decompress(buffer)?;
-                let (descriptor, buffer) = read_cargo_package(&contents)?;
+                let contents = decompress(buffer, limit)?;
+                let (descriptor, buffer) =
+                    read_cargo_package(&contents, max_entries)?;
                 let value: Value = toml::from_slice(buffer)?;
                 Ok((descriptor, value))
             }
+            RegistryKind::Oci => {
+                let contents = decompress(buffer, limit)?;
+                let (descriptor, buffer) =
+                    read_oci_package(&contents, max_entries)?;
+                let value: Value = serde_json::from_slice(buffer)?;
+                Ok((descriptor, value))
+            }
+            RegistryKind::Pypi => read_pypi_package(
+                buffer,
+                ArchiveFormat::from_mime(mime),
+                limit,
+                max_entries,
+            ),
+        }
+    }
+
+    /// Verify the file extensions of every entry in a package archive.
+    ///
+    /// Extensions should be lowercase without the leading period.
+    pub fn check_extensions(
+        mime: &str,
+        buffer: &[u8],
+        limit: Option<u64>,
+        max_entries: Option<usize>,
+        allowed: Option<&HashSet<String>>,
+        denied: Option<&HashSet<String>>,
+    ) -> Result<()> {
+        let entry = match ArchiveFormat::from_mime(mime) {
+            ArchiveFormat::TarGzip => {
+                let contents = decompress(buffer, limit)?;
+                find_disallowed_entry(
+                    &contents,
+                    max_entries,
+                    allowed,
+                    denied,
+                )?
+            }
+            ArchiveFormat::Zip => find_disallowed_zip_entry(
+                buffer,
+                limit,
+                max_entries,
+                allowed,
+                denied,
+            )?,
+        };
+        if let Some(entry) = entry {
+            return Err(Error::DisallowedEntry(entry));
+        }
+        Ok(())
+    }
+
+    /// Check a package archive and the declared MIME type match
+    /// the expected kind.
+    ///
+    /// The declared MIME type is only rejected when it belongs
+    /// to another kind; an archive without a recognised manifest
+    /// is left for the reader to reject.
+    pub fn check_kind(
+        kind: RegistryKind,
+        mime: &str,
+        buffer: &[u8],
+        limit: Option<u64>,
+        max_entries: Option<usize>,
+    ) -> Result<()> {
+        if !kind.accepts_mime(mime) {
+            if let Some(other) = RegistryKind::ALL
+                .iter()
+                .find(|other| other.mime_type() == mime)
+            {
+                return Err(Error::WrongKind(*other, kind));
+            }
+        }
+
+        let kinds = match ArchiveFormat::from_mime(mime) {
+            ArchiveFormat::TarGzip => {
+                let contents = decompress(buffer, limit)?;
+                detect_kinds(&contents, max_entries)?
+            }
+            ArchiveFormat::Zip => detect_zip_kinds(buffer, max_entries)?,
+        };
+        if let Some(other) = kinds.first() {
+            if !kinds.contains(&kind) {
+                return Err(Error::WrongKind(*other, kind));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the README from the root of the package directory.
+    ///
+    /// Zip archives do not have a package directory so a
+    /// README is never found.
+    pub fn read_readme(
+        mime: &str,
+        buffer: &[u8],
+        limit: Option<u64>,
+        max_entries: Option<usize>,
+    ) -> Result<Option<String>> {
+        match ArchiveFormat::from_mime(mime) {
+            ArchiveFormat::TarGzip => {
+                let contents = decompress(buffer, limit)?;
+                find_readme(&contents, max_entries)
+            }
+            ArchiveFormat::Zip => Ok(None),
+        }
+    }
+
+    /// Extract the dependencies declared in the meta data read
+    /// from a package.
+    ///
+    /// For NPM packages the `dependencies` are read and for Cargo
+    /// crates the `[dependencies]` table; a crate dependency that
+    /// is renamed uses the name of the package it refers to and a
+    /// dependency without a version (eg: `path` or `git`) matches
+    /// any version. OCI artifacts do not declare dependencies and
+    /// Python requirements are not semantic version ranges so
+    /// they are not extracted.
+    pub fn dependencies(
+        kind: RegistryKind,
+        value: &Value,
+    ) -> Vec<Dependency> {
+        let dependencies = match kind {
+            RegistryKind::Npm | RegistryKind::Cargo => {
+                value.get("dependencies").and_then(|v| v.as_object())
+            }
+            RegistryKind::Oci | RegistryKind::Pypi => None,
+        };
+
+        let mut result = Vec::new();
+        if let Some(dependencies) = dependencies {
+            for (name, value) in dependencies {
+                let dependency = match value {
+                    Value::String(range) => Some(Dependency {
+                        name: name.to_owned(),
+                        range: range.to_owned(),
+                    }),
+                    Value::Object(table) if kind == RegistryKind::Cargo => {
+                        let name = table
+                            .get("package")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or(name);
+                        let range = table
+                            .get("version")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("*");
+                        Some(Dependency {
+                            name: name.to_owned(),
+                            range: range.to_owned(),
+                        })
+                    }
+                    _ => None,
+                };
+                if let Some(dependency) = dependency {
+                    result.push(dependency);
+                }
+            }
+        }
+        result
+    }
+
+    /// Validate the required fields of the meta data read from a package.
+    pub fn validate(kind: RegistryKind, value: &Value) -> Result<()> {
+        match kind {
+            RegistryKind::Npm => require_fields(value, NPM_REQUIRED),
+            RegistryKind::Cargo => {
+                let package = value
+                    .get("package")
+                    .ok_or_else(|| Error::ManifestField("package".into()))?;
+                require_fields(package, CARGO_REQUIRED)?;
+                if !has_field(package, "license")
+                    && !has_field(package, "license-file")
+                {
+                    return Err(Error::ManifestField("license".into()));
+                }
+                Ok(())
+            }
+            RegistryKind::Oci => {
+                let annotations =
+                    value.get("annotations").ok_or_else(|| {
+                        Error::ManifestField("annotations".into())
+                    })?;
+                require_fields(annotations, OCI_REQUIRED)
+            }
+            RegistryKind::Pypi => require_fields(value, PYPI_REQUIRED),
         }
     }
 }
@@ -501,7 +935,12 @@ mod tests {
     fn read_npm_package() -> Result<()> {
         let buffer =
             include_bytes!("../../../fixtures/mock-package-1.0.0.tgz");
-        assert!(PackageReader::read(RegistryKind::Npm, buffer).is_ok());
+        assert!(PackageReader::read(
+            RegistryKind::Npm,
+            RegistryKind::Npm.mime_type(),
+            buffer
+        )
+        .is_ok());
         Ok(())
     }
 
@@ -509,7 +948,423 @@ mod tests {
     fn read_cargo_package() -> Result<()> {
         let buffer =
             include_bytes!("../../../fixtures/mock-crate-1.0.0.crate");
-        assert!(PackageReader::read(RegistryKind::Cargo, buffer).is_ok());
+        assert!(PackageReader::read(
+            RegistryKind::Cargo,
+            RegistryKind::Cargo.mime_type(),
+            buffer
+        )
+        .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn npm_dependencies() -> Result<()> {
+        let value = serde_json::json!({
+            "name": "mock-package",
+            "dependencies": {
+                "@scope/mock-scoped": "~2.1.0",
+                "mock-dependency": "^1.0.0",
+            },
+            "devDependencies": {
+                "mock-dev-dependency": "^3.0.0",
+            },
+        });
+        let dependencies =
+            PackageReader::dependencies(RegistryKind::Npm, &value);
+        assert_eq!(
+            vec![
+                Dependency {
+                    name: String::from("@scope/mock-scoped"),
+                    range: String::from("~2.1.0"),
+                },
+                Dependency {
+                    name: String::from("mock-dependency"),
+                    range: String::from("^1.0.0"),
+                },
+            ],
+            dependencies
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cargo_dependencies() -> Result<()> {
+        let manifest = r#"
+            [package]
+            name = "mock-crate"
+            version = "1.0.0"
+
+            [dependencies]
+            mock-dependency = "1.0"
+            mock-path = { path = "../mock-path" }
+            mock-renamed = { package = "mock-original", version = "0.2" }
+
+            [dev-dependencies]
+            mock-dev-dependency = "3"
+        "#;
+        let value: Value = toml::from_str(manifest)?;
+        let dependencies =
+            PackageReader::dependencies(RegistryKind::Cargo, &value);
+        assert_eq!(
+            vec![
+                Dependency {
+                    name: String::from("mock-dependency"),
+                    range: String::from("1.0"),
+                },
+                Dependency {
+                    name: String::from("mock-path"),
+                    range: String::from("*"),
+                },
+                Dependency {
+                    name: String::from("mock-original"),
+                    range: String::from("0.2"),
+                },
+            ],
+            dependencies
+        );
+
+        let buffer =
+            include_bytes!("../../../fixtures/mock-crate-1.0.0.crate");
+        let (_, value) = PackageReader::read(
+            RegistryKind::Cargo,
+            RegistryKind::Cargo.mime_type(),
+            buffer,
+        )?;
+        assert!(PackageReader::dependencies(RegistryKind::Cargo, &value)
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn check_package_kind() -> Result<()> {
+        let npm = include_bytes!("../../../fixtures/mock-package-1.0.0.tgz");
+        let cargo =
+            include_bytes!("../../../fixtures/mock-crate-1.0.0.crate");
+        let gzip = RegistryKind::Npm.mime_type();
+
+        assert!(PackageReader::check_kind(
+            RegistryKind::Npm,
+            gzip,
+            npm,
+            None,
+            None
+        )
+        .is_ok());
+        assert!(PackageReader::check_kind(
+            RegistryKind::Cargo,
+            gzip,
+            cargo,
+            None,
+            None
+        )
+        .is_ok());
+        assert!(matches!(
+            PackageReader::check_kind(
+                RegistryKind::Cargo,
+                gzip,
+                npm,
+                None,
+                None
+            ),
+            Err(Error::WrongKind(RegistryKind::Npm, RegistryKind::Cargo))
+        ));
+        assert!(matches!(
+            PackageReader::check_kind(
+                RegistryKind::Npm,
+                RegistryKind::Oci.mime_type(),
+                npm,
+                None,
+                None
+            ),
+            Err(Error::WrongKind(RegistryKind::Oci, RegistryKind::Npm))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn read_package_readme() -> Result<()> {
+        let buffer =
+            include_bytes!("../../../fixtures/mock-readme-1.0.0.tgz");
+        let readme = PackageReader::read_readme(
+            RegistryKind::Npm.mime_type(),
+            buffer,
+            None,
+            None,
+        )?;
+        assert_eq!(
+            Some("# mock-readme\n\nMock package with a README.\n"),
+            readme.as_deref()
+        );
+
+        let buffer =
+            include_bytes!("../../../fixtures/mock-package-1.0.0.tgz");
+        assert!(PackageReader::read_readme(
+            RegistryKind::Npm.mime_type(),
+            buffer,
+            None,
+            None
+        )?
+        .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn read_oci_package() -> Result<()> {
+        let buffer = include_bytes!("../../../fixtures/mock-oci-1.0.0.tgz");
+        let (descriptor, value) = PackageReader::read(
+            RegistryKind::Oci,
+            RegistryKind::Oci.mime_type(),
+            buffer,
+        )?;
+        assert_eq!(PackageName::new_unchecked("mock-oci"), descriptor.name);
+        assert_eq!(Version::new(1, 0, 0), descriptor.version);
+        assert!(PackageReader::validate(RegistryKind::Oci, &value).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn read_pypi_wheel() -> Result<()> {
+        let buffer = include_bytes!(
+            "../../../fixtures/Mock_Pypi-1.0.0-py3-none-any.whl"
+        );
+        let (descriptor, value) = PackageReader::read(
+            RegistryKind::Pypi,
+            RegistryKind::Pypi.mime_type(),
+            buffer,
+        )?;
+        assert_eq!(PackageName::new_unchecked("mock-pypi"), descriptor.name);
+        assert_eq!(Version::new(1, 0, 0), descriptor.version);
+        assert_eq!(Some("Mock_Pypi"), value["name"].as_str());
+        assert_eq!(2, value["classifier"].as_array().unwrap().len());
+        assert!(PackageReader::validate(RegistryKind::Pypi, &value).is_ok());
+
+        // Container format is determined by the MIME type
+        assert!(PackageReader::read(
+            RegistryKind::Pypi,
+            "application/gzip",
+            buffer
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn read_pypi_sdist() -> Result<()> {
+        let buffer =
+            include_bytes!("../../../fixtures/Mock_Pypi-1.0.0.tar.gz");
+        let (descriptor, value) = PackageReader::read(
+            RegistryKind::Pypi,
+            "application/gzip",
+            buffer,
+        )?;
+        assert_eq!(PackageName::new_unchecked("mock-pypi"), descriptor.name);
+        assert_eq!(Version::new(1, 0, 0), descriptor.version);
+        assert_eq!(
+            Some("Mock package to test PyPI registry support"),
+            value["summary"].as_str()
+        );
+        assert!(value["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("# Mock Pypi"));
+        Ok(())
+    }
+
+    #[test]
+    fn check_pypi_kind() -> Result<()> {
+        let wheel = include_bytes!(
+            "../../../fixtures/Mock_Pypi-1.0.0-py3-none-any.whl"
+        );
+        let sdist =
+            include_bytes!("../../../fixtures/Mock_Pypi-1.0.0.tar.gz");
+        let npm = include_bytes!("../../../fixtures/mock-package-1.0.0.tgz");
+        let gzip = RegistryKind::Npm.mime_type();
+        let zip = RegistryKind::Pypi.mime_type();
+
+        assert!(PackageReader::check_kind(
+            RegistryKind::Pypi,
+            zip,
+            wheel,
+            None,
+            None
+        )
+        .is_ok());
+        assert!(PackageReader::check_kind(
+            RegistryKind::Pypi,
+            gzip,
+            sdist,
+            None,
+            None
+        )
+        .is_ok());
+        assert!(matches!(
+            PackageReader::check_kind(
+                RegistryKind::Pypi,
+                gzip,
+                npm,
+                None,
+                None
+            ),
+            Err(Error::WrongKind(RegistryKind::Npm, RegistryKind::Pypi))
+        ));
+        assert!(matches!(
+            PackageReader::check_kind(
+                RegistryKind::Npm,
+                zip,
+                wheel,
+                None,
+                None
+            ),
+            Err(Error::WrongKind(RegistryKind::Pypi, RegistryKind::Npm))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_npm_manifest() -> Result<()> {
+        let buffer =
+            include_bytes!("../../../fixtures/mock-package-1.0.0.tgz");
+        let (_, value) = PackageReader::read(
+            RegistryKind::Npm,
+            RegistryKind::Npm.mime_type(),
+            buffer,
+        )?;
+        assert!(PackageReader::validate(RegistryKind::Npm, &value).is_ok());
+
+        let buffer =
+            include_bytes!("../../../fixtures/invalid-manifest-1.0.0.tgz");
+        let (_, value) = PackageReader::read(
+            RegistryKind::Npm,
+            RegistryKind::Npm.mime_type(),
+            buffer,
+        )?;
+        let result = PackageReader::validate(RegistryKind::Npm, &value);
+        assert!(
+            matches!(result, Err(Error::ManifestField(field)) if field == "license")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cargo_manifest() -> Result<()> {
+        // Mock crate does not declare a description or license
+        let buffer =
+            include_bytes!("../../../fixtures/mock-crate-1.0.0.crate");
+        let (_, value) = PackageReader::read(
+            RegistryKind::Cargo,
+            RegistryKind::Cargo.mime_type(),
+            buffer,
+        )?;
+        let result = PackageReader::validate(RegistryKind::Cargo, &value);
+        assert!(
+            matches!(result, Err(Error::ManifestField(field)) if field == "description")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn package_key_signed_message() -> Result<()> {
+        let id: PackageKey = "mock-namespace/mock-package/1.0.0".parse()?;
+        assert_eq!(
+            b"yank\nmock-namespace/mock-package/1.0.0\nmock message".to_vec(),
+            id.signed_message("yank", b"mock message")
+        );
+
+        let other: PackageKey =
+            "mock-namespace/mock-package/1.0.1".parse()?;
+        assert_ne!(
+            id.signed_message("yank", b""),
+            other.signed_message("yank", b"")
+        );
+        assert_ne!(
+            id.signed_message("yank", b""),
+            id.signed_message("unyank", b"")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn signature_default_scheme() -> Result<()> {
+        // Signatures stored before the scheme was recorded
+        let value = serde_json::json!({
+            "signer": "0x1fc770ac21067a04f83101ebf19a670db9e3eb21",
+            "value": "mgtkUNH0I4D4JqhvLYEG1snbBByRLZCmBj5r+KKJiTAVUdBFj7Sm9JtGczTX0dk2jjtBH0wbLOcFIWesQiwVAwE="
+        });
+        let signature: PackageSignature = serde_json::from_value(value)?;
+        assert_eq!(SignatureScheme::Secp256k1, signature.scheme);
+
+        let value = serde_json::to_value(&PackageSignature {
+            scheme: SignatureScheme::Secp256k1Checksum,
+            ..signature
+        })?;
+        assert_eq!("secp256k1-checksum", value["scheme"]);
+
+        let scheme: SignatureScheme = "secp256k1-checksum".parse()?;
+        assert_eq!(SignatureScheme::Secp256k1Checksum, scheme);
+        assert!("ed25519".parse::<SignatureScheme>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn check_archive_extensions() -> Result<()> {
+        let buffer =
+            include_bytes!("../../../fixtures/denied-extension-1.0.0.tgz");
+        let gzip = RegistryKind::Npm.mime_type();
+
+        let denied: HashSet<String> = ["exe".to_owned()].into();
+        let result = PackageReader::check_extensions(
+            gzip,
+            buffer,
+            None,
+            None,
+            None,
+            Some(&denied),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::DisallowedEntry(entry)) if entry == "package/bin/install.exe"
+        ));
+
+        let allowed: HashSet<String> =
+            ["js".to_owned(), "json".to_owned()].into();
+        let result = PackageReader::check_extensions(
+            gzip,
+            buffer,
+            None,
+            None,
+            Some(&allowed),
+            None,
+        );
+        assert!(matches!(result, Err(Error::DisallowedEntry(_))));
+
+        let buffer =
+            include_bytes!("../../../fixtures/mock-package-1.0.0.tgz");
+        assert!(PackageReader::check_extensions(
+            gzip,
+            buffer,
+            None,
+            None,
+            Some(&allowed),
+            Some(&denied)
+        )
+        .is_ok());
+
+        // Entries of a zip archive
+        let buffer = include_bytes!(
+            "../../../fixtures/Mock_Pypi-1.0.0-py3-none-any.whl"
+        );
+        let result = PackageReader::check_extensions(
+            RegistryKind::Pypi.mime_type(),
+            buffer,
+            None,
+            None,
+            Some(&allowed),
+            None,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::DisallowedEntry(entry)) if entry == "mock_pypi/__init__.py"
+        ));
         Ok(())
     }
 
@@ -555,6 +1410,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn any_ref_package_key() -> Result<()> {
+        let any: AnyRef = "mock-namespace".parse()?;
+        let result: std::result::Result<PackageKey, Error> = any.try_into();
+        assert!(matches!(result, Err(Error::PackageComponent)));
+
+        let any: AnyRef = "mock-namespace/mock-package".parse()?;
+        let result: std::result::Result<PackageKey, Error> = any.try_into();
+        assert!(matches!(result, Err(Error::VersionComponent)));
+
+        let any: AnyRef = "mock-namespace/mock-package/1.0.0".parse()?;
+        let key: PackageKey = any.try_into()?;
+        assert!(matches!(
+            key,
+            PackageKey::Pointer(ns, pkg, ver)
+                if ns == Namespace::new_unchecked("mock-namespace")
+                    && pkg == PackageName::new_unchecked("mock-package")
+                    && ver == Version::new(1, 0, 0)
+        ));
+
+        let any: AnyRef =
+            "/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+                .parse()?;
+        let key: PackageKey = any.try_into()?;
+        assert!(matches!(key, PackageKey::Cid(_)));
+        assert_eq!(
+            "/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            key.to_string()
+        );
+        Ok(())
+    }
+
     #[test]
     fn parse_package_key_ipfs() -> Result<()> {
         let key = "/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
@@ -1,24 +1,126 @@
-use std::{io::prelude::*, path::PathBuf};
+use std::{
+    collections::HashSet,
+    io::{prelude::*, Cursor},
+    path::{Path, PathBuf},
+};
 
 use flate2::read::GzDecoder;
 use serde::Deserialize;
+use serde_json::{Map, Value};
 use tar::Archive;
+use zip::ZipArchive;
 
-use crate::{Error, PackageMeta, Result};
+use semver::{BuildMetadata, Prerelease, Version};
+
+use crate::{Error, PackageMeta, PackageName, RegistryKind, Result};
 
 const NPM: &str = "package/package.json";
 const CARGO: &str = "Cargo.toml";
+const OCI: &str = "manifest.json";
+const PYPI_SDIST: &str = "PKG-INFO";
+const PYPI_WHEEL: &str = "METADATA";
+const WHEEL_INFO: &str = ".dist-info";
+
+/// MIME type for gzip compressed tarballs.
+const GZIP_MIME: &str = "application/gzip";
+
+/// MIME type for zip archives.
+const ZIP_MIME: &str = "application/zip";
+
+/// Python core metadata fields that may be given more than once.
+const PYPI_MULTIPLE: &[&str] = &[
+    "platform",
+    "supported_platform",
+    "classifier",
+    "requires_dist",
+    "requires_external",
+    "project_url",
+    "provides_extra",
+    "provides_dist",
+    "obsoletes_dist",
+    "dynamic",
+    "license_file",
+];
+
+/// Container formats for package archives.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArchiveFormat {
+    /// Gzip compressed tarball.
+    TarGzip,
+    /// Zip archive, for example a Python wheel.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Container format for a MIME type.
+    ///
+    /// Every MIME type other than `application/zip` is
+    /// treated as a gzip compressed tarball.
+    pub fn from_mime(mime: &str) -> Self {
+        if mime == ZIP_MIME {
+            Self::Zip
+        } else {
+            Self::TarGzip
+        }
+    }
+
+    /// Detect the container format from the leading bytes
+    /// of an archive.
+    pub fn detect(buffer: &[u8]) -> Option<Self> {
+        if buffer.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::TarGzip)
+        } else if buffer.starts_with(b"PK\x03\x04") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+
+    /// MIME type for archives in this format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::TarGzip => GZIP_MIME,
+            Self::Zip => ZIP_MIME,
+        }
+    }
+}
 
 #[derive(Deserialize)]
 struct CargoPackage {
     package: PackageMeta,
 }
 
+#[derive(Deserialize)]
+struct OciManifest {
+    annotations: OciAnnotations,
+}
+
+#[derive(Deserialize)]
+struct OciAnnotations {
+    #[serde(rename = "org.opencontainers.image.title")]
+    name: PackageName,
+    #[serde(rename = "org.opencontainers.image.version")]
+    version: Version,
+}
+
 /// Decompress a gzip buffer.
-pub(crate) fn decompress(buffer: &[u8]) -> Result<Vec<u8>> {
+///
+/// When a limit is given decompression is aborted once the
+/// decompressed data exceeds the limit.
+pub(crate) fn decompress(
+    buffer: &[u8],
+    limit: Option<u64>,
+) -> Result<Vec<u8>> {
     let mut decoder = GzDecoder::new(buffer);
     let mut result = Vec::new();
-    decoder.read_to_end(&mut result)?;
+    if let Some(limit) = limit {
+        decoder.take(limit + 1).read_to_end(&mut result)?;
+        if result.len() as u64 > limit {
+            return Err(Error::DecompressedSize(limit));
+        }
+    } else {
+        decoder.read_to_end(&mut result)?;
+    }
     Ok(result)
 }
 
@@ -36,9 +138,10 @@ pub(crate) fn remove_npm_scope(
 /// Read a package descriptor from an NPM compatible tarball.
 pub(crate) fn read_npm_package(
     buffer: &[u8],
+    max_entries: Option<usize>,
 ) -> Result<(PackageMeta, &[u8])> {
     let package_path = PathBuf::from(NPM);
-    let buffer = find_tar_entry(package_path, buffer, true)?;
+    let buffer = find_tar_entry(package_path, buffer, true, max_entries)?;
     let descriptor: PackageMeta = serde_json::from_slice(buffer)?;
     let descriptor = remove_npm_scope(descriptor)?;
     Ok((descriptor, buffer))
@@ -47,22 +150,336 @@ pub(crate) fn read_npm_package(
 /// Read a package descriptor from a Cargo compatible tarball.
 pub(crate) fn read_cargo_package(
     buffer: &[u8],
+    max_entries: Option<usize>,
 ) -> Result<(PackageMeta, &[u8])> {
     let package_path = PathBuf::from(CARGO);
-    let buffer = find_tar_entry(package_path, buffer, false)?;
+    let buffer = find_tar_entry(package_path, buffer, false, max_entries)?;
     let descriptor: CargoPackage = toml::from_slice(buffer)?;
     Ok((descriptor.package, buffer))
 }
 
+/// Read a package descriptor from the annotations of an OCI
+/// image manifest in a tarball.
+pub(crate) fn read_oci_package(
+    buffer: &[u8],
+    max_entries: Option<usize>,
+) -> Result<(PackageMeta, &[u8])> {
+    let package_path = PathBuf::from(OCI);
+    let buffer = find_tar_entry(package_path, buffer, true, max_entries)?;
+    let manifest: OciManifest = serde_json::from_slice(buffer)?;
+    let descriptor = PackageMeta {
+        name: manifest.annotations.name,
+        version: manifest.annotations.version,
+    };
+    Ok((descriptor, buffer))
+}
+
+/// Read a package descriptor from the core metadata of a Python
+/// wheel (zip) or source distribution (gzip compressed tarball).
+///
+/// The name is normalized as described by PEP 503 and the version
+/// must be representable as a semantic version; the metadata is
+/// converted to JSON as described by PEP 566.
+pub(crate) fn read_pypi_package(
+    buffer: &[u8],
+    format: ArchiveFormat,
+    limit: Option<u64>,
+    max_entries: Option<usize>,
+) -> Result<(PackageMeta, Value)> {
+    let metadata = match format {
+        ArchiveFormat::TarGzip => {
+            let contents = decompress(buffer, limit)?;
+            find_sdist_metadata(&contents, max_entries)?.to_vec()
+        }
+        ArchiveFormat::Zip => {
+            find_wheel_metadata(buffer, limit, max_entries)?
+        }
+    };
+
+    let metadata = String::from_utf8(metadata)
+        .map_err(|_| Error::ManifestField("metadata".into()))?;
+    let value = parse_pypi_metadata(&metadata);
+
+    let name = value
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::ManifestField("name".into()))?;
+    let version = value
+        .get("version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::ManifestField("version".into()))?;
+
+    let descriptor = PackageMeta {
+        name: normalize_pypi_name(name).parse()?,
+        version: pypi_version(version)?,
+    };
+    Ok((descriptor, value))
+}
+
+/// Find the `PKG-INFO` in the top-level directory of a source
+/// distribution tarball.
+fn find_sdist_metadata(
+    buffer: &[u8],
+    max_entries: Option<usize>,
+) -> Result<&[u8]> {
+    let mut archive = Archive::new(buffer);
+    for (index, entry) in archive.entries()?.enumerate() {
+        check_entry_count(index + 1, max_entries)?;
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        if is_sdist_metadata(&path) {
+            let start_byte = entry.raw_file_position() as usize;
+            let entry_size = entry.header().entry_size()? as usize;
+            let end_byte = start_byte + entry_size;
+            return Ok(&buffer[start_byte..end_byte]);
+        }
+    }
+    Err(Error::NoPackage(PathBuf::from(PYPI_SDIST)))
+}
+
+/// Find and decompress the `METADATA` in the `.dist-info`
+/// directory of a wheel.
+fn find_wheel_metadata(
+    buffer: &[u8],
+    limit: Option<u64>,
+    max_entries: Option<usize>,
+) -> Result<Vec<u8>> {
+    let mut archive = open_zip(buffer, limit, max_entries)?;
+    for index in 0..archive.len() {
+        let file = archive.by_index(index)?;
+        if !is_wheel_metadata(Path::new(file.name())) {
+            continue;
+        }
+
+        let mut result = Vec::new();
+        if let Some(limit) = limit {
+            file.take(limit + 1).read_to_end(&mut result)?;
+            if result.len() as u64 > limit {
+                return Err(Error::DecompressedSize(limit));
+            }
+        } else {
+            let mut file = file;
+            file.read_to_end(&mut result)?;
+        }
+        return Ok(result);
+    }
+    Err(Error::NoPackage(PathBuf::from(PYPI_WHEEL)))
+}
+
+/// Open a zip archive checking the number of entries and the
+/// declared decompressed size are within the limits.
+fn open_zip(
+    buffer: &[u8],
+    limit: Option<u64>,
+    max_entries: Option<usize>,
+) -> Result<ZipArchive<Cursor<&[u8]>>> {
+    let mut archive = ZipArchive::new(Cursor::new(buffer))?;
+    check_entry_count(archive.len(), max_entries)?;
+    if let Some(limit) = limit {
+        let mut size = 0u64;
+        for index in 0..archive.len() {
+            size = size.saturating_add(archive.by_index_raw(index)?.size());
+            if size > limit {
+                return Err(Error::DecompressedSize(limit));
+            }
+        }
+    }
+    Ok(archive)
+}
+
+/// Determine if a tarball path is the `PKG-INFO` of a source
+/// distribution.
+fn is_sdist_metadata(path: &Path) -> bool {
+    path.components().count() == 2 && path.ends_with(PYPI_SDIST)
+}
+
+/// Determine if a zip path is the `METADATA` of a wheel.
+fn is_wheel_metadata(path: &Path) -> bool {
+    path.components().count() == 2
+        && path.ends_with(PYPI_WHEEL)
+        && path
+            .parent()
+            .and_then(|dir| dir.to_str())
+            .map(|dir| dir.ends_with(WHEEL_INFO))
+            .unwrap_or(false)
+}
+
+/// Convert Python core metadata to JSON.
+///
+/// Keys are lowercase with hyphens replaced by underscores, fields
+/// that may be given more than once are arrays and the message body
+/// is the `description`.
+fn parse_pypi_metadata(metadata: &str) -> Value {
+    let mut fields = Map::new();
+    let mut insert = |key: String, value: String| {
+        if PYPI_MULTIPLE.contains(&key.as_str()) {
+            let values = fields
+                .entry(key)
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(values) = values {
+                values.push(Value::String(value));
+            }
+        } else {
+            fields.insert(key, Value::String(value));
+        }
+    };
+
+    let mut lines = metadata.lines();
+    let mut current: Option<(String, String)> = None;
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+
+        // Continuation of a folded field
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = &mut current {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = current.take() {
+            insert(key, value);
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim().to_lowercase().replace('-', "_");
+            current = Some((key, value.trim().to_owned()));
+        }
+    }
+    if let Some((key, value)) = current.take() {
+        insert(key, value);
+    }
+
+    let body = lines.collect::<Vec<_>>().join("\n");
+    if !body.trim().is_empty() {
+        fields.insert("description".to_owned(), Value::String(body));
+    }
+    Value::Object(fields)
+}
+
+/// Normalize a Python package name as described by PEP 503.
+///
+/// Runs of `-`, `_` and `.` are replaced by a single hyphen
+/// and the name is converted to lowercase.
+pub(crate) fn normalize_pypi_name(name: &str) -> String {
+    let mut result = String::new();
+    let mut separator = false;
+    for c in name.chars() {
+        if c == '-' || c == '_' || c == '.' {
+            separator = true;
+            continue;
+        }
+        if separator {
+            result.push('-');
+            separator = false;
+        }
+        result.extend(c.to_lowercase());
+    }
+    if separator {
+        result.push('-');
+    }
+    result
+}
+
+/// Convert a PEP 440 version to a semantic version.
+///
+/// Missing release components are zero, alpha, beta and release
+/// candidate segments become the pre-release (`1.0rc1` is
+/// `1.0.0-rc.1`) and a local version becomes the build metadata.
+/// Epochs, post-releases and development releases have no
+/// equivalent precedence and are rejected.
+pub(crate) fn pypi_version(value: &str) -> Result<Version> {
+    let invalid = || Error::PypiVersion(value.to_owned());
+    let normalized = value.trim().to_lowercase();
+    let normalized = normalized.strip_prefix('v').unwrap_or(&normalized);
+
+    let (public, local) = match normalized.split_once('+') {
+        Some((public, local)) => (public, Some(local)),
+        None => (normalized, None),
+    };
+
+    let release_len = public
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(public.len());
+    let (release, rest) = public.split_at(release_len);
+    let release = release.trim_end_matches('.');
+
+    let mut parts = [0u64; 3];
+    let components: Vec<&str> = release.split('.').collect();
+    if release.is_empty() || components.len() > parts.len() {
+        return Err(invalid());
+    }
+    for (part, component) in parts.iter_mut().zip(components) {
+        *part = component.parse().map_err(|_| invalid())?;
+    }
+
+    let mut version = Version::new(parts[0], parts[1], parts[2]);
+
+    let rest = rest.trim_start_matches(['.', '-', '_']);
+    if !rest.is_empty() {
+        let label_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (label, number) = rest.split_at(label_len);
+        let label = match label {
+            "a" | "alpha" => "a",
+            "b" | "beta" => "b",
+            "c" | "rc" | "pre" | "preview" => "rc",
+            _ => return Err(invalid()),
+        };
+        let number = number.trim_start_matches(['.', '-']);
+        let number: u64 = if number.is_empty() {
+            0
+        } else {
+            number.parse().map_err(|_| invalid())?
+        };
+        version.pre = Prerelease::new(&format!("{}.{}", label, number))
+            .map_err(|_| invalid())?;
+    }
+
+    if let Some(local) = local {
+        let local = local.replace(['-', '_'], ".");
+        version.build = BuildMetadata::new(&local).map_err(|_| invalid())?;
+    }
+
+    Ok(version)
+}
+
+/// Ensure the number of entries read from an archive is
+/// within the limit.
+fn check_entry_count(count: usize, limit: Option<usize>) -> Result<()> {
+    match limit {
+        Some(limit) if count > limit => Err(Error::ArchiveEntries(limit)),
+        _ => Ok(()),
+    }
+}
+
 /// Find the file data for a specific entry in a tarball.
+///
+/// When a limit is given every entry is counted so the walk
+/// is aborted once the archive exceeds the limit even if the
+/// entry has already been found.
 fn find_tar_entry(
     package_path: PathBuf,
     buffer: &[u8],
     exact: bool,
+    max_entries: Option<usize>,
 ) -> Result<&[u8]> {
     let mut archive = Archive::new(buffer);
-    for entry in archive.entries()? {
+    let mut file_bytes = None;
+    for (index, entry) in archive.entries()?.enumerate() {
+        check_entry_count(index + 1, max_entries)?;
         let entry = entry?;
+        if file_bytes.is_some() {
+            continue;
+        }
+
         let path = entry.path()?;
 
         let matched = if exact {
@@ -75,11 +492,185 @@ fn find_tar_entry(
             let start_byte = entry.raw_file_position() as usize;
             let entry_size = entry.header().entry_size()? as usize;
             let end_byte = start_byte + entry_size;
-            let file_bytes = &buffer[start_byte..end_byte];
-            return Ok(file_bytes);
+            file_bytes = Some(&buffer[start_byte..end_byte]);
+            if max_entries.is_none() {
+                break;
+            }
         }
     }
-    Err(Error::NoPackage(package_path))
+    file_bytes.ok_or(Error::NoPackage(package_path))
+}
+
+/// Detect the kinds of package in a tarball from the manifest
+/// entries in the archive.
+///
+/// An archive may contain the manifests for more than one kind,
+/// for example an NPM package that bundles a crate; an empty list
+/// means no manifest was found.
+pub(crate) fn detect_kinds(
+    buffer: &[u8],
+    max_entries: Option<usize>,
+) -> Result<Vec<RegistryKind>> {
+    let mut kinds = Vec::new();
+    let mut archive = Archive::new(buffer);
+    for (index, entry) in archive.entries()?.enumerate() {
+        check_entry_count(index + 1, max_entries)?;
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        let kind = if path.as_ref() == PathBuf::from(NPM).as_path() {
+            Some(RegistryKind::Npm)
+        } else if path.as_ref() == PathBuf::from(OCI).as_path() {
+            Some(RegistryKind::Oci)
+        } else if path.components().count() == 2 && path.ends_with(CARGO) {
+            Some(RegistryKind::Cargo)
+        } else if is_sdist_metadata(&path) {
+            Some(RegistryKind::Pypi)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+    }
+    Ok(kinds)
+}
+
+/// Detect the kinds of package in a zip archive from the
+/// manifest entries in the archive.
+///
+/// Only Python wheels are distributed as zip archives.
+pub(crate) fn detect_zip_kinds(
+    buffer: &[u8],
+    max_entries: Option<usize>,
+) -> Result<Vec<RegistryKind>> {
+    let archive = open_zip(buffer, None, max_entries)?;
+    let is_wheel = archive
+        .file_names()
+        .any(|name| is_wheel_metadata(Path::new(name)));
+    Ok(if is_wheel {
+        vec![RegistryKind::Pypi]
+    } else {
+        Vec::new()
+    })
+}
+
+/// Find the README at the root of the package directory in a tarball.
+///
+/// The package directory is the top-level directory of the
+/// archive, for example `package/` for NPM; the file name is
+/// matched case-insensitively with or without an extension.
+/// A README that is not valid UTF-8 is ignored.
+pub(crate) fn find_readme(
+    buffer: &[u8],
+    max_entries: Option<usize>,
+) -> Result<Option<String>> {
+    let mut archive = Archive::new(buffer);
+    for (index, entry) in archive.entries()?.enumerate() {
+        check_entry_count(index + 1, max_entries)?;
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        let is_readme = path.components().count() == 2
+            && path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_lowercase() == "readme")
+                .unwrap_or(false);
+
+        if is_readme {
+            let start_byte = entry.raw_file_position() as usize;
+            let entry_size = entry.header().entry_size()? as usize;
+            let end_byte = start_byte + entry_size;
+            if let Ok(readme) =
+                std::str::from_utf8(&buffer[start_byte..end_byte])
+            {
+                return Ok(Some(readme.to_owned()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Find the first file entry in a tarball with a file extension
+/// that is denied or, when an allowed list is given, not allowed.
+///
+/// Extensions are compared case-insensitively without the leading
+/// period; files without an extension match the empty string.
+pub(crate) fn find_disallowed_entry(
+    buffer: &[u8],
+    max_entries: Option<usize>,
+    allowed: Option<&HashSet<String>>,
+    denied: Option<&HashSet<String>>,
+) -> Result<Option<String>> {
+    let mut archive = Archive::new(buffer);
+    for (index, entry) in archive.entries()?.enumerate() {
+        check_entry_count(index + 1, max_entries)?;
+        let entry = entry?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        if is_disallowed(&path, allowed, denied) {
+            return Ok(Some(path.to_string_lossy().into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Find the first file entry in a zip archive with a file extension
+/// that is denied or, when an allowed list is given, not allowed.
+pub(crate) fn find_disallowed_zip_entry(
+    buffer: &[u8],
+    limit: Option<u64>,
+    max_entries: Option<usize>,
+    allowed: Option<&HashSet<String>>,
+    denied: Option<&HashSet<String>>,
+) -> Result<Option<String>> {
+    let mut archive = open_zip(buffer, limit, max_entries)?;
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index)?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let path = Path::new(file.name());
+        if is_disallowed(path, allowed, denied) {
+            return Ok(Some(file.name().to_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Determine if the file extension of an archive entry is denied
+/// or, when an allowed list is given, not allowed.
+fn is_disallowed(
+    path: &Path,
+    allowed: Option<&HashSet<String>>,
+    denied: Option<&HashSet<String>>,
+) -> bool {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let is_denied = denied
+        .map(|denied| denied.contains(&extension))
+        .unwrap_or(false);
+    let is_allowed = allowed
+        .map(|allowed| allowed.contains(&extension))
+        .unwrap_or(true);
+
+    is_denied || !is_allowed
 }
 
 #[cfg(test)]
@@ -105,12 +696,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn pypi_name_normalize() -> Result<()> {
+        assert_eq!("mock-pypi", normalize_pypi_name("Mock_Pypi"));
+        assert_eq!("mock-pypi", normalize_pypi_name("mock.-_pypi"));
+        assert_eq!("mock-pypi", normalize_pypi_name("MOCK-PYPI"));
+        Ok(())
+    }
+
+    #[test]
+    fn pypi_version_convert() -> Result<()> {
+        assert_eq!(Version::new(1, 0, 0), pypi_version("1")?);
+        assert_eq!(Version::new(1, 2, 0), pypi_version("1.2")?);
+        assert_eq!(Version::new(1, 2, 3), pypi_version("v1.2.3")?);
+        assert_eq!(Version::parse("1.0.0-rc.1")?, pypi_version("1.0rc1")?);
+        assert_eq!(Version::parse("2.0.0-b.0")?, pypi_version("2.0b")?);
+        assert_eq!(
+            Version::parse("1.0.0+ubuntu.1")?,
+            pypi_version("1.0+ubuntu-1")?
+        );
+        for version in ["1.0.post1", "1.0.dev1", "1!1.0", "1.2.3.4", ""] {
+            assert!(matches!(
+                pypi_version(version),
+                Err(Error::PypiVersion(_))
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pypi_metadata_parse() -> Result<()> {
+        let metadata = "Metadata-Version: 2.1\nName: mock\nRequires-Dist: a\nRequires-Dist: b\nLicense: line one\n        line two\n\nBody text\n";
+        let value = parse_pypi_metadata(metadata);
+        assert_eq!(Some("2.1"), value["metadata_version"].as_str());
+        assert_eq!(serde_json::json!(["a", "b"]), value["requires_dist"]);
+        assert_eq!(Some("line one\nline two"), value["license"].as_str());
+        assert_eq!(Some("Body text"), value["description"].as_str());
+        Ok(())
+    }
+
     #[test]
     fn decompress_tarball() -> Result<()> {
         let file = PathBuf::from("../../fixtures/mock-package-1.0.0.tgz");
         let contents = std::fs::read(&file)?;
-        let decompressed = decompress(&contents)?;
-        let (descriptor, _) = read_npm_package(&decompressed)?;
+        let decompressed = decompress(&contents, None)?;
+        let (descriptor, _) = read_npm_package(&decompressed, None)?;
         assert_eq!(1u64, descriptor.version.major);
         assert_eq!(
             PackageName::new_unchecked("mock-package"),
@@ -118,4 +748,41 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn decompress_limit() -> Result<()> {
+        let file =
+            PathBuf::from("../../fixtures/decompression-bomb-1.0.0.tgz");
+        let contents = std::fs::read(&file)?;
+        let result = decompress(&contents, Some(65536));
+        assert!(matches!(result, Err(Error::DecompressedSize(65536))));
+
+        let file = PathBuf::from("../../fixtures/mock-package-1.0.0.tgz");
+        let contents = std::fs::read(&file)?;
+        assert!(decompress(&contents, Some(65536)).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn archive_entry_limit() -> Result<()> {
+        let file = PathBuf::from("../../fixtures/entry-bomb-1.0.0.tgz");
+        let contents = std::fs::read(&file)?;
+        let decompressed = decompress(&contents, None)?;
+        let result = read_npm_package(&decompressed, Some(1024));
+        assert!(matches!(result, Err(Error::ArchiveEntries(1024))));
+        let result =
+            find_disallowed_entry(&decompressed, Some(1024), None, None);
+        assert!(matches!(result, Err(Error::ArchiveEntries(1024))));
+        assert!(read_npm_package(&decompressed, None).is_ok());
+
+        // Limit applies after the manifest has been found
+        let file = PathBuf::from("../../fixtures/mock-package-1.0.0.tgz");
+        let contents = std::fs::read(&file)?;
+        let decompressed = decompress(&contents, None)?;
+        assert!(read_npm_package(&decompressed, Some(1)).is_ok());
+        let result =
+            find_tar_entry(PathBuf::from(NPM), &decompressed, true, Some(0));
+        assert!(matches!(result, Err(Error::ArchiveEntries(0))));
+        Ok(())
+    }
 }
@@ -0,0 +1,76 @@
+//! Proof of work for namespace registration.
+use sha3::{Digest, Sha3_256};
+
+use crate::Namespace;
+
+/// Maximum difficulty for a proof of work, the number of bits
+/// in the hash.
+pub const MAX_DIFFICULTY: u32 = 256;
+
+/// Compute the hash of a namespace and nonce.
+fn work_hash(namespace: &Namespace, nonce: u64) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(namespace.as_bytes());
+    hasher.update(nonce.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Count the number of leading zero bits in a hash.
+fn leading_zeros(hash: &[u8]) -> u32 {
+    let mut count = 0;
+    for byte in hash {
+        if *byte == 0 {
+            count += 8;
+        } else {
+            count += byte.leading_zeros();
+            break;
+        }
+    }
+    count
+}
+
+/// Verify a nonce is a valid proof of work for a namespace.
+///
+/// The hash of the namespace followed by the big-endian bytes
+/// of the nonce must have at least `difficulty` leading zero bits.
+pub fn verify_work(
+    namespace: &Namespace,
+    nonce: u64,
+    difficulty: u32,
+) -> bool {
+    leading_zeros(&work_hash(namespace, nonce)) >= difficulty
+}
+
+/// Find a nonce that is a valid proof of work for a namespace.
+pub fn solve_work(namespace: &Namespace, difficulty: u32) -> u64 {
+    let mut nonce = 0u64;
+    while !verify_work(namespace, nonce, difficulty) {
+        nonce += 1;
+    }
+    nonce
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn count_leading_zeros() -> Result<()> {
+        assert_eq!(0, leading_zeros(&[0x80, 0x00]));
+        assert_eq!(7, leading_zeros(&[0x01, 0x00]));
+        assert_eq!(12, leading_zeros(&[0x00, 0x08]));
+        assert_eq!(16, leading_zeros(&[0x00, 0x00]));
+        Ok(())
+    }
+
+    #[test]
+    fn solve_and_verify_work() -> Result<()> {
+        let namespace = Namespace::new_unchecked("mock-namespace");
+        let nonce = solve_work(&namespace, 12);
+        assert!(verify_work(&namespace, nonce, 12));
+        assert!(verify_work(&namespace, nonce, 0));
+        assert!(!verify_work(&namespace, nonce, 256));
+        Ok(())
+    }
+}
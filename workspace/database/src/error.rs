@@ -42,10 +42,26 @@ pub enum Error {
     #[error("package {0}/{1}/{2} already exists")]
     PackageExists(Namespace, PackageName, Version),
 
+    /// Error generated when a package name is already taken in a namespace.
+    #[error("package {0}/{1} already exists")]
+    PackageNameExists(Namespace, PackageName),
+
+    /// Error generated when a namespace name is already taken.
+    #[error("namespace {0} already exists")]
+    NamespaceExists(Namespace),
+
+    /// Error generated when a namespace is merged into itself.
+    #[error("namespace {0} cannot be merged into itself")]
+    MergeSelf(Namespace),
+
     /// Error generated when a user is not authorized.
     #[error("user {0} is not authorized")]
     Unauthorized(Address),
 
+    /// Error generated when an address is not a registered publisher.
+    #[error("publisher {0} is not registered")]
+    UnknownPublisher(Address),
+
     /// Error generated when a user already exists.
     #[error("user {0} already exists in {1}")]
     UserExists(Address, String),
@@ -54,6 +70,11 @@ pub enum Error {
     #[error("{0}")]
     NotFound(NotFound),
 
+    /// Error generated when an address is already in use
+    /// by a publisher or was used by a publisher.
+    #[error("publisher key {0} already exists")]
+    PublisherKeyExists(Address),
+
     /// Error generated when an access restriction already exists.
     #[error("user {0} already has access to {1}")]
     AccessRestrictionExists(Address, PackageName),
@@ -62,14 +83,45 @@ pub enum Error {
     #[error("user {0} does not have access to {1}")]
     AccessRestrictionMissing(Address, PackageName),
 
+    /// Error generated when a trusted key already exists.
+    #[error("key {0} is already trusted for {1}")]
+    TrustedKeyExists(Address, PackageName),
+
+    /// Error generated when a trusted key does not exist.
+    #[error("key {0} is not trusted for {1}")]
+    TrustedKeyMissing(Address, PackageName),
+
+    /// Error generated when publishing a version that was yanked.
+    #[error("version {0}/{1}/{2} was yanked, choose a new version")]
+    VersionYanked(Namespace, PackageName, Version),
+
+    /// Error generated when restoring a yanked version whose
+    /// artifact has been purged.
+    #[error("version {0} was purged and cannot be restored")]
+    VersionPurged(PackageKey),
+
+    /// Error generated when a forced publish is outside of the
+    /// window for overwriting a version.
+    #[error("version {0}/{1}/{2} can no longer be overwritten")]
+    OverwriteWindow(Namespace, PackageName, Version),
+
     /// Error generated when a version is not ahead of the latest version.
     #[error("version {0} is not ahead of latest {1}")]
     VersionNotAhead(Version, Version),
 
+    /// Error generated when a content identifier matches more than
+    /// one version and duplicates are rejected.
+    #[error("{0} is ambiguous, it matches {1} versions")]
+    AmbiguousCid(PackageKey, usize),
+
     /// Error generated if fetching a record fails immediately after insertion.
     #[error("failed to fetch record {0} after insert")]
     InsertFetch(i64),
 
+    /// Error generated when a pagination cursor is invalid.
+    #[error("invalid cursor {0}")]
+    InvalidCursor(String),
+
     /// Error generated when a sort order is invalid.
     #[error("invalid sort order {0}")]
     InvalidSortOrder(String),
@@ -78,6 +130,14 @@ pub enum Error {
     #[error("invalid version includes {0}")]
     InvalidVersionIncludes(String),
 
+    /// Error generated when a publish permission is invalid.
+    #[error("invalid publish permission {0}")]
+    UnknownPermission(String),
+
+    /// Error generated when a description is too long.
+    #[error("description exceeds the maximum length of {0} bytes")]
+    DescriptionLength(usize),
+
     /// Error generated when the a version for a package could not be found.
     #[error("could not find a version for a package")]
     NoPackageVersion,
@@ -117,4 +177,8 @@ pub enum Error {
     /// Error generated by the time library when parsing.
     #[error(transparent)]
     TimeParse(#[from] time::error::Parse),
+
+    /// Error generated by the time library when formatting.
+    #[error(transparent)]
+    TimeFormat(#[from] time::error::Format),
 }
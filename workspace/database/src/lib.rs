@@ -1,12 +1,18 @@
 //! Database model and value objects.
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
+// Existing code predates these lints
+#![allow(
+    clippy::needless_borrow,
+    clippy::collapsible_match,
+    clippy::get_first
+)]
 
 mod error;
 mod model;
 mod value_objects;
 
-pub use error::Error;
+pub use error::{Error, NotFound};
 
 /// Result type for the database library.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,100 @@
+//! Model for the audit log.
+use ipfs_registry_core::Namespace;
+use serde_json::Value;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
+
+use crate::{
+    model::{Pager, SQL_NOW},
+    value_objects::*,
+    Result,
+};
+
+/// Record and list actions taken by registry administrators.
+pub struct AuditModel;
+
+impl AuditModel {
+    /// Record an action in the audit log as part of a transaction.
+    pub(crate) async fn insert(
+        tx: &mut Transaction<'_, Sqlite>,
+        publisher_id: i64,
+        action: &str,
+        namespace: Option<&Namespace>,
+        detail: &Value,
+    ) -> Result<i64> {
+        let mut builder = QueryBuilder::new(
+            r#"
+                INSERT INTO audit_log
+                    ( publisher_id, action, namespace, detail, created_at )
+                VALUES (
+            "#,
+        );
+        let mut separated = builder.separated(", ");
+        separated.push_bind(publisher_id);
+        separated.push_bind(action);
+        separated.push_bind(namespace.map(|n| n.as_str()));
+        separated.push_bind(serde_json::to_string(detail)?);
+        builder.push(format!(", {} )", SQL_NOW));
+
+        let id = builder.build().execute(tx).await?.last_insert_rowid();
+
+        Ok(id)
+    }
+
+    /// List entries in the audit log that match the filter
+    /// in the order they were recorded.
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &AuditFilter,
+        pager: &Pager,
+    ) -> Result<ResultSet<AuditRecord>> {
+        let mut builder = QueryBuilder::new(
+            r#"
+                SELECT
+                    COUNT(*) OVER () as count,
+                    audit_log.audit_id,
+                    audit_log.action,
+                    audit_log.namespace,
+                    audit_log.detail,
+                    audit_log.created_at,
+                    publishers.address
+                FROM audit_log
+                INNER JOIN publishers
+                    ON (audit_log.publisher_id = publishers.publisher_id)
+                WHERE 1 = 1
+            "#,
+        );
+
+        if let Some(action) = &filter.action {
+            builder.push(" AND audit_log.action = ");
+            builder.push_bind(action);
+        }
+        if let Some(namespace) = &filter.namespace {
+            builder.push(" AND audit_log.namespace = ");
+            builder.push_bind(namespace.as_str());
+        }
+        if let Some(address) = &filter.address {
+            builder.push(" AND publishers.address = ");
+            builder.push_bind(address.as_ref());
+        }
+        if let Some(since) = &filter.since {
+            builder.push(" AND audit_log.created_at >= ");
+            builder.push_bind(format_date_time(since)?);
+        }
+        if let Some(until) = &filter.until {
+            builder.push(" AND audit_log.created_at < ");
+            builder.push_bind(format_date_time(until)?);
+        }
+
+        builder.push(format!(" ORDER BY audit_log.audit_id {}", pager.sort));
+        builder.push(" LIMIT ");
+        builder.push_bind(pager.limit);
+        builder.push(" OFFSET ");
+        builder.push_bind(pager.offset);
+
+        let records = builder
+            .build_query_as::<AuditRecord>()
+            .fetch_all(pool)
+            .await?;
+        Ok(records.into_result_set())
+    }
+}
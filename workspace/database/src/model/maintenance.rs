@@ -0,0 +1,605 @@
+//! Model for registry maintenance tasks.
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite, SqlitePool, Transaction};
+use std::collections::{BTreeMap, HashSet};
+use web3_address::ethereum::Address;
+
+use ipfs_registry_core::{Namespace, PackageName};
+
+use crate::{
+    error::NotFound,
+    model::{AuditModel, PackageModel, Pager, PublisherModel, SQL_NOW},
+    value_objects::*,
+    Error, Result,
+};
+
+/// Run maintenance tasks against the registry database.
+pub struct MaintenanceModel;
+
+impl MaintenanceModel {
+    /// Check the database is available.
+    pub async fn ping(pool: &SqlitePool) -> Result<()> {
+        sqlx::query("SELECT 1").execute(pool).await?;
+        Ok(())
+    }
+
+    /// Count the records in the registry.
+    pub async fn count_records(pool: &SqlitePool) -> Result<RegistryCounts> {
+        let (publishers, namespaces, packages, versions) =
+            sqlx::query_as::<_, (i64, i64, i64, i64)>(
+                r#"
+                    SELECT
+                        (SELECT COUNT(*) FROM publishers),
+                        (SELECT COUNT(*) FROM namespaces),
+                        (SELECT COUNT(*) FROM packages),
+                        (SELECT COUNT(*) FROM versions)
+                "#,
+            )
+            .fetch_one(pool)
+            .await?;
+
+        Ok(RegistryCounts {
+            publishers,
+            namespaces,
+            packages,
+            versions,
+        })
+    }
+
+    /// Recompute the confusable skeleton for every namespace
+    /// and package name.
+    ///
+    /// Skeletons that have changed are updated in a single
+    /// transaction and any collisions involving a changed
+    /// skeleton are reported.
+    ///
+    /// Namespace skeletons must be unique so when names collide
+    /// only the oldest namespace is assigned the skeleton, the
+    /// others keep their existing skeleton and must be resolved
+    /// manually.
+    pub async fn reindex_skeletons(
+        pool: &SqlitePool,
+    ) -> Result<ReindexReport> {
+        let mut report: ReindexReport = Default::default();
+        let mut tx = pool.begin().await?;
+
+        // Namespaces
+        let rows = sqlx::query_as::<_, (i64, String, String)>(
+            r#"
+                SELECT namespace_id, name, skeleton
+                FROM namespaces
+                ORDER BY namespace_id
+            "#,
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        let mut groups: BTreeMap<String, Vec<(i64, String, String)>> =
+            BTreeMap::new();
+        for (namespace_id, name, current) in rows {
+            let skeleton = Namespace::new_unchecked(&name).skeleton();
+            groups.entry(skeleton).or_default().push((
+                namespace_id,
+                name,
+                current,
+            ));
+        }
+
+        let mut updates = Vec::new();
+        let assigned: HashSet<String> = groups.keys().cloned().collect();
+        for (skeleton, rows) in groups {
+            let changed =
+                rows.iter().any(|(_, _, current)| current != &skeleton);
+            if rows.len() > 1 && changed {
+                report.collisions.push(SkeletonCollision {
+                    skeleton: skeleton.clone(),
+                    names: rows
+                        .iter()
+                        .map(|(_, name, _)| name.clone())
+                        .collect(),
+                });
+            }
+
+            for (index, (namespace_id, _, current)) in
+                rows.into_iter().enumerate()
+            {
+                let target = if index == 0 {
+                    skeleton.clone()
+                } else if !assigned.contains(&current) {
+                    current.clone()
+                } else {
+                    format!("{}#{}", skeleton, namespace_id)
+                };
+                if target != current {
+                    updates.push((namespace_id, target));
+                }
+            }
+        }
+
+        // Move changed rows out of the way first so swapping
+        // skeletons does not violate the unique constraint
+        for (namespace_id, _) in &updates {
+            let mut builder = QueryBuilder::new(
+                r#"
+                    UPDATE namespaces
+                    SET skeleton = '#' || namespace_id
+                    WHERE namespace_id =
+                "#,
+            );
+            builder.push_bind(namespace_id);
+            builder.build().execute(&mut tx).await?;
+        }
+
+        for (namespace_id, skeleton) in &updates {
+            let mut builder =
+                QueryBuilder::new("UPDATE namespaces SET skeleton = ");
+            builder.push_bind(skeleton);
+            builder.push(" WHERE namespace_id = ");
+            builder.push_bind(namespace_id);
+            builder.build().execute(&mut tx).await?;
+        }
+        report.namespaces = updates.len();
+
+        // Package skeletons are scoped to the namespace
+        let rows = sqlx::query_as::<_, (i64, String, String, String)>(
+            r#"
+                SELECT
+                    packages.package_id,
+                    namespaces.name,
+                    packages.name,
+                    packages.skeleton
+                FROM packages
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                ORDER BY packages.package_id
+            "#,
+        )
+        .fetch_all(&mut tx)
+        .await?;
+
+        let mut groups: BTreeMap<(String, String), Vec<(String, bool)>> =
+            BTreeMap::new();
+        for (package_id, namespace, name, current) in rows {
+            let skeleton = PackageName::new_unchecked(&name).skeleton();
+            let changed = skeleton != current;
+            if changed {
+                let mut builder =
+                    QueryBuilder::new("UPDATE packages SET skeleton = ");
+                builder.push_bind(&skeleton);
+                builder.push(" WHERE package_id = ");
+                builder.push_bind(package_id);
+                builder.build().execute(&mut tx).await?;
+                report.packages += 1;
+            }
+            groups
+                .entry((namespace.clone(), skeleton))
+                .or_default()
+                .push((format!("{}/{}", namespace, name), changed));
+        }
+
+        for ((_, skeleton), rows) in groups {
+            if rows.len() > 1 && rows.iter().any(|(_, changed)| *changed) {
+                report.collisions.push(SkeletonCollision {
+                    skeleton,
+                    names: rows.into_iter().map(|(name, _)| name).collect(),
+                });
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    /// Find all versions of the packages in a namespace including
+    /// yanked and purged versions grouped by package.
+    pub async fn find_namespace_versions(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+    ) -> Result<Vec<(PackageName, Vec<VersionRecord>)>> {
+        let rows = sqlx::query(
+            r#"
+                SELECT versions.*, packages.name AS package_name
+                FROM versions
+                INNER JOIN packages
+                    ON (versions.package_id = packages.package_id)
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                WHERE namespaces.name = ?
+                ORDER BY versions.package_id, versions.version_id
+            "#,
+        )
+        .bind(namespace.as_str())
+        .fetch_all(pool)
+        .await?;
+
+        let mut packages: Vec<(PackageName, Vec<VersionRecord>)> = Vec::new();
+        for row in rows {
+            let name: String = row.try_get("package_name")?;
+            let record = VersionRecord::from_row(&row)?;
+            match packages.last_mut() {
+                Some((last, versions)) if last.as_str() == name => {
+                    versions.push(record);
+                }
+                _ => {
+                    packages.push((
+                        PackageName::new_unchecked(&name),
+                        vec![record],
+                    ));
+                }
+            }
+        }
+        Ok(packages)
+    }
+
+    /// Resolve a namespace collision by renaming the namespace
+    /// or merging the packages into another namespace.
+    ///
+    /// Changes are applied in a single transaction and recorded
+    /// in the audit log; for a dry run the transaction is rolled
+    /// back so the report reflects the changes that would be made.
+    ///
+    /// Pointer identifiers are derived from the namespace so the
+    /// locations of the artifacts stored for the new namespace
+    /// are updated in the same transaction.
+    ///
+    /// Callers must verify the caller is a registry administrator.
+    pub async fn resolve_namespace(
+        pool: &SqlitePool,
+        caller: &Address,
+        request: &ResolveNamespace,
+        locations: &[VersionLocation],
+    ) -> Result<ResolveReport> {
+        let publisher_record = PublisherModel::find_by_address(pool, caller)
+            .await?
+            .ok_or(Error::NotFound(NotFound::User(*caller)))?;
+
+        let mut tx = pool.begin().await?;
+
+        let namespace_id =
+            MaintenanceModel::find_namespace_id(&mut tx, &request.namespace)
+                .await?;
+
+        let packages = sqlx::query_scalar::<_, String>(
+            r#"
+                SELECT name FROM packages
+                WHERE namespace_id = ?
+                ORDER BY package_id
+            "#,
+        )
+        .bind(namespace_id)
+        .fetch_all(&mut tx)
+        .await?
+        .into_iter()
+        .map(|name| PackageName::new_unchecked(&name))
+        .collect::<Vec<_>>();
+
+        match &request.action {
+            NamespaceAction::Rename(name) => {
+                let skeleton = name.skeleton();
+                let existing = sqlx::query_scalar::<_, String>(
+                    r#"
+                        SELECT name FROM namespaces
+                        WHERE (name = ? OR skeleton = ?)
+                        AND namespace_id != ?
+                    "#,
+                )
+                .bind(name.as_str())
+                .bind(&skeleton)
+                .bind(namespace_id)
+                .fetch_optional(&mut tx)
+                .await?;
+                if let Some(existing) = existing {
+                    return Err(Error::NamespaceExists(
+                        Namespace::new_unchecked(&existing),
+                    ));
+                }
+
+                let mut builder =
+                    QueryBuilder::new("UPDATE namespaces SET name = ");
+                builder.push_bind(name.as_str());
+                builder.push(", skeleton = ");
+                builder.push_bind(&skeleton);
+                builder.push(" WHERE namespace_id = ");
+                builder.push_bind(namespace_id);
+                builder.build().execute(&mut tx).await?;
+            }
+            NamespaceAction::Merge(target) => {
+                if target == &request.namespace {
+                    return Err(Error::MergeSelf(target.clone()));
+                }
+
+                let target_id =
+                    MaintenanceModel::find_namespace_id(&mut tx, target)
+                        .await?;
+
+                for package in &packages {
+                    let existing = sqlx::query_scalar::<_, String>(
+                        r#"
+                            SELECT name FROM packages
+                            WHERE namespace_id = ? AND skeleton = ?
+                        "#,
+                    )
+                    .bind(target_id)
+                    .bind(package.skeleton())
+                    .fetch_optional(&mut tx)
+                    .await?;
+                    if let Some(existing) = existing {
+                        return Err(Error::PackageNameExists(
+                            target.clone(),
+                            PackageName::new_unchecked(&existing),
+                        ));
+                    }
+                }
+
+                // Restrictions were granted to users of the
+                // namespace being removed
+                sqlx::query(
+                    r#"
+                        DELETE FROM publisher_restrictions
+                        WHERE package_id IN (
+                            SELECT package_id FROM packages
+                            WHERE namespace_id = ?
+                        )
+                    "#,
+                )
+                .bind(namespace_id)
+                .execute(&mut tx)
+                .await?;
+
+                sqlx::query(
+                    "UPDATE packages SET namespace_id = ? WHERE namespace_id = ?",
+                )
+                .bind(target_id)
+                .bind(namespace_id)
+                .execute(&mut tx)
+                .await?;
+
+                sqlx::query(
+                    "DELETE FROM namespace_publishers WHERE namespace_id = ?",
+                )
+                .bind(namespace_id)
+                .execute(&mut tx)
+                .await?;
+
+                sqlx::query("DELETE FROM namespaces WHERE namespace_id = ?")
+                    .bind(namespace_id)
+                    .execute(&mut tx)
+                    .await?;
+            }
+        }
+
+        PackageModel::relocate_versions(&mut tx, locations).await?;
+
+        let report = ResolveReport {
+            namespace: request.namespace.clone(),
+            action: request.action.clone(),
+            packages,
+            dry_run: request.dry_run,
+        };
+
+        AuditModel::insert(
+            &mut tx,
+            publisher_record.publisher_id,
+            "resolve_namespace",
+            Some(&report.namespace),
+            &serde_json::to_value(&report)?,
+        )
+        .await?;
+
+        if request.dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        Ok(report)
+    }
+
+    /// Remove packages that do not have any versions.
+    ///
+    /// A package is only removed when it is still empty inside
+    /// the transaction so a concurrent publish is not lost;
+    /// publisher restrictions and trusted keys for a removed
+    /// package are also removed.
+    pub async fn remove_empty_packages(
+        pool: &SqlitePool,
+        dry_run: bool,
+    ) -> Result<CleanupReport> {
+        let packages = PackageModel::find_empty_packages(pool).await?;
+        if dry_run {
+            return Ok(CleanupReport { packages, dry_run });
+        }
+
+        let mut report: CleanupReport = Default::default();
+        let mut tx = pool.begin().await?;
+        for package in packages {
+            let versions = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM versions WHERE package_id = ?",
+            )
+            .bind(package.package_id)
+            .fetch_one(&mut tx)
+            .await?;
+
+            if versions > 0 {
+                continue;
+            }
+
+            for table in
+                ["publisher_restrictions", "package_trusted_keys", "packages"]
+            {
+                sqlx::query(&format!(
+                    "DELETE FROM {} WHERE package_id = ?",
+                    table
+                ))
+                .bind(package.package_id)
+                .execute(&mut tx)
+                .await?;
+            }
+
+            report.packages.push(package);
+        }
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    /// Rotate the key of a publisher.
+    ///
+    /// The previous address is moved to the key history so
+    /// versions signed before the rotation can still be verified;
+    /// an address that belongs to or belonged to any publisher
+    /// cannot be rotated to.
+    pub async fn rotate_key(
+        pool: &SqlitePool,
+        caller: &Address,
+        request: &RotateKey,
+    ) -> Result<PublisherRecord> {
+        let caller_record = PublisherModel::find_by_address(pool, caller)
+            .await?
+            .ok_or(Error::NotFound(NotFound::User(*caller)))?;
+
+        let publisher_record =
+            PublisherModel::find_by_address(pool, &request.from)
+                .await?
+                .ok_or(Error::NotFound(NotFound::User(request.from)))?;
+
+        let mut tx = pool.begin().await?;
+
+        let exists = sqlx::query_scalar::<_, i64>(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM publishers WHERE address = ?
+                    UNION ALL
+                    SELECT 1 FROM key_history WHERE address = ?
+                )
+            "#,
+        )
+        .bind(request.to.as_ref())
+        .bind(request.to.as_ref())
+        .fetch_one(&mut tx)
+        .await?;
+
+        if exists == 1 {
+            return Err(Error::PublisherKeyExists(request.to));
+        }
+
+        let mut builder = QueryBuilder::new(
+            r#"
+                INSERT INTO key_history ( publisher_id, address, created_at )
+                VALUES (
+            "#,
+        );
+        let mut separated = builder.separated(", ");
+        separated.push_bind(publisher_record.publisher_id);
+        separated.push_bind(request.from.as_ref());
+        builder.push(format!(", {} )", SQL_NOW));
+        builder.build().execute(&mut tx).await?;
+
+        sqlx::query(
+            "UPDATE publishers SET address = ? WHERE publisher_id = ?",
+        )
+        .bind(request.to.as_ref())
+        .bind(publisher_record.publisher_id)
+        .execute(&mut tx)
+        .await?;
+
+        AuditModel::insert(
+            &mut tx,
+            caller_record.publisher_id,
+            "rotate_key",
+            None,
+            &serde_json::to_value(request)?,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        PublisherModel::find_by_address(pool, &request.to)
+            .await?
+            .ok_or(Error::InsertFetch(publisher_record.publisher_id))
+    }
+
+    /// List versions signed by a key the publisher has since
+    /// rotated out.
+    ///
+    /// A version is signed by the key the publisher used when
+    /// the version was published or last overwritten; the
+    /// signing key is the first key rotated out after that time.
+    pub async fn list_rotated_signatures(
+        pool: &SqlitePool,
+        pager: &Pager,
+    ) -> Result<ResultSet<RotatedSignatureRecord>> {
+        let sql = format!(
+            r#"
+                SELECT
+                    COUNT(*) OVER () as count,
+                    namespaces.name as namespace,
+                    packages.name as package,
+                    signed.major,
+                    signed.minor,
+                    signed.patch,
+                    signed.pre,
+                    signed.build,
+                    publishers.address as publisher,
+                    key_history.address,
+                    key_history.created_at as rotated_at
+                FROM (
+                    SELECT
+                        versions.*,
+                        COALESCE(
+                            (
+                                SELECT MAX(created_at)
+                                FROM version_overwrites
+                                WHERE version_overwrites.version_id
+                                    = versions.version_id
+                            ),
+                            versions.created_at
+                        ) as signed_at
+                    FROM versions
+                ) AS signed
+                INNER JOIN key_history
+                    ON (key_history.publisher_id = signed.publisher_id
+                        AND key_history.created_at >= signed.signed_at)
+                INNER JOIN publishers
+                    ON (publishers.publisher_id = signed.publisher_id)
+                INNER JOIN packages
+                    ON (packages.package_id = signed.package_id)
+                INNER JOIN namespaces
+                    ON (namespaces.namespace_id = packages.namespace_id)
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM key_history AS earlier
+                    WHERE earlier.publisher_id = key_history.publisher_id
+                    AND earlier.created_at >= signed.signed_at
+                    AND earlier.key_id < key_history.key_id
+                )
+                ORDER BY signed.version_id {}
+                LIMIT ? OFFSET ?
+            "#,
+            pager.sort,
+        );
+
+        let records = sqlx::query_as::<_, RotatedSignatureRecord>(&sql)
+            .bind(pager.limit)
+            .bind(pager.offset)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(records.into_result_set())
+    }
+
+    /// Find the primary key for a namespace by exact name.
+    async fn find_namespace_id(
+        tx: &mut Transaction<'_, Sqlite>,
+        namespace: &Namespace,
+    ) -> Result<i64> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT namespace_id FROM namespaces WHERE name = ?",
+        )
+        .bind(namespace.as_str())
+        .fetch_optional(tx)
+        .await?
+        .ok_or_else(|| {
+            Error::NotFound(NotFound::Namespace(namespace.clone()))
+        })
+    }
+}
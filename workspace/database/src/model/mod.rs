@@ -1,22 +1,67 @@
 //! Database model.
+mod audit;
+mod maintenance;
 mod namespace;
 mod package;
 mod publisher;
 
+pub use audit::AuditModel;
+pub use maintenance::MaintenanceModel;
 pub use namespace::NamespaceModel;
 pub use package::PackageModel;
 pub use publisher::PublisherModel;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
 use crate::Error;
 
+/// SQL expression for the current date and time.
+///
+/// Timestamps are stored in UTC as ISO 8601 with millisecond
+/// precision so they sort in the order they were written.
+pub(crate) const SQL_NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+/// SQLite extended result code for a unique constraint violation.
+const SQLITE_CONSTRAINT_UNIQUE: &str = "2067";
+
+/// Determine if an error is a unique constraint violation.
+pub(crate) fn is_unique_violation(e: &sqlx::Error) -> bool {
+    matches!(
+        e,
+        sqlx::Error::Database(e)
+            if e.code().as_deref() == Some(SQLITE_CONSTRAINT_UNIQUE)
+    )
+}
+
 /// Default limit for pagination.
 pub fn default_limit() -> i64 {
     25
 }
 
+/// Maximum length in bytes of a namespace or package description.
+pub const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+/// Validate a description, an empty description is treated
+/// as no description.
+pub(crate) fn validate_description(
+    description: &str,
+) -> Result<Option<&str>, Error> {
+    let description = description.trim();
+    if description.len() > MAX_DESCRIPTION_LENGTH {
+        Err(Error::DescriptionLength(MAX_DESCRIPTION_LENGTH))
+    } else if description.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(description))
+    }
+}
+
+/// Default for whether yanked versions are included in results.
+pub fn default_include_yanked() -> bool {
+    true
+}
+
 /// Determines how versions should be fetched when listing packages.
 #[derive(Default, Debug, Deserialize, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -26,6 +71,8 @@ pub enum VersionIncludes {
     None,
     /// Fetch the latest version.
     Latest,
+    /// Count the versions without fetching any records.
+    Count,
 }
 
 impl fmt::Display for VersionIncludes {
@@ -36,6 +83,7 @@ impl fmt::Display for VersionIncludes {
             match self {
                 Self::None => "none",
                 Self::Latest => "latest",
+                Self::Count => "count",
             }
         )
     }
@@ -49,6 +97,8 @@ impl FromStr for VersionIncludes {
             Ok(Self::None)
         } else if s.to_lowercase() == "latest" {
             Ok(Self::Latest)
+        } else if s.to_lowercase() == "count" {
+            Ok(Self::Count)
         } else {
             Err(Error::InvalidVersionIncludes(s.to_owned()))
         }
@@ -65,6 +115,11 @@ pub struct Pager {
     pub limit: i64,
     /// Sort order.
     pub sort: SortOrder,
+    /// Cursor for the next page, takes precedence over the offset.
+    ///
+    /// Only supported by list queries that return a next cursor.
+    #[serde(skip)]
+    pub cursor: Option<String>,
 }
 
 impl Default for Pager {
@@ -73,10 +128,79 @@ impl Default for Pager {
             offset: 0,
             limit: default_limit(),
             sort: Default::default(),
+            cursor: None,
         }
     }
 }
 
+/// Position of the last record in a page of results.
+///
+/// Pages that follow a cursor begin after the sort key of the
+/// record so rows inserted between requests do not shift the
+/// page boundaries. Cursors are opaque to clients and encoded
+/// as URL safe base64 of the JSON.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Cursor {
+    /// Position in a list of packages sorted by name.
+    Package {
+        /// Package primary key.
+        package_id: i64,
+        /// Package name.
+        name: String,
+    },
+    /// Position in a list of versions sorted by precedence.
+    Version {
+        /// Version primary key.
+        version_id: i64,
+        /// Major version.
+        major: i64,
+        /// Minor version.
+        minor: i64,
+        /// Patch version.
+        patch: i64,
+        /// Pre-release identifiers.
+        pre: String,
+    },
+}
+
+impl Cursor {
+    /// Encode the cursor for a client.
+    pub fn encode(&self) -> String {
+        let buffer =
+            serde_json::to_vec(self).expect("failed to encode cursor");
+        base64::encode_config(buffer, base64::URL_SAFE_NO_PAD)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let buffer = base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+            .map_err(|_| Error::InvalidCursor(s.to_owned()))?;
+        serde_json::from_slice(&buffer)
+            .map_err(|_| Error::InvalidCursor(s.to_owned()))
+    }
+}
+
+/// Determines which version is selected when a content identifier
+/// matches more than one version.
+///
+/// Identical archives published to different packages share a
+/// content identifier.
+#[derive(Debug, Default, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateCid {
+    /// Select the most recently published version.
+    #[default]
+    Latest,
+    /// Select the first version published with the content.
+    Earliest,
+    /// Reject the lookup as ambiguous.
+    Reject,
+}
+
 /// Represents a sort order.
 #[derive(Debug, Default, Deserialize, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -96,6 +220,36 @@ impl SortOrder {
             Self::Desc => "DESC",
         }
     }
+
+    /// Comparison operator for rows after a cursor in this order.
+    pub(crate) fn cursor_operator(&self) -> &'static str {
+        match self {
+            Self::Asc => ">",
+            Self::Desc => "<",
+        }
+    }
+}
+
+/// SQL `ORDER BY` terms for version columns in semver precedence.
+///
+/// A release sorts above pre-releases of the same version; build
+/// metadata is not significant for precedence so the primary key
+/// breaks any remaining ties to keep the ordering deterministic.
+///
+/// When `include_pre` is not set the query must only select releases.
+pub(crate) fn version_order_by(sort: SortOrder, include_pre: bool) -> String {
+    let sort = sort.as_str();
+    let mut terms = vec![
+        format!("major {}", sort),
+        format!("minor {}", sort),
+        format!("patch {}", sort),
+    ];
+    if include_pre {
+        terms.push(format!("pre = '' {}", sort));
+        terms.push(format!("pre {}", sort));
+    }
+    terms.push(format!("version_id {}", sort));
+    terms.join(", ")
 }
 
 impl fmt::Display for SortOrder {
@@ -2,11 +2,14 @@
 use sqlx::{sqlite::SqliteArguments, Arguments, QueryBuilder, SqlitePool};
 use web3_address::ethereum::Address;
 
-use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_core::{Namespace, PackageName, RegistryKind};
 
 use crate::{
     error::NotFound,
-    model::{PackageModel, PublisherModel},
+    model::{
+        is_unique_violation, validate_description, AuditModel, PackageModel,
+        PublisherModel, SQL_NOW,
+    },
     value_objects::*,
     Error, Result,
 };
@@ -16,14 +19,18 @@ pub struct NamespaceModel;
 
 impl NamespaceModel {
     /// Add a namespace.
+    ///
+    /// When the name or a confusable name is already taken
+    /// a namespace exists error is returned.
     pub async fn insert(
         pool: &SqlitePool,
         name: &Namespace,
         publisher_id: i64,
+        kind: Option<RegistryKind>,
     ) -> Result<i64> {
         let mut builder = QueryBuilder::new(
             r#"
-                INSERT INTO namespaces ( name, skeleton, publisher_id, created_at )
+                INSERT INTO namespaces ( name, skeleton, publisher_id, kind, created_at )
                 VALUES (
             "#,
         );
@@ -32,9 +39,21 @@ impl NamespaceModel {
         separated.push_bind(name.as_str());
         separated.push_bind(&skeleton);
         separated.push_bind(publisher_id);
-        builder.push(", datetime('now') )");
+        separated.push_bind(kind.map(|kind| kind.to_string()));
+        builder.push(format!(", {} )", SQL_NOW));
 
-        let id = builder.build().execute(pool).await?.last_insert_rowid();
+        let id = builder
+            .build()
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                if is_unique_violation(&e) {
+                    Error::NamespaceExists(name.clone())
+                } else {
+                    e.into()
+                }
+            })?
+            .last_insert_rowid();
 
         Ok(id)
     }
@@ -44,9 +63,11 @@ impl NamespaceModel {
         pool: &SqlitePool,
         name: &Namespace,
         publisher_id: i64,
+        kind: Option<RegistryKind>,
     ) -> Result<NamespaceRecord> {
-        let id = NamespaceModel::insert(pool, name, publisher_id).await?;
-        let record = NamespaceModel::find_by_name(pool, name)
+        let id =
+            NamespaceModel::insert(pool, name, publisher_id, kind).await?;
+        let record = NamespaceModel::find_by_name_shallow(pool, name)
             .await?
             .ok_or(Error::InsertFetch(id))?;
         Ok(record)
@@ -257,6 +278,72 @@ impl NamespaceModel {
         Ok(())
     }
 
+    /// Transfer ownership of a namespace to another publisher.
+    ///
+    /// Only the owner may transfer a namespace; the previous owner
+    /// is not added as a user so they keep no access unless they
+    /// are added again by the new owner.
+    pub async fn transfer_ownership(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        caller: &Address,
+        new_owner: &Address,
+    ) -> Result<NamespaceRecord> {
+        let (_, namespace_record) =
+            NamespaceModel::can_access_namespace(pool, caller, namespace)
+                .await?;
+
+        if !namespace_record.is_owner(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let owner_record = PublisherModel::find_by_address(pool, new_owner)
+            .await?
+            .ok_or(Error::UnknownPublisher(*new_owner))?;
+
+        let mut tx = pool.begin().await?;
+
+        let mut builder =
+            QueryBuilder::new("UPDATE namespaces SET publisher_id = ");
+        builder.push_bind(owner_record.publisher_id);
+        builder.push(" WHERE namespace_id = ");
+        builder.push_bind(namespace_record.namespace_id);
+        builder.build().execute(&mut tx).await?;
+
+        // The owner is implicitly a user of the namespace
+        let mut builder = QueryBuilder::new(
+            r#"DELETE FROM namespace_publishers WHERE namespace_id = "#,
+        );
+        builder.push_bind(namespace_record.namespace_id);
+        builder.push(" AND publisher_id = ");
+        builder.push_bind(owner_record.publisher_id);
+        builder.build().execute(&mut tx).await?;
+
+        // Owners are not restricted to packages
+        let mut builder = QueryBuilder::new(
+            r#"
+                DELETE FROM publisher_restrictions
+                WHERE publisher_id = "#,
+        );
+        builder.push_bind(owner_record.publisher_id);
+        builder.push(
+            r#"
+                AND package_id IN (
+                    SELECT package_id FROM packages WHERE namespace_id = "#,
+        );
+        builder.push_bind(namespace_record.namespace_id);
+        builder.push(" )");
+        builder.build().execute(&mut tx).await?;
+
+        tx.commit().await?;
+
+        NamespaceModel::find_by_name(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })
+    }
+
     /// Grant an existing user in this namespace
     /// access to an existing package.
     pub async fn grant_access(
@@ -388,6 +475,250 @@ impl NamespaceModel {
         Ok(())
     }
 
+    /// Set the description for a namespace.
+    ///
+    /// Only the owner or an administrator may change the description,
+    /// an empty description removes the description.
+    pub async fn set_description(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+        description: &str,
+    ) -> Result<NamespaceRecord> {
+        let (publisher_record, namespace_record) =
+            NamespaceModel::can_access_namespace(pool, caller, namespace)
+                .await?;
+
+        if !namespace_record.can_administrate(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let description = validate_description(description)?;
+
+        let mut tx = pool.begin().await?;
+
+        let mut builder =
+            QueryBuilder::new("UPDATE namespaces SET description = ");
+        builder.push_bind(description);
+        builder.push(" WHERE namespace_id = ");
+        builder.push_bind(namespace_record.namespace_id);
+        builder.build().execute(&mut tx).await?;
+
+        AuditModel::insert(
+            &mut tx,
+            publisher_record.publisher_id,
+            "set_description",
+            Some(namespace),
+            &serde_json::json!({ "description": description }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        NamespaceModel::find_by_name(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })
+    }
+
+    /// Set whether downloads from a namespace must be signed
+    /// by a user of the namespace.
+    pub async fn set_private(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+        private: bool,
+    ) -> Result<NamespaceRecord> {
+        let (_, namespace_record) =
+            NamespaceModel::can_access_namespace(pool, caller, namespace)
+                .await?;
+
+        if !namespace_record.can_administrate(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let mut builder =
+            QueryBuilder::new("UPDATE namespaces SET private = ");
+        builder.push_bind(private);
+        builder.push(" WHERE namespace_id = ");
+        builder.push_bind(namespace_record.namespace_id);
+        builder.build().execute(pool).await?;
+
+        NamespaceModel::find_by_name(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })
+    }
+
+    /// Set whether users of a namespace without package
+    /// restrictions may not publish to any package until
+    /// access is granted.
+    ///
+    /// Only the namespace owner may change the default.
+    pub async fn set_default_restricted(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+        default_restricted: bool,
+    ) -> Result<NamespaceRecord> {
+        let (_, namespace_record) =
+            NamespaceModel::can_access_namespace(pool, caller, namespace)
+                .await?;
+
+        if !namespace_record.is_owner(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let mut builder =
+            QueryBuilder::new("UPDATE namespaces SET default_restricted = ");
+        builder.push_bind(default_restricted);
+        builder.push(" WHERE namespace_id = ");
+        builder.push_bind(namespace_record.namespace_id);
+        builder.build().execute(pool).await?;
+
+        NamespaceModel::find_by_name(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })
+    }
+
+    /// Mark a namespace as deprecated.
+    ///
+    /// Only the namespace owner may deprecate a namespace.
+    pub async fn deprecate(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+        message: &str,
+    ) -> Result<NamespaceRecord> {
+        NamespaceModel::set_deprecated(pool, caller, namespace, Some(message))
+            .await
+    }
+
+    /// Remove the deprecation notice for a namespace.
+    ///
+    /// Only the namespace owner may undeprecate a namespace.
+    pub async fn undeprecate(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+    ) -> Result<NamespaceRecord> {
+        NamespaceModel::set_deprecated(pool, caller, namespace, None).await
+    }
+
+    async fn set_deprecated(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+        message: Option<&str>,
+    ) -> Result<NamespaceRecord> {
+        let record = NamespaceModel::find_by_name_shallow(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })?;
+
+        if !record.is_owner(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let mut builder =
+            QueryBuilder::new("UPDATE namespaces SET deprecated = ");
+        builder.push_bind(message);
+        builder.push(" WHERE namespace_id = ");
+        builder.push_bind(record.namespace_id);
+        builder.build().execute(pool).await?;
+
+        NamespaceModel::find_by_name(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })
+    }
+
+    /// Consolidated view of the users of a namespace and
+    /// the packages each user is restricted to.
+    ///
+    /// Only the namespace owner may view the access list.
+    pub async fn list_access(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        caller: &Address,
+    ) -> Result<NamespaceAccess> {
+        let record = NamespaceModel::find_by_name_shallow(pool, namespace)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(namespace.clone()))
+            })?;
+
+        if !record.is_owner(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let mut args: SqliteArguments = Default::default();
+        args.add(record.namespace_id);
+
+        let rows = sqlx::query_as_with::<_, (Vec<u8>, bool, Option<String>), _>(
+            r#"
+                SELECT
+                    publishers.address,
+                    namespace_publishers.administrator,
+                    packages.name
+                FROM namespace_publishers
+                INNER JOIN publishers
+                    ON (namespace_publishers.publisher_id = publishers.publisher_id)
+                LEFT JOIN publisher_restrictions
+                    ON (namespace_publishers.publisher_id = publisher_restrictions.publisher_id)
+                LEFT JOIN packages
+                    ON (publisher_restrictions.package_id = packages.package_id
+                        AND packages.namespace_id = namespace_publishers.namespace_id)
+                WHERE namespace_publishers.namespace_id = ?
+                ORDER BY namespace_publishers.rowid, packages.name
+            "#,
+            args,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut users: Vec<UserAccess> = Vec::new();
+        for (address, administrator, package) in rows {
+            let address: [u8; 20] = address
+                .as_slice()
+                .try_into()
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            let address: Address = address.into();
+
+            let user = match users.last_mut() {
+                Some(user) if user.address == address => user,
+                _ => {
+                    users.push(UserAccess {
+                        address,
+                        administrator,
+                        restrictions: vec![],
+                    });
+                    users.last_mut().unwrap()
+                }
+            };
+
+            if let Some(package) = package {
+                user.restrictions.push(
+                    package
+                        .parse::<PackageName>()
+                        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+                );
+            }
+        }
+
+        Ok(NamespaceAccess {
+            name: record.name,
+            owner: record.owner,
+            users,
+        })
+    }
+
     /// Find a package access entry.
     async fn find_access_restriction(
         pool: &SqlitePool,
@@ -410,8 +741,11 @@ impl NamespaceModel {
         Ok(record)
     }
 
-    /// Find a namespace by name.
-    pub async fn find_by_name(
+    /// Find a namespace by name without loading the users.
+    ///
+    /// The `publishers` of the returned record are always empty
+    /// so it must not be used for access control checks.
+    pub async fn find_by_name_shallow(
         pool: &SqlitePool,
         name: &Namespace,
     ) -> Result<Option<NamespaceRecord>> {
@@ -424,6 +758,11 @@ impl NamespaceModel {
                 SELECT
                     namespaces.namespace_id,
                     namespaces.name,
+                    namespaces.description,
+                    namespaces.kind,
+                    namespaces.private,
+                    namespaces.default_restricted,
+                    namespaces.deprecated,
                     namespaces.publisher_id,
                     namespaces.created_at,
                     publishers.address
@@ -437,6 +776,17 @@ impl NamespaceModel {
         .fetch_optional(pool)
         .await?;
 
+        Ok(record)
+    }
+
+    /// Find a namespace by name including the users
+    /// and their package restrictions.
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        name: &Namespace,
+    ) -> Result<Option<NamespaceRecord>> {
+        let record = NamespaceModel::find_by_name_shallow(pool, name).await?;
+
         if let Some(mut record) = record {
             let mut args: SqliteArguments = Default::default();
             args.add(record.namespace_id);
@@ -472,6 +822,34 @@ impl NamespaceModel {
         }
     }
 
+    /// Count the packages and versions in a namespace.
+    pub async fn count_packages(
+        pool: &SqlitePool,
+        namespace_id: i64,
+    ) -> Result<(i64, i64)> {
+        let counts = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+                SELECT
+                    (
+                        SELECT COUNT(*) FROM packages
+                        WHERE namespace_id = ?
+                    ) as package_count,
+                    (
+                        SELECT COUNT(*) FROM versions
+                        INNER JOIN packages
+                            ON (versions.package_id = packages.package_id)
+                        WHERE packages.namespace_id = ?
+                    ) as version_count
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(namespace_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(counts)
+    }
+
     /// Find a namespace by id.
     pub async fn find_namespace_by_id(
         pool: &SqlitePool,
@@ -485,6 +863,11 @@ impl NamespaceModel {
                 SELECT
                     namespaces.namespace_id,
                     namespaces.name,
+                    namespaces.description,
+                    namespaces.kind,
+                    namespaces.private,
+                    namespaces.default_restricted,
+                    namespaces.deprecated,
                     namespaces.publisher_id,
                     namespaces.created_at,
                     publishers.address
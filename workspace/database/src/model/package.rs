@@ -1,83 +1,296 @@
 //! Model for packages.
+use std::cmp::Ordering;
+
+use cid::Cid;
 use semver::{Op, Version, VersionReq};
 
 use sqlx::{
-    sqlite::SqliteArguments, Arguments, QueryBuilder, Sqlite, SqlitePool,
+    sqlite::SqliteArguments, Arguments, FromRow, QueryBuilder, Row, Sqlite,
+    SqlitePool, Transaction,
 };
+use time::{Duration, OffsetDateTime};
 use web3_address::ethereum::Address;
 
 use ipfs_registry_core::{
-    Namespace, ObjectKey, PackageKey, PackageName, Pointer,
+    Namespace, ObjectKey, PackageKey, PackageName, PackageReader, Pointer,
+    StoredObject,
 };
 
 use crate::{
     error::NotFound,
-    model::{NamespaceModel, Pager, VersionIncludes},
+    model::{
+        validate_description, version_order_by, AuditModel, Cursor,
+        DuplicateCid, NamespaceModel, Pager, PublisherModel, SortOrder,
+        VersionIncludes, SQL_NOW,
+    },
     value_objects::*,
     Error, Result,
 };
 
+/// Join the package of a version so the package deprecation
+/// message is selected with the version.
+const PACKAGE_JOIN: &str =
+    "INNER JOIN packages ON (packages.package_id = versions.package_id)";
+
+/// Version columns selected for the latest version of each
+/// package when listing packages.
+const LATEST_COLUMNS: [&str; 20] = [
+    "version_id",
+    "publisher_id",
+    "package_id",
+    "major",
+    "minor",
+    "patch",
+    "pre",
+    "build",
+    "package",
+    "content_id",
+    "pointer_id",
+    "signature",
+    "signature_scheme",
+    "checksum",
+    "kind",
+    "size",
+    "yanked",
+    "downloads",
+    "purged_at",
+    "created_at",
+];
+
+/// Compare versions by precedence ignoring build metadata.
+fn cmp_precedence(a: &Version, b: &Version) -> Ordering {
+    (a.major, a.minor, a.patch, &a.pre)
+        .cmp(&(b.major, b.minor, b.patch, &b.pre))
+}
+
 /// Manage registry packages.
 pub struct PackageModel;
 
 impl PackageModel {
     /// List packages for a namespace.
+    ///
+    /// When `include_yanked` is false the latest version included
+    /// for each package skips yanked versions and yanked versions
+    /// are not counted.
+    ///
+    /// Version counts are computed in the same query as the
+    /// packages so no records are fetched for each package.
     pub async fn list_packages(
         pool: &SqlitePool,
         namespace: &Namespace,
         pager: &Pager,
         versions: VersionIncludes,
+        include_yanked: bool,
     ) -> Result<ResultSet<PackageRecord>> {
         // Check the namespace exists
-        let namespace_record = NamespaceModel::find_by_name(pool, namespace)
-            .await?
-            .ok_or_else(|| {
-                Error::NotFound(NotFound::Namespace(namespace.clone()))
-            })?;
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(pool, namespace)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::Namespace(namespace.clone()))
+                })?;
+
+        let cursor = pager
+            .cursor
+            .as_ref()
+            .map(|cursor| cursor.parse::<Cursor>())
+            .transpose()?;
 
         let mut args: SqliteArguments = Default::default();
         args.add(namespace_record.namespace_id);
+
+        // Page after the cursor instead of using the offset
+        let cursor_clause = match cursor {
+            Some(Cursor::Package { package_id, name }) => {
+                args.add(name);
+                args.add(package_id);
+                format!(
+                    "WHERE (name, package_id) {} (?, ?)",
+                    pager.sort.cursor_operator()
+                )
+            }
+            Some(_) => {
+                return Err(Error::InvalidCursor(
+                    pager.cursor.clone().unwrap_or_default(),
+                ))
+            }
+            None => String::new(),
+        };
+
         args.add(pager.limit);
-        args.add(pager.offset);
+        args.add(if cursor_clause.is_empty() {
+            pager.offset
+        } else {
+            0
+        });
+
+        let (version_columns, version_join) = match versions {
+            VersionIncludes::Count => (
+                String::from(
+                    ", IFNULL(version_counts.version_count, 0) as version_count",
+                ),
+                format!(
+                    r#"
+                    LEFT JOIN (
+                        SELECT package_id, COUNT(*) as version_count
+                        FROM versions {}
+                        GROUP BY package_id
+                    ) as version_counts
+                    ON (packages.package_id = version_counts.package_id)
+                    "#,
+                    if include_yanked {
+                        ""
+                    } else {
+                        "WHERE yanked IS NULL"
+                    },
+                ),
+            ),
+            // Attach the latest version of each package in the same
+            // query using the ordering of `find_latest`
+            VersionIncludes::Latest => (
+                LATEST_COLUMNS
+                    .iter()
+                    .map(|name| {
+                        format!(", latest.{0} as latest_{0}", name)
+                    })
+                    .chain(std::iter::once(String::from(
+                        ", (SELECT COUNT(version_id) FROM versions) as latest_count",
+                    )))
+                    .collect::<String>(),
+                format!(
+                    r#"
+                    LEFT JOIN versions as latest
+                    ON (latest.version_id = (
+                        SELECT version_id FROM versions
+                        WHERE package_id = packages.package_id
+                        AND pre = '' {}
+                        ORDER BY {}
+                        LIMIT 1
+                    ))
+                    "#,
+                    if include_yanked {
+                        ""
+                    } else {
+                        "AND yanked IS NULL"
+                    },
+                    version_order_by(SortOrder::Desc, false),
+                ),
+            ),
+            VersionIncludes::None => (String::new(), String::new()),
+        };
 
+        // The count is computed before the cursor is applied
+        // so it is the total number of packages
         let sql = format!(
             r#"
-            SELECT
-                (SELECT COUNT(package_id) FROM packages) as count,
-                namespace_id,
-                package_id,
-                created_at,
-                name,
-                deprecated
-            FROM packages
-            WHERE namespace_id = ?
-            --GROUP BY package_id
-            ORDER BY name {}
+            SELECT * FROM (
+                SELECT
+                    COUNT(*) OVER () as count,
+                    packages.namespace_id,
+                    packages.package_id,
+                    packages.created_at,
+                    packages.name,
+                    packages.description,
+                    packages.deprecated
+                    {}
+                FROM packages
+                {}
+                WHERE namespace_id = ?
+            )
+            {}
+            ORDER BY name {}, package_id {}
             LIMIT ? OFFSET ?"#,
+            version_columns,
+            version_join,
+            cursor_clause,
+            pager.sort,
             pager.sort
         );
 
-        let records = sqlx::query_as_with::<_, PackageRecord, _>(&sql, args)
-            .fetch_all(pool)
-            .await?;
-
         let packages = match versions {
             VersionIncludes::Latest => {
-                let mut packages = Vec::with_capacity(records.len());
-                for mut package in records {
-                    let latest =
-                        PackageModel::find_latest(pool, &package, false)
-                            .await?
-                            .ok_or(Error::NoPackageVersion)?;
-                    package.versions.count = latest.count;
-                    package.versions.records = vec![latest];
+                let rows =
+                    sqlx::query_with(&sql, args).fetch_all(pool).await?;
+                let mut packages = Vec::with_capacity(rows.len());
+                for row in rows {
+                    let mut package = PackageRecord::from_row(&row)?;
+                    let latest_id: Option<i64> =
+                        row.try_get("latest_version_id")?;
+                    match latest_id {
+                        Some(_) => {
+                            let latest = VersionRecord::from_row_prefix(
+                                &row, "latest_",
+                            )?;
+                            package.versions.count = latest.count;
+                            package.versions.records = vec![latest];
+                        }
+                        // All versions of the package may be yanked
+                        None if !include_yanked => {}
+                        None => return Err(Error::NoPackageVersion),
+                    }
                     packages.push(package);
                 }
                 packages
             }
-            VersionIncludes::None => records,
+            VersionIncludes::Count | VersionIncludes::None => {
+                sqlx::query_as_with::<_, PackageRecord, _>(&sql, args)
+                    .fetch_all(pool)
+                    .await?
+            }
         };
 
+        Ok(packages
+            .into_result_set()
+            .with_next_cursor(pager, |record| Cursor::Package {
+                package_id: record.package_id,
+                name: record.name.to_string(),
+            }))
+    }
+
+    /// Search packages in all namespaces by name prefix.
+    ///
+    /// Names are also matched on their confusable skeleton so a
+    /// query finds packages that only look similar. The query is
+    /// used in a `LIKE` pattern so callers must ensure it is a
+    /// valid identifier without wildcard characters.
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        pager: &Pager,
+    ) -> Result<ResultSet<PackageRecord>> {
+        let skeleton = PackageName::new_unchecked(query).skeleton();
+
+        let sql = format!(
+            r#"
+                SELECT
+                    COUNT(*) OVER () as count,
+                    packages.namespace_id,
+                    namespaces.name as namespace,
+                    packages.package_id,
+                    packages.created_at,
+                    packages.name,
+                    packages.description,
+                    packages.deprecated
+                FROM packages
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                WHERE (packages.name LIKE ? || '%'
+                    OR packages.skeleton LIKE ? || '%')
+                    AND namespaces.private = 0
+                ORDER BY packages.name {}, namespaces.name {}
+                LIMIT ? OFFSET ?
+            "#,
+            pager.sort, pager.sort
+        );
+
+        let packages = sqlx::query_as::<_, PackageRecord>(&sql)
+            .bind(query)
+            .bind(&skeleton)
+            .bind(pager.limit)
+            .bind(pager.offset)
+            .fetch_all(pool)
+            .await?;
+
         Ok(packages.into_result_set())
     }
 
@@ -87,13 +300,15 @@ impl PackageModel {
         namespace: &Namespace,
         name: &PackageName,
         pager: &Pager,
+        include_yanked: bool,
     ) -> Result<ResultSet<VersionRecord>> {
         // Find the namespace
-        let namespace_record = NamespaceModel::find_by_name(pool, namespace)
-            .await?
-            .ok_or_else(|| {
-                Error::NotFound(NotFound::Namespace(namespace.clone()))
-            })?;
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(pool, namespace)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::Namespace(namespace.clone()))
+                })?;
 
         // Find the package
         let package_record = PackageModel::find_by_name(
@@ -106,49 +321,119 @@ impl PackageModel {
             Error::NotFound(NotFound::PackageName(name.to_owned()))
         })?;
 
+        let cursor = pager
+            .cursor
+            .as_ref()
+            .map(|cursor| cursor.parse::<Cursor>())
+            .transpose()?;
+
         let mut args: SqliteArguments = Default::default();
         args.add(package_record.package_id);
-        args.add(pager.limit);
-        args.add(pager.offset);
 
-        let sql = format!(
-            r#"
-            SELECT
-                (SELECT COUNT(version_id) FROM versions) as count,
+        // Page after the cursor instead of using the offset,
+        // the terms match the order of `version_order_by`
+        let cursor_clause = match cursor {
+            Some(Cursor::Version {
                 version_id,
-                publisher_id,
-                package_id,
                 major,
                 minor,
                 patch,
                 pre,
-                build,
-                -- package,
-                content_id,
-                pointer_id,
-                signature,
-                checksum,
-                yanked,
-                created_at
-            FROM versions
-            WHERE package_id = ?
-            --GROUP BY version_id
-            ORDER BY major {}, minor {}, patch {}, pre {}, build {}
+            }) => {
+                args.add(major);
+                args.add(minor);
+                args.add(patch);
+                args.add(pre.is_empty());
+                args.add(pre);
+                args.add(version_id);
+                format!(
+                    "WHERE (major, minor, patch, pre = '', pre, version_id) {} (?, ?, ?, ?, ?, ?)",
+                    pager.sort.cursor_operator()
+                )
+            }
+            Some(_) => {
+                return Err(Error::InvalidCursor(
+                    pager.cursor.clone().unwrap_or_default(),
+                ))
+            }
+            None => String::new(),
+        };
+
+        args.add(pager.limit);
+        args.add(if cursor_clause.is_empty() {
+            pager.offset
+        } else {
+            0
+        });
+
+        // The count is computed before the cursor is applied
+        // so it is the total number of versions
+        let sql = format!(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    COUNT(*) OVER () as count,
+                    version_id,
+                    publisher_id,
+                    versions.package_id,
+                    major,
+                    minor,
+                    patch,
+                    pre,
+                    build,
+                    -- package,
+                    content_id,
+                    pointer_id,
+                    signature,
+                    signature_scheme,
+                    checksum,
+                    kind,
+                    size,
+                    yanked,
+                    downloads,
+                    purged_at,
+                    versions.created_at,
+                    packages.deprecated
+                FROM versions
+                {}
+                WHERE versions.package_id = ? {}
+            )
+            {}
+            ORDER BY {}
             LIMIT ? OFFSET ?"#,
-            pager.sort, pager.sort, pager.sort, pager.sort, pager.sort,
+            PACKAGE_JOIN,
+            if include_yanked {
+                ""
+            } else {
+                "AND yanked IS NULL"
+            },
+            cursor_clause,
+            version_order_by(pager.sort, true),
         );
 
         let records = sqlx::query_as_with::<_, VersionRecord, _>(&sql, args)
             .fetch_all(pool)
             .await?;
 
-        Ok(records.into_result_set())
+        Ok(records.into_result_set().with_next_cursor(pager, |record| {
+            Cursor::Version {
+                version_id: record.version_id,
+                major: record.version.major as i64,
+                minor: record.version.minor as i64,
+                patch: record.version.patch as i64,
+                pre: record.version.pre.to_string(),
+            }
+        }))
     }
 
     /// Find a package version by package key.
+    ///
+    /// When a content identifier matches more than one version
+    /// the version is selected according to the duplicate policy.
     pub async fn find_by_key(
         pool: &SqlitePool,
         package_key: &PackageKey,
+        duplicate_cid: DuplicateCid,
     ) -> Result<(
         Option<NamespaceRecord>,
         Option<PackageRecord>,
@@ -176,16 +461,21 @@ impl PackageModel {
                 Ok((Some(namespace_record), package_record, version_record))
             }
             PackageKey::Cid(cid) => {
-                let mut args: SqliteArguments = Default::default();
-                args.add(cid.to_string());
-
-                let version_record =
-                    sqlx::query_as_with::<_, VersionRecord, _>(
-                        r#"SELECT * FROM versions WHERE content_id = ?"#,
-                        args,
-                    )
-                    .fetch_optional(pool)
-                    .await?;
+                let mut records =
+                    PackageModel::find_by_cid(pool, cid).await?;
+                let version_record = match duplicate_cid {
+                    DuplicateCid::Latest => records.pop(),
+                    DuplicateCid::Earliest => records.into_iter().next(),
+                    DuplicateCid::Reject => {
+                        if records.len() > 1 {
+                            return Err(Error::AmbiguousCid(
+                                package_key.clone(),
+                                records.len(),
+                            ));
+                        }
+                        records.pop()
+                    }
+                };
 
                 let package_record =
                     if let Some(version_record) = &version_record {
@@ -214,6 +504,74 @@ impl PackageModel {
         }
     }
 
+    /// Find every version with a content identifier in publish order.
+    pub async fn find_by_cid(
+        pool: &SqlitePool,
+        cid: &Cid,
+    ) -> Result<Vec<VersionRecord>> {
+        let mut args: SqliteArguments = Default::default();
+        args.add(cid.to_string());
+
+        let sql = format!(
+            r#"
+                SELECT versions.*, packages.deprecated FROM versions
+                {}
+                WHERE content_id = ?
+                ORDER BY version_id
+            "#,
+            PACKAGE_JOIN,
+        );
+        let records = sqlx::query_as_with::<_, VersionRecord, _>(&sql, args)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    /// Determine if a version that has not been purged references
+    /// a content identifier, ignoring the excluded version.
+    pub async fn is_content_referenced(
+        pool: &SqlitePool,
+        cid: &Cid,
+        excluding: Option<i64>,
+    ) -> Result<bool> {
+        // Comparing with NULL using IS NOT matches every version
+        let exists = sqlx::query_scalar::<_, i64>(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM versions
+                    WHERE content_id = ?
+                    AND purged_at IS NULL
+                    AND version_id IS NOT ?
+                )
+            "#,
+        )
+        .bind(cid.to_string())
+        .bind(excluding)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists == 1)
+    }
+
+    /// Find versions by the checksum of the package archive.
+    pub async fn find_by_checksum(
+        pool: &SqlitePool,
+        checksum: &[u8; 32],
+    ) -> Result<Vec<VersionRecord>> {
+        let mut args: SqliteArguments = Default::default();
+        args.add(checksum.to_vec());
+
+        let records = sqlx::query_as_with::<_, VersionRecord, _>(
+            r#"SELECT * FROM versions WHERE checksum = ? ORDER BY version_id"#,
+            args,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
     /// Find multiple packages by name.
     pub async fn find_many_by_name<'a>(
         pool: &SqlitePool,
@@ -248,6 +606,7 @@ impl PackageModel {
                     package_id,
                     created_at,
                     name,
+                    description,
                     deprecated
                 FROM packages
                 WHERE namespace_id = ? AND skeleton = ?
@@ -416,13 +775,15 @@ impl PackageModel {
         name: &PackageName,
         versions: &VersionReq,
         pager: &Pager,
+        include_yanked: bool,
     ) -> Result<ResultSet<VersionRecord>> {
         // Find the namespace
-        let namespace_record = NamespaceModel::find_by_name(pool, namespace)
-            .await?
-            .ok_or_else(|| {
-                Error::NotFound(NotFound::Namespace(namespace.clone()))
-            })?;
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(pool, namespace)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::Namespace(namespace.clone()))
+                })?;
 
         // Find the package
         let package_record = PackageModel::find_by_name(
@@ -441,10 +802,10 @@ impl PackageModel {
         let mut builder = QueryBuilder::<Sqlite>::new(
             r#"
                 SELECT
-                    (SELECT COUNT(version_id) FROM versions) as count,
+                    COUNT(*) OVER () as count,
                     version_id,
                     publisher_id,
-                    package_id,
+                    versions.package_id,
                     major,
                     minor,
                     patch,
@@ -457,13 +818,24 @@ impl PackageModel {
                     content_id,
                     pointer_id,
                     signature,
+                    signature_scheme,
                     checksum,
+                    kind,
+                    size,
                     yanked,
-                    created_at
+                    downloads,
+                    purged_at,
+                    versions.created_at,
+                    packages.deprecated
                 FROM versions
-                WHERE package_id = "#,
+                "#,
         );
+        builder.push(PACKAGE_JOIN);
+        builder.push(" WHERE versions.package_id = ");
         builder.push_bind(package_record.package_id);
+        if !include_yanked {
+            builder.push(" AND yanked IS NULL");
+        }
         builder.push(
             r#"
             GROUP BY version_id
@@ -479,16 +851,11 @@ impl PackageModel {
         args.add(pager.limit);
         args.add(pager.offset);
 
-        let ordering = format!(
-            "major {}, minor {}, patch {}, pre {}, build {}",
-            pager.sort, pager.sort, pager.sort, pager.sort, pager.sort
-        );
-
         builder.push(format!(
             r#"
                 ORDER BY {}
                 LIMIT "#,
-            ordering
+            version_order_by(pager.sort, true)
         ));
         builder.push_bind(pager.limit);
         builder.push(r#" OFFSET "#);
@@ -503,19 +870,61 @@ impl PackageModel {
         Ok(records.into_result_set())
     }
 
+    /// Find the highest version of a package that satisfies a range.
+    pub async fn find_best_match(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        name: &PackageName,
+        range: &VersionReq,
+        include_yanked: bool,
+    ) -> Result<Option<VersionRecord>> {
+        let mut pager: Pager = Default::default();
+        let mut best: Option<VersionRecord> = None;
+        loop {
+            let page = PackageModel::find_versions(
+                pool,
+                namespace,
+                name,
+                range,
+                &pager,
+                include_yanked,
+            )
+            .await?;
+
+            let done = (page.records.len() as i64) < pager.limit;
+            for record in page.records {
+                let is_better = best
+                    .as_ref()
+                    .map(|best| record.version > best.version)
+                    .unwrap_or(true);
+                if range.matches(&record.version) && is_better {
+                    best = Some(record);
+                }
+            }
+
+            if done {
+                break;
+            }
+            pager.offset += pager.limit;
+        }
+        Ok(best)
+    }
+
     /// Find latest version by namespace and package name.
     pub async fn find_latest_by_name(
         pool: &SqlitePool,
         namespace: &Namespace,
         name: &PackageName,
         include_prerelease: bool,
+        include_yanked: bool,
     ) -> Result<Option<VersionRecord>> {
         // Find the namespace
-        let namespace_record = NamespaceModel::find_by_name(pool, namespace)
-            .await?
-            .ok_or_else(|| {
-                Error::NotFound(NotFound::Namespace(namespace.clone()))
-            })?;
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(pool, namespace)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::Namespace(namespace.clone()))
+                })?;
 
         // Find the package
         let package_record = PackageModel::find_by_name(
@@ -528,8 +937,13 @@ impl PackageModel {
             Error::NotFound(NotFound::PackageName(name.to_owned()))
         })?;
 
-        PackageModel::find_latest(pool, &package_record, include_prerelease)
-            .await
+        PackageModel::find_latest(
+            pool,
+            &package_record,
+            include_prerelease,
+            include_yanked,
+        )
+        .await
     }
 
     /// Find latest version of a package.
@@ -537,6 +951,7 @@ impl PackageModel {
         pool: &SqlitePool,
         package_record: &PackageRecord,
         include_prerelease: bool,
+        include_yanked: bool,
     ) -> Result<Option<VersionRecord>> {
         let mut args: SqliteArguments = Default::default();
         args.add(package_record.package_id);
@@ -547,7 +962,7 @@ impl PackageModel {
                     (SELECT COUNT(version_id) FROM versions) as count,
                     version_id,
                     publisher_id,
-                    package_id,
+                    versions.package_id,
                     major,
                     minor,
                     patch,
@@ -557,30 +972,35 @@ impl PackageModel {
                     content_id,
                     pointer_id,
                     signature,
+                    signature_scheme,
                     checksum,
+                    kind,
+                    size,
                     yanked,
-                    created_at
-                FROM versions WHERE package_id =
+                    downloads,
+                    purged_at,
+                    versions.created_at,
+                    packages.deprecated
+                FROM versions
             "#,
         );
+        builder.push(PACKAGE_JOIN);
+        builder.push(" WHERE versions.package_id = ");
         builder.push_bind(package_record.package_id);
 
-        if include_prerelease {
-            builder.push(
-                r#"
-                    ORDER BY major DESC, minor DESC, patch DESC, pre DESC, build DESC
-                    LIMIT 1
-                "#);
-        } else {
-            builder.push(
-                r#"
-                    AND pre = ""
-                    ORDER BY major DESC, minor DESC, patch DESC
-                    LIMIT 1
-                "#,
-            );
+        if !include_yanked {
+            builder.push(" AND yanked IS NULL");
+        }
+
+        if !include_prerelease {
+            builder.push(" AND pre = ''");
         }
 
+        builder.push(format!(
+            " ORDER BY {} LIMIT 1",
+            version_order_by(SortOrder::Desc, include_prerelease)
+        ));
+
         let sql = builder.into_sql();
         let record = sqlx::query_as_with::<_, VersionRecord, _>(&sql, args)
             .fetch_optional(pool)
@@ -590,6 +1010,9 @@ impl PackageModel {
     }
 
     /// Find a package by name and version.
+    ///
+    /// Build metadata is not significant so a version matches
+    /// regardless of build metadata.
     pub async fn find_by_name_version(
         pool: &SqlitePool,
         namespace_id: i64,
@@ -605,17 +1028,21 @@ impl PackageModel {
             args.add(version.minor as i64);
             args.add(version.patch as i64);
             args.add(version.pre.to_string());
-            args.add(version.build.to_string());
 
-            let record = sqlx::query_as_with::<_, VersionRecord, _>(
+            let sql = format!(
                 r#"
-                    SELECT * FROM versions
-                    WHERE package_id = ? AND major = ? AND minor = ? AND patch = ? AND pre = ? AND build = ?
+                    SELECT versions.*, packages.deprecated FROM versions
+                    {}
+                    WHERE versions.package_id = ? AND major = ? AND minor = ? AND patch = ? AND pre = ?
+                    ORDER BY version_id
+                    LIMIT 1
                 "#,
-                args
-            )
-            .fetch_optional(pool)
-            .await?;
+                PACKAGE_JOIN,
+            );
+            let record =
+                sqlx::query_as_with::<_, VersionRecord, _>(&sql, args)
+                    .fetch_optional(pool)
+                    .await?;
 
             Ok((Some(package_record), record))
         } else {
@@ -639,16 +1066,30 @@ impl PackageModel {
         Ok(record)
     }
 
-    /// Find or insert a new package.
+    /// Find or insert a new package and return the package
+    /// primary key.
+    ///
+    /// Runs in the caller's transaction so a new package is only
+    /// committed together with its first version.
     pub async fn find_or_insert(
-        pool: &SqlitePool,
+        tx: &mut Transaction<'_, Sqlite>,
         namespace_id: i64,
         name: &PackageName,
-    ) -> Result<PackageRecord> {
-        if let Some(record) =
-            PackageModel::find_by_name(pool, namespace_id, name).await?
-        {
-            Ok(record)
+    ) -> Result<i64> {
+        let skeleton = name.skeleton();
+        let package_id = sqlx::query_scalar::<_, i64>(
+            r#"
+                SELECT package_id FROM packages
+                WHERE namespace_id = ? AND skeleton = ?
+            "#,
+        )
+        .bind(namespace_id)
+        .bind(&skeleton)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(package_id) = package_id {
+            Ok(package_id)
         } else {
             let mut builder = QueryBuilder::new(
                 r#"
@@ -657,21 +1098,50 @@ impl PackageModel {
                 "#,
             );
 
-            let skeleton = name.skeleton();
             let mut separated = builder.separated(", ");
             separated.push_bind(namespace_id);
             separated.push_bind(name.as_str());
             separated.push_bind(&skeleton);
-            builder.push(", datetime('now') )");
+            builder.push(format!(", {} )", SQL_NOW));
 
-            let id = builder.build().execute(pool).await?.last_insert_rowid();
+            Ok(builder.build().execute(&mut *tx).await?.last_insert_rowid())
+        }
+    }
 
-            let record = PackageModel::find_package_by_id(pool, id)
-                .await?
-                .ok_or(Error::InsertFetch(id))?;
+    /// Find packages that do not have any versions.
+    pub async fn find_empty_packages(
+        pool: &SqlitePool,
+    ) -> Result<Vec<EmptyPackage>> {
+        let rows = sqlx::query_as::<_, (i64, String, String, String)>(
+            r#"
+                SELECT
+                    packages.package_id,
+                    namespaces.name,
+                    packages.name,
+                    packages.created_at
+                FROM packages
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM versions
+                    WHERE versions.package_id = packages.package_id
+                )
+                ORDER BY packages.package_id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
 
-            Ok(record)
+        let mut packages = Vec::with_capacity(rows.len());
+        for (package_id, namespace, name, created_at) in rows {
+            packages.push(EmptyPackage {
+                package_id,
+                namespace: Namespace::new_unchecked(&namespace),
+                name: PackageName::new_unchecked(&name),
+                created_at: parse_date_time(&created_at)?,
+            });
         }
+        Ok(packages)
     }
 
     /// Add a package version to a namespace.
@@ -681,6 +1151,7 @@ impl PackageModel {
         namespace_record: &NamespaceRecord,
         _publisher: &Address,
         pointer: &Pointer,
+        objects: &[StoredObject],
     ) -> Result<i64> {
         let name = &pointer.definition.artifact.package.name;
         let version = &pointer.definition.artifact.package.version;
@@ -696,10 +1167,12 @@ impl PackageModel {
 
         // Find or insert the package
         let package = serde_json::to_string(&pointer.package)?;
+        let objects = serde_json::to_string(objects)?;
 
-        //let version = version.to_string();
-        let package_record = PackageModel::find_or_insert(
-            pool,
+        let mut tx = pool.begin().await?;
+
+        let package_id = PackageModel::find_or_insert(
+            &mut tx,
             namespace_record.namespace_id,
             name,
         )
@@ -708,13 +1181,13 @@ impl PackageModel {
         // Insert the package version
         let mut builder = QueryBuilder::new(
             r#"
-                INSERT INTO versions ( publisher_id, package_id, major, minor, patch, pre, build, package, content_id, pointer_id, signature, checksum, created_at )
+                INSERT INTO versions ( publisher_id, package_id, major, minor, patch, pre, build, package, content_id, pointer_id, signature, signature_scheme, checksum, kind, objects, size, created_at )
                 VALUES (
             "#,
         );
         let mut separated = builder.separated(", ");
         separated.push_bind(publisher_record.publisher_id);
-        separated.push_bind(package_record.package_id);
+        separated.push_bind(package_id);
         separated.push_bind(version.major as i64);
         separated.push_bind(version.minor as i64);
         separated.push_bind(version.patch as i64);
@@ -724,18 +1197,121 @@ impl PackageModel {
         separated.push_bind(content_id);
         separated.push_bind(pointer_id);
         separated.push_bind(pointer.definition.signature.value.to_vec());
+        separated.push_bind(pointer.definition.signature.scheme.to_string());
         separated.push_bind(pointer.definition.checksum.to_vec());
-        builder.push(", datetime('now') )");
+        separated.push_bind(pointer.definition.artifact.kind.to_string());
+        separated.push_bind(objects);
+        separated.push_bind(pointer.definition.size.map(|size| size as i64));
+        builder.push(format!(", {} )", SQL_NOW));
+
+        let id = builder.build().execute(&mut tx).await?.last_insert_rowid();
+
+        PackageModel::insert_dependencies(&mut tx, id, pointer).await?;
 
-        let id = builder.build().execute(pool).await?.last_insert_rowid();
+        tx.commit().await?;
 
         Ok(id)
     }
 
+    /// Index the dependencies declared in the manifest of a version.
+    async fn insert_dependencies(
+        tx: &mut Transaction<'_, Sqlite>,
+        version_id: i64,
+        pointer: &Pointer,
+    ) -> Result<()> {
+        let dependencies = PackageReader::dependencies(
+            pointer.definition.artifact.kind,
+            &pointer.package,
+        );
+        for dependency in dependencies {
+            sqlx::query(
+                r#"
+                    INSERT INTO version_dependencies ( version_id, name, requirement )
+                    VALUES ( ?, ?, ? )
+                "#,
+            )
+            .bind(version_id)
+            .bind(dependency.name)
+            .bind(dependency.range)
+            .execute(&mut *tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// List the versions that declare a dependency on a package.
+    ///
+    /// Dependencies are matched by the package name in any
+    /// namespace; yanked versions and versions in private
+    /// namespaces are not included.
+    pub async fn list_dependents(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        name: &PackageName,
+        pager: &Pager,
+    ) -> Result<ResultSet<DependentRecord>> {
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(pool, namespace)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::Namespace(namespace.clone()))
+                })?;
+
+        PackageModel::find_by_name(pool, namespace_record.namespace_id, name)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::PackageName(name.to_owned()))
+            })?;
+
+        let mut builder = QueryBuilder::new(
+            r#"
+                SELECT
+                    COUNT(*) OVER () as count,
+                    namespaces.name as namespace,
+                    packages.name as package,
+                    versions.major,
+                    versions.minor,
+                    versions.patch,
+                    versions.pre,
+                    versions.build,
+                    version_dependencies.requirement
+                FROM version_dependencies
+                INNER JOIN versions
+                    ON (version_dependencies.version_id = versions.version_id)
+                INNER JOIN packages
+                    ON (versions.package_id = packages.package_id)
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                WHERE versions.yanked IS NULL
+                AND namespaces.private = 0
+                AND version_dependencies.name =
+            "#,
+        );
+        builder.push_bind(name.as_str());
+        builder.push(format!(" ORDER BY versions.version_id {}", pager.sort));
+        builder.push(" LIMIT ");
+        builder.push_bind(pager.limit);
+        builder.push(" OFFSET ");
+        builder.push_bind(pager.offset);
+
+        let records = builder
+            .build_query_as::<DependentRecord>()
+            .fetch_all(pool)
+            .await?;
+        Ok(records.into_result_set())
+    }
+
     /// Assert publishing is ok by checking a package
     /// with the given name and version does not already exist, the
     /// target version is ahead of the latest published version
     /// and verify access control permissions.
+    ///
+    /// Build metadata is ignored so publishing a version that
+    /// only differs in build metadata is a conflict.
+    ///
+    /// When the namespace is restricted by default users that are
+    /// not administrators and have no package restrictions may not
+    /// publish to any package rather than all packages.
     pub async fn can_publish_package(
         pool: &SqlitePool,
         address: &Address,
@@ -743,21 +1319,101 @@ impl PackageModel {
         name: &PackageName,
         version: Option<&Version>,
     ) -> Result<Option<PackageRecord>> {
-        let not_owner = address != &namespace_record.owner;
-        let user = namespace_record
-            .publishers
-            .iter()
-            .find(|u| &u.address == address);
-        let is_restricted = if let (Some(user), true) = (user, not_owner) {
-            !user.restrictions.is_empty()
-        } else {
-            false
-        };
-
-        // Not the owner and no user found for the namespace
-        // so access is denied
-        if not_owner && user.is_none() {
-            return Err(Error::Unauthorized(*address));
+        PackageModel::authorize_publish(
+            pool,
+            address,
+            namespace_record,
+            name,
+            version,
+        )
+        .await?
+        .map_err(|(_, e)| e)
+    }
+
+    /// Check whether an address can publish to a package
+    /// without returning an error when access is denied.
+    ///
+    /// Performs the same checks as `can_publish_package`; when no
+    /// version is given only the access control checks apply.
+    pub async fn check_publish(
+        pool: &SqlitePool,
+        address: &Address,
+        namespace_record: &NamespaceRecord,
+        name: &PackageName,
+        version: Option<&Version>,
+    ) -> Result<PublishCheck> {
+        let result = PackageModel::authorize_publish(
+            pool,
+            address,
+            namespace_record,
+            name,
+            version,
+        )
+        .await?;
+
+        Ok(match result {
+            Ok(_) => {
+                let permission = if namespace_record.is_owner(address) {
+                    PublishPermission::Owner
+                } else {
+                    match namespace_record.find_user(address) {
+                        Some(user) if user.administrator => {
+                            PublishPermission::Administrator
+                        }
+                        Some(user)
+                            if !user.restrictions.is_empty()
+                                || namespace_record.default_restricted =>
+                        {
+                            PublishPermission::Restricted
+                        }
+                        _ => PublishPermission::Publisher,
+                    }
+                };
+                PublishCheck {
+                    allowed: true,
+                    permission: Some(permission),
+                    reason: None,
+                }
+            }
+            Err((reason, _)) => PublishCheck {
+                allowed: false,
+                permission: None,
+                reason: Some(reason),
+            },
+        })
+    }
+
+    /// Run the publish checks returning the reason and
+    /// error when publishing is denied.
+    async fn authorize_publish(
+        pool: &SqlitePool,
+        address: &Address,
+        namespace_record: &NamespaceRecord,
+        name: &PackageName,
+        version: Option<&Version>,
+    ) -> Result<
+        std::result::Result<Option<PackageRecord>, (PublishDenied, Error)>,
+    > {
+        let not_owner = address != &namespace_record.owner;
+        let user = namespace_record
+            .publishers
+            .iter()
+            .find(|u| &u.address == address);
+        let is_restricted = if let (Some(user), true) = (user, not_owner) {
+            !user.restrictions.is_empty()
+                || (namespace_record.default_restricted
+                    && !user.administrator)
+        } else {
+            false
+        };
+
+        // Not the owner and no user found for the namespace
+        // so access is denied
+        if not_owner && user.is_none() {
+            return Ok(Err((
+                PublishDenied::NotMember,
+                Error::Unauthorized(*address),
+            )));
         }
 
         let package_record = if let Some(version) = version {
@@ -770,12 +1426,25 @@ impl PackageModel {
                     version,
                 )
                 .await?;
-            if version_record.is_some() {
-                return Err(Error::PackageExists(
-                    namespace_record.name.clone(),
-                    name.clone(),
-                    version.clone(),
-                ));
+            if let Some(version_record) = version_record {
+                if version_record.yanked.is_some() {
+                    return Ok(Err((
+                        PublishDenied::VersionYanked,
+                        Error::VersionYanked(
+                            namespace_record.name.clone(),
+                            name.clone(),
+                            version.clone(),
+                        ),
+                    )));
+                }
+                return Ok(Err((
+                    PublishDenied::VersionExists,
+                    Error::PackageExists(
+                        namespace_record.name.clone(),
+                        name.clone(),
+                        version.clone(),
+                    ),
+                )));
             }
 
             package_record
@@ -797,10 +1466,29 @@ impl PackageModel {
                         package_id == &package_record.package_id
                     });
                 if !can_publish {
-                    return Err(Error::Unauthorized(*address));
+                    return Ok(Err((
+                        PublishDenied::NotGranted,
+                        Error::Unauthorized(*address),
+                    )));
                 }
             }
 
+            // Package has trusted keys so only those keys may
+            // publish regardless of the namespace permissions
+            let trusted_keys = PackageModel::find_trusted_keys(
+                pool,
+                package_record.package_id,
+            )
+            .await?;
+            if !trusted_keys.is_empty()
+                && !trusted_keys.iter().any(|key| &key.address == address)
+            {
+                return Ok(Err((
+                    PublishDenied::UntrustedKey,
+                    Error::Unauthorized(*address),
+                )));
+            }
+
             if let Some(version) = version {
                 // Verify the version to publish is ahead of the latest version
                 if let Some(latest) = PackageModel::find_latest_by_name(
@@ -808,14 +1496,20 @@ impl PackageModel {
                     &namespace_record.name,
                     name,
                     true,
+                    true,
                 )
                 .await?
                 {
-                    if version <= &latest.version {
-                        return Err(Error::VersionNotAhead(
-                            version.clone(),
-                            latest.version,
-                        ));
+                    if cmp_precedence(version, &latest.version)
+                        != Ordering::Greater
+                    {
+                        return Ok(Err((
+                            PublishDenied::VersionNotAhead,
+                            Error::VersionNotAhead(
+                                version.clone(),
+                                latest.version,
+                            ),
+                        )));
                     }
                 }
             }
@@ -824,11 +1518,450 @@ impl PackageModel {
             // publish for the package, restricted users should
             // be denied access
             if let (Some(_), true) = (user, is_restricted) {
-                return Err(Error::Unauthorized(*address));
+                return Ok(Err((
+                    PublishDenied::NotGranted,
+                    Error::Unauthorized(*address),
+                )));
             }
         }
 
-        Ok(package_record)
+        Ok(Ok(package_record))
+    }
+
+    /// Assert a forced publish is ok by checking the address is the
+    /// namespace owner and when the version already exists that it
+    /// was published within the overwrite window.
+    ///
+    /// When the version does not exist the checks for a regular
+    /// publish apply and no version record is returned.
+    pub async fn can_overwrite_version(
+        pool: &SqlitePool,
+        address: &Address,
+        namespace_record: &NamespaceRecord,
+        name: &PackageName,
+        version: &Version,
+        window: Duration,
+    ) -> Result<Option<VersionRecord>> {
+        if address != &namespace_record.owner {
+            return Err(Error::Unauthorized(*address));
+        }
+
+        let (_, version_record) = PackageModel::find_by_name_version(
+            pool,
+            namespace_record.namespace_id,
+            name,
+            version,
+        )
+        .await?;
+
+        if let Some(version_record) = version_record {
+            // Trusted keys still apply to the owner
+            PackageModel::can_publish_package(
+                pool,
+                address,
+                namespace_record,
+                name,
+                None,
+            )
+            .await?;
+
+            if OffsetDateTime::now_utc() - version_record.created_at >= window
+            {
+                return Err(Error::OverwriteWindow(
+                    namespace_record.name.clone(),
+                    name.clone(),
+                    version.clone(),
+                ));
+            }
+
+            Ok(Some(version_record))
+        } else {
+            PackageModel::can_publish_package(
+                pool,
+                address,
+                namespace_record,
+                name,
+                Some(version),
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+
+    /// Assert publishing a version that may replace a yanked
+    /// version is ok.
+    ///
+    /// When the version exists and was yanked the access checks
+    /// for the package apply and the yanked version record is
+    /// returned; otherwise the checks for a regular publish apply
+    /// and no version record is returned.
+    pub async fn can_replace_yanked(
+        pool: &SqlitePool,
+        address: &Address,
+        namespace_record: &NamespaceRecord,
+        name: &PackageName,
+        version: &Version,
+    ) -> Result<Option<VersionRecord>> {
+        let (_, version_record) = PackageModel::find_by_name_version(
+            pool,
+            namespace_record.namespace_id,
+            name,
+            version,
+        )
+        .await?;
+
+        match version_record {
+            Some(version_record) if version_record.yanked.is_some() => {
+                PackageModel::can_publish_package(
+                    pool,
+                    address,
+                    namespace_record,
+                    name,
+                    None,
+                )
+                .await?;
+                Ok(Some(version_record))
+            }
+            _ => {
+                PackageModel::can_publish_package(
+                    pool,
+                    address,
+                    namespace_record,
+                    name,
+                    Some(version),
+                )
+                .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Replace the artifact for an existing version.
+    ///
+    /// The previous artifact is recorded in the overwrite history
+    /// and the original publish time is preserved so the overwrite
+    /// window cannot be extended.
+    ///
+    /// This is the only permitted change to the content of a
+    /// published version, the `versions_immutable` trigger rejects
+    /// updates that are not preceded by an overwrite record.
+    pub async fn overwrite(
+        pool: &SqlitePool,
+        publisher_record: &PublisherRecord,
+        version_record: &VersionRecord,
+        pointer: &Pointer,
+        objects: &[StoredObject],
+    ) -> Result<i64> {
+        let mut tx = pool.begin().await?;
+        let id = PackageModel::overwrite_version(
+            &mut tx,
+            publisher_record,
+            version_record,
+            pointer,
+            objects,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Replace the artifact for a yanked version and restore
+    /// the version so it is no longer yanked.
+    ///
+    /// The previous artifact is recorded in the overwrite history
+    /// in the same way as a forced publish.
+    pub async fn replace_yanked(
+        pool: &SqlitePool,
+        publisher_record: &PublisherRecord,
+        version_record: &VersionRecord,
+        pointer: &Pointer,
+        objects: &[StoredObject],
+    ) -> Result<i64> {
+        let mut tx = pool.begin().await?;
+        let id = PackageModel::overwrite_version(
+            &mut tx,
+            publisher_record,
+            version_record,
+            pointer,
+            objects,
+        )
+        .await?;
+        sqlx::query(
+            r#"
+                UPDATE versions
+                SET yanked = NULL, yanked_at = NULL, purged_at = NULL
+                WHERE version_id = ?
+            "#,
+        )
+        .bind(version_record.version_id)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Record the previous artifact for a version and replace
+    /// the artifact as part of a transaction.
+    ///
+    /// The overwrite is also recorded in the audit log.
+    async fn overwrite_version(
+        tx: &mut Transaction<'_, Sqlite>,
+        publisher_record: &PublisherRecord,
+        version_record: &VersionRecord,
+        pointer: &Pointer,
+        objects: &[StoredObject],
+    ) -> Result<i64> {
+        let content_id = pointer.definition.objects.iter().find_map(|o| {
+            if let ObjectKey::Cid(cid) = o {
+                Some(cid.to_string())
+            } else {
+                None
+            }
+        });
+        let package = serde_json::to_string(&pointer.package)?;
+        let objects = serde_json::to_string(objects)?;
+
+        let mut builder = QueryBuilder::new(
+            r#"
+                INSERT INTO version_overwrites ( version_id, publisher_id, content_id, pointer_id, signature, signature_scheme, checksum, created_at )
+                VALUES (
+            "#,
+        );
+        let mut separated = builder.separated(", ");
+        separated.push_bind(version_record.version_id);
+        separated.push_bind(publisher_record.publisher_id);
+        separated.push_bind(version_record.content_id.map(|c| c.to_string()));
+        separated.push_bind(&version_record.pointer_id);
+        separated.push_bind(version_record.signature.to_vec());
+        separated.push_bind(version_record.signature_scheme.to_string());
+        separated.push_bind(version_record.checksum.to_vec());
+        builder.push(format!(", {} )", SQL_NOW));
+
+        let id = builder.build().execute(&mut *tx).await?.last_insert_rowid();
+
+        let mut builder = QueryBuilder::new("UPDATE versions SET ");
+        let mut separated = builder.separated(", ");
+        separated.push("publisher_id = ");
+        separated.push_bind_unseparated(publisher_record.publisher_id);
+        separated.push("package = ");
+        separated.push_bind_unseparated(package);
+        separated.push("content_id = ");
+        separated.push_bind_unseparated(content_id);
+        separated.push("pointer_id = ");
+        separated
+            .push_bind_unseparated(pointer.definition.artifact.pointer_id());
+        separated.push("signature = ");
+        separated.push_bind_unseparated(
+            pointer.definition.signature.value.to_vec(),
+        );
+        separated.push("signature_scheme = ");
+        separated.push_bind_unseparated(
+            pointer.definition.signature.scheme.to_string(),
+        );
+        separated.push("checksum = ");
+        separated.push_bind_unseparated(pointer.definition.checksum.to_vec());
+        separated.push("objects = ");
+        separated.push_bind_unseparated(objects);
+        separated.push("size = ");
+        separated.push_bind_unseparated(
+            pointer.definition.size.map(|size| size as i64),
+        );
+        builder.push(" WHERE version_id = ");
+        builder.push_bind(version_record.version_id);
+
+        builder.build().execute(&mut *tx).await?;
+
+        // Replace the dependencies from the previous manifest
+        sqlx::query("DELETE FROM version_dependencies WHERE version_id = ?")
+            .bind(version_record.version_id)
+            .execute(&mut *tx)
+            .await?;
+        PackageModel::insert_dependencies(
+            tx,
+            version_record.version_id,
+            pointer,
+        )
+        .await?;
+
+        let artifact = &pointer.definition.artifact;
+        AuditModel::insert(
+            tx,
+            publisher_record.publisher_id,
+            "overwrite_version",
+            Some(&artifact.namespace),
+            &serde_json::json!({
+                "package": artifact.package.name,
+                "version": artifact.package.version.to_string(),
+                "checksum": hex::encode(pointer.definition.checksum),
+                "previous_checksum": hex::encode(version_record.checksum),
+                "yanked": version_record.yanked.is_some(),
+            }),
+        )
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Find the trusted keys for a package.
+    pub async fn find_trusted_keys(
+        pool: &SqlitePool,
+        package_id: i64,
+    ) -> Result<Vec<PublisherRecord>> {
+        let mut args: SqliteArguments = Default::default();
+        args.add(package_id);
+
+        let records = sqlx::query_as_with::<_, PublisherRecord, _>(
+            r#"
+                SELECT
+                    publishers.publisher_id,
+                    publishers.address,
+                    publishers.created_at
+                FROM package_trusted_keys
+                INNER JOIN publishers
+                    ON (package_trusted_keys.publisher_id = publishers.publisher_id)
+                WHERE package_trusted_keys.package_id = ?
+                ORDER BY package_trusted_keys.created_at
+            "#,
+            args,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// List the trusted keys for a package.
+    pub async fn list_trusted_keys(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        package: &PackageName,
+    ) -> Result<Vec<PublisherRecord>> {
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(pool, namespace)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::Namespace(namespace.clone()))
+                })?;
+
+        let package_record = PackageModel::find_by_name(
+            pool,
+            namespace_record.namespace_id,
+            package,
+        )
+        .await?
+        .ok_or_else(|| {
+            Error::NotFound(NotFound::PackageName(package.clone()))
+        })?;
+
+        PackageModel::find_trusted_keys(pool, package_record.package_id).await
+    }
+
+    /// Add a key to the trusted keys for a package.
+    ///
+    /// Only the namespace owner or an administrator may
+    /// modify the trusted keys.
+    pub async fn add_trusted_key(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        package: &PackageName,
+        caller: &Address,
+        key: &Address,
+    ) -> Result<()> {
+        let (package_record, publisher_record, trusted_keys) =
+            PackageModel::can_manage_trusted_keys(
+                pool, namespace, package, caller, key,
+            )
+            .await?;
+
+        if trusted_keys.iter().any(|record| &record.address == key) {
+            return Err(Error::TrustedKeyExists(*key, package.clone()));
+        }
+
+        let mut builder = QueryBuilder::new(
+            r#"
+                INSERT INTO package_trusted_keys ( package_id, publisher_id, created_at )
+                VALUES (
+            "#,
+        );
+        let mut separated = builder.separated(", ");
+        separated.push_bind(package_record.package_id);
+        separated.push_bind(publisher_record.publisher_id);
+        builder.push(format!(", {} )", SQL_NOW));
+        builder.build().execute(pool).await?;
+
+        Ok(())
+    }
+
+    /// Remove a key from the trusted keys for a package.
+    ///
+    /// Only the namespace owner or an administrator may
+    /// modify the trusted keys.
+    pub async fn remove_trusted_key(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        package: &PackageName,
+        caller: &Address,
+        key: &Address,
+    ) -> Result<()> {
+        let (package_record, publisher_record, trusted_keys) =
+            PackageModel::can_manage_trusted_keys(
+                pool, namespace, package, caller, key,
+            )
+            .await?;
+
+        if !trusted_keys.iter().any(|record| &record.address == key) {
+            return Err(Error::TrustedKeyMissing(*key, package.clone()));
+        }
+
+        let mut builder = QueryBuilder::new(
+            r#"DELETE FROM package_trusted_keys WHERE package_id = "#,
+        );
+        builder.push_bind(package_record.package_id);
+        builder.push(" AND publisher_id = ");
+        builder.push_bind(publisher_record.publisher_id);
+        builder.build().execute(pool).await?;
+
+        Ok(())
+    }
+
+    /// Verify the caller may modify the trusted keys for a package
+    /// and find the package, the publisher for the key and the
+    /// existing trusted keys.
+    async fn can_manage_trusted_keys(
+        pool: &SqlitePool,
+        namespace: &Namespace,
+        package: &PackageName,
+        caller: &Address,
+        key: &Address,
+    ) -> Result<(PackageRecord, PublisherRecord, Vec<PublisherRecord>)> {
+        let (_, namespace_record) =
+            NamespaceModel::can_access_namespace(pool, caller, namespace)
+                .await?;
+
+        // Only administrators can modify the trusted keys
+        if !namespace_record.can_administrate(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let package_record = PackageModel::find_by_name(
+            pool,
+            namespace_record.namespace_id,
+            package,
+        )
+        .await?
+        .ok_or_else(|| {
+            Error::NotFound(NotFound::PackageName(package.clone()))
+        })?;
+
+        let publisher_record = PublisherModel::find_by_address(pool, key)
+            .await?
+            .ok_or(Error::NotFound(NotFound::User(*key)))?;
+
+        let trusted_keys =
+            PackageModel::find_trusted_keys(pool, package_record.package_id)
+                .await?;
+
+        Ok((package_record, publisher_record, trusted_keys))
     }
 
     /// Mark a package as deprecated.
@@ -877,6 +2010,249 @@ impl PackageModel {
         Ok(())
     }
 
+    /// Set the description for a package.
+    ///
+    /// Only the namespace owner or an administrator may change the
+    /// description, an empty description removes the description.
+    pub async fn set_description(
+        pool: &SqlitePool,
+        caller: &Address,
+        namespace: &Namespace,
+        package: &PackageName,
+        description: &str,
+    ) -> Result<PackageRecord> {
+        let (publisher_record, namespace_record) =
+            NamespaceModel::can_access_namespace(pool, caller, namespace)
+                .await?;
+
+        if !namespace_record.can_administrate(caller) {
+            return Err(Error::Unauthorized(*caller));
+        }
+
+        let package_record = PackageModel::find_by_name(
+            pool,
+            namespace_record.namespace_id,
+            package,
+        )
+        .await?
+        .ok_or(Error::NotFound(NotFound::PackageName(package.clone())))?;
+
+        let description = validate_description(description)?;
+
+        let mut tx = pool.begin().await?;
+
+        let mut builder =
+            QueryBuilder::<Sqlite>::new("UPDATE packages SET description = ");
+        builder.push_bind(description);
+        builder.push(" WHERE package_id = ");
+        builder.push_bind(package_record.package_id);
+        builder.build().execute(&mut tx).await?;
+
+        AuditModel::insert(
+            &mut tx,
+            publisher_record.publisher_id,
+            "set_description",
+            Some(namespace),
+            &serde_json::json!({
+                "package": package_record.name,
+                "description": description,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        PackageModel::find_by_name(
+            pool,
+            namespace_record.namespace_id,
+            package,
+        )
+        .await?
+        .ok_or(Error::NotFound(NotFound::PackageName(package.clone())))
+    }
+
+    /// Assert a package can be transferred to another namespace
+    /// and return the publisher and package records.
+    ///
+    /// The owners of the source and target namespace must both
+    /// sign the transfer unless it is signed by a registry
+    /// administrator; the publisher record is for the signer of
+    /// the source namespace or the administrator.
+    pub async fn can_transfer(
+        pool: &SqlitePool,
+        signers: &TransferSigners,
+        from: &Namespace,
+        package: &PackageName,
+        to: &Namespace,
+    ) -> Result<(PublisherRecord, PackageRecord)> {
+        let caller = signers.caller();
+        let publisher_record = PublisherModel::find_by_address(pool, caller)
+            .await?
+            .ok_or(Error::NotFound(NotFound::User(*caller)))?;
+
+        let from_record =
+            NamespaceModel::find_by_name(pool, from).await?.ok_or_else(
+                || Error::NotFound(NotFound::Namespace(from.clone())),
+            )?;
+        let to_record =
+            NamespaceModel::find_by_name(pool, to).await?.ok_or_else(
+                || Error::NotFound(NotFound::Namespace(to.clone())),
+            )?;
+
+        if let TransferSigners::Owners {
+            from: from_owner,
+            to: to_owner,
+        } = signers
+        {
+            if !from_record.is_owner(from_owner) {
+                return Err(Error::Unauthorized(*from_owner));
+            }
+            if !to_record.is_owner(to_owner) {
+                return Err(Error::Unauthorized(*to_owner));
+            }
+        }
+
+        let package_record = PackageModel::find_by_name(
+            pool,
+            from_record.namespace_id,
+            package,
+        )
+        .await?
+        .ok_or_else(|| {
+            Error::NotFound(NotFound::PackageName(package.clone()))
+        })?;
+
+        // Check the name (or a confusable) is not taken in the target
+        if PackageModel::find_by_name(
+            pool,
+            to_record.namespace_id,
+            &package_record.name,
+        )
+        .await?
+        .is_some()
+        {
+            return Err(Error::PackageNameExists(
+                to_record.name.clone(),
+                package_record.name.clone(),
+            ));
+        }
+
+        Ok((publisher_record, package_record))
+    }
+
+    /// Find all versions of a package including yanked
+    /// and purged versions.
+    pub async fn find_all_versions(
+        pool: &SqlitePool,
+        package_id: i64,
+    ) -> Result<Vec<VersionRecord>> {
+        let records = sqlx::query_as::<_, VersionRecord>(
+            r#"
+                SELECT * FROM versions
+                WHERE package_id = ?
+                ORDER BY version_id
+            "#,
+        )
+        .bind(package_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Transfer a package to another namespace.
+    ///
+    /// The owners of both namespaces or a registry administrator
+    /// must sign the transfer.
+    ///
+    /// Pointer identifiers are derived from the namespace so the
+    /// locations of the artifacts stored for the target namespace
+    /// are updated with the package.
+    pub async fn transfer(
+        pool: &SqlitePool,
+        signers: &TransferSigners,
+        from: &Namespace,
+        package: &PackageName,
+        to: &Namespace,
+        locations: &[VersionLocation],
+    ) -> Result<PackageRecord> {
+        let (publisher_record, package_record) =
+            PackageModel::can_transfer(pool, signers, from, package, to)
+                .await?;
+
+        let to_record = NamespaceModel::find_by_name_shallow(pool, to)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(NotFound::Namespace(to.clone()))
+            })?;
+
+        let mut tx = pool.begin().await?;
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "UPDATE packages SET namespace_id = ",
+        );
+        builder.push_bind(to_record.namespace_id);
+        builder.push(" WHERE package_id = ");
+        builder.push_bind(package_record.package_id);
+        builder.build().execute(&mut tx).await?;
+
+        // Restrictions belong to users of the source namespace
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "DELETE FROM publisher_restrictions WHERE package_id = ",
+        );
+        builder.push_bind(package_record.package_id);
+        builder.build().execute(&mut tx).await?;
+
+        PackageModel::relocate_versions(&mut tx, locations).await?;
+
+        AuditModel::insert(
+            &mut tx,
+            publisher_record.publisher_id,
+            "transfer_package",
+            Some(from),
+            &serde_json::json!({
+                "package": package_record.name,
+                "from": from,
+                "to": to,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        let record =
+            PackageModel::find_package_by_id(pool, package_record.package_id)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(NotFound::PackageName(package.clone()))
+                })?;
+
+        Ok(record)
+    }
+
+    /// Update the storage locations of versions as part
+    /// of a transaction.
+    pub(crate) async fn relocate_versions(
+        tx: &mut Transaction<'_, Sqlite>,
+        locations: &[VersionLocation],
+    ) -> Result<()> {
+        for location in locations {
+            let objects = serde_json::to_string(&location.objects)?;
+            sqlx::query(
+                r#"
+                    UPDATE versions SET pointer_id = ?, objects = ?
+                    WHERE version_id = ?
+                "#,
+            )
+            .bind(&location.pointer_id)
+            .bind(objects)
+            .bind(location.version_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        Ok(())
+    }
+
     /// Yank a package.
     pub async fn yank(
         pool: &SqlitePool,
@@ -884,8 +2260,9 @@ impl PackageModel {
         id: &PackageKey,
         message: &str,
     ) -> Result<()> {
+        // Never yank an arbitrary version for shared content
         let (namespace_record, package_record, version_record) =
-            PackageModel::find_by_key(pool, id).await?;
+            PackageModel::find_by_key(pool, id, DuplicateCid::Reject).await?;
 
         let package_record = package_record
             .ok_or(Error::NotFound(NotFound::PackageKey(id.clone())))?;
@@ -895,9 +2272,9 @@ impl PackageModel {
         // Should have namespace if we have version record
         let namespace_record = namespace_record.unwrap();
 
-        NamespaceModel::can_access_namespace(
+        let (publisher_record, _) = NamespaceModel::can_access_namespace(
             pool,
-            &address,
+            address,
             &namespace_record.name,
         )
         .await?;
@@ -914,7 +2291,8 @@ impl PackageModel {
         let mut builder =
             QueryBuilder::<Sqlite>::new("UPDATE versions SET yanked = ");
         builder.push_bind(message);
-        builder.push("WHERE version_id = ");
+        builder
+            .push(format!(", yanked_at = {} WHERE version_id = ", SQL_NOW));
         builder.push_bind(version_record.version_id);
 
         let mut args: SqliteArguments = Default::default();
@@ -923,7 +2301,231 @@ impl PackageModel {
 
         let sql = builder.into_sql();
 
-        sqlx::query_with::<_, _>(&sql, args).execute(pool).await?;
+        let mut tx = pool.begin().await?;
+        sqlx::query_with::<_, _>(&sql, args)
+            .execute(&mut tx)
+            .await?;
+        AuditModel::insert(
+            &mut tx,
+            publisher_record.publisher_id,
+            "yank",
+            Some(&namespace_record.name),
+            &serde_json::json!({
+                "package": package_record.name,
+                "version": version_record.version.to_string(),
+                "message": message,
+            }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Restore a yanked version of a package.
+    ///
+    /// The caller must be allowed to yank the version; restoring
+    /// a version that is not yanked is a no-op and the artifact
+    /// of a purged version cannot be restored.
+    pub async fn unyank(
+        pool: &SqlitePool,
+        address: &Address,
+        id: &PackageKey,
+    ) -> Result<()> {
+        // Never restore an arbitrary version for shared content
+        let (namespace_record, package_record, version_record) =
+            PackageModel::find_by_key(pool, id, DuplicateCid::Reject).await?;
+
+        let package_record = package_record
+            .ok_or(Error::NotFound(NotFound::PackageKey(id.clone())))?;
+        let version_record = version_record
+            .ok_or(Error::NotFound(NotFound::PackageKey(id.clone())))?;
+
+        // Should have namespace if we have version record
+        let namespace_record = namespace_record.unwrap();
+
+        let (publisher_record, _) = NamespaceModel::can_access_namespace(
+            pool,
+            address,
+            &namespace_record.name,
+        )
+        .await?;
+
+        PackageModel::can_publish_package(
+            pool,
+            address,
+            &namespace_record,
+            &package_record.name,
+            None,
+        )
+        .await?;
+
+        if version_record.yanked.is_none() {
+            return Ok(());
+        }
+
+        if version_record.purged_at.is_some() {
+            return Err(Error::VersionPurged(id.clone()));
+        }
+
+        // The retention policy may purge the version concurrently
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+                UPDATE versions
+                SET yanked = NULL, yanked_at = NULL
+                WHERE version_id = ?
+                AND purged_at IS NULL
+            "#,
+        )
+        .bind(version_record.version_id)
+        .execute(&mut tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::VersionPurged(id.clone()));
+        }
+        AuditModel::insert(
+            &mut tx,
+            publisher_record.publisher_id,
+            "unyank",
+            Some(&namespace_record.name),
+            &serde_json::json!({
+                "package": package_record.name,
+                "version": version_record.version.to_string(),
+            }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Record an object stored for the version at a pointer
+    /// identifier after the version was published.
+    ///
+    /// Replaces any object for the same storage layer; when no
+    /// version has the pointer identifier nothing is recorded.
+    pub async fn add_version_object(
+        pool: &SqlitePool,
+        pointer_id: &str,
+        object: &StoredObject,
+    ) -> Result<()> {
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query_as::<_, (i64, Option<String>)>(
+            "SELECT version_id, objects FROM versions WHERE pointer_id = ?",
+        )
+        .bind(pointer_id)
+        .fetch_optional(&mut tx)
+        .await?;
+
+        if let Some((version_id, objects)) = row {
+            let mut objects: Vec<StoredObject> =
+                if let Some(objects) = objects {
+                    serde_json::from_str(&objects)?
+                } else {
+                    vec![]
+                };
+            objects.retain(|o| o.layer != object.layer);
+            objects.push(object.clone());
+            objects.sort_by_key(|o| o.layer);
+
+            sqlx::query(
+                "UPDATE versions SET objects = ? WHERE version_id = ?",
+            )
+            .bind(serde_json::to_string(&objects)?)
+            .bind(version_id)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Increment the download count for a version.
+    pub async fn increment_downloads(
+        pool: &SqlitePool,
+        version_id: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE versions SET downloads = downloads + 1 WHERE version_id = ?",
+        )
+        .bind(version_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Find yanked versions that were yanked before the given
+    /// date and time and whose artifacts have not been purged.
+    pub async fn find_purgeable(
+        pool: &SqlitePool,
+        yanked_before: &OffsetDateTime,
+    ) -> Result<Vec<VersionRecord>> {
+        let mut args: SqliteArguments = Default::default();
+        args.add(format_date_time(yanked_before)?);
+
+        let records = sqlx::query_as_with::<_, VersionRecord, _>(
+            r#"
+                SELECT * FROM versions
+                WHERE yanked IS NOT NULL
+                AND purged_at IS NULL
+                AND yanked_at <= ?
+                ORDER BY version_id
+            "#,
+            args,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Mark the artifact for a version as removed from storage.
+    ///
+    /// The version is only marked when it is still yanked before
+    /// the date and time with the same artifact as the record so
+    /// a version restored or overwritten after the record was
+    /// found is not purged; returns whether the version was marked.
+    pub async fn set_purged(
+        pool: &SqlitePool,
+        record: &VersionRecord,
+        yanked_before: &OffsetDateTime,
+    ) -> Result<bool> {
+        let mut args: SqliteArguments = Default::default();
+        args.add(record.version_id);
+        args.add(format_date_time(yanked_before)?);
+        args.add(record.checksum.to_vec());
+
+        let sql = format!(
+            r#"
+                UPDATE versions SET purged_at = {}
+                WHERE version_id = ?
+                AND yanked IS NOT NULL
+                AND purged_at IS NULL
+                AND yanked_at <= ?
+                AND checksum = ?
+            "#,
+            SQL_NOW
+        );
+
+        let result =
+            sqlx::query_with::<_, _>(&sql, args).execute(pool).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the mark for a version whose artifact could not
+    /// be removed from storage.
+    pub async fn clear_purged(
+        pool: &SqlitePool,
+        version_id: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE versions SET purged_at = NULL WHERE version_id = ?",
+        )
+        .bind(version_id)
+        .execute(pool)
+        .await?;
 
         Ok(())
     }
@@ -3,7 +3,12 @@ use sqlx::{sqlite::SqliteArguments, Arguments, QueryBuilder, SqlitePool};
 
 use web3_address::ethereum::Address;
 
-use crate::{value_objects::*, Error, Result};
+use crate::{
+    error::NotFound,
+    model::{Pager, SQL_NOW},
+    value_objects::*,
+    Error, Result,
+};
 
 /// Manage registry publishers.
 pub struct PublisherModel;
@@ -21,7 +26,7 @@ impl PublisherModel {
         );
         let mut separated = builder.separated(", ");
         separated.push_bind(owner.as_ref());
-        builder.push(", datetime('now') )");
+        builder.push(format!(", {} )", SQL_NOW));
 
         let id = builder
             .build()
@@ -70,4 +75,187 @@ impl PublisherModel {
 
         Ok(record)
     }
+
+    /// Determine if an address is the key of a publisher or a
+    /// key the publisher has rotated out.
+    pub async fn is_signing_key(
+        pool: &SqlitePool,
+        publisher_id: i64,
+        address: &Address,
+    ) -> Result<bool> {
+        let addr = address.as_ref();
+
+        let mut args: SqliteArguments = Default::default();
+        args.add(publisher_id);
+        args.add(addr);
+        args.add(publisher_id);
+        args.add(addr);
+
+        let found = sqlx::query_scalar_with::<_, i64, _>(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM publishers
+                    WHERE publisher_id = ? AND address = ?
+                    UNION ALL
+                    SELECT 1 FROM key_history
+                    WHERE publisher_id = ? AND address = ?
+                )
+            "#,
+            args,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(found == 1)
+    }
+
+    /// List the current address of a publisher and the keys
+    /// the publisher has rotated out.
+    pub async fn signing_keys(
+        pool: &SqlitePool,
+        publisher_id: i64,
+    ) -> Result<Vec<Address>> {
+        let mut args: SqliteArguments = Default::default();
+        args.add(publisher_id);
+        args.add(publisher_id);
+
+        let rows = sqlx::query_scalar_with::<_, Vec<u8>, _>(
+            r#"
+                SELECT address FROM publishers
+                WHERE publisher_id = ?
+                UNION ALL
+                SELECT address FROM (
+                    SELECT address FROM key_history
+                    WHERE publisher_id = ?
+                    ORDER BY key_id
+                )
+            "#,
+            args,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|address| {
+                let address: [u8; 20] = address
+                    .as_slice()
+                    .try_into()
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                Ok(address.into())
+            })
+            .collect()
+    }
+
+    /// List the packages a publisher may publish to.
+    ///
+    /// Includes every package in namespaces the publisher owns,
+    /// every package in namespaces where the publisher is an
+    /// unrestricted user and the packages the publisher has
+    /// been granted access to.
+    ///
+    /// Packages with trusted keys are only listed when the
+    /// publisher is one of the trusted keys.
+    ///
+    /// When a namespace is restricted by default users that are
+    /// not administrators are only listed for the packages they
+    /// have been granted access to.
+    pub async fn list_packages(
+        pool: &SqlitePool,
+        publisher: &Address,
+        pager: &Pager,
+    ) -> Result<ResultSet<PublisherPackageRecord>> {
+        let publisher_record =
+            PublisherModel::find_by_address(pool, publisher)
+                .await?
+                .ok_or(Error::NotFound(NotFound::User(*publisher)))?;
+        let publisher_id = publisher_record.publisher_id;
+
+        let mut args: SqliteArguments = Default::default();
+        args.add(publisher_id);
+        args.add(publisher_id);
+        args.add(publisher_id);
+        args.add(publisher_id);
+        args.add(publisher_id);
+        args.add(pager.limit);
+        args.add(pager.offset);
+
+        let sql = format!(
+            r#"
+            SELECT
+                COUNT(*) OVER () as count,
+                namespace,
+                package,
+                permission
+            FROM (
+                SELECT
+                    packages.package_id,
+                    namespaces.name as namespace,
+                    packages.name as package,
+                    'owner' as permission
+                FROM packages
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                WHERE namespaces.publisher_id = ?
+
+                UNION ALL
+
+                SELECT
+                    packages.package_id,
+                    namespaces.name as namespace,
+                    packages.name as package,
+                    CASE WHEN namespace_publishers.administrator = 1
+                        THEN 'administrator'
+                        ELSE 'publisher'
+                    END as permission
+                FROM packages
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                INNER JOIN namespace_publishers
+                    ON (namespaces.namespace_id = namespace_publishers.namespace_id)
+                WHERE namespace_publishers.publisher_id = ?
+                AND NOT EXISTS (
+                    SELECT 1 FROM publisher_restrictions
+                    WHERE publisher_restrictions.publisher_id = ?
+                )
+                AND (namespace_publishers.administrator = 1
+                    OR namespaces.default_restricted = 0)
+
+                UNION ALL
+
+                SELECT
+                    packages.package_id,
+                    namespaces.name as namespace,
+                    packages.name as package,
+                    'restricted' as permission
+                FROM publisher_restrictions
+                INNER JOIN packages
+                    ON (publisher_restrictions.package_id = packages.package_id)
+                INNER JOIN namespaces
+                    ON (packages.namespace_id = namespaces.namespace_id)
+                INNER JOIN namespace_publishers
+                    ON (namespaces.namespace_id = namespace_publishers.namespace_id
+                        AND publisher_restrictions.publisher_id = namespace_publishers.publisher_id)
+                WHERE publisher_restrictions.publisher_id = ?
+            ) AS permissions
+            WHERE NOT EXISTS (
+                SELECT 1 FROM package_trusted_keys
+                WHERE package_trusted_keys.package_id = permissions.package_id
+            )
+            OR EXISTS (
+                SELECT 1 FROM package_trusted_keys
+                WHERE package_trusted_keys.package_id = permissions.package_id
+                AND package_trusted_keys.publisher_id = ?
+            )
+            ORDER BY namespace {}, package {}
+            LIMIT ? OFFSET ?"#,
+            pager.sort, pager.sort,
+        );
+
+        let records =
+            sqlx::query_as_with::<_, PublisherPackageRecord, _>(&sql, args)
+                .fetch_all(pool)
+                .await?;
+
+        Ok(records.into_result_set())
+    }
 }
@@ -3,23 +3,46 @@ use semver::{BuildMetadata, Prerelease, Version};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::{base64::Base64, serde_as, DisplayFromStr};
-use time::{format_description, OffsetDateTime, PrimitiveDateTime};
+use time::{
+    format_description::{self, well_known::Rfc3339},
+    OffsetDateTime, PrimitiveDateTime, UtcOffset,
+};
 use web3_address::ethereum::Address;
 
 use cid::Cid;
-use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_core::{
+    Namespace, PackageName, RegistryKind, SignatureScheme, StoredObject,
+};
 
 use sqlx::{sqlite::SqliteRow, FromRow, Row};
+use std::str::FromStr;
 
-use crate::Result;
+use crate::{Cursor, Error, Pager, Result};
 
+/// Parse a date and time stored in the database.
+///
+/// Timestamps written before millisecond precision was
+/// introduced do not include the `T` separator, sub-second
+/// digits or offset and are assumed to be UTC.
 pub(crate) fn parse_date_time(date_time: &str) -> Result<OffsetDateTime> {
+    if let Ok(date_time) = OffsetDateTime::parse(date_time, &Rfc3339) {
+        return Ok(date_time.to_offset(UtcOffset::UTC));
+    }
     let format = format_description::parse(
         "[year]-[month]-[day] [hour]:[minute]:[second]",
     )?;
     Ok(PrimitiveDateTime::parse(date_time, &format)?.assume_utc())
 }
 
+/// Format a date and time for comparison with stored timestamps.
+pub(crate) fn format_date_time(date_time: &OffsetDateTime) -> Result<String> {
+    let format = format_description::parse(
+        "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z",
+    )?;
+    let date_time = date_time.to_offset(UtcOffset::UTC);
+    Ok(date_time.format(&format)?)
+}
+
 /// Collection of records with associated total row count.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResultSet<T> {
@@ -27,6 +50,12 @@ pub struct ResultSet<T> {
     pub records: Vec<T>,
     /// The total number of available records.
     pub count: i64,
+    /// Cursor for the page after these records.
+    ///
+    /// Only available when the page is full, the next page
+    /// may be empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl<T> Default for ResultSet<T> {
@@ -34,6 +63,7 @@ impl<T> Default for ResultSet<T> {
         Self {
             records: Vec::new(),
             count: 0,
+            next_cursor: None,
         }
     }
 }
@@ -53,6 +83,20 @@ impl<T> ResultSet<T> {
     pub fn is_zero(&self) -> bool {
         self.is_empty() && self.count == 0
     }
+
+    /// Assign the cursor for the next page using the last record
+    /// when the page is full.
+    pub(crate) fn with_next_cursor(
+        mut self,
+        pager: &Pager,
+        cursor: impl Fn(&T) -> Cursor,
+    ) -> Self {
+        if self.records.len() as i64 == pager.limit {
+            self.next_cursor =
+                self.records.last().map(|record| cursor(record).encode());
+        }
+        self
+    }
 }
 
 /// Convert into a result set.
@@ -71,6 +115,7 @@ impl IntoResultSet<Vec<PackageRecord>, PackageRecord> for Vec<PackageRecord> {
         ResultSet {
             records: self,
             count,
+            next_cursor: None,
         }
     }
 }
@@ -85,12 +130,79 @@ impl IntoResultSet<Vec<VersionRecord>, VersionRecord> for Vec<VersionRecord> {
         ResultSet {
             records: self,
             count,
+            next_cursor: None,
+        }
+    }
+}
+
+impl IntoResultSet<Vec<AuditRecord>, AuditRecord> for Vec<AuditRecord> {
+    fn into_result_set(self) -> ResultSet<AuditRecord> {
+        let count = if self.is_empty() {
+            0
+        } else {
+            self.first().unwrap().count
+        };
+        ResultSet {
+            records: self,
+            count,
+            next_cursor: None,
+        }
+    }
+}
+
+impl IntoResultSet<Vec<RotatedSignatureRecord>, RotatedSignatureRecord>
+    for Vec<RotatedSignatureRecord>
+{
+    fn into_result_set(self) -> ResultSet<RotatedSignatureRecord> {
+        let count = if self.is_empty() {
+            0
+        } else {
+            self.first().unwrap().count
+        };
+        ResultSet {
+            records: self,
+            count,
+            next_cursor: None,
+        }
+    }
+}
+
+impl IntoResultSet<Vec<PublisherPackageRecord>, PublisherPackageRecord>
+    for Vec<PublisherPackageRecord>
+{
+    fn into_result_set(self) -> ResultSet<PublisherPackageRecord> {
+        let count = if self.is_empty() {
+            0
+        } else {
+            self.first().unwrap().count
+        };
+        ResultSet {
+            records: self,
+            count,
+            next_cursor: None,
+        }
+    }
+}
+
+impl IntoResultSet<Vec<DependentRecord>, DependentRecord>
+    for Vec<DependentRecord>
+{
+    fn into_result_set(self) -> ResultSet<DependentRecord> {
+        let count = if self.is_empty() {
+            0
+        } else {
+            self.first().unwrap().count
+        };
+        ResultSet {
+            records: self,
+            count,
+            next_cursor: None,
         }
     }
 }
 
 /// Record for a single publisher.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublisherRecord {
     /// Publisher primary key.
     #[serde(skip)]
@@ -147,8 +259,166 @@ impl FromRow<'_, SqliteRow> for AccessRestriction {
     }
 }
 
-/// User that has access to a namespace.
+/// Effective permission a publisher has for a package.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublishPermission {
+    /// Publisher owns the namespace.
+    Owner,
+    /// Publisher administrates the namespace.
+    Administrator,
+    /// Publisher may publish to any package in the namespace.
+    Publisher,
+    /// Publisher was granted access to the package.
+    Restricted,
+}
+
+impl FromStr for PublishPermission {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(Self::Owner),
+            "administrator" => Ok(Self::Administrator),
+            "publisher" => Ok(Self::Publisher),
+            "restricted" => Ok(Self::Restricted),
+            _ => Err(Error::UnknownPermission(s.to_owned())),
+        }
+    }
+}
+
+/// Reason publishing to a package is denied.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PublishDenied {
+    /// Address is not the owner or a user of the namespace.
+    NotMember,
+    /// User is restricted and has not been granted
+    /// access to the package.
+    NotGranted,
+    /// Package has trusted keys that do not include the address.
+    UntrustedKey,
+    /// Version already exists.
+    VersionExists,
+    /// Version was yanked and cannot be published again.
+    VersionYanked,
+    /// Version is not ahead of the latest version.
+    VersionNotAhead,
+}
+
+/// Outcome of checking whether an address can publish a package.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PublishCheck {
+    /// Whether publishing is allowed.
+    pub allowed: bool,
+    /// Effective permission when publishing is allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission: Option<PublishPermission>,
+    /// Reason when publishing is denied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<PublishDenied>,
+}
+
+/// Package that a publisher may publish to.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct PublisherPackageRecord {
+    /// Namespace for the package.
+    pub namespace: Namespace,
+    /// Name of the package.
+    pub package: PackageName,
+    /// Effective permission for the package.
+    pub permission: PublishPermission,
+    /// Count of total rows.
+    #[serde(skip)]
+    pub count: i64,
+}
+
+impl FromRow<'_, SqliteRow> for PublisherPackageRecord {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let namespace: String = row.try_get("namespace")?;
+        let package: String = row.try_get("package")?;
+        let permission: String = row.try_get("permission")?;
+
+        let namespace: Namespace = namespace
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let package: PackageName = package
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let permission: PublishPermission = permission
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let count = row.try_get::<i64, _>("count").unwrap_or_default();
+
+        Ok(Self {
+            namespace,
+            package,
+            permission,
+            count,
+        })
+    }
+}
+
+/// Version of a package that declares a dependency on another package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependentRecord {
+    /// Namespace of the dependent package.
+    pub namespace: Namespace,
+    /// Name of the dependent package.
+    pub package: PackageName,
+    /// Version of the dependent package.
+    pub version: Version,
+    /// Version range required for the dependency.
+    pub range: String,
+    /// Count of total rows.
+    #[serde(skip)]
+    pub count: i64,
+}
+
+impl FromRow<'_, SqliteRow> for DependentRecord {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let namespace: String = row.try_get("namespace")?;
+        let package: String = row.try_get("package")?;
+        let major: i64 = row.try_get("major")?;
+        let minor: i64 = row.try_get("minor")?;
+        let patch: i64 = row.try_get("patch")?;
+        let pre: Option<String> = row.try_get("pre")?;
+        let build: Option<String> = row.try_get("build")?;
+        let range: String = row.try_get("requirement")?;
+
+        let namespace: Namespace = namespace
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let package: PackageName = package
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let mut version =
+            Version::new(major as u64, minor as u64, patch as u64);
+        if let Some(pre) = &pre {
+            version.pre = Prerelease::new(pre)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        }
+        if let Some(build) = &build {
+            version.build = BuildMetadata::new(build)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        }
+
+        let count = row.try_get::<i64, _>("count").unwrap_or_default();
+
+        Ok(Self {
+            namespace,
+            package,
+            version,
+            range,
+            count,
+        })
+    }
+}
+
+/// User that has access to a namespace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserRecord {
     /// Namespace foreign key.
     #[serde(skip)]
@@ -207,18 +477,44 @@ impl FromRow<'_, SqliteRow> for UserRecord {
 }
 
 /// Record for a single namespace.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NamespaceRecord {
     /// Namespace primary key.
     #[serde(skip)]
     pub namespace_id: i64,
     /// Name for the namespace.
     pub name: Namespace,
+    /// Description of the namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// Owner of the namespace.
     pub owner: Address,
+    /// Registry kind for packages in the namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<RegistryKind>,
+    /// Whether downloads must be signed by a user of the namespace.
+    #[serde(default)]
+    pub private: bool,
+    /// Whether users without package restrictions may not publish
+    /// to any package until access is granted.
+    #[serde(default)]
+    pub default_restricted: bool,
+    /// Deprecation notice for the namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
     /// Additional publishers.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub publishers: Vec<UserRecord>,
+    /// Number of packages in the namespace.
+    ///
+    /// Only included when fetching a single namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package_count: Option<i64>,
+    /// Number of versions of all packages in the namespace.
+    ///
+    /// Only included when fetching a single namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_count: Option<i64>,
     /// Creation date and time.
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
@@ -229,12 +525,26 @@ impl FromRow<'_, SqliteRow> for NamespaceRecord {
         let namespace_id: i64 = row.try_get("namespace_id")?;
         //let publisher_id: i64 = row.try_get("publisher_id")?;
         let name: String = row.try_get("name")?;
+        let description: Option<String> = row.try_get("description")?;
         let address: Vec<u8> = row.try_get("address")?;
+        let kind: Option<String> = row.try_get("kind")?;
+        let private: bool = row.try_get("private")?;
+        let default_restricted: bool = row.try_get("default_restricted")?;
+        let deprecated: Option<String> = row.try_get("deprecated")?;
         let created_at: String = row.try_get("created_at")?;
 
         let name: Namespace =
             name.parse().map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
 
+        let kind = if let Some(kind) = kind {
+            Some(
+                kind.parse::<RegistryKind>()
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            )
+        } else {
+            None
+        };
+
         let address: [u8; 20] = address
             .as_slice()
             .try_into()
@@ -248,7 +558,14 @@ impl FromRow<'_, SqliteRow> for NamespaceRecord {
             namespace_id,
             publishers: Default::default(),
             name,
+            description,
             owner: address,
+            kind,
+            private,
+            default_restricted,
+            deprecated,
+            package_count: None,
+            version_count: None,
             created_at,
         })
     }
@@ -293,11 +610,17 @@ pub struct PackageRecord {
     /// Namespace foreign key.
     #[serde(skip)]
     pub namespace_id: i64,
+    /// Name of the namespace when listed across namespaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<Namespace>,
     /// Package primary key.
     #[serde(skip)]
     pub package_id: i64,
     /// Name of the package.
     pub name: PackageName,
+    /// Description of the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// Message if the package is deprecated.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<String>,
@@ -317,6 +640,7 @@ impl FromRow<'_, SqliteRow> for PackageRecord {
         let namespace_id: i64 = row.try_get("namespace_id")?;
         let package_id: i64 = row.try_get("package_id")?;
         let name: String = row.try_get("name")?;
+        let description: Option<String> = row.try_get("description")?;
         let deprecated: Option<String> = row.try_get("deprecated")?;
         let created_at: String = row.try_get("created_at")?;
 
@@ -326,21 +650,29 @@ impl FromRow<'_, SqliteRow> for PackageRecord {
         let created_at = parse_date_time(&created_at)
             .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
 
-        let count = if let Ok(count) = row.try_get::<i64, _>("count") {
-            count
-        } else {
-            0
-        };
+        let namespace = row
+            .try_get::<Option<String>, _>("namespace")
+            .unwrap_or_default()
+            .map(|namespace| namespace.parse())
+            .transpose()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let count = row.try_get::<i64, _>("count").unwrap_or_default();
+        let version_count =
+            row.try_get::<i64, _>("version_count").unwrap_or_default();
 
         Ok(Self {
             namespace_id,
+            namespace,
             package_id,
             name,
+            description,
             deprecated,
             created_at,
             versions: ResultSet::<VersionRecord> {
                 records: vec![],
-                count: 0,
+                count: version_count,
+                next_cursor: None,
             },
             count,
         })
@@ -348,6 +680,9 @@ impl FromRow<'_, SqliteRow> for PackageRecord {
 }
 
 /// Record for a single package version.
+///
+/// Published content is immutable, only the yank and
+/// purge fields may change after insertion.
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VersionRecord {
@@ -374,6 +709,9 @@ pub struct VersionRecord {
     /// Package archive signature.
     #[serde_as(as = "Base64")]
     pub signature: [u8; 65],
+    /// Scheme used to create the package archive signature.
+    #[serde(default)]
+    pub signature_scheme: SignatureScheme,
     /// Package archive checksum.
     #[serde(
         serialize_with = "hex::serde::serialize",
@@ -384,37 +722,112 @@ pub struct VersionRecord {
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
 
+    /// Registry kind of the package archive.
+    ///
+    /// Versions published before the kind was recorded
+    /// do not have a kind.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<RegistryKind>,
+
+    /// Size of the package archive in bytes.
+    ///
+    /// Versions published before the size was recorded
+    /// do not have a size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+
     /// Yanked message.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub yanked: Option<String>,
 
+    /// Number of times the artifact was downloaded.
+    #[serde(default)]
+    pub downloads: i64,
+
+    /// Deprecation message of the package.
+    ///
+    /// Only selected when resolving, listing or fetching
+    /// an exact version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+
+    /// Date and time the artifact was removed from storage.
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub purged_at: Option<OffsetDateTime>,
+
+    /// Object keys for the artifact in each storage layer.
+    ///
+    /// Versions published before the keys were recorded
+    /// have no objects.
+    #[serde(skip)]
+    pub objects: Vec<StoredObject>,
+
+    /// Addresses that may have signed the artifact, the current
+    /// address of the publisher and the keys the publisher has
+    /// rotated out.
+    ///
+    /// Only selected when fetching an exact version.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signers: Vec<Address>,
+
     /// Count of total rows.
     #[serde(skip)]
     pub count: i64,
 }
 
-impl FromRow<'_, SqliteRow> for VersionRecord {
-    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
-        let publisher_id: i64 = row.try_get("publisher_id")?;
-        let version_id: i64 = row.try_get("version_id")?;
-        let package_id: i64 = row.try_get("package_id")?;
+impl VersionRecord {
+    /// Decode a version record from columns with a name prefix.
+    ///
+    /// Allows a version to be selected alongside another record
+    /// that shares column names.
+    pub(crate) fn from_row_prefix(
+        row: &SqliteRow,
+        prefix: &str,
+    ) -> sqlx::Result<Self> {
+        let column = |name: &str| format!("{}{}", prefix, name);
 
-        let major: i64 = row.try_get("major")?;
-        let minor: i64 = row.try_get("minor")?;
-        let patch: i64 = row.try_get("patch")?;
+        let publisher_id: i64 =
+            row.try_get(column("publisher_id").as_str())?;
+        let version_id: i64 = row.try_get(column("version_id").as_str())?;
+        let package_id: i64 = row.try_get(column("package_id").as_str())?;
 
-        let pre: Option<String> = row.try_get("pre")?;
-        let build: Option<String> = row.try_get("build")?;
+        let major: i64 = row.try_get(column("major").as_str())?;
+        let minor: i64 = row.try_get(column("minor").as_str())?;
+        let patch: i64 = row.try_get(column("patch").as_str())?;
 
-        let content_id: Option<String> = row.try_get("content_id")?;
-        let pointer_id: String = row.try_get("pointer_id")?;
+        let pre: Option<String> = row.try_get(column("pre").as_str())?;
+        let build: Option<String> = row.try_get(column("build").as_str())?;
 
-        let signature: Vec<u8> = row.try_get("signature")?;
-        let checksum: Vec<u8> = row.try_get("checksum")?;
+        let content_id: Option<String> =
+            row.try_get(column("content_id").as_str())?;
+        let pointer_id: String =
+            row.try_get(column("pointer_id").as_str())?;
 
-        let created_at: String = row.try_get("created_at")?;
+        let signature: Vec<u8> = row.try_get(column("signature").as_str())?;
+        let signature_scheme: String =
+            row.try_get(column("signature_scheme").as_str())?;
+        let checksum: Vec<u8> = row.try_get(column("checksum").as_str())?;
+
+        let created_at: String =
+            row.try_get(column("created_at").as_str())?;
 
-        let yanked: Option<String> = row.try_get("yanked")?;
+        let kind: Option<String> = row.try_get(column("kind").as_str())?;
+        let size: Option<i64> =
+            row.try_get(column("size").as_str()).unwrap_or_default();
+        let downloads: i64 = row
+            .try_get(column("downloads").as_str())
+            .unwrap_or_default();
+        let yanked: Option<String> =
+            row.try_get(column("yanked").as_str())?;
+        let purged_at: Option<String> =
+            row.try_get(column("purged_at").as_str())?;
+        let deprecated: Option<String> = row
+            .try_get(column("deprecated").as_str())
+            .unwrap_or_default();
 
         let mut version =
             Version::new(major as u64, minor as u64, patch as u64);
@@ -427,7 +840,8 @@ impl FromRow<'_, SqliteRow> for VersionRecord {
                 .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
         }
 
-        let package = if let Ok(package) = row.try_get::<String, _>("package")
+        let package = if let Ok(package) =
+            row.try_get::<String, _>(column("package").as_str())
         {
             let package: Value = serde_json::from_str(&package)
                 .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
@@ -444,10 +858,22 @@ impl FromRow<'_, SqliteRow> for VersionRecord {
             None
         };
 
+        let objects = if let Ok(objects) =
+            row.try_get::<String, _>(column("objects").as_str())
+        {
+            serde_json::from_str(&objects)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
+        } else {
+            Vec::new()
+        };
+
         let signature: [u8; 65] = signature
             .as_slice()
             .try_into()
             .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let signature_scheme: SignatureScheme = signature_scheme
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
         let checksum: [u8; 32] = checksum
             .as_slice()
             .try_into()
@@ -456,12 +882,28 @@ impl FromRow<'_, SqliteRow> for VersionRecord {
         let created_at = parse_date_time(&created_at)
             .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
 
-        let count = if let Ok(count) = row.try_get::<i64, _>("count") {
-            count
+        let purged_at = if let Some(purged_at) = purged_at {
+            Some(
+                parse_date_time(&purged_at)
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            )
         } else {
-            0
+            None
+        };
+
+        let kind = if let Some(kind) = kind {
+            Some(
+                kind.parse::<RegistryKind>()
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            )
+        } else {
+            None
         };
 
+        let count = row
+            .try_get::<i64, _>(column("count").as_str())
+            .unwrap_or_default();
+
         Ok(Self {
             publisher_id,
             version_id,
@@ -471,10 +913,341 @@ impl FromRow<'_, SqliteRow> for VersionRecord {
             version,
             package,
             signature,
+            signature_scheme,
             checksum,
             created_at,
+            kind,
+            size: size.map(|size| size as u64),
             yanked,
+            downloads,
+            deprecated,
+            purged_at,
+            objects,
+            signers: Vec::new(),
             count,
         })
     }
 }
+
+impl FromRow<'_, SqliteRow> for VersionRecord {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        VersionRecord::from_row_prefix(row, "")
+    }
+}
+
+/// Storage location of the artifact for a version.
+#[derive(Debug, Clone)]
+pub struct VersionLocation {
+    /// Version primary key.
+    pub version_id: i64,
+    /// Pointer identifier for the artifact.
+    pub pointer_id: String,
+    /// Object keys for the artifact in each storage layer.
+    pub objects: Vec<StoredObject>,
+}
+
+/// Signers that authorize transferring a package
+/// to another namespace.
+#[derive(Debug, Clone, Copy)]
+pub enum TransferSigners {
+    /// Owners of the source and target namespaces.
+    Owners {
+        /// Owner of the source namespace.
+        from: Address,
+        /// Owner of the target namespace.
+        to: Address,
+    },
+    /// Registry administrator acting for both namespaces.
+    Administrator(Address),
+}
+
+impl TransferSigners {
+    /// Address recorded as the caller of the transfer.
+    pub fn caller(&self) -> &Address {
+        match self {
+            Self::Owners { from, .. } => from,
+            Self::Administrator(address) => address,
+        }
+    }
+}
+
+/// Names that share the same confusable skeleton.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkeletonCollision {
+    /// The shared skeleton.
+    pub skeleton: String,
+    /// Names that map to the skeleton; package names
+    /// are prefixed with the namespace.
+    pub names: Vec<String>,
+}
+
+/// Report generated when recomputing skeletons.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReindexReport {
+    /// Number of namespace skeletons that were updated.
+    pub namespaces: usize,
+    /// Number of package skeletons that were updated.
+    pub packages: usize,
+    /// Newly detected skeleton collisions.
+    pub collisions: Vec<SkeletonCollision>,
+}
+
+/// Number of records in the registry.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RegistryCounts {
+    /// Number of publishers.
+    pub publishers: i64,
+    /// Number of namespaces.
+    pub namespaces: i64,
+    /// Number of packages.
+    pub packages: i64,
+    /// Number of versions.
+    pub versions: i64,
+}
+
+/// Action taken to resolve a namespace collision.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NamespaceAction {
+    /// Rename the namespace.
+    Rename(Namespace),
+    /// Move the packages into another namespace and
+    /// remove the namespace.
+    Merge(Namespace),
+}
+
+/// Request to resolve a namespace collision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveNamespace {
+    /// Namespace to resolve; the name must match exactly
+    /// as colliding names share a skeleton.
+    pub namespace: Namespace,
+    /// Action to take.
+    pub action: NamespaceAction,
+    /// Report the changes without applying them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Report generated when resolving a namespace collision.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveReport {
+    /// Namespace that was resolved.
+    pub namespace: Namespace,
+    /// Action that was taken.
+    pub action: NamespaceAction,
+    /// Packages in the namespace that were renamed or moved.
+    pub packages: Vec<PackageName>,
+    /// Whether the changes were discarded.
+    pub dry_run: bool,
+}
+
+/// Package that does not have any versions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmptyPackage {
+    /// Package primary key.
+    #[serde(skip)]
+    pub package_id: i64,
+    /// Namespace of the package.
+    pub namespace: Namespace,
+    /// Name of the package.
+    pub name: PackageName,
+    /// Creation date and time.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Report generated when removing packages without versions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CleanupReport {
+    /// Packages that were removed.
+    pub packages: Vec<EmptyPackage>,
+    /// Whether the changes were discarded.
+    pub dry_run: bool,
+}
+
+/// Filters for listing entries in the audit log.
+#[derive(Debug, Default, Clone)]
+pub struct AuditFilter {
+    /// Only include entries for this action.
+    pub action: Option<String>,
+    /// Only include entries affecting this namespace.
+    pub namespace: Option<Namespace>,
+    /// Only include entries for actions taken by this address.
+    pub address: Option<Address>,
+    /// Only include entries recorded at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Only include entries recorded before this time.
+    pub until: Option<OffsetDateTime>,
+}
+
+/// Record of an action in the audit log.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Audit log primary key.
+    #[serde(skip)]
+    pub audit_id: i64,
+    /// Address of the publisher that took the action.
+    pub address: Address,
+    /// Name of the action.
+    pub action: String,
+    /// Namespace affected by the action.
+    pub namespace: Option<Namespace>,
+    /// Details of the action.
+    pub detail: Value,
+    /// Date and time the action was taken.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    /// Count of total rows.
+    #[serde(skip)]
+    pub count: i64,
+}
+
+impl FromRow<'_, SqliteRow> for AuditRecord {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let audit_id: i64 = row.try_get("audit_id")?;
+        let address: Vec<u8> = row.try_get("address")?;
+        let action: String = row.try_get("action")?;
+        let namespace: Option<String> = row.try_get("namespace")?;
+        let detail: String = row.try_get("detail")?;
+        let created_at: String = row.try_get("created_at")?;
+        let count = row.try_get::<i64, _>("count").unwrap_or_default();
+
+        let address: [u8; 20] = address
+            .as_slice()
+            .try_into()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let address: Address = address.into();
+
+        // Names are recorded as given which includes colliding
+        // names that would not pass validation
+        let namespace: Option<Namespace> =
+            namespace.map(|namespace| Namespace::new_unchecked(&namespace));
+
+        let detail: Value = serde_json::from_str(&detail)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let created_at = parse_date_time(&created_at)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        Ok(Self {
+            audit_id,
+            address,
+            action,
+            namespace,
+            detail,
+            created_at,
+            count,
+        })
+    }
+}
+
+/// Request to rotate the key of a publisher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotateKey {
+    /// Current address of the publisher.
+    pub from: Address,
+    /// New address for the publisher.
+    pub to: Address,
+}
+
+/// Version whose signature was created by a key the
+/// publisher has since rotated out.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RotatedSignatureRecord {
+    /// Namespace of the package.
+    pub namespace: Namespace,
+    /// Name of the package.
+    pub package: PackageName,
+    /// Version of the package.
+    pub version: Version,
+    /// Current address of the publisher.
+    pub publisher: Address,
+    /// Address of the rotated key that signed the version.
+    pub address: Address,
+    /// Date and time the key was rotated.
+    #[serde(with = "time::serde::rfc3339")]
+    pub rotated_at: OffsetDateTime,
+    /// Count of total rows.
+    #[serde(skip)]
+    pub count: i64,
+}
+
+impl FromRow<'_, SqliteRow> for RotatedSignatureRecord {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let namespace: String = row.try_get("namespace")?;
+        let package: String = row.try_get("package")?;
+        let major: i64 = row.try_get("major")?;
+        let minor: i64 = row.try_get("minor")?;
+        let patch: i64 = row.try_get("patch")?;
+        let pre: Option<String> = row.try_get("pre")?;
+        let build: Option<String> = row.try_get("build")?;
+        let publisher: Vec<u8> = row.try_get("publisher")?;
+        let address: Vec<u8> = row.try_get("address")?;
+        let rotated_at: String = row.try_get("rotated_at")?;
+        let count = row.try_get::<i64, _>("count").unwrap_or_default();
+
+        let namespace: Namespace = namespace
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let package: PackageName = package
+            .parse()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let mut version =
+            Version::new(major as u64, minor as u64, patch as u64);
+        if let Some(pre) = &pre {
+            version.pre = Prerelease::new(pre)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        }
+        if let Some(build) = &build {
+            version.build = BuildMetadata::new(build)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        }
+
+        let publisher: [u8; 20] = publisher
+            .as_slice()
+            .try_into()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let address: [u8; 20] = address
+            .as_slice()
+            .try_into()
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        let rotated_at = parse_date_time(&rotated_at)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        Ok(Self {
+            namespace,
+            package,
+            version,
+            publisher: publisher.into(),
+            address: address.into(),
+            rotated_at,
+            count,
+        })
+    }
+}
+
+/// Access granted to a user of a namespace.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct UserAccess {
+    /// Address of the user.
+    pub address: Address,
+    /// User is allowed to administrate the namespace.
+    pub administrator: bool,
+    /// Packages the user is restricted to, when empty
+    /// the user may publish to any package.
+    pub restrictions: Vec<PackageName>,
+}
+
+/// Access control view for a namespace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceAccess {
+    /// Name of the namespace.
+    pub name: Namespace,
+    /// Owner of the namespace.
+    pub owner: Address,
+    /// Users that have access to the namespace.
+    pub users: Vec<UserAccess>,
+}
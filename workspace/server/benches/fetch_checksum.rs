@@ -0,0 +1,82 @@
+//! Compare verifying the checksum of a fetched artifact in a
+//! single pass while reading from the storage layer against
+//! digesting the artifact after it has been read.
+//!
+//! Run with `cargo bench -p ipfs-registry-server`.
+use axum::body::Bytes;
+use indexmap::IndexSet;
+use semver::Version;
+use sha3::{Digest, Sha3_256};
+use std::time::{Duration, Instant};
+
+use ipfs_registry_core::{Artifact, Namespace, PackageMeta, PackageName};
+use ipfs_registry_server::{
+    build_layers,
+    config::{LayerConfig, ServerConfig, StorageConfig},
+};
+
+/// Size of the artifact to fetch.
+const ARTIFACT_SIZE: usize = 64 * 1024 * 1024;
+
+/// Number of fetches for each measurement.
+const ITERATIONS: u32 = 20;
+
+fn report(name: &str, elapsed: Duration) {
+    let per_fetch = elapsed / ITERATIONS;
+    let throughput =
+        ARTIFACT_SIZE as f64 / (1024.0 * 1024.0) / per_fetch.as_secs_f64();
+    println!(
+        "{:<12} {:>10.2?}/fetch {:>10.1} MiB/s",
+        name, per_fetch, throughput
+    );
+}
+
+#[tokio::main]
+async fn main() -> ipfs_registry_server::Result<()> {
+    let directory = std::env::temp_dir().join("ipfs-registry-bench");
+    std::fs::create_dir_all(&directory)?;
+
+    let mut layers = IndexSet::new();
+    layers.insert(LayerConfig::File {
+        directory: directory.clone(),
+    });
+    let config = ServerConfig::new(StorageConfig {
+        layers,
+        write_quorum: None,
+    });
+    let layers = build_layers(&config)?;
+
+    let artifact = Artifact {
+        kind: Default::default(),
+        namespace: Namespace::new_unchecked("bench-namespace"),
+        package: PackageMeta {
+            name: PackageName::new_unchecked("bench-package"),
+            version: Version::new(1, 0, 0),
+        },
+    };
+    let data: Vec<u8> = (0..ARTIFACT_SIZE).map(|i| i as u8).collect();
+    let checksum = Sha3_256::digest(&data).to_vec();
+    let _ = layers.publish(Bytes::from(data), &artifact).await?;
+
+    let pointer_id = artifact.pointer_id();
+
+    // Read the artifact then digest the buffer
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        let body = layers.fetch(&pointer_id, None, &[], None).await?;
+        assert_eq!(Sha3_256::digest(&body).as_slice(), checksum.as_slice());
+    }
+    report("two-pass", started.elapsed());
+
+    // Digest each chunk as the artifact is read
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        layers
+            .fetch(&pointer_id, None, &[], Some(&checksum))
+            .await?;
+    }
+    report("single-pass", started.elapsed());
+
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use std::{env, process::Command};
+
+fn main() {
+    // Record the compiler version for the server information
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    if let Ok(output) = Command::new(rustc).arg("--version").output() {
+        if output.status.success() {
+            let version = String::from_utf8_lossy(&output.stdout);
+            println!("cargo:rustc-env=IPKG_RUSTC_VERSION={}", version.trim());
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
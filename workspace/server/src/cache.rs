@@ -0,0 +1,304 @@
+//! Short lived cache of publisher and namespace records.
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use sqlx::SqlitePool;
+use web3_address::ethereum::Address;
+
+use ipfs_registry_core::Namespace;
+use ipfs_registry_database::{
+    Error as DatabaseError, NamespaceModel, NamespaceRecord, NotFound,
+    PublisherModel, PublisherRecord,
+};
+
+type DatabaseResult<T> = std::result::Result<T, DatabaseError>;
+
+/// Cached records with the time they were stored.
+struct Entries<K, V> {
+    records: HashMap<K, (Instant, V)>,
+}
+
+impl<K: Eq + Hash, V: Clone> Entries<K, V> {
+    fn get(&mut self, key: &K, ttl: Duration, now: Instant) -> Option<V> {
+        match self.records.get(key) {
+            Some((stored, value)) if now < *stored + ttl => {
+                Some(value.clone())
+            }
+            Some(_) => {
+                self.records.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, key: K, value: V, ttl: Duration, now: Instant) {
+        if !self.records.contains_key(&key) {
+            self.records.retain(|_, (stored, _)| now < *stored + ttl);
+        }
+        self.records.insert(key, (now, value));
+    }
+}
+
+impl<K, V> Default for Entries<K, V> {
+    fn default() -> Self {
+        Self {
+            records: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Incremented on every invalidation so lookups that
+    /// started before a mutation do not store stale records.
+    generation: u64,
+    publishers: Entries<Address, PublisherRecord>,
+    /// Namespaces keyed by confusable skeleton.
+    namespaces: Entries<String, NamespaceRecord>,
+}
+
+/// Caches publisher and namespace records used when publishing
+/// to reduce the number of database queries.
+///
+/// Namespace records carry the access control lists so they
+/// must be invalidated whenever the users or restrictions
+/// for a namespace change.
+pub(crate) struct RecordCache {
+    ttl: Duration,
+    inner: Mutex<Inner>,
+    queries: AtomicU64,
+}
+
+impl RecordCache {
+    /// Create a new cache where records expire after `ttl` seconds.
+    pub(crate) fn new(ttl: u64) -> Self {
+        Self {
+            ttl: Duration::from_secs(ttl),
+            inner: Mutex::new(Default::default()),
+            queries: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of database queries made by the cache.
+    #[cfg(test)]
+    pub(crate) fn queries(&self) -> u64 {
+        self.queries.load(Ordering::Relaxed)
+    }
+
+    /// Find a publisher by address.
+    pub(crate) async fn find_publisher(
+        &self,
+        pool: &SqlitePool,
+        address: &Address,
+    ) -> DatabaseResult<Option<PublisherRecord>> {
+        let generation = {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(record) =
+                inner.publishers.get(address, self.ttl, Instant::now())
+            {
+                return Ok(Some(record));
+            }
+            inner.generation
+        };
+
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        let record = PublisherModel::find_by_address(pool, address).await?;
+
+        if let Some(record) = &record {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.generation == generation {
+                inner.publishers.insert(
+                    *address,
+                    record.clone(),
+                    self.ttl,
+                    Instant::now(),
+                );
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Find a namespace by name.
+    pub(crate) async fn find_namespace(
+        &self,
+        pool: &SqlitePool,
+        namespace: &Namespace,
+    ) -> DatabaseResult<Option<NamespaceRecord>> {
+        let skeleton = namespace.skeleton();
+        let generation = {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(record) =
+                inner.namespaces.get(&skeleton, self.ttl, Instant::now())
+            {
+                return Ok(Some(record));
+            }
+            inner.generation
+        };
+
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        let record = NamespaceModel::find_by_name(pool, namespace).await?;
+
+        if let Some(record) = &record {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.generation == generation {
+                inner.namespaces.insert(
+                    skeleton,
+                    record.clone(),
+                    self.ttl,
+                    Instant::now(),
+                );
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Verify an address can access a namespace using cached records.
+    ///
+    /// Performs the same checks as `NamespaceModel::can_access_namespace`.
+    pub(crate) async fn can_access_namespace(
+        &self,
+        pool: &SqlitePool,
+        publisher: &Address,
+        namespace: &Namespace,
+    ) -> DatabaseResult<(PublisherRecord, NamespaceRecord)> {
+        let publisher_record = self
+            .find_publisher(pool, publisher)
+            .await?
+            .ok_or(DatabaseError::NotFound(NotFound::User(*publisher)))?;
+
+        let namespace_record =
+            self.find_namespace(pool, namespace).await?.ok_or_else(|| {
+                DatabaseError::NotFound(NotFound::Namespace(
+                    namespace.clone(),
+                ))
+            })?;
+
+        if !namespace_record.has_user(publisher) {
+            return Err(DatabaseError::Unauthorized(*publisher));
+        }
+
+        Ok((publisher_record, namespace_record))
+    }
+
+    /// Remove a namespace from the cache.
+    pub(crate) fn invalidate_namespace(&self, namespace: &Namespace) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.generation += 1;
+        inner.namespaces.records.remove(&namespace.skeleton());
+    }
+
+    /// Remove every record from the cache.
+    pub(crate) fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.generation += 1;
+        inner.publishers.records.clear();
+        inner.namespaces.records.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    async fn mock_pool() -> Result<SqlitePool> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("../../migrations").run(&pool).await?;
+        Ok(pool)
+    }
+
+    fn mock_address(value: u8) -> Address {
+        [value; 20].into()
+    }
+
+    #[tokio::test]
+    async fn record_cache_queries() -> Result<()> {
+        let pool = mock_pool().await?;
+        let address = mock_address(1);
+        let namespace = Namespace::new_unchecked("mock-namespace");
+        let publisher_id = PublisherModel::insert(&pool, &address).await?;
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+
+        let cache = RecordCache::new(60);
+        for _ in 0..10 {
+            cache
+                .can_access_namespace(&pool, &address, &namespace)
+                .await?;
+        }
+
+        // Without the cache each check makes two queries
+        assert_eq!(2, cache.queries());
+
+        // Invalidation only refreshes the namespace
+        cache.invalidate_namespace(&namespace);
+        cache
+            .can_access_namespace(&pool, &address, &namespace)
+            .await?;
+        assert_eq!(3, cache.queries());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn record_cache_invalidate() -> Result<()> {
+        let pool = mock_pool().await?;
+        let owner = mock_address(1);
+        let user = mock_address(2);
+        let namespace = Namespace::new_unchecked("mock-namespace");
+        let publisher_id = PublisherModel::insert(&pool, &owner).await?;
+        PublisherModel::insert(&pool, &user).await?;
+        NamespaceModel::insert(&pool, &namespace, publisher_id, None).await?;
+        NamespaceModel::add_user(
+            &pool,
+            &namespace,
+            &owner,
+            &user,
+            false,
+            vec![],
+        )
+        .await?;
+
+        let cache = RecordCache::new(60);
+        assert!(cache
+            .can_access_namespace(&pool, &user, &namespace)
+            .await
+            .is_ok());
+
+        NamespaceModel::remove_user(&pool, &namespace, &owner, &user).await?;
+
+        // Stale record is served until invalidated
+        assert!(cache
+            .can_access_namespace(&pool, &user, &namespace)
+            .await
+            .is_ok());
+
+        cache.invalidate_namespace(&namespace);
+        assert!(matches!(
+            cache.can_access_namespace(&pool, &user, &namespace).await,
+            Err(DatabaseError::Unauthorized(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn record_cache_expired() {
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+        let mut entries: Entries<u64, u64> = Default::default();
+        entries.insert(1, 1, ttl, now);
+        assert_eq!(Some(1), entries.get(&1, ttl, now));
+        assert_eq!(None, entries.get(&1, ttl, now + ttl));
+        assert!(entries.records.is_empty());
+    }
+}
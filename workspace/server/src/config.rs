@@ -1,22 +1,33 @@
 //! Configuration types.
+use cid::Cid;
 use indexmap::set::IndexSet;
 use k256::ecdsa::SigningKey;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
     path::{Path, PathBuf},
 };
 use url::Url;
 use web3_address::ethereum::Address;
 use web3_keystore::{decrypt, KeyStore};
 
-use crate::{Error, Result};
-use ipfs_registry_core::RegistryKind;
+use crate::{layer::s3::normalize_prefix, Error, Result};
+use ipfs_registry_core::{
+    to_canonical_vec, Error as CoreError, Namespace, RegistryKind,
+    Result as CoreResult, MAX_DIFFICULTY,
+};
+use ipfs_registry_database::{DuplicateCid, SortOrder};
+use semver::Version;
 
 const KEYSTORE_PASSWORD_ENV: &str = "IPKG_WEBHOOK_KEYSTORE_PASSWORD";
 
+/// Minimum length in bytes of the secret for signed URLs.
+const MIN_SIGNED_URL_SECRET: usize = 32;
+
 /// Configuration for the server.
 #[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct ServerConfig {
     /// Configuration for the database.
     #[serde(default)]
@@ -39,6 +50,23 @@ pub struct ServerConfig {
     /// Configuration for CORS.
     pub cors: Option<CorsConfig>,
 
+    /// Configuration for the canonical host.
+    pub canonical_host: Option<CanonicalHostConfig>,
+
+    /// Configuration for timeouts.
+    pub timeout: Option<TimeoutConfig>,
+
+    /// Configuration for signed download URLs.
+    pub signed_urls: Option<SignedUrlConfig>,
+
+    /// Number of leading zero bits required for the proof of
+    /// work when registering a namespace.
+    pub namespace_difficulty: Option<u32>,
+
+    /// Response for requests to the root path.
+    #[serde(default)]
+    pub root_behavior: RootBehavior,
+
     /// Path the file was loaded from used to determine
     /// relative paths.
     #[serde(skip)]
@@ -55,6 +83,11 @@ impl ServerConfig {
             webhooks: Default::default(),
             tls: None,
             cors: None,
+            canonical_host: None,
+            timeout: None,
+            signed_urls: None,
+            namespace_difficulty: None,
+            root_behavior: Default::default(),
             file: None,
         }
     }
@@ -73,6 +106,55 @@ impl ServerConfig {
             return Err(Error::NoStorageLayers);
         }
 
+        if let Some(quorum) = config.storage.write_quorum {
+            if quorum == 0 || quorum > config.storage.layers.len() {
+                return Err(Error::WriteQuorum(
+                    quorum,
+                    config.storage.layers.len(),
+                ));
+            }
+        }
+
+        for layer in &config.storage.layers {
+            match layer {
+                LayerConfig::Ipfs {
+                    concurrency: Some(0),
+                    ..
+                } => return Err(Error::IpfsConcurrency),
+                LayerConfig::Aws { prefix, .. } => {
+                    normalize_prefix(prefix)?;
+                }
+                _ => {}
+            }
+        }
+
+        // Archive entries are matched against lowercase
+        // extensions without the leading period
+        for extensions in [
+            config.registry.allowed_extensions.as_mut(),
+            config.registry.denied_extensions.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *extensions = extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .collect();
+        }
+
+        if let Some(signed_urls) = &config.signed_urls {
+            if signed_urls.secret.len() < MIN_SIGNED_URL_SECRET {
+                return Err(Error::SignedUrlSecret(MIN_SIGNED_URL_SECRET));
+            }
+        }
+
+        if let Some(difficulty) = config.namespace_difficulty {
+            if difficulty == 0 || difficulty > MAX_DIFFICULTY {
+                return Err(Error::NamespaceDifficulty(MAX_DIFFICULTY));
+            }
+        }
+
         let dir = config.directory();
 
         if let Some(tls) = config.tls.as_mut() {
@@ -96,15 +178,23 @@ impl ServerConfig {
             let buffer = std::fs::read(&hooks.key)?;
             let keystore: KeyStore = serde_json::from_slice(&buffer)?;
 
-            let password = std::env::var(KEYSTORE_PASSWORD_ENV)
-                .ok()
-                .ok_or(Error::WebHookKeystorePassword)?;
+            let password = hooks.password_source.read(&dir)?;
 
             let key = decrypt(&keystore, &password)?;
             let signing_key = SigningKey::from_bytes(&key)?;
             hooks.signing_key = Some(signing_key);
         }
 
+        if let RootBehavior::Static(directory) = &mut config.root_behavior {
+            if directory.is_relative() {
+                *directory = dir.join(directory.clone());
+            }
+            *directory = directory.canonicalize()?;
+            if !directory.is_dir() {
+                return Err(Error::NotDirectory(directory.clone()));
+            }
+        }
+
         let mut layers = IndexSet::new();
         for mut layer in config.storage.layers.drain(..) {
             if let LayerConfig::File { directory } = &mut layer {
@@ -125,6 +215,24 @@ impl ServerConfig {
 
         config.storage.layers = layers;
 
+        if let Some(canonical) = &config.canonical_host {
+            if canonical.url.host_str().is_none() {
+                return Err(Error::InvalidHost(canonical.url.clone()));
+            }
+        }
+
+        if config.registry.immutable_versions
+            && config.registry.allow_force_window.is_some()
+        {
+            return Err(Error::ImmutableVersions);
+        }
+
+        if config.registry.immutable_versions
+            && config.registry.allow_yanked_overwrite
+        {
+            return Err(Error::ImmutableYankedVersions);
+        }
+
         // Sanity check the MIME type
         let _: mime::Mime = config.registry.mime.parse()?;
 
@@ -142,18 +250,39 @@ impl ServerConfig {
     }
 }
 
+/// Response for requests to the root path.
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RootBehavior {
+    /// Respond with the server information.
+    #[default]
+    Info,
+    /// Redirect to the API.
+    Redirect,
+    /// Serve a landing page and the files in a directory.
+    Static(PathBuf),
+}
+
 /// Configuration for the storage layers.
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct StorageConfig {
     /// Collection of storage layers.
     pub layers: IndexSet<LayerConfig>,
+    /// Number of layers that must accept an artifact for a
+    /// publish to succeed, default is all layers.
+    #[serde(default)]
+    pub write_quorum: Option<usize>,
 }
 
 impl Default for StorageConfig {
     fn default() -> Self {
         let mut layers = IndexSet::new();
         layers.insert(Default::default());
-        Self { layers }
+        Self {
+            layers,
+            write_quorum: None,
+        }
     }
 }
 
@@ -161,7 +290,10 @@ impl From<LayerConfig> for StorageConfig {
     fn from(layer: LayerConfig) -> Self {
         let mut layers = IndexSet::new();
         layers.insert(layer);
-        Self { layers }
+        Self {
+            layers,
+            write_quorum: None,
+        }
     }
 }
 
@@ -189,6 +321,10 @@ fn default_mime() -> String {
     String::from("application/gzip")
 }
 
+fn default_max_message_length() -> usize {
+    1024
+}
+
 /// Configuration for the registry.
 #[derive(Debug, Deserialize)]
 #[serde(default, rename_all = "kebab-case")]
@@ -205,6 +341,82 @@ pub struct RegistryConfig {
     pub allow: Option<HashSet<Address>>,
     /// Set of addresses that are not allowed to publish.
     pub deny: Option<HashSet<Address>>,
+    /// Set of addresses allowed to use the administration endpoints.
+    pub admins: Option<HashSet<Address>>,
+    /// Reject packages whose manifest is missing required fields.
+    pub validate_manifest: bool,
+    /// Reject packages whose archive format or content type is
+    /// for a different kind than the namespace.
+    pub reject_wrong_kind: bool,
+    /// Maximum size in bytes of a package archive once decompressed.
+    pub max_decompressed_size: Option<u64>,
+    /// Maximum number of entries in a package archive.
+    pub max_archive_entries: Option<usize>,
+    /// Allow clients to request MessagePack encoded metadata.
+    pub msgpack: bool,
+    /// Limit the rate at which each package may be fetched.
+    pub fetch_limit: Option<FetchLimitConfig>,
+    /// Remove artifacts for yanked versions from storage.
+    pub retention: Option<RetentionConfig>,
+    /// Create a missing namespace for the publisher on first publish.
+    pub auto_create_namespace: bool,
+    /// Maximum length in bytes of a yank or deprecation message.
+    #[serde(default = "default_max_message_length")]
+    pub max_message_length: usize,
+    /// File extensions permitted for entries in package archives.
+    pub allowed_extensions: Option<HashSet<String>>,
+    /// File extensions rejected for entries in package archives.
+    pub denied_extensions: Option<HashSet<String>>,
+    /// Number of seconds after a version is published that the
+    /// namespace owner may force publish to replace the artifact.
+    pub allow_force_window: Option<u64>,
+    /// Reject any modification to the content of a published
+    /// version; force publish may not be enabled.
+    pub immutable_versions: bool,
+    /// Allow publishing a version that was yanked to replace the
+    /// artifact and restore the version.
+    pub allow_yanked_overwrite: bool,
+    /// Number of seconds to cache the publisher and namespace
+    /// records used when publishing.
+    pub cache_ttl: Option<u64>,
+    /// Reject packages published with version 0.0.0, including
+    /// pre-releases of 0.0.0.
+    pub forbid_zero_version: bool,
+    /// Reject packages published with build metadata in the version.
+    pub require_no_build_metadata: bool,
+    /// Shell command that receives the artifact bytes on stdin
+    /// and exits with status one when a threat is found.
+    pub scan_command: Option<String>,
+    /// Sort order for listing versions when the request does
+    /// not specify a sort order.
+    pub default_version_sort: SortOrder,
+    /// Constrain the versions and codecs of content identifiers.
+    pub cid: Option<CidConfig>,
+    /// Version selected when a content identifier matches
+    /// more than one version.
+    pub duplicate_cid: DuplicateCid,
+    /// Skip signature verification when fetching versions that
+    /// have a content identifier once the checksum has been
+    /// verified; content addressed artifacts are immutable and
+    /// the signature was verified when the version was published.
+    pub trust_content_id: bool,
+}
+
+impl RegistryConfig {
+    /// Check a version being published satisfies the version rules.
+    pub fn check_version(&self, version: &Version) -> CoreResult<()> {
+        if self.forbid_zero_version
+            && version.major == 0
+            && version.minor == 0
+            && version.patch == 0
+        {
+            return Err(CoreError::ZeroVersion(version.clone()));
+        }
+        if self.require_no_build_metadata && !version.build.is_empty() {
+            return Err(CoreError::VersionBuildMetadata(version.clone()));
+        }
+        Ok(())
+    }
 }
 
 impl Default for RegistryConfig {
@@ -215,10 +427,88 @@ impl Default for RegistryConfig {
             kind: Default::default(),
             allow: None,
             deny: None,
+            admins: None,
+            validate_manifest: false,
+            reject_wrong_kind: false,
+            max_decompressed_size: None,
+            max_archive_entries: None,
+            msgpack: true,
+            fetch_limit: None,
+            retention: None,
+            auto_create_namespace: false,
+            max_message_length: default_max_message_length(),
+            allowed_extensions: None,
+            denied_extensions: None,
+            allow_force_window: None,
+            immutable_versions: false,
+            allow_yanked_overwrite: false,
+            cache_ttl: None,
+            forbid_zero_version: false,
+            require_no_build_metadata: false,
+            scan_command: None,
+            default_version_sort: SortOrder::Desc,
+            cid: None,
+            duplicate_cid: Default::default(),
+            trust_content_id: false,
+        }
+    }
+}
+
+/// Configuration for acceptable content identifiers.
+///
+/// Applies to identifiers returned by storage layers when
+/// publishing and to identifiers used to fetch packages.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CidConfig {
+    /// Allowed CID versions, `0` or `1`.
+    pub versions: Option<HashSet<u64>>,
+    /// Allowed multicodec codes, for example `0x70` for dag-pb
+    /// or `0x55` for raw.
+    pub codecs: Option<HashSet<u64>>,
+}
+
+impl CidConfig {
+    /// Check a content identifier uses an allowed version and codec.
+    pub fn check(&self, cid: &Cid) -> CoreResult<()> {
+        let version = u64::from(cid.version());
+        let codec = cid.codec();
+        let allowed = |set: &Option<HashSet<u64>>, value| {
+            set.as_ref().map(|set| set.contains(&value)).unwrap_or(true)
+        };
+        if !allowed(&self.versions, version) || !allowed(&self.codecs, codec)
+        {
+            return Err(CoreError::CidNotAllowed(*cid, version, codec));
         }
+        Ok(())
     }
 }
 
+/// Configuration for per-package fetch limits.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FetchLimitConfig {
+    /// Maximum number of fetches for a package in each window.
+    pub requests: u32,
+    /// Length of the window in seconds.
+    pub window: u64,
+}
+
+fn retention_interval() -> u64 {
+    3600
+}
+
+/// Configuration for artifact retention.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RetentionConfig {
+    /// Number of days after a version is yanked before the
+    /// artifact is removed from storage.
+    pub yanked_after_days: u64,
+    /// Number of seconds between each retention run.
+    #[serde(default = "retention_interval")]
+    pub interval: u64,
+}
+
 fn retry_limit() -> u64 {
     5
 }
@@ -241,20 +531,176 @@ pub struct WebHookConfig {
     /// Number of seconds for initial backoff interval.
     #[serde(default = "backoff_seconds")]
     pub backoff_seconds: u64,
+    /// Source of the password for the signing key.
+    #[serde(default)]
+    pub password_source: PasswordSource,
+    /// Serialization for the signed webhook payloads.
+    #[serde(default)]
+    pub format: PayloadFormat,
+    /// Endpoints called for events that pertain to a namespace.
+    #[serde(default)]
+    pub namespaces: HashMap<Namespace, NamespaceWebHookConfig>,
     /// Signing key decrypted from the keystore.
     #[serde(skip)]
     pub(crate) signing_key: Option<SigningKey>,
 }
 
+fn webhook_sign() -> bool {
+    true
+}
+
+/// Configuration for the webhooks of a namespace.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NamespaceWebHookConfig {
+    /// Endpoints to call for events in the namespace.
+    pub endpoints: Vec<Url>,
+    /// Secret used to compute an HMAC-SHA256 of the payload
+    /// sent in the `x-hub-signature-256` header.
+    pub secret: Option<String>,
+    /// Send the signature from the webhook signing key
+    /// in the `x-signature` header.
+    #[serde(default = "webhook_sign")]
+    pub sign: bool,
+}
+
+/// Serialization for signed payloads.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PayloadFormat {
+    /// Canonical JSON with sorted keys and no whitespace so
+    /// receivers can serialize the payload again to verify
+    /// the signature.
+    #[default]
+    Canonical,
+    /// JSON with keys in declaration order.
+    Json,
+}
+
+impl PayloadFormat {
+    /// Serialize a payload.
+    pub fn to_vec<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Canonical => to_canonical_vec(value)?,
+            Self::Json => serde_json::to_vec(value)?,
+        })
+    }
+}
+
+/// Source of the password used to decrypt a keystore.
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PasswordSource {
+    /// Read the password from the `IPKG_WEBHOOK_KEYSTORE_PASSWORD`
+    /// environment variable.
+    #[default]
+    Env,
+    /// Read the password from a file, relative paths are resolved
+    /// from the directory of the configuration file.
+    File(PathBuf),
+    /// Run a shell command and read the password from stdout.
+    Command(String),
+}
+
+impl PasswordSource {
+    /// Read the password, a trailing line break is removed
+    /// from file contents and command output.
+    pub fn read(&self, dir: &Path) -> Result<String> {
+        let password = match self {
+            Self::Env => std::env::var(KEYSTORE_PASSWORD_ENV)
+                .ok()
+                .ok_or(Error::WebHookKeystorePassword)?,
+            Self::File(path) => {
+                let path = if path.is_relative() {
+                    dir.join(path)
+                } else {
+                    path.clone()
+                };
+                std::fs::read_to_string(path)?
+            }
+            Self::Command(command) => {
+                let output = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .current_dir(dir)
+                    .output()?;
+                if !output.status.success() {
+                    return Err(Error::PasswordCommand(
+                        command.clone(),
+                        output.status.to_string(),
+                    ));
+                }
+                String::from_utf8(output.stdout)
+                    .map_err(|_| Error::PasswordEncoding)?
+            }
+        };
+
+        let password = password
+            .strip_suffix('\n')
+            .map(|s| s.strip_suffix('\r').unwrap_or(s))
+            .unwrap_or(&password);
+
+        Ok(password.to_owned())
+    }
+}
+
 /// Configuration for TLS.
 ///
 /// Required to run the server using SSL.
 #[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct TlsConfig {
     /// Path to the certificate.
     pub cert: PathBuf,
     /// Path to the certificate key file.
     pub key: PathBuf,
+    /// Send the Strict-Transport-Security header.
+    pub hsts: Option<HstsConfig>,
+    /// Address for a plaintext listener that redirects to HTTPS.
+    pub redirect: Option<SocketAddr>,
+}
+
+fn hsts_max_age() -> u64 {
+    31536000
+}
+
+/// Configuration for HTTP Strict Transport Security.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HstsConfig {
+    /// Number of seconds clients should only use HTTPS.
+    #[serde(default = "hsts_max_age")]
+    pub max_age: u64,
+    /// Apply the policy to all subdomains.
+    #[serde(default)]
+    pub include_subdomains: bool,
+    /// Request inclusion in browser preload lists.
+    #[serde(default)]
+    pub preload: bool,
+}
+
+impl Default for HstsConfig {
+    fn default() -> Self {
+        Self {
+            max_age: hsts_max_age(),
+            include_subdomains: false,
+            preload: false,
+        }
+    }
+}
+
+impl HstsConfig {
+    /// Value for the Strict-Transport-Security header.
+    pub fn header_value(&self) -> String {
+        let mut value = format!("max-age={}", self.max_age);
+        if self.include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
+        if self.preload {
+            value.push_str("; preload");
+        }
+        value
+    }
 }
 
 /// Configuration for CORS.
@@ -264,14 +710,65 @@ pub struct CorsConfig {
     pub origins: Vec<Url>,
 }
 
+/// Configuration for the canonical host.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CanonicalHostConfig {
+    /// URL for the canonical host.
+    pub url: Url,
+    /// Only redirect requests for metadata.
+    #[serde(default)]
+    pub metadata_only: bool,
+}
+
+/// Configuration for signed download URLs.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SignedUrlConfig {
+    /// Secret key for the HMAC of signed URLs.
+    pub secret: String,
+    /// Maximum number of seconds a signed URL is valid.
+    #[serde(default = "max_expires_in")]
+    pub max_expires_in: u64,
+}
+
+fn max_expires_in() -> u64 {
+    3600
+}
+
+/// Configuration for timeouts.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct TimeoutConfig {
+    /// Maximum number of seconds to handle a request.
+    pub request: Option<u64>,
+    /// Maximum number of seconds for each storage layer operation.
+    pub storage: Option<u64>,
+}
+
 /// Configuration for a storage layer.
 #[derive(Debug, Clone, Deserialize, Hash, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum LayerConfig {
     /// Storage layer backed by IPFS.
     Ipfs {
-        /// URL for the IPFS node.
+        /// URL for the IPFS node API.
         url: Url,
+        /// URL for an IPFS gateway used to read artifacts.
+        ///
+        /// When not set artifacts are read using the node API.
+        #[serde(default)]
+        gateway: Option<Url>,
+        /// Send an explicit pin request after adding an artifact.
+        ///
+        /// Disable when the node pins content on add to save
+        /// a round trip for each publish.
+        #[serde(default = "ipfs_pin")]
+        pin: bool,
+        /// Maximum number of concurrent add and pin operations.
+        ///
+        /// When not set operations are not limited.
+        #[serde(default)]
+        concurrency: Option<usize>,
     },
     /// Storage layer backed by AWS S3.
     Aws {
@@ -295,12 +792,311 @@ pub enum LayerConfig {
         /// Directory for the file storage layer.
         directory: PathBuf,
     },
+    /// Storage layer backed by an OCI registry.
+    Oci {
+        /// URL for the OCI registry.
+        registry: Url,
+        /// Repository for artifacts.
+        repository: String,
+        /// Credentials for the registry.
+        #[serde(default)]
+        credentials: Option<OciCredentials>,
+    },
+}
+
+/// Credentials for basic authentication with an OCI registry.
+#[derive(Debug, Clone, Deserialize, Hash, Eq, PartialEq)]
+pub struct OciCredentials {
+    /// Username for the registry.
+    pub username: String,
+    /// Password or token for the registry.
+    pub password: String,
+}
+
+fn ipfs_pin() -> bool {
+    true
 }
 
 impl Default for LayerConfig {
     fn default() -> Self {
         Self::Ipfs {
             url: Url::parse("http://localhost:5001").unwrap(),
+            gateway: None,
+            pin: true,
+            concurrency: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipfs_layer_gateway() -> Result<()> {
+        let config: StorageConfig = toml::from_str(
+            r#"
+                layers = [
+                  { url = "http://localhost:5001" },
+                  { url = "http://localhost:5001", gateway = "http://localhost:8080" },
+                ]
+            "#,
+        )?;
+
+        let mut layers = config.layers.iter();
+        assert_eq!(Some(&LayerConfig::default()), layers.next());
+        assert_eq!(
+            Some(&LayerConfig::Ipfs {
+                url: Url::parse("http://localhost:5001")?,
+                gateway: Some(Url::parse("http://localhost:8080")?),
+                pin: true,
+                concurrency: None,
+            }),
+            layers.next()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn root_behavior() -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct Root {
+            #[serde(default)]
+            root_behavior: RootBehavior,
+        }
+
+        let root: Root = toml::from_str("")?;
+        assert_eq!(RootBehavior::Info, root.root_behavior);
+
+        let root: Root = toml::from_str(r#"root-behavior = "redirect""#)?;
+        assert_eq!(RootBehavior::Redirect, root.root_behavior);
+
+        let root: Root =
+            toml::from_str(r#"root-behavior = { static = "www" }"#)?;
+        assert_eq!(
+            RootBehavior::Static(PathBuf::from("www")),
+            root.root_behavior
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn webhook_payload_format() -> Result<()> {
+        #[derive(Serialize)]
+        struct Payload {
+            event: &'static str,
+            body: &'static str,
+        }
+        let payload = Payload {
+            event: "publish",
+            body: "mock",
+        };
+
+        let config: WebHookConfig = toml::from_str(
+            r#"
+                key = "webhooks.json"
+                endpoints = []
+            "#,
+        )?;
+        assert_eq!(PayloadFormat::Canonical, config.format);
+        assert_eq!(
+            br#"{"body":"mock","event":"publish"}"#.to_vec(),
+            config.format.to_vec(&payload)?
+        );
+
+        let config: WebHookConfig = toml::from_str(
+            r#"
+                key = "webhooks.json"
+                endpoints = []
+                format = "json"
+            "#,
+        )?;
+        assert_eq!(PayloadFormat::Json, config.format);
+        assert_eq!(
+            br#"{"event":"publish","body":"mock"}"#.to_vec(),
+            config.format.to_vec(&payload)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ipfs_layer_pin_concurrency() -> Result<()> {
+        let config: StorageConfig = toml::from_str(
+            r#"
+                layers = [
+                  { url = "http://localhost:5001", pin = false, concurrency = 4 },
+                ]
+            "#,
+        )?;
+
+        assert_eq!(
+            Some(&LayerConfig::Ipfs {
+                url: Url::parse("http://localhost:5001")?,
+                gateway: None,
+                pin: false,
+                concurrency: Some(4),
+            }),
+            config.layers.first()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn registry_cid_rules() -> Result<()> {
+        let v0: Cid =
+            "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb".parse()?;
+        let dag_pb: Cid =
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+                .parse()?;
+        let raw: Cid =
+            "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy"
+                .parse()?;
+
+        // Permissive by default
+        let config: CidConfig = Default::default();
+        for cid in [&v0, &dag_pb, &raw] {
+            assert!(config.check(cid).is_ok());
+        }
+
+        // CIDv1 dag-pb only
+        let config: RegistryConfig =
+            toml::from_str("[cid]\nversions = [1]\ncodecs = [0x70]\n")?;
+        let config = config.cid.unwrap();
+        assert!(config.check(&dag_pb).is_ok());
+        assert!(matches!(
+            config.check(&v0),
+            Err(CoreError::CidNotAllowed(_, 0, 0x70))
+        ));
+        assert!(matches!(
+            config.check(&raw),
+            Err(CoreError::CidNotAllowed(_, 1, 0x55))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn registry_version_rules() {
+        let zero: Version = "0.0.0-alpha.1".parse().unwrap();
+        let build: Version = "1.0.0+build.1".parse().unwrap();
+
+        // Permissive by default
+        let config: RegistryConfig = Default::default();
+        assert!(config.check_version(&zero).is_ok());
+        assert!(config.check_version(&build).is_ok());
+
+        let config = RegistryConfig {
+            forbid_zero_version: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.check_version(&zero),
+            Err(CoreError::ZeroVersion(_))
+        ));
+        assert!(config.check_version(&Version::new(0, 0, 1)).is_ok());
+        assert!(config.check_version(&build).is_ok());
+
+        let config = RegistryConfig {
+            require_no_build_metadata: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.check_version(&build),
+            Err(CoreError::VersionBuildMetadata(_))
+        ));
+        assert!(config.check_version(&zero).is_ok());
+    }
+
+    #[test]
+    fn password_source_parse() -> Result<()> {
+        let parse = |value: &str| -> Result<PasswordSource> {
+            let config: WebHookConfig = toml::from_str(&format!(
+                "key = \"key.json\"\nendpoints = []\n{}",
+                value
+            ))?;
+            Ok(config.password_source)
+        };
+
+        assert_eq!(PasswordSource::Env, parse("")?);
+        assert_eq!(PasswordSource::Env, parse("password-source = \"env\"")?);
+        assert_eq!(
+            PasswordSource::File(PathBuf::from("password.txt")),
+            parse("password-source = { file = \"password.txt\" }")?
+        );
+        assert_eq!(
+            PasswordSource::Command("pass show ipkg".to_owned()),
+            parse("password-source = { command = \"pass show ipkg\" }")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn password_source_env() -> Result<()> {
+        std::env::set_var(KEYSTORE_PASSWORD_ENV, "mock-password");
+        let password = PasswordSource::Env.read(&std::env::temp_dir())?;
+        std::env::remove_var(KEYSTORE_PASSWORD_ENV);
+        assert_eq!("mock-password", password);
+        assert!(matches!(
+            PasswordSource::Env.read(&std::env::temp_dir()),
+            Err(Error::WebHookKeystorePassword)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn password_source_file() -> Result<()> {
+        let dir = std::env::temp_dir();
+        let name = format!("ipkg-password-{}.txt", std::process::id());
+        std::fs::write(dir.join(&name), "mock-password\n")?;
+
+        // Relative paths resolve from the config directory
+        let password = PasswordSource::File(PathBuf::from(&name)).read(&dir);
+        std::fs::remove_file(dir.join(&name))?;
+        assert_eq!("mock-password", password?);
+
+        assert!(PasswordSource::File(PathBuf::from(&name))
+            .read(&dir)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn password_source_command() -> Result<()> {
+        let dir = std::env::temp_dir();
+        let password =
+            PasswordSource::Command("echo mock-password".to_owned())
+                .read(&dir)?;
+        assert_eq!("mock-password", password);
+        assert!(matches!(
+            PasswordSource::Command("exit 1".to_owned()).read(&dir),
+            Err(Error::PasswordCommand(_, _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn namespace_difficulty_range() -> Result<()> {
+        let dir = std::env::temp_dir();
+        let name = format!("ipkg-difficulty-{}.toml", std::process::id());
+        let load = |difficulty: u32| -> Result<ServerConfig> {
+            std::fs::write(
+                dir.join(&name),
+                format!(
+                    "namespace-difficulty = {}\n[storage]\nlayers = [{{ memory = true }}]\n",
+                    difficulty
+                ),
+            )?;
+            ServerConfig::load(dir.join(&name))
+        };
+
+        let config = load(MAX_DIFFICULTY);
+        let zero = load(0);
+        let too_large = load(MAX_DIFFICULTY + 1);
+        std::fs::remove_file(dir.join(&name))?;
+
+        assert_eq!(Some(MAX_DIFFICULTY), config?.namespace_difficulty);
+        assert!(matches!(zero, Err(Error::NamespaceDifficulty(_))));
+        assert!(matches!(too_large, Err(Error::NamespaceDifficulty(_))));
+        Ok(())
+    }
+}
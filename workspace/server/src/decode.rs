@@ -0,0 +1,95 @@
+//! Decoding of HTTP content encodings for request bodies.
+use axum::{
+    body::Bytes,
+    http::{HeaderValue, StatusCode},
+};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::io::Read;
+
+/// Decode a request body according to the `Content-Encoding` header.
+///
+/// Encodings are removed in the reverse order they were applied;
+/// unsupported encodings are rejected with `415 Unsupported Media Type`
+/// and a decoded body larger than `limit` is rejected with
+/// `413 Payload Too Large`.
+pub(crate) fn decode_body(
+    encoding: Option<&HeaderValue>,
+    body: Bytes,
+    limit: usize,
+) -> std::result::Result<Bytes, StatusCode> {
+    let encoding = if let Some(encoding) = encoding {
+        encoding
+            .to_str()
+            .map_err(|_| StatusCode::UNSUPPORTED_MEDIA_TYPE)?
+    } else {
+        return Ok(body);
+    };
+
+    let mut body = body;
+    for coding in encoding.rsplit(',') {
+        let coding = coding.trim().to_ascii_lowercase();
+        body = match coding.as_str() {
+            "" | "identity" => body,
+            "gzip" | "x-gzip" => {
+                read_limited(GzDecoder::new(&body[..]), limit)?
+            }
+            "deflate" => read_limited(ZlibDecoder::new(&body[..]), limit)?,
+            _ => return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        };
+    }
+    Ok(body)
+}
+
+/// Read a decoder to the end failing if more than `limit` bytes
+/// are decoded.
+fn read_limited<R: Read>(
+    reader: R,
+    limit: usize,
+) -> std::result::Result<Bytes, StatusCode> {
+    let mut buffer = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut buffer)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if buffer.len() > limit {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    Ok(Bytes::from(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Bytes {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        Bytes::from(encoder.finish().unwrap())
+    }
+
+    #[test]
+    fn decode_gzip_body() {
+        let body = gzip(b"mock-payload");
+        let encoding = HeaderValue::from_static("gzip");
+        let decoded = decode_body(Some(&encoding), body, 1024).unwrap();
+        assert_eq!(&b"mock-payload"[..], &decoded[..]);
+
+        let identity = Bytes::from_static(b"mock-payload");
+        let decoded = decode_body(None, identity.clone(), 1024).unwrap();
+        assert_eq!(identity, decoded);
+    }
+
+    #[test]
+    fn decode_rejects_body() {
+        let encoding = HeaderValue::from_static("br");
+        let result =
+            decode_body(Some(&encoding), Bytes::from_static(b"mock"), 1024);
+        assert_eq!(Err(StatusCode::UNSUPPORTED_MEDIA_TYPE), result);
+
+        let encoding = HeaderValue::from_static("gzip");
+        let result = decode_body(Some(&encoding), gzip(&[0; 64]), 32);
+        assert_eq!(Err(StatusCode::PAYLOAD_TOO_LARGE), result);
+    }
+}
@@ -0,0 +1,171 @@
+//! Content negotiation for metadata responses.
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequest, RequestParts},
+    http::{
+        header::{ACCEPT, CONTENT_TYPE, VARY},
+        HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::convert::Infallible;
+use url::form_urlencoded;
+
+use ipfs_registry_core::MSGPACK_MIME;
+
+use crate::server::ServerState;
+
+/// Encoding for a response body.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Encoding {
+    /// Encode as JSON.
+    Json,
+    /// Encode as pretty printed JSON.
+    PrettyJson,
+    /// Encode as MessagePack.
+    MessagePack,
+}
+
+impl Encoding {
+    /// Wrap a value so it is encoded in the response.
+    pub(crate) fn encode<T: Serialize>(self, value: T) -> Encoded<T> {
+        Encoded(self, value)
+    }
+}
+
+/// Determine if an `Accept` header value lists a media type.
+///
+/// A media type with a quality value of zero is not acceptable.
+fn accepts(value: &str, media_type: &str) -> bool {
+    value.split(',').any(|entry| {
+        let mut parts = entry.split(';');
+        let matches = parts
+            .next()
+            .map(|value| value.trim() == media_type)
+            .unwrap_or(false);
+        matches
+            && parts.all(|param| match param.split_once('=') {
+                Some((name, quality)) if name.trim() == "q" => quality
+                    .trim()
+                    .parse::<f32>()
+                    .map(|quality| quality > 0.0)
+                    .unwrap_or(false),
+                _ => true,
+            })
+    })
+}
+
+#[async_trait]
+impl<B: Send> FromRequest<B> for Encoding {
+    type Rejection = Infallible;
+
+    async fn from_request(
+        req: &mut RequestParts<B>,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let enabled = req
+            .extensions()
+            .get::<ServerState>()
+            .map(|state| state.config.registry.msgpack)
+            .unwrap_or(false);
+
+        let accepts_msgpack = req
+            .headers()
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .any(|value| accepts(value, MSGPACK_MIME));
+
+        let pretty = req
+            .uri()
+            .query()
+            .map(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .any(|(key, value)| key == "pretty" && value == "true")
+            })
+            .unwrap_or(false);
+
+        if enabled && accepts_msgpack {
+            Ok(Encoding::MessagePack)
+        } else if pretty {
+            Ok(Encoding::PrettyJson)
+        } else {
+            Ok(Encoding::Json)
+        }
+    }
+}
+
+/// Response body encoded using the negotiated encoding.
+pub(crate) struct Encoded<T>(Encoding, T);
+
+impl<T: Serialize> IntoResponse for Encoded<T> {
+    fn into_response(self) -> Response {
+        let vary = [(VARY, HeaderValue::from_static("accept"))];
+        match self.0 {
+            Encoding::Json => (vary, Json(self.1)).into_response(),
+            Encoding::PrettyJson => {
+                match serde_json::to_vec_pretty(&self.1) {
+                    Ok(buffer) => (
+                        vary,
+                        [(
+                            CONTENT_TYPE,
+                            HeaderValue::from_static(
+                                mime::APPLICATION_JSON.as_ref(),
+                            ),
+                        )],
+                        buffer,
+                    )
+                        .into_response(),
+                    Err(e) => {
+                        tracing::error!("{}", e);
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                }
+            }
+            Encoding::MessagePack => {
+                let mut buffer = Vec::new();
+                let mut serializer = rmp_serde::Serializer::new(&mut buffer)
+                    .with_struct_map()
+                    .with_human_readable();
+                match self.1.serialize(&mut serializer) {
+                    Ok(_) => (
+                        vary,
+                        [(
+                            CONTENT_TYPE,
+                            HeaderValue::from_static(MSGPACK_MIME),
+                        )],
+                        buffer,
+                    )
+                        .into_response(),
+                    Err(e) => {
+                        tracing::error!("{}", e);
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_quality() {
+        assert!(accepts(MSGPACK_MIME, MSGPACK_MIME));
+        assert!(accepts(
+            "application/json, application/msgpack",
+            MSGPACK_MIME
+        ));
+        assert!(accepts("application/msgpack; q=0.5", MSGPACK_MIME));
+        assert!(!accepts("application/json", MSGPACK_MIME));
+        assert!(!accepts("application/msgpack;q=0", MSGPACK_MIME));
+        assert!(!accepts("application/msgpack; q=0.0", MSGPACK_MIME));
+        assert!(!accepts(
+            "application/json, application/msgpack;q=0",
+            MSGPACK_MIME
+        ));
+    }
+}
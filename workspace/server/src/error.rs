@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use thiserror::Error;
 use url::Url;
+use web3_address::ethereum::Address;
 
 /// Errors thrown by the server library.
 #[derive(Debug, Error)]
@@ -33,6 +34,18 @@ pub enum Error {
     #[error("no storage layers, check configuration")]
     NoStorageLayers,
 
+    /// Error generated when the write quorum is out of range.
+    #[error("write quorum {0} must be between 1 and the number of storage layers ({1})")]
+    WriteQuorum(usize, usize),
+
+    /// Error generated when the IPFS concurrency limit is zero.
+    #[error("IPFS concurrency must be greater than zero")]
+    IpfsConcurrency,
+
+    /// Error generated when an S3 prefix is invalid.
+    #[error("S3 prefix {0:?} must not start with a slash or contain backslashes or control characters")]
+    InvalidPrefix(String),
+
     /// Error generated when an object key is the wrong kind.
     #[error("wrong kind of object key for the layer")]
     BadObjectKey,
@@ -41,10 +54,70 @@ pub enum Error {
     #[error("object {0} is not available")]
     ObjectMissing(String),
 
+    /// Error generated when an artifact does not match the stored checksum.
+    #[error("checksum mismatch for {0}")]
+    Checksum(String),
+
+    /// Error generated when a storage layer operation times out.
+    #[error("storage layer did not respond within {0:?}")]
+    StorageTimeout(std::time::Duration),
+
+    /// Error generated when an OCI registry returns an unexpected response.
+    #[error("unexpected response code {0} from OCI registry")]
+    OciResponse(u16),
+
+    /// Error generated when an OCI registry does not return an upload location.
+    #[error("OCI registry did not return a location for the blob upload")]
+    OciUploadLocation,
+
+    /// Error generated when an IPFS gateway returns an unexpected status code.
+    #[error("unexpected response code {0} from IPFS gateway")]
+    GatewayResponse(u16),
+
     /// Error generated when webhooks are configured without a password for the signing key.
     #[error("webhooks are configured but IPKG_WEBHOOK_KEYSTORE_PASSWORD is not set")]
     WebHookKeystorePassword,
 
+    /// Error generated when force publish is enabled for
+    /// immutable versions.
+    #[error("allow-force-window cannot be used with immutable-versions")]
+    ImmutableVersions,
+
+    /// Error generated when overwriting yanked versions is
+    /// enabled for immutable versions.
+    #[error("allow-yanked-overwrite cannot be used with immutable-versions")]
+    ImmutableYankedVersions,
+
+    /// Error generated when a password command exits unsuccessfully.
+    #[error("password command '{0}' failed with {1}")]
+    PasswordCommand(String, String),
+
+    /// Error generated when a scan command fails to run or
+    /// exits with an unexpected status.
+    #[error("scan command '{0}' failed with {1}")]
+    ScanCommand(String, String),
+
+    /// Error generated when the namespace difficulty is out of range.
+    #[error("namespace-difficulty must be between 1 and {0}")]
+    NamespaceDifficulty(u32),
+
+    /// Error generated when the secret for signed URLs is too short.
+    #[error("signed-urls secret must be at least {0} bytes")]
+    SignedUrlSecret(usize),
+
+    /// Error generated when a password is not valid UTF-8.
+    #[error("password must be valid UTF-8")]
+    PasswordEncoding,
+
+    /// Error generated when an artifact signature was not created
+    /// by a key of the publisher of the version.
+    #[error("signer {0} is not a key of the publisher")]
+    UnknownSigner(Address),
+
+    /// Error generated by the core library.
+    #[error(transparent)]
+    Core(#[from] ipfs_registry_core::Error),
+
     /// Error generated by the io module.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -77,6 +150,10 @@ pub enum Error {
     #[error(transparent)]
     Ipfs(#[from] ipfs_api_backend_hyper::Error),
 
+    /// Error generated acquiring a permit for a storage operation.
+    #[error(transparent)]
+    Acquire(#[from] tokio::sync::AcquireError),
+
     /// Error generated parsing MIME type.
     #[error(transparent)]
     Mime(#[from] mime::FromStrError),
@@ -109,10 +186,20 @@ pub enum Error {
     #[error(transparent)]
     PutObject(#[from] rusoto_core::RusotoError<rusoto_s3::PutObjectError>),
 
+    /// Error generated deleting an object from S3.
+    #[error(transparent)]
+    DeleteObject(
+        #[from] rusoto_core::RusotoError<rusoto_s3::DeleteObjectError>,
+    ),
+
     /// Error generated parsing an S3 region.
     #[error(transparent)]
     ParseRegion(#[from] rusoto_signature::region::ParseRegionError),
 
+    /// Error generated by the database library.
+    #[error(transparent)]
+    Database(#[from] ipfs_registry_database::Error),
+
     /// Error generated by the SQL library.
     #[error(transparent)]
     Sql(#[from] sqlx::Error),
@@ -121,6 +208,10 @@ pub enum Error {
     #[error(transparent)]
     Migrate(#[from] sqlx::migrate::MigrateError),
 
+    /// Error generated parsing a URL.
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+
     /// Error generated by the CID library.
     #[error(transparent)]
     Cid(#[from] cid::Error),
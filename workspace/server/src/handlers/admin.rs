@@ -0,0 +1,335 @@
+use axum::{
+    body::Bytes,
+    extract::{Extension, TypedHeader},
+    http::{HeaderMap, StatusCode, Uri},
+    Json,
+};
+use serde::Deserialize;
+use time::OffsetDateTime;
+use web3_address::ethereum::Address;
+
+use ipfs_registry_core::{expiring_message, Namespace};
+use ipfs_registry_database::{
+    default_limit, AuditFilter, AuditModel, AuditRecord,
+    Error as DatabaseError, MaintenanceModel, NamespaceAction, Pager,
+    PublisherRecord, ResolveNamespace, ResolveReport, ResultSet, RotateKey,
+    RotatedSignatureRecord, SortOrder,
+};
+
+use crate::{
+    encoding::{Encoded, Encoding},
+    handlers::{
+        check_expiry, link_headers,
+        package::{
+            relocate_artifacts, remove_relocated_artifacts, retry_writes,
+        },
+        verify_signature,
+    },
+    headers::{Signature, SignatureExpires},
+    query::{validate_pager, ValidQuery, Validate},
+    server::ServerState,
+};
+
+/// Map an error from resolving a namespace to a status code.
+fn resolve_error(e: DatabaseError) -> StatusCode {
+    match e {
+        DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+        DatabaseError::NamespaceExists(_)
+        | DatabaseError::PackageNameExists(_, _) => StatusCode::CONFLICT,
+        DatabaseError::MergeSelf(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct AuditQuery {
+    action: Option<String>,
+    namespace: Option<Namespace>,
+    address: Option<Address>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    until: Option<OffsetDateTime>,
+    offset: i64,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    sort: SortOrder,
+}
+
+impl Validate for AuditQuery {
+    fn validate(&self) -> std::result::Result<(), String> {
+        if let (Some(since), Some(until)) = (&self.since, &self.until) {
+            if since > until {
+                return Err(String::from("since must not be after until"));
+            }
+        }
+        validate_pager(self.offset, self.limit)
+    }
+}
+
+impl AuditQuery {
+    fn to_pager(&self) -> Pager {
+        Pager {
+            offset: self.offset,
+            limit: self.limit,
+            sort: self.sort,
+            cursor: None,
+        }
+    }
+
+    fn to_filter(&self) -> AuditFilter {
+        AuditFilter {
+            action: self.action.clone(),
+            namespace: self.namespace.clone(),
+            address: self.address,
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
+
+pub(crate) struct AdminHandler;
+
+impl AdminHandler {
+    /// Verify the signature is from a registry administrator.
+    fn verify_admin(
+        state: &ServerState,
+        signature: Signature,
+        message: &[u8],
+    ) -> std::result::Result<Address, StatusCode> {
+        let admins = state
+            .config
+            .registry
+            .admins
+            .as_ref()
+            .ok_or(StatusCode::FORBIDDEN)?;
+
+        let caller = verify_signature(signature.into(), message)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        if !admins.contains(&caller) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        Ok(caller)
+    }
+
+    /// Resolve a namespace collision by renaming the namespace
+    /// or merging it into another namespace.
+    ///
+    /// Only addresses listed as registry administrators may
+    /// call this endpoint.
+    pub(crate) async fn resolve_namespace(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        body: Bytes,
+    ) -> std::result::Result<Json<ResolveReport>, StatusCode> {
+        let caller = Self::verify_admin(&state, signature, &body)?;
+
+        let request: ResolveNamespace = serde_json::from_slice(&body)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let (NamespaceAction::Rename(target)
+        | NamespaceAction::Merge(target)) = &request.action;
+
+        // Artifacts are stored under pointer identifiers for the new
+        // namespace so check the request can be applied before they
+        // are copied
+        let mut locations = Vec::new();
+        let mut versions = Vec::new();
+        let mut retries = Vec::new();
+        if !request.dry_run {
+            let check = ResolveNamespace {
+                dry_run: true,
+                ..request.clone()
+            };
+            MaintenanceModel::resolve_namespace(
+                &state.pool,
+                &caller,
+                &check,
+                &[],
+            )
+            .await
+            .map_err(resolve_error)?;
+
+            versions = MaintenanceModel::find_namespace_versions(
+                &state.pool,
+                &request.namespace,
+            )
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            for (index, (name, records)) in versions.iter().enumerate() {
+                match relocate_artifacts(&state, target, name, records).await
+                {
+                    Ok((relocated, pending)) => {
+                        locations.extend(relocated);
+                        retries.extend(pending);
+                    }
+                    Err(status) => {
+                        for (name, records) in &versions[..index] {
+                            remove_relocated_artifacts(
+                                &state, target, name, records,
+                            )
+                            .await;
+                        }
+                        return Err(status);
+                    }
+                }
+            }
+        }
+
+        match MaintenanceModel::resolve_namespace(
+            &state.pool,
+            &caller,
+            &request,
+            &locations,
+        )
+        .await
+        {
+            Ok(report) => {
+                if !report.dry_run {
+                    for pending in retries {
+                        retry_writes(&state, pending);
+                    }
+                    for (name, records) in &versions {
+                        remove_relocated_artifacts(
+                            &state,
+                            &report.namespace,
+                            name,
+                            records,
+                        )
+                        .await;
+                    }
+                    state.invalidate_namespace(&report.namespace);
+                    state.invalidate_namespace(target);
+                }
+                tracing::warn!(
+                    caller = %caller,
+                    namespace = %report.namespace,
+                    action = ?report.action,
+                    dry_run = report.dry_run,
+                    "namespace resolved");
+                Ok(Json(report))
+            }
+            Err(e) => {
+                for (name, records) in &versions {
+                    remove_relocated_artifacts(&state, target, name, records)
+                        .await;
+                }
+                Err(resolve_error(e))
+            }
+        }
+    }
+
+    /// Rotate the key of a publisher.
+    ///
+    /// Versions signed by the previous key remain verifiable
+    /// as the previous key is kept in the key history.
+    pub(crate) async fn rotate_key(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        body: Bytes,
+    ) -> std::result::Result<Json<PublisherRecord>, StatusCode> {
+        let caller = Self::verify_admin(&state, signature, &body)?;
+
+        let request: RotateKey = serde_json::from_slice(&body)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        match MaintenanceModel::rotate_key(&state.pool, &caller, &request)
+            .await
+        {
+            Ok(record) => {
+                state.invalidate_all();
+                tracing::warn!(
+                    caller = %caller,
+                    from = %request.from,
+                    to = %request.to,
+                    "publisher key rotated");
+                Ok(Json(record))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::PublisherKeyExists(_) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// List versions signed by keys that have been rotated out.
+    ///
+    /// The signature is of the endpoint name and the query string
+    /// with an expiry so it cannot be used to request a different
+    /// page, for another endpoint or once it expires.
+    pub(crate) async fn list_rotated_signatures(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        signature: Signature,
+        signature_expires: Option<TypedHeader<SignatureExpires>>,
+        ValidQuery(pager): ValidQuery<Pager>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<RotatedSignatureRecord>>),
+        StatusCode,
+    > {
+        let expires = check_expiry(
+            signature_expires.map(|TypedHeader(expires)| expires.into()),
+        )?;
+        let message =
+            format!("rotated-signatures:{}", uri.query().unwrap_or_default());
+        Self::verify_admin(
+            &state,
+            signature,
+            &expiring_message(message.as_bytes(), expires),
+        )?;
+
+        let records =
+            MaintenanceModel::list_rotated_signatures(&state.pool, &pager)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok((
+            link_headers(&uri, &pager, &records)?,
+            encoding.encode(records),
+        ))
+    }
+
+    /// List entries in the audit log.
+    ///
+    /// The signature is of the endpoint name and the query string
+    /// with an expiry so it cannot be used to request a different
+    /// page or filter, for another endpoint or once it expires.
+    pub(crate) async fn list_audit(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        signature: Signature,
+        signature_expires: Option<TypedHeader<SignatureExpires>>,
+        ValidQuery(query): ValidQuery<AuditQuery>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<AuditRecord>>),
+        StatusCode,
+    > {
+        let expires = check_expiry(
+            signature_expires.map(|TypedHeader(expires)| expires.into()),
+        )?;
+        let message = format!("audit:{}", uri.query().unwrap_or_default());
+        Self::verify_admin(
+            &state,
+            signature,
+            &expiring_message(message.as_bytes(), expires),
+        )?;
+
+        let pager = query.to_pager();
+        let records =
+            AuditModel::list(&state.pool, &query.to_filter(), &pager)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        Ok((
+            link_headers(&uri, &pager, &records)?,
+            encoding.encode(records),
+        ))
+    }
+}
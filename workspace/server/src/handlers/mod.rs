@@ -1,25 +1,153 @@
+mod admin;
 mod namespace;
 mod package;
 mod publisher;
 mod webhooks;
 
+pub(crate) use admin::AdminHandler;
 pub(crate) use namespace::NamespaceHandler;
 pub(crate) use package::PackageHandler;
 pub(crate) use publisher::PublisherHandler;
 
 use crate::Result;
-use k256::ecdsa::recoverable;
+use axum::http::{header::LINK, HeaderMap, HeaderValue, StatusCode, Uri};
+use ipfs_registry_core::{DefaultIdentity, Identity};
+use ipfs_registry_database::{Pager, ResultSet};
+use time::OffsetDateTime;
+use url::form_urlencoded;
 use web3_address::ethereum::Address;
 
-/// Verify a signature against a message and return the address.
+/// Maximum number of seconds a signature with an expiry
+/// may be valid for.
+const SIGNATURE_LIFETIME: i64 = 300;
+
+/// Verify a signature against a message and return the address
+/// using the identity system of the registry.
 pub(crate) fn verify_signature(
     signature: [u8; 65],
     message: &[u8],
 ) -> Result<Address> {
-    let recoverable: recoverable::Signature =
-        signature.as_slice().try_into()?;
-    let public_key = recoverable.recover_verifying_key(message)?;
-    let public_key: [u8; 33] = public_key.to_bytes().as_slice().try_into()?;
-    let address: Address = (&public_key).try_into()?;
-    Ok(address)
+    Ok(DefaultIdentity::verify(&signature, message)?)
+}
+
+/// Check the expiry of a signature.
+///
+/// A signature with an expiry is a bearer credential until it
+/// expires so the expiry must be in the future and no further
+/// away than the maximum lifetime.
+pub(crate) fn check_expiry(
+    expires: Option<i64>,
+) -> std::result::Result<i64, StatusCode> {
+    let expires = expires.ok_or(StatusCode::UNAUTHORIZED)?;
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    if expires <= now || expires > now + SIGNATURE_LIFETIME {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(expires)
+}
+
+/// Read a UTF-8 message from a request body.
+///
+/// Control characters other than line breaks and tabs are
+/// rejected as are messages longer than `max_length` bytes.
+pub(crate) fn read_message(
+    body: &[u8],
+    max_length: Option<usize>,
+) -> std::result::Result<&str, StatusCode> {
+    let message =
+        std::str::from_utf8(body).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if let Some(max_length) = max_length {
+        if message.len() > max_length {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    if message
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(message)
+}
+
+/// Build an RFC 8288 `Link` header value for a page of results.
+///
+/// Links keep the path and query of the request replacing
+/// the `offset` and `limit` parameters; any `cursor` is removed
+/// as it would take precedence over the offset.
+///
+/// When the request used a cursor the `next` link uses the
+/// cursor for the next page and the `prev` and `last` links
+/// are omitted as they cannot be reached from a cursor.
+pub(crate) fn pagination_links(
+    uri: &Uri,
+    pager: &Pager,
+    count: i64,
+    next_cursor: Option<&str>,
+) -> String {
+    let limit = pager.limit.max(1);
+    let offset = pager.offset.max(0);
+
+    let query: Vec<(String, String)> = uri
+        .query()
+        .map(|query| {
+            form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .filter(|(key, _)| {
+                    key != "offset" && key != "limit" && key != "cursor"
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let link_at = |position: (&str, &str), rel: &str| {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(query.iter())
+            .append_pair(position.0, position.1)
+            .append_pair("limit", &limit.to_string())
+            .finish();
+        format!("<{}?{}>; rel=\"{}\"", uri.path(), query, rel)
+    };
+    let link = |offset: i64, rel: &str| {
+        link_at(("offset", &offset.to_string()), rel)
+    };
+
+    let mut links = vec![link(0, "first")];
+    if pager.cursor.is_some() {
+        if let Some(cursor) = next_cursor {
+            links.push(link_at(("cursor", cursor), "next"));
+        }
+        return links.join(", ");
+    }
+    if offset > 0 {
+        links.push(link((offset - limit).max(0), "prev"));
+    }
+    if offset + limit < count {
+        links.push(link(offset + limit, "next"));
+    }
+    if count > 0 {
+        links.push(link(((count - 1) / limit) * limit, "last"));
+    }
+    links.join(", ")
+}
+
+/// Headers with pagination links for a list response.
+pub(crate) fn link_headers<T>(
+    uri: &Uri,
+    pager: &Pager,
+    records: &ResultSet<T>,
+) -> std::result::Result<HeaderMap, StatusCode> {
+    let links = pagination_links(
+        uri,
+        pager,
+        records.count,
+        records.next_cursor.as_deref(),
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        LINK,
+        HeaderValue::from_str(&links)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+    Ok(headers)
 }
@@ -1,18 +1,26 @@
 use axum::{
+    body::Bytes,
     extract::{Extension, Path, Query, TypedHeader},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use web3_address::ethereum::Address;
 
-use ipfs_registry_core::{Namespace, PackageName};
+use ipfs_registry_core::{
+    expiring_message, verify_work, Namespace, PackageName, RegistryKind,
+};
 use ipfs_registry_database::{
-    Error as DatabaseError, NamespaceModel, NamespaceRecord, PublisherModel,
+    Error as DatabaseError, NamespaceAccess, NamespaceModel, NamespaceRecord,
+    PublisherModel,
 };
 
 use crate::{
-    handlers::verify_signature, headers::Signature, server::ServerState,
+    encoding::{Encoded, Encoding},
+    handlers::{check_expiry, read_message, verify_signature},
+    headers::{Nonce, Signature, SignatureExpires},
+    server::{ServerState, State},
 };
 
 #[derive(Default, Debug, Deserialize)]
@@ -22,68 +30,235 @@ pub struct AddUserQuery {
     package: Option<PackageName>,
 }
 
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct RegisterQuery {
+    kind: Option<RegistryKind>,
+}
+
+/// Reason a namespace could not be registered.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ConflictKind {
+    /// Namespace with the same name already exists.
+    Exists,
+    /// Namespace with a different name but the same
+    /// confusable skeleton already exists.
+    Confusable,
+}
+
+/// Body of the conflict response when registering a namespace.
+#[derive(Debug, Serialize)]
+struct RegisterConflict {
+    error: ConflictKind,
+    /// Name of the existing namespace.
+    namespace: Namespace,
+    message: String,
+}
+
+impl RegisterConflict {
+    fn new(requested: &Namespace, existing: Namespace) -> Self {
+        if requested == &existing {
+            Self {
+                error: ConflictKind::Exists,
+                message: format!("namespace {} already exists", existing),
+                namespace: existing,
+            }
+        } else {
+            Self {
+                error: ConflictKind::Confusable,
+                message: format!(
+                    "namespace {} is confusable with existing namespace {}",
+                    requested, existing
+                ),
+                namespace: existing,
+            }
+        }
+    }
+}
+
+impl IntoResponse for RegisterConflict {
+    fn into_response(self) -> Response {
+        (StatusCode::CONFLICT, Json(self)).into_response()
+    }
+}
+
 pub(crate) struct NamespaceHandler;
 
 impl NamespaceHandler {
     /// Create a new namespace.
     pub(crate) async fn register(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
+        nonce: Option<TypedHeader<Nonce>>,
         Path(namespace): Path<Namespace>,
-    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        Query(query): Query<RegisterQuery>,
+    ) -> std::result::Result<Json<NamespaceRecord>, Response> {
         // FIXME: verify namespace is sane - no slashes!
 
         // Verify the signature header against supplied namespace
         let address =
             verify_signature(signature.into(), namespace.as_bytes())
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
+                .map_err(|_| StatusCode::BAD_REQUEST.into_response())?;
+
+        // Verify the proof of work for the namespace
+        if let Some(difficulty) = state.config.namespace_difficulty {
+            let TypedHeader(nonce) = nonce
+                .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())?;
+            if !verify_work(&namespace, nonce.into(), difficulty) {
+                return Err(StatusCode::UNAUTHORIZED.into_response());
+            }
+        }
 
         let publisher =
             PublisherModel::find_by_address(&state.pool, &address)
                 .await
-                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                .map_err(|_| {
+                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                })?;
 
         if let Some(publisher) = publisher {
             let record =
-                NamespaceModel::find_by_name(&state.pool, &namespace)
+                NamespaceModel::find_by_name_shallow(&state.pool, &namespace)
                     .await
-                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                    .map_err(|_| {
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    })?;
 
-            if record.is_some() {
-                return Err(StatusCode::CONFLICT);
+            // Distinguish an exact match from a confusable
+            // match so the caller knows which name is taken
+            if let Some(existing) = record {
+                return Err(RegisterConflict::new(&namespace, existing.name)
+                    .into_response());
             }
 
             let record = NamespaceModel::insert_fetch(
                 &state.pool,
                 &namespace,
                 publisher.publisher_id,
+                query.kind,
             )
             .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            .map_err(|e| match e {
+                // Registered by a concurrent request
+                DatabaseError::NamespaceExists(_) => {
+                    StatusCode::CONFLICT.into_response()
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            })?;
 
             Ok(Json(record))
         } else {
-            Err(StatusCode::UNAUTHORIZED)
+            Err(StatusCode::UNAUTHORIZED.into_response())
         }
     }
 
     /// Get a namespace record.
     pub(crate) async fn get_namespace(
         Extension(state): Extension<ServerState>,
+        encoding: Encoding,
         Path(namespace): Path<Namespace>,
-    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
-        let namespace_record =
-            NamespaceModel::find_by_name(&state.pool, &namespace)
+    ) -> std::result::Result<Encoded<NamespaceRecord>, StatusCode> {
+        let mut namespace_record =
+            NamespaceModel::find_by_name_shallow(&state.pool, &namespace)
                 .await
                 .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
                 .ok_or(StatusCode::NOT_FOUND)?;
-        Ok(Json(namespace_record))
+
+        let (package_count, version_count) = NamespaceModel::count_packages(
+            &state.pool,
+            namespace_record.namespace_id,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        namespace_record.package_count = Some(package_count);
+        namespace_record.version_count = Some(version_count);
+
+        Ok(encoding.encode(namespace_record))
+    }
+
+    /// Get the users of a namespace and the packages
+    /// each user is restricted to.
+    ///
+    /// The signature is of the action and namespace with an expiry
+    /// so it cannot be replayed from another endpoint or reused
+    /// once it expires.
+    pub(crate) async fn list_access(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        signature_expires: Option<TypedHeader<SignatureExpires>>,
+        encoding: Encoding,
+        Path(namespace): Path<Namespace>,
+    ) -> std::result::Result<Encoded<NamespaceAccess>, StatusCode> {
+        let expires = check_expiry(
+            signature_expires.map(|TypedHeader(expires)| expires.into()),
+        )?;
+        let message = expiring_message(
+            format!("access:{}", namespace).as_bytes(),
+            expires,
+        );
+        let caller = verify_signature(signature.into(), &message)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        match NamespaceModel::list_access(&state.pool, &namespace, &caller)
+            .await
+        {
+            Ok(access) => Ok(encoding.encode(access)),
+            Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Set the description for a namespace.
+    pub(crate) async fn set_description(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+        body: Bytes,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        // Sign the namespace so a description cannot be replayed
+        // against another namespace or package
+        let mut message = format!("{}:", namespace).into_bytes();
+        message.extend_from_slice(&body);
+        let caller = verify_signature(signature.into(), &message)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let description = read_message(&body, None)?;
+
+        match NamespaceModel::set_description(
+            &state.pool,
+            &caller,
+            &namespace,
+            description,
+        )
+        .await
+        {
+            Ok(record) => {
+                state.invalidate_namespace(&namespace);
+                tracing::info!(
+                    caller = %caller,
+                    namespace = %namespace,
+                    "namespace description changed");
+                Ok(Json(record))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::DescriptionLength(_) => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
     }
 
     /// Add a user to a namespace.
     pub(crate) async fn add_user(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
         Path((namespace, user)): Path<(Namespace, Address)>,
         Query(query): Query<AddUserQuery>,
     ) -> std::result::Result<StatusCode, StatusCode> {
@@ -107,7 +282,10 @@ impl NamespaceHandler {
         )
         .await
         {
-            Ok(_) => Ok(StatusCode::OK),
+            Ok(_) => {
+                state.invalidate_namespace(&namespace);
+                Ok(StatusCode::OK)
+            }
             Err(e) => Err(match e {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
@@ -120,7 +298,7 @@ impl NamespaceHandler {
     /// Remove a user from a namespace.
     pub(crate) async fn remove_user(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
         Path((namespace, user)): Path<(Namespace, Address)>,
     ) -> std::result::Result<StatusCode, StatusCode> {
         let caller = verify_signature(signature.into(), user.as_ref())
@@ -134,7 +312,10 @@ impl NamespaceHandler {
         )
         .await
         {
-            Ok(_) => Ok(StatusCode::OK),
+            Ok(_) => {
+                state.invalidate_namespace(&namespace);
+                Ok(StatusCode::OK)
+            }
             Err(e) => Err(match e {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
@@ -144,10 +325,48 @@ impl NamespaceHandler {
         }
     }
 
+    /// Transfer ownership of a namespace to another publisher.
+    pub(crate) async fn transfer(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path((namespace, new_owner)): Path<(Namespace, Address)>,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        // Sign the namespace so a signature cannot be replayed
+        // to transfer another namespace to the same owner
+        let message = format!("{}/{}", namespace, new_owner);
+        let caller = verify_signature(signature.into(), message.as_bytes())
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        match NamespaceModel::transfer_ownership(
+            &state.pool,
+            &namespace,
+            &caller,
+            &new_owner,
+        )
+        .await
+        {
+            Ok(record) => {
+                state.invalidate_namespace(&namespace);
+                tracing::info!(
+                    caller = %caller,
+                    namespace = %namespace,
+                    owner = %new_owner,
+                    "namespace ownership transferred");
+                Ok(Json(record))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                DatabaseError::NotFound(_)
+                | DatabaseError::UnknownPublisher(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
     /// Grant a user access to a package.
     pub(crate) async fn grant_access(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
         Path((namespace, user, package)): Path<(
             Namespace,
             Address,
@@ -166,7 +385,10 @@ impl NamespaceHandler {
         )
         .await
         {
-            Ok(_) => Ok(StatusCode::OK),
+            Ok(_) => {
+                state.invalidate_namespace(&namespace);
+                Ok(StatusCode::OK)
+            }
             Err(e) => Err(match e {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
@@ -181,7 +403,7 @@ impl NamespaceHandler {
     /// Revoke user access to a package.
     pub(crate) async fn revoke_access(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
         Path((namespace, user, package)): Path<(
             Namespace,
             Address,
@@ -200,7 +422,10 @@ impl NamespaceHandler {
         )
         .await
         {
-            Ok(_) => Ok(StatusCode::OK),
+            Ok(_) => {
+                state.invalidate_namespace(&namespace);
+                Ok(StatusCode::OK)
+            }
             Err(e) => Err(match e {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
@@ -211,4 +436,195 @@ impl NamespaceHandler {
             }),
         }
     }
+
+    /// Require downloads from a namespace to be signed.
+    pub(crate) async fn set_private(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        change_private(&state, signature, &namespace, true).await
+    }
+
+    /// Allow unsigned downloads from a namespace.
+    pub(crate) async fn set_public(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        change_private(&state, signature, &namespace, false).await
+    }
+
+    /// Require users without package restrictions to be granted
+    /// access before publishing to a package.
+    pub(crate) async fn set_restricted(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        change_default_restricted(&state, signature, &namespace, true).await
+    }
+
+    /// Allow users without package restrictions to publish
+    /// to every package.
+    pub(crate) async fn set_unrestricted(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        change_default_restricted(&state, signature, &namespace, false).await
+    }
+
+    /// Deprecate a namespace.
+    pub(crate) async fn deprecate(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+        body: Bytes,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        // Name the action and the namespace so a signature cannot
+        // be replayed against another namespace
+        let mut signed = format!("deprecate:{}:", namespace).into_bytes();
+        signed.extend_from_slice(&body);
+        let caller = verify_signature(signature.into(), &signed)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let message = read_message(
+            &body,
+            Some(state.config.registry.max_message_length),
+        )?;
+
+        let result = NamespaceModel::deprecate(
+            &state.pool,
+            &caller,
+            &namespace,
+            message,
+        )
+        .await;
+        deprecation_changed(&state, &caller, &namespace, result)
+    }
+
+    /// Remove the deprecation notice for a namespace.
+    pub(crate) async fn undeprecate(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path(namespace): Path<Namespace>,
+    ) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+        // Name the action so a signature over the namespace made
+        // for another request cannot be replayed
+        let message = format!("undeprecate:{}", namespace);
+        let caller = verify_signature(signature.into(), message.as_bytes())
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let result =
+            NamespaceModel::undeprecate(&state.pool, &caller, &namespace)
+                .await;
+        deprecation_changed(&state, &caller, &namespace, result)
+    }
+}
+
+/// Respond to a change in the deprecation of a namespace.
+fn deprecation_changed(
+    state: &State,
+    caller: &Address,
+    namespace: &Namespace,
+    result: std::result::Result<NamespaceRecord, DatabaseError>,
+) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+    match result {
+        Ok(record) => {
+            state.invalidate_namespace(namespace);
+            tracing::info!(
+                caller = %caller,
+                namespace = %namespace,
+                deprecated = ?record.deprecated,
+                "namespace deprecation changed");
+            Ok(Json(record))
+        }
+        Err(e) => Err(match e {
+            DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }),
+    }
+}
+
+/// Change whether downloads from a namespace must be signed.
+async fn change_private(
+    state: &State,
+    signature: Signature,
+    namespace: &Namespace,
+    private: bool,
+) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+    // Name the action so a signature over the namespace made for
+    // another request cannot be replayed to change the visibility
+    let action = if private { "private" } else { "public" };
+    let message = format!("{}:{}", action, namespace);
+    let caller = verify_signature(signature.into(), message.as_bytes())
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match NamespaceModel::set_private(
+        &state.pool,
+        &caller,
+        namespace,
+        private,
+    )
+    .await
+    {
+        Ok(record) => {
+            state.invalidate_namespace(namespace);
+            tracing::info!(
+                caller = %caller,
+                namespace = %namespace,
+                private = %private,
+                "namespace visibility changed");
+            Ok(Json(record))
+        }
+        Err(e) => Err(match e {
+            DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }),
+    }
+}
+
+/// Change whether users without package restrictions must be
+/// granted access before publishing.
+async fn change_default_restricted(
+    state: &State,
+    signature: Signature,
+    namespace: &Namespace,
+    default_restricted: bool,
+) -> std::result::Result<Json<NamespaceRecord>, StatusCode> {
+    let action = if default_restricted {
+        "restricted"
+    } else {
+        "unrestricted"
+    };
+    let message = format!("{}:{}", action, namespace);
+    let caller = verify_signature(signature.into(), message.as_bytes())
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match NamespaceModel::set_default_restricted(
+        &state.pool,
+        &caller,
+        namespace,
+        default_restricted,
+    )
+    .await
+    {
+        Ok(record) => {
+            state.invalidate_namespace(namespace);
+            tracing::info!(
+                caller = %caller,
+                namespace = %namespace,
+                default_restricted = %default_restricted,
+                "namespace default restriction changed");
+            Ok(Json(record))
+        }
+        Err(e) => Err(match e {
+            DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }),
+    }
 }
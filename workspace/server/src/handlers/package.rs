@@ -1,62 +1,208 @@
 use axum::{
-    body::Bytes,
+    body::{Bytes, StreamBody},
     extract::{Extension, Path, Query, TypedHeader},
     headers::ContentType,
-    http::{HeaderMap, StatusCode},
+    http::{
+        header::{
+            CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LENGTH,
+            CONTENT_TYPE, RETRY_AFTER,
+        },
+        HeaderMap, HeaderValue, StatusCode, Uri,
+    },
+    response::{IntoResponse, Response},
     Json,
 };
+use futures::{future, stream, Stream, StreamExt};
+use std::sync::Arc;
 
 //use axum_macros::debug_handler;
 
-use semver::VersionReq;
-use serde::Deserialize;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::{Digest, Sha3_256};
+use time::OffsetDateTime;
+use web3_address::ethereum::Address;
 
 use ipfs_registry_core::{
-    Artifact, Definition, Namespace, ObjectKey, PackageKey, PackageName,
-    PackageReader, PackageSignature, Pointer, Receipt,
+    download_message, validate_id, ArchiveFormat, Artifact, Definition,
+    Error as CoreError, Namespace, ObjectKey, PackageKey, PackageMeta,
+    PackageName, PackageReader, PackageSignature, Pointer, Receipt,
+    RegistryKind, SignatureScheme, SignedUrl, StoredObject, VersionDiff,
 };
 
 use ipfs_registry_database::{
-    default_limit, Error as DatabaseError, NamespaceModel, PackageModel,
-    PackageRecord, Pager, ResultSet, SortOrder, VersionIncludes,
-    VersionRecord,
+    default_include_yanked, default_limit, DependentRecord,
+    Error as DatabaseError, NamespaceModel, NamespaceRecord, PackageModel,
+    PackageRecord, Pager, PublishCheck, PublishDenied, PublisherModel,
+    PublisherRecord, ResultSet, SortOrder, TransferSigners, VersionIncludes,
+    VersionLocation, VersionRecord,
 };
 
 use crate::{
+    decode::decode_body,
+    encoding::{Encoded, Encoding},
     handlers::{
-        verify_signature,
+        check_expiry, link_headers, read_message, verify_signature,
         webhooks::{
             execute_webhooks, WebHookBody, WebHookEvent, WebHookPacket,
         },
     },
-    headers::Signature,
-    server::ServerState,
+    headers::{
+        Checksum, OptionalSignature, Signature, SignatureExpires,
+        TargetSignature,
+    },
+    layer::PendingWrites,
+    query::{validate_cursor, validate_pager, ValidQuery, Validate},
+    scan::{scan, ScanOutcome},
+    server::{ServerState, State},
+    signed_url, Error, Result,
 };
 
+/// Body of the response when a manifest fails validation.
+#[derive(Debug, Serialize)]
+pub(crate) struct InvalidManifest {
+    /// Name of the missing or invalid field.
+    field: String,
+    message: String,
+}
+
+impl InvalidManifest {
+    fn new(field: String) -> Self {
+        Self {
+            message: format!(
+                "manifest field {} is missing or invalid",
+                field
+            ),
+            field,
+        }
+    }
+}
+
+/// Body of the conflict response when publishing a version
+/// that was yanked.
+#[derive(Debug, Serialize)]
+pub(crate) struct YankedConflict {
+    reason: PublishDenied,
+    message: String,
+}
+
+impl YankedConflict {
+    fn new(message: String) -> Self {
+        Self {
+            reason: PublishDenied::VersionYanked,
+            message,
+        }
+    }
+}
+
+/// Rejection for a publish request.
+pub(crate) enum PublishRejection {
+    /// Rejected with a status code.
+    Status(StatusCode),
+    /// Manifest failed validation.
+    Manifest(InvalidManifest),
+    /// Version was yanked and cannot be published again.
+    Yanked(YankedConflict),
+}
+
+impl From<StatusCode> for PublishRejection {
+    fn from(status: StatusCode) -> Self {
+        Self::Status(status)
+    }
+}
+
+impl IntoResponse for PublishRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Status(status) => status.into_response(),
+            Self::Manifest(body) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+            }
+            Self::Yanked(body) => {
+                (StatusCode::CONFLICT, Json(body)).into_response()
+            }
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PackageQuery {
     id: PackageKey,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FetchQuery {
+    id: PackageKey,
+    expires: Option<i64>,
+    token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignUrlQuery {
+    id: PackageKey,
+    expires_in: Option<u64>,
+}
+
 #[derive(Default, Debug, Deserialize)]
 #[serde(default)]
 pub struct ListPackagesQuery {
     include: VersionIncludes,
+    #[serde(default = "default_include_yanked")]
+    include_yanked: bool,
     // NOTE: cannot use #[serde(flatten)]
     // SEE: https://github.com/tokio-rs/axum/issues/1366
     offset: i64,
     #[serde(default = "default_limit")]
     limit: i64,
     sort: SortOrder,
+    cursor: Option<String>,
+}
+
+impl Validate for ListPackagesQuery {
+    fn validate(&self) -> std::result::Result<(), String> {
+        validate_cursor(self.cursor.as_deref())?;
+        validate_pager(self.offset, self.limit)
+    }
 }
 
 impl ListPackagesQuery {
-    fn into_pager(&self) -> Pager {
+    fn to_pager(&self) -> Pager {
         Pager {
             offset: self.offset,
             limit: self.limit,
             sort: self.sort,
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    q: String,
+    offset: i64,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    sort: SortOrder,
+}
+
+impl Validate for SearchQuery {
+    fn validate(&self) -> std::result::Result<(), String> {
+        if !validate_id(&self.q) {
+            return Err(format!("invalid search query {:?}", self.q));
+        }
+        validate_pager(self.offset, self.limit)
+    }
+}
+
+impl SearchQuery {
+    fn to_pager(&self) -> Pager {
+        Pager {
+            offset: self.offset,
+            limit: self.limit,
+            sort: self.sort,
+            cursor: None,
         }
     }
 }
@@ -65,67 +211,1166 @@ impl ListPackagesQuery {
 #[serde(default)]
 pub struct ListVersionsQuery {
     range: Option<VersionReq>,
+    #[serde(default = "default_include_yanked")]
+    include_yanked: bool,
     offset: i64,
     #[serde(default = "default_limit")]
     limit: i64,
-    sort: SortOrder,
+    sort: Option<SortOrder>,
+    cursor: Option<String>,
+}
+
+impl Validate for ListVersionsQuery {
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.range.is_some() && self.cursor.is_some() {
+            return Err(String::from("cursor cannot be used with a range"));
+        }
+        validate_cursor(self.cursor.as_deref())?;
+        validate_pager(self.offset, self.limit)
+    }
 }
 
 impl ListVersionsQuery {
-    fn into_pager(&self) -> Pager {
+    /// Pager for the query using the default sort order
+    /// when no sort is given.
+    fn to_pager(&self, default_sort: SortOrder) -> Pager {
         Pager {
             offset: self.offset,
             limit: self.limit,
-            sort: self.sort,
+            sort: self.sort.unwrap_or(default_sort),
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct LatestQuery {
+    prerelease: bool,
+    range: Option<VersionReq>,
+    #[serde(default = "default_include_yanked")]
+    include_yanked: bool,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct PublishQuery {
+    force: bool,
+    verbose: bool,
+}
+
+impl Validate for LatestQuery {}
+
+#[derive(Debug, Deserialize)]
+pub struct ChecksumQuery {
+    sha3: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    from: Version,
+    to: Version,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct BundleQuery {
+    range: Option<VersionReq>,
+}
+
+impl Validate for BundleQuery {}
+
+#[derive(Debug, Deserialize)]
+pub struct CanPublishQuery {
+    address: Address,
+    version: Option<Version>,
+}
+
+impl Validate for CanPublishQuery {}
+
+/// Name of the manifest entry in a bundle archive.
+const BUNDLE_MANIFEST: &str = "versions.json";
+
+/// Size of a tar block.
+const BLOCK_SIZE: usize = 512;
+
+/// Encode a file entry for a tar archive.
+fn tar_entry(path: &str, data: &[u8]) -> Result<Bytes> {
+    let mut header = tar::Header::new_gnu();
+    header.set_path(path)?;
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    let padding = (BLOCK_SIZE - data.len() % BLOCK_SIZE) % BLOCK_SIZE;
+    let mut buffer = Vec::with_capacity(BLOCK_SIZE + data.len() + padding);
+    buffer.extend_from_slice(header.as_bytes());
+    buffer.extend_from_slice(data);
+    buffer.resize(buffer.len() + padding, 0);
+    Ok(Bytes::from(buffer))
+}
+
+/// Find the stored manifest for a package version.
+async fn version_manifest(
+    state: &State,
+    namespace: &Namespace,
+    package: &PackageName,
+    version: &Version,
+) -> std::result::Result<Value, StatusCode> {
+    let id = PackageKey::Pointer(
+        namespace.clone(),
+        package.clone(),
+        version.clone(),
+    );
+    match PackageModel::find_by_key(
+        &state.pool,
+        &id,
+        state.config.registry.duplicate_cid,
+    )
+    .await
+    {
+        Ok((_, _, record)) => {
+            let record = record.ok_or(StatusCode::NOT_FOUND)?;
+            record.package.ok_or(StatusCode::NOT_FOUND)
+        }
+        Err(e) => Err(match e {
+            DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }),
+    }
+}
+
+/// Verify a download from a private namespace is signed
+/// by a user of the namespace.
+async fn authorize_download(
+    state: &State,
+    namespace: &NamespaceRecord,
+    signature: Option<Signature>,
+    expires: Option<i64>,
+    message: &[u8],
+) -> std::result::Result<(), StatusCode> {
+    if !namespace.private {
+        return Ok(());
+    }
+
+    let signature = signature.ok_or(StatusCode::UNAUTHORIZED)?;
+    let expires = check_expiry(expires)?;
+
+    let message = download_message(message, expires);
+    let address = verify_signature(signature.into(), &message)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    state
+        .can_access_namespace(&address, &namespace.name)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::Unauthorized(_) | DatabaseError::NotFound(_) => {
+                StatusCode::UNAUTHORIZED
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+
+    Ok(())
+}
+
+/// Remove the objects stored for an artifact when the version
+/// could not be recorded so that storage is not orphaned.
+///
+/// Best effort, failures are logged; the objects are kept when
+/// a concurrent publish recorded the same version and the content
+/// identifier is kept when a recorded version shares the content.
+async fn remove_orphaned_artifact(
+    state: &State,
+    artifact: &Artifact,
+    stored: &[StoredObject],
+) {
+    let key = PackageKey::Pointer(
+        artifact.namespace.clone(),
+        artifact.package.name.clone(),
+        artifact.package.version.clone(),
+    );
+    if let Ok((_, _, Some(_))) = PackageModel::find_by_key(
+        &state.pool,
+        &key,
+        state.config.registry.duplicate_cid,
+    )
+    .await
+    {
+        return;
+    }
+
+    let content_id = stored.iter().find_map(|o| {
+        if let ObjectKey::Cid(value) = &o.key {
+            Some(*value)
+        } else {
+            None
+        }
+    });
+
+    // Removing shared content would unpin a recorded version
+    let content_id = match content_id {
+        Some(cid) => {
+            match PackageModel::is_content_referenced(&state.pool, &cid, None)
+                .await
+            {
+                Ok(false) => Some(cid),
+                _ => None,
+            }
+        }
+        None => None,
+    };
+
+    match state
+        .layers
+        .remove(&artifact.pointer_id(), content_id.as_ref(), stored)
+        .await
+    {
+        Ok(_) => tracing::warn!(id = %key, "removed orphaned artifact"),
+        Err(e) => tracing::error!(
+            id = %key,
+            error = %e,
+            "failed to remove orphaned artifact"),
+    }
+}
+
+/// Restore the previous artifact for a version when an overwrite
+/// could not be recorded so the stored artifact matches the
+/// checksum of the version.
+///
+/// The replacement is stored under the same pointer identifier so
+/// it is removed first; content identifiers are derived from the
+/// content so the previous content identifier is still valid.
+///
+/// Best effort, failures are logged.
+async fn restore_artifact(
+    state: &State,
+    artifact: &Artifact,
+    previous: Option<Vec<u8>>,
+) {
+    let pointer_id = artifact.pointer_id();
+    if let Err(e) = state.layers.remove(&pointer_id, None, &[]).await {
+        tracing::error!(
+            pointer_id = %pointer_id,
+            error = %e,
+            "failed to remove replacement artifact");
+    }
+
+    if let Some(data) = previous {
+        match state.layers.publish(Bytes::from(data), artifact).await {
+            Ok((_, pending)) => {
+                retry_writes(state, pending);
+                tracing::warn!(
+                    pointer_id = %pointer_id,
+                    "restored previous artifact");
+            }
+            Err(e) => tracing::error!(
+                pointer_id = %pointer_id,
+                error = %e,
+                "failed to restore previous artifact"),
+        }
+    }
+}
+
+/// Retry the writes to storage layers that failed when publishing
+/// an artifact for a recorded version and add the objects stored
+/// by the retries to the version.
+pub(crate) fn retry_writes(state: &State, pending: PendingWrites) {
+    let pool = state.pool.clone();
+    let pointer_id = pending.pointer_id();
+    pending.retry(Arc::new(move |object| {
+        let pool = pool.clone();
+        let pointer_id = pointer_id.clone();
+        Box::pin(async move {
+            if let Err(e) =
+                PackageModel::add_version_object(&pool, &pointer_id, &object)
+                    .await
+            {
+                tracing::error!(
+                    pointer_id = %pointer_id,
+                    error = %e,
+                    "failed to record retried write");
+            }
+        })
+    }));
+}
+
+/// Map an error from transferring a package to a status code.
+fn transfer_error(e: DatabaseError) -> StatusCode {
+    match e {
+        DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+        DatabaseError::PackageNameExists(_, _) => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Artifact descriptor for a version of a package in a namespace.
+fn version_artifact(
+    namespace: &Namespace,
+    name: &PackageName,
+    record: &VersionRecord,
+) -> Artifact {
+    Artifact {
+        kind: record.kind.unwrap_or_default(),
+        namespace: namespace.clone(),
+        package: PackageMeta {
+            name: name.clone(),
+            version: record.version.clone(),
+        },
+    }
+}
+
+/// Store the artifacts for versions of a package under the
+/// pointer identifiers for another namespace.
+///
+/// Purged versions have no artifact so only the pointer changes;
+/// when a copy fails the copies already stored are removed.
+///
+/// Writes to retry are returned so they can be started once
+/// the new locations have been recorded.
+pub(crate) async fn relocate_artifacts(
+    state: &State,
+    namespace: &Namespace,
+    name: &PackageName,
+    versions: &[VersionRecord],
+) -> std::result::Result<(Vec<VersionLocation>, Vec<PendingWrites>), StatusCode>
+{
+    let mut locations = Vec::new();
+    let mut retries = Vec::new();
+    for (index, record) in versions.iter().enumerate() {
+        let artifact = version_artifact(namespace, name, record);
+        let objects = if record.purged_at.is_some() {
+            vec![]
+        } else {
+            let copy = async {
+                let data = state
+                    .layers
+                    .fetch(
+                        &record.pointer_id,
+                        record.content_id.as_ref(),
+                        &record.objects,
+                        Some(&record.checksum),
+                    )
+                    .await?;
+                state.layers.publish(Bytes::from(data), &artifact).await
+            };
+            match copy.await {
+                Ok((objects, pending)) => {
+                    retries.push(pending);
+                    objects
+                }
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    remove_relocated_artifacts(
+                        state,
+                        namespace,
+                        name,
+                        &versions[..index],
+                    )
+                    .await;
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        };
+        locations.push(VersionLocation {
+            version_id: record.version_id,
+            pointer_id: artifact.pointer_id(),
+            objects,
+        });
+    }
+    Ok((locations, retries))
+}
+
+/// Remove the artifacts stored for versions of a package under
+/// the pointer identifiers for a namespace that does not have
+/// the package.
+///
+/// Content identifiers are shared between namespaces so only
+/// the objects keyed by pointer are removed.
+pub(crate) async fn remove_relocated_artifacts(
+    state: &State,
+    namespace: &Namespace,
+    name: &PackageName,
+    versions: &[VersionRecord],
+) {
+    for record in versions.iter().filter(|v| v.purged_at.is_none()) {
+        let artifact = version_artifact(namespace, name, record);
+        remove_orphaned_artifact(state, &artifact, &[]).await;
+    }
+}
+
+/// Fetch and verify the artifact for a package version
+/// and prepare the response headers.
+///
+/// A `presigned` download was authorized by a signed URL so
+/// a signature is not required for private namespaces.
+async fn fetch_artifact(
+    state: &State,
+    key: &PackageKey,
+    signature: Option<Signature>,
+    expires: Option<i64>,
+    presigned: bool,
+) -> std::result::Result<(HeaderMap, Bytes), Response> {
+    if let (PackageKey::Cid(cid), Some(policy)) =
+        (key, &state.config.registry.cid)
+    {
+        policy
+            .check(cid)
+            .map_err(|_| StatusCode::BAD_REQUEST.into_response())?;
+    }
+
+    match PackageModel::find_by_key(
+        &state.pool,
+        key,
+        state.config.registry.duplicate_cid,
+    )
+    .await
+    {
+        Ok((namespace_record, package_record, record)) => {
+            let record = record
+                .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+
+            if let (Some(namespace_record), false) =
+                (&namespace_record, presigned)
+            {
+                authorize_download(
+                    state,
+                    namespace_record,
+                    signature,
+                    expires,
+                    key.to_string().as_bytes(),
+                )
+                .await
+                .map_err(|status| status.into_response())?;
+            }
+
+            // Artifact was removed by the retention policy
+            if record.purged_at.is_some() {
+                return Err(StatusCode::GONE.into_response());
+            }
+
+            if let Some(limiter) = &state.fetch_limiter {
+                if let Err(retry_after) = limiter.acquire(record.package_id) {
+                    return Err((
+                        StatusCode::TOO_MANY_REQUESTS,
+                        [(RETRY_AFTER, retry_after.to_string())],
+                    )
+                        .into_response());
+                }
+            }
+
+            // Verify the checksum, a corrupt mirror falls
+            // through to the next storage layer
+            let body = state
+                .layers
+                .fetch(
+                    &record.pointer_id,
+                    record.content_id.as_ref(),
+                    &record.objects,
+                    Some(record.checksum.as_slice()),
+                )
+                .await
+                .map_err(|e| match e {
+                    Error::Checksum(_) => {
+                        StatusCode::UNPROCESSABLE_ENTITY.into_response()
+                    }
+                    _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+                })?;
+
+            verify_artifact(state, &record, &body).await.map_err(
+                |e| match e {
+                    Error::Database(_) => {
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                    _ => StatusCode::UNPROCESSABLE_ENTITY.into_response(),
+                },
+            )?;
+
+            // Versions published before the kind was recorded
+            // use the kind for the registry
+            let kind = record.kind.unwrap_or(state.config.registry.kind);
+
+            // Python packages may be a wheel or a source distribution
+            let (mime_type, extension) =
+                match (kind, ArchiveFormat::detect(&body)) {
+                    (RegistryKind::Pypi, Some(ArchiveFormat::TarGzip)) => {
+                        (ArchiveFormat::TarGzip.mime_type(), "tar.gz")
+                    }
+                    _ => (kind.mime_type(), kind.extension()),
+                };
+
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(mime_type));
+            headers.insert(
+                CONTENT_LENGTH,
+                HeaderValue::from(record.size.unwrap_or(body.len() as u64)),
+            );
+            if let Some(package_record) = &package_record {
+                let file_name = format!(
+                    "{}-{}.{}",
+                    package_record.name, record.version, extension
+                );
+                headers.insert(
+                    CONTENT_DISPOSITION,
+                    HeaderValue::from_str(&format!(
+                        "attachment; filename=\"{}\"",
+                        file_name
+                    ))
+                    .map_err(|_| {
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    })?,
+                );
+            }
+
+            // Count the download without delaying the response
+            let pool = state.pool.clone();
+            let version_id = record.version_id;
+            tokio::spawn(async move {
+                if let Err(e) =
+                    PackageModel::increment_downloads(&pool, version_id).await
+                {
+                    tracing::warn!(
+                        version_id = %version_id,
+                        error = %e,
+                        "failed to count download");
+                }
+            });
+
+            if let Some(hooks) = state.config.webhooks.clone() {
+                let body = WebHookBody { inner: record };
+                let packet = WebHookPacket {
+                    event: WebHookEvent::Fetch,
+                    body,
+                };
+                let namespace = namespace_record.map(|record| record.name);
+                tokio::spawn(execute_webhooks(hooks, namespace, packet));
+            }
+
+            Ok((headers, Bytes::from(body)))
+        }
+        Err(e) => Err(match e {
+            DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+            DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+        .into_response()),
+    }
+}
+
+/// Determine if signature verification may be skipped for a
+/// fetched artifact whose checksum has already been verified.
+fn skip_signature(state: &State, record: &VersionRecord) -> bool {
+    state.config.registry.trust_content_id && record.content_id.is_some()
+}
+
+/// Verify the signature of a fetched artifact was created by the
+/// publisher of the version.
+///
+/// Signatures from keys the publisher has rotated out are accepted
+/// so versions published before a key rotation remain verifiable.
+async fn verify_artifact(
+    state: &State,
+    record: &VersionRecord,
+    body: &[u8],
+) -> Result<()> {
+    if skip_signature(state, record) {
+        return Ok(());
+    }
+
+    let signer = verify_signature(
+        record.signature,
+        record.signature_scheme.message(body, &record.checksum),
+    )?;
+
+    if !PublisherModel::is_signing_key(
+        &state.pool,
+        record.publisher_id,
+        &signer,
+    )
+    .await?
+    {
+        return Err(Error::UnknownSigner(signer));
+    }
+
+    Ok(())
+}
+
+/// Fetch and verify a version artifact and encode it as a tar entry.
+async fn bundle_entry(
+    state: &State,
+    name: &PackageName,
+    record: &VersionRecord,
+) -> Result<Bytes> {
+    let body = state
+        .layers
+        .fetch(
+            &record.pointer_id,
+            record.content_id.as_ref(),
+            &record.objects,
+            Some(record.checksum.as_slice()),
+        )
+        .await?;
+
+    verify_artifact(state, record, &body).await?;
+
+    // Each artifact in a bundle is a download of the version
+    if let Err(e) =
+        PackageModel::increment_downloads(&state.pool, record.version_id)
+            .await
+    {
+        tracing::warn!(
+            version_id = %record.version_id,
+            error = %e,
+            "failed to count download");
+    }
+
+    let kind = record.kind.unwrap_or(state.config.registry.kind);
+    tar_entry(
+        &format!("{}-{}.{}", name, record.version, kind.extension()),
+        &body,
+    )
+}
+
+pub(crate) struct PackageHandler;
+
+impl PackageHandler {
+    /// Get a package record.
+    pub(crate) async fn get_package(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+    ) -> std::result::Result<Encoded<PackageRecord>, StatusCode> {
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(&state.pool, &namespace)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+
+        let package_record = PackageModel::find_by_name(
+            &state.pool,
+            namespace_record.namespace_id,
+            &package,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+        Ok(encoding.encode(package_record))
+    }
+
+    /// List packages for a namespace.
+    pub(crate) async fn list_packages(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        Path(namespace): Path<Namespace>,
+        ValidQuery(query): ValidQuery<ListPackagesQuery>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<PackageRecord>>),
+        StatusCode,
+    > {
+        let pager = query.to_pager();
+
+        match PackageModel::list_packages(
+            &state.pool,
+            &namespace,
+            &pager,
+            query.include,
+            query.include_yanked,
+        )
+        .await
+        {
+            Ok(records) => Ok((
+                link_headers(&uri, &pager, &records)?,
+                encoding.encode(records),
+            )),
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::InvalidCursor(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Search packages in all namespaces by name prefix.
+    pub(crate) async fn search(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        ValidQuery(query): ValidQuery<SearchQuery>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<PackageRecord>>),
+        StatusCode,
+    > {
+        let pager = query.to_pager();
+        let records = PackageModel::search(&state.pool, &query.q, &pager)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        Ok((
+            link_headers(&uri, &pager, &records)?,
+            encoding.encode(records),
+        ))
+    }
+
+    /// List the versions of packages that depend on a package.
+    pub(crate) async fn list_dependents(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        ValidQuery(pager): ValidQuery<Pager>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<DependentRecord>>),
+        StatusCode,
+    > {
+        match PackageModel::list_dependents(
+            &state.pool,
+            &namespace,
+            &package,
+            &pager,
+        )
+        .await
+        {
+            Ok(records) => Ok((
+                link_headers(&uri, &pager, &records)?,
+                encoding.encode(records),
+            )),
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// List versions for a namespace and package.
+    pub(crate) async fn list_versions(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        ValidQuery(query): ValidQuery<ListVersionsQuery>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<VersionRecord>>),
+        StatusCode,
+    > {
+        let pager =
+            query.to_pager(state.config.registry.default_version_sort);
+
+        let result = if let Some(range) = query.range {
+            PackageModel::find_versions(
+                &state.pool,
+                &namespace,
+                &package,
+                &range,
+                &pager,
+                query.include_yanked,
+            )
+            .await
+        } else {
+            PackageModel::list_versions(
+                &state.pool,
+                &namespace,
+                &package,
+                &pager,
+                query.include_yanked,
+            )
+            .await
+        };
+
+        match result {
+            Ok(records) => Ok((
+                link_headers(&uri, &pager, &records)?,
+                encoding.encode(records),
+            )),
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::InvalidCursor(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Get the latest version of a package.
+    pub(crate) async fn latest_version(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        ValidQuery(latest): ValidQuery<LatestQuery>,
+    ) -> std::result::Result<Encoded<VersionRecord>, StatusCode> {
+        let result = if let Some(range) = &latest.range {
+            PackageModel::find_best_match(
+                &state.pool,
+                &namespace,
+                &package,
+                range,
+                latest.include_yanked,
+            )
+            .await
+        } else {
+            PackageModel::find_latest_by_name(
+                &state.pool,
+                &namespace,
+                &package,
+                latest.prerelease,
+                latest.include_yanked,
+            )
+            .await
+        };
+
+        match result {
+            Ok(record) => {
+                let record = record.ok_or(StatusCode::NOT_FOUND)?;
+                Ok(encoding.encode(record))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Get the exact version of a package.
+    pub(crate) async fn exact_version(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        Query(query): Query<PackageQuery>,
+    ) -> std::result::Result<Encoded<VersionRecord>, StatusCode> {
+        match PackageModel::find_by_key(
+            &state.pool,
+            &query.id,
+            state.config.registry.duplicate_cid,
+        )
+        .await
+        {
+            Ok((_, _, record)) => {
+                let mut record = record.ok_or(StatusCode::NOT_FOUND)?;
+                record.signers = PublisherModel::signing_keys(
+                    &state.pool,
+                    record.publisher_id,
+                )
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                Ok(encoding.encode(record))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Find the versions that match a package archive checksum.
+    pub(crate) async fn find_by_checksum(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        Query(query): Query<ChecksumQuery>,
+    ) -> std::result::Result<Encoded<Vec<VersionRecord>>, StatusCode> {
+        let checksum: [u8; 32] = hex::decode(&query.sha3)
+            .ok()
+            .and_then(|checksum| checksum.try_into().ok())
+            .ok_or(StatusCode::BAD_REQUEST)?;
+
+        let records = PackageModel::find_by_checksum(&state.pool, &checksum)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        if records.is_empty() {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Ok(encoding.encode(records))
+    }
+
+    /// Compare the manifests of two versions of a package.
+    pub(crate) async fn diff(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        Query(query): Query<DiffQuery>,
+    ) -> std::result::Result<Encoded<VersionDiff>, StatusCode> {
+        let before =
+            version_manifest(&state, &namespace, &package, &query.from)
+                .await?;
+        let after =
+            version_manifest(&state, &namespace, &package, &query.to).await?;
+        Ok(encoding
+            .encode(VersionDiff::new(query.from, query.to, &before, &after)))
+    }
+
+    /// Get the stored manifest for an exact version of a package.
+    pub(crate) async fn manifest(
+        Extension(state): Extension<ServerState>,
+        Query(query): Query<PackageQuery>,
+    ) -> std::result::Result<Json<Value>, StatusCode> {
+        match PackageModel::find_by_key(
+            &state.pool,
+            &query.id,
+            state.config.registry.duplicate_cid,
+        )
+        .await
+        {
+            Ok((_, _, record)) => {
+                let record = record.ok_or(StatusCode::NOT_FOUND)?;
+                let manifest = record.package.ok_or(StatusCode::NOT_FOUND)?;
+                Ok(Json(manifest))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Get the object keys stored in each layer for an exact
+    /// version of a package.
+    pub(crate) async fn objects(
+        Extension(state): Extension<ServerState>,
+        Query(query): Query<PackageQuery>,
+    ) -> std::result::Result<Json<Vec<StoredObject>>, StatusCode> {
+        match PackageModel::find_by_key(
+            &state.pool,
+            &query.id,
+            state.config.registry.duplicate_cid,
+        )
+        .await
+        {
+            Ok((_, _, record)) => {
+                let record = record.ok_or(StatusCode::NOT_FOUND)?;
+                Ok(Json(record.objects))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Deprecate a package.
+    pub(crate) async fn deprecate(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        body: Bytes,
+    ) -> std::result::Result<StatusCode, StatusCode> {
+        let address = verify_signature(signature.into(), &body)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let message = read_message(
+            &body,
+            Some(state.config.registry.max_message_length),
+        )?;
+
+        match PackageModel::deprecate(
+            &state.pool,
+            &address,
+            &namespace,
+            &package,
+            &message,
+        )
+        .await
+        {
+            Ok(_) => Ok(StatusCode::OK),
+            Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Set the description for a package.
+    pub(crate) async fn set_description(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        body: Bytes,
+    ) -> std::result::Result<Json<PackageRecord>, StatusCode> {
+        // Sign the package so a description cannot be replayed
+        // against another package or namespace
+        let mut message = format!("{}/{}:", namespace, package).into_bytes();
+        message.extend_from_slice(&body);
+        let caller = verify_signature(signature.into(), &message)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let description = read_message(&body, None)?;
+
+        match PackageModel::set_description(
+            &state.pool,
+            &caller,
+            &namespace,
+            &package,
+            description,
+        )
+        .await
+        {
+            Ok(record) => {
+                tracing::info!(
+                    caller = %caller,
+                    namespace = %namespace,
+                    package = %package,
+                    "package description changed");
+                Ok(Json(record))
+            }
+            Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::DescriptionLength(_) => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Transfer a package to another namespace.
+    ///
+    /// The owner of the source namespace signs the `x-signature`
+    /// header and the owner of the target namespace signs the
+    /// `x-target-signature` header; a registry administrator
+    /// may sign the transfer alone.
+    pub(crate) async fn transfer(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        target_signature: Option<TypedHeader<TargetSignature>>,
+        Path((namespace, package, target)): Path<(
+            Namespace,
+            PackageName,
+            Namespace,
+        )>,
+    ) -> std::result::Result<Json<PackageRecord>, StatusCode> {
+        // Sign the package and target so a signature cannot be
+        // replayed to move another package
+        let message = format!("{}/{}/{}", namespace, package, target);
+        let address = verify_signature(signature.into(), message.as_bytes())
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let administrator = state
+            .config
+            .registry
+            .admins
+            .as_ref()
+            .map(|admins| admins.contains(&address))
+            .unwrap_or(false);
+        let signers = if administrator {
+            TransferSigners::Administrator(address)
+        } else {
+            let TypedHeader(target_signature) =
+                target_signature.ok_or(StatusCode::UNAUTHORIZED)?;
+            let to =
+                verify_signature(target_signature.into(), message.as_bytes())
+                    .map_err(|_| StatusCode::BAD_REQUEST)?;
+            TransferSigners::Owners { from: address, to }
+        };
+
+        let (_, package_record) = PackageModel::can_transfer(
+            &state.pool,
+            &signers,
+            &namespace,
+            &package,
+            &target,
+        )
+        .await
+        .map_err(transfer_error)?;
+
+        let versions = PackageModel::find_all_versions(
+            &state.pool,
+            package_record.package_id,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        let (locations, retries) =
+            relocate_artifacts(&state, &target, &package, &versions).await?;
+
+        match PackageModel::transfer(
+            &state.pool,
+            &signers,
+            &namespace,
+            &package,
+            &target,
+            &locations,
+        )
+        .await
+        {
+            Ok(record) => {
+                for pending in retries {
+                    retry_writes(&state, pending);
+                }
+                remove_relocated_artifacts(
+                    &state, &namespace, &package, &versions,
+                )
+                .await;
+                state.invalidate_namespace(&namespace);
+                state.invalidate_namespace(&target);
+                Ok(Json(record))
+            }
+            Err(e) => {
+                remove_relocated_artifacts(
+                    &state, &target, &package, &versions,
+                )
+                .await;
+                Err(transfer_error(e))
+            }
         }
     }
-}
-
-#[derive(Default, Debug, Deserialize)]
-#[serde(default)]
-pub struct LatestQuery {
-    prerelease: bool,
-}
-
-pub(crate) struct PackageHandler;
 
-impl PackageHandler {
-    /// Get a package record.
-    pub(crate) async fn get_package(
+    /// Check whether an address can publish to a package.
+    ///
+    /// Only the namespace owner, an administrator or the
+    /// queried address may perform the check.
+    ///
+    /// The signature is of the action, package and address so it
+    /// cannot be replayed from the endpoints to add or remove a user.
+    pub(crate) async fn can_publish(
         Extension(state): Extension<ServerState>,
+        signature: Signature,
+        encoding: Encoding,
         Path((namespace, package)): Path<(Namespace, PackageName)>,
-    ) -> std::result::Result<Json<PackageRecord>, StatusCode> {
+        ValidQuery(query): ValidQuery<CanPublishQuery>,
+    ) -> std::result::Result<Encoded<PublishCheck>, StatusCode> {
+        let message = format!(
+            "can-publish:{}/{}/{}",
+            namespace, package, query.address
+        );
+        let caller = verify_signature(signature.into(), message.as_bytes())
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
         let namespace_record =
             NamespaceModel::find_by_name(&state.pool, &namespace)
                 .await
                 .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
                 .ok_or(StatusCode::NOT_FOUND)?;
 
-        let package_record = PackageModel::find_by_name(
+        if caller != query.address
+            && !namespace_record.can_administrate(&caller)
+        {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        let check = PackageModel::check_publish(
             &state.pool,
-            namespace_record.namespace_id,
+            &query.address,
+            &namespace_record,
             &package,
+            query.version.as_ref(),
         )
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        Ok(Json(package_record))
+        Ok(encoding.encode(check))
     }
 
-    /// List packages for a namespace.
-    pub(crate) async fn list_packages(
+    /// List the trusted keys for a package.
+    pub(crate) async fn list_trusted_keys(
         Extension(state): Extension<ServerState>,
-        Path(namespace): Path<Namespace>,
-        Query(query): Query<ListPackagesQuery>,
-    ) -> std::result::Result<Json<ResultSet<PackageRecord>>, StatusCode> {
-        let pager = query.into_pager();
-
-        match PackageModel::list_packages(
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+    ) -> std::result::Result<Json<Vec<PublisherRecord>>, StatusCode> {
+        match PackageModel::list_trusted_keys(
             &state.pool,
             &namespace,
-            &pager,
-            query.include,
+            &package,
         )
         .await
         {
@@ -137,109 +1382,65 @@ impl PackageHandler {
         }
     }
 
-    /// List versions for a namespace and package.
-    pub(crate) async fn list_versions(
+    /// Add a trusted key for a package.
+    pub(crate) async fn add_trusted_key(
         Extension(state): Extension<ServerState>,
-        Path((namespace, package)): Path<(Namespace, PackageName)>,
-        Query(query): Query<ListVersionsQuery>,
-    ) -> std::result::Result<Json<ResultSet<VersionRecord>>, StatusCode> {
-        let pager = query.into_pager();
-
-        if let Some(range) = query.range {
-            match PackageModel::find_versions(
-                &state.pool,
-                &namespace,
-                &package,
-                &range,
-                &pager,
-            )
-            .await
-            {
-                Ok(records) => Ok(Json(records)),
-                Err(e) => Err(match e {
-                    DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
-                    _ => StatusCode::INTERNAL_SERVER_ERROR,
-                }),
-            }
-        } else {
-            match PackageModel::list_versions(
-                &state.pool,
-                &namespace,
-                &package,
-                &pager,
-            )
-            .await
-            {
-                Ok(records) => Ok(Json(records)),
-                Err(e) => Err(match e {
-                    DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
-                    _ => StatusCode::INTERNAL_SERVER_ERROR,
-                }),
-            }
-        }
-    }
+        signature: Signature,
+        Path((namespace, package, key)): Path<(
+            Namespace,
+            PackageName,
+            Address,
+        )>,
+    ) -> std::result::Result<StatusCode, StatusCode> {
+        // Name the action and the package so a signature cannot be
+        // replayed as a removal or against another package
+        let message =
+            format!("add-trusted-key:{}/{}/{}", namespace, package, key);
+        let caller = verify_signature(signature.into(), message.as_bytes())
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    /// Get the latest version of a package.
-    pub(crate) async fn latest_version(
-        Extension(state): Extension<ServerState>,
-        Path((namespace, package)): Path<(Namespace, PackageName)>,
-        Query(latest): Query<LatestQuery>,
-    ) -> std::result::Result<Json<VersionRecord>, StatusCode> {
-        match PackageModel::find_latest_by_name(
+        match PackageModel::add_trusted_key(
             &state.pool,
             &namespace,
             &package,
-            latest.prerelease,
+            &caller,
+            &key,
         )
         .await
         {
-            Ok(record) => {
-                let record = record.ok_or_else(|| StatusCode::NOT_FOUND)?;
-                Ok(Json(record))
-            }
-            Err(e) => Err(match e {
-                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            }),
-        }
-    }
-
-    /// Get the exact version of a package.
-    pub(crate) async fn exact_version(
-        Extension(state): Extension<ServerState>,
-        Query(query): Query<PackageQuery>,
-    ) -> std::result::Result<Json<VersionRecord>, StatusCode> {
-        match PackageModel::find_by_key(&state.pool, &query.id).await {
-            Ok((_, _, record)) => {
-                let record = record.ok_or_else(|| StatusCode::NOT_FOUND)?;
-                Ok(Json(record))
-            }
+            Ok(_) => Ok(StatusCode::OK),
             Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::TrustedKeyExists(_, _) => StatusCode::CONFLICT,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }),
         }
     }
 
-    /// Deprecate a package.
-    pub(crate) async fn deprecate(
+    /// Remove a trusted key for a package.
+    pub(crate) async fn remove_trusted_key(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
-        Path((namespace, package)): Path<(Namespace, PackageName)>,
-        body: Bytes,
+        signature: Signature,
+        Path((namespace, package, key)): Path<(
+            Namespace,
+            PackageName,
+            Address,
+        )>,
     ) -> std::result::Result<StatusCode, StatusCode> {
-        let address = verify_signature(signature.into(), &body)
-            .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-        let message = std::str::from_utf8(&body)
+        // Name the action and the package so a signature cannot be
+        // replayed as an addition or against another package
+        let message =
+            format!("remove-trusted-key:{}/{}/{}", namespace, package, key);
+        let caller = verify_signature(signature.into(), message.as_bytes())
             .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-        match PackageModel::deprecate(
+        match PackageModel::remove_trusted_key(
             &state.pool,
-            &address,
             &namespace,
             &package,
-            &message,
+            &caller,
+            &key,
         )
         .await
         {
@@ -247,6 +1448,9 @@ impl PackageHandler {
             Err(e) => Err(match e {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::TrustedKeyMissing(_, _) => {
+                    StatusCode::CONFLICT
+                }
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }),
         }
@@ -255,145 +1459,609 @@ impl PackageHandler {
     /// Yank a version of a package.
     pub(crate) async fn yank(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
         Query(query): Query<PackageQuery>,
         body: Bytes,
     ) -> std::result::Result<StatusCode, StatusCode> {
-        let address = verify_signature(signature.into(), &body)
-            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        let address = verify_signature(
+            signature.into(),
+            &query.id.signed_message("yank", &body),
+        )
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-        let message = std::str::from_utf8(&body)
-            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        let message = read_message(
+            &body,
+            Some(state.config.registry.max_message_length),
+        )?;
 
-        match PackageModel::yank(&state.pool, &address, &query.id, &message)
+        match PackageModel::yank(&state.pool, &address, &query.id, message)
             .await
         {
             Ok(_) => Ok(StatusCode::OK),
             Err(e) => Err(match e {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
+
+    /// Restore a yanked version of a package.
+    ///
+    /// The body is signed with the `unyank` action and the
+    /// identifier and is otherwise ignored.
+    pub(crate) async fn unyank(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Query(query): Query<PackageQuery>,
+        body: Bytes,
+    ) -> std::result::Result<StatusCode, StatusCode> {
+        let address = verify_signature(
+            signature.into(),
+            &query.id.signed_message("unyank", &body),
+        )
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        match PackageModel::unyank(&state.pool, &address, &query.id).await {
+            Ok(_) => Ok(StatusCode::OK),
+            Err(e) => Err(match e {
+                DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+                DatabaseError::VersionPurged(_) => StatusCode::GONE,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }),
         }
     }
 
     /// Download a package.
+    ///
+    /// Downloads using a signed URL are verified against the
+    /// `expires` and `token` query parameters.
     pub(crate) async fn fetch(
         Extension(state): Extension<ServerState>,
-        Query(query): Query<PackageQuery>,
-    ) -> std::result::Result<(HeaderMap, Bytes), StatusCode> {
-        let mime_type = state.config.registry.mime.clone();
-        let _kind = state.config.registry.kind;
+        OptionalSignature(signature): OptionalSignature,
+        signature_expires: Option<TypedHeader<SignatureExpires>>,
+        Query(query): Query<FetchQuery>,
+    ) -> std::result::Result<(HeaderMap, Bytes), Response> {
+        let presigned = match (query.expires, &query.token) {
+            (None, None) => false,
+            (Some(expires), Some(token)) => {
+                let config =
+                    state.config.signed_urls.as_ref().ok_or_else(|| {
+                        StatusCode::BAD_REQUEST.into_response()
+                    })?;
+                if !signed_url::verify(
+                    config.secret.as_bytes(),
+                    &query.id,
+                    expires,
+                    token,
+                ) || OffsetDateTime::now_utc().unix_timestamp() >= expires
+                {
+                    return Err(StatusCode::FORBIDDEN.into_response());
+                }
+                true
+            }
+            _ => return Err(StatusCode::BAD_REQUEST.into_response()),
+        };
+        let signature_expires =
+            signature_expires.map(|TypedHeader(expires)| expires.into());
+        fetch_artifact(
+            &state,
+            &query.id,
+            signature,
+            signature_expires,
+            presigned,
+        )
+        .await
+    }
 
-        match PackageModel::find_by_key(&state.pool, &query.id).await {
-            Ok((_, _, record)) => {
-                let record = record.ok_or(StatusCode::NOT_FOUND)?;
+    /// Create a signed URL to download a package version
+    /// without a signature until the URL expires.
+    ///
+    /// The caller must be a user of the namespace when the
+    /// namespace is private otherwise any publisher may
+    /// create a signed URL.
+    pub(crate) async fn sign_url(
+        Extension(state): Extension<ServerState>,
+        signature: Signature,
+        Query(query): Query<SignUrlQuery>,
+    ) -> std::result::Result<Json<SignedUrl>, StatusCode> {
+        let config = state
+            .config
+            .signed_urls
+            .as_ref()
+            .ok_or(StatusCode::NOT_FOUND)?;
 
-                let body = state
-                    .layers
-                    .fetch(&record.pointer_id, record.content_id.as_ref())
+        let expires_in_message = query
+            .expires_in
+            .map(|expires_in| expires_in.to_string())
+            .unwrap_or_default();
+        let address = verify_signature(
+            signature.into(),
+            &query
+                .id
+                .signed_message("sign-url", expires_in_message.as_bytes()),
+        )
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let expires_in = query.expires_in.unwrap_or(config.max_expires_in);
+        if expires_in == 0 || expires_in > config.max_expires_in {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        let (namespace_record, _, record) = PackageModel::find_by_key(
+            &state.pool,
+            &query.id,
+            state.config.registry.duplicate_cid,
+        )
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+            DatabaseError::AmbiguousCid(_, _) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+        record.ok_or(StatusCode::NOT_FOUND)?;
+
+        match &namespace_record {
+            Some(namespace_record) if namespace_record.private => {
+                state
+                    .can_access_namespace(&address, &namespace_record.name)
                     .await
-                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                    .map_err(|e| match e {
+                        DatabaseError::Unauthorized(_)
+                        | DatabaseError::NotFound(_) => {
+                            StatusCode::UNAUTHORIZED
+                        }
+                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                    })?;
+            }
+            _ => {
+                PublisherModel::find_by_address(&state.pool, &address)
+                    .await
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                    .ok_or(StatusCode::UNAUTHORIZED)?;
+            }
+        }
 
-                // Verify the checksum
-                let checksum = Sha3_256::digest(&body);
-                if checksum.as_slice() != record.checksum.as_slice() {
-                    return Err(StatusCode::UNPROCESSABLE_ENTITY);
-                }
+        let expires =
+            OffsetDateTime::now_utc().unix_timestamp() + expires_in as i64;
+        let token =
+            signed_url::sign(config.secret.as_bytes(), &query.id, expires);
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("id", &query.id.to_string())
+            .append_pair("expires", &expires.to_string())
+            .append_pair("token", &token)
+            .finish();
+
+        Ok(Json(SignedUrl {
+            url: format!("/api/package?{}", query),
+            expires,
+        }))
+    }
+
+    /// Download a package version identified by the request path.
+    pub(crate) async fn download(
+        Extension(state): Extension<ServerState>,
+        OptionalSignature(signature): OptionalSignature,
+        expires: Option<TypedHeader<SignatureExpires>>,
+        Path((namespace, package, version)): Path<(
+            Namespace,
+            PackageName,
+            Version,
+        )>,
+    ) -> std::result::Result<(HeaderMap, Bytes), Response> {
+        let key = PackageKey::Pointer(namespace, package, version);
+        let expires = expires.map(|TypedHeader(expires)| expires.into());
+        fetch_artifact(&state, &key, signature, expires, false).await
+    }
+
+    /// Download all versions of a package as a tar archive.
+    pub(crate) async fn bundle(
+        Extension(state): Extension<ServerState>,
+        OptionalSignature(signature): OptionalSignature,
+        expires: Option<TypedHeader<SignatureExpires>>,
+        Path((namespace, package)): Path<(Namespace, PackageName)>,
+        ValidQuery(query): ValidQuery<BundleQuery>,
+    ) -> std::result::Result<
+        (HeaderMap, StreamBody<impl Stream<Item = Result<Bytes>>>),
+        Response,
+    > {
+        let namespace_record =
+            NamespaceModel::find_by_name_shallow(&state.pool, &namespace)
+                .await
+                .map_err(|_| {
+                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                })?
+                .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+        authorize_download(
+            &state,
+            &namespace_record,
+            signature,
+            expires.map(|TypedHeader(expires)| expires.into()),
+            format!("{}/{}", namespace, package).as_bytes(),
+        )
+        .await
+        .map_err(|status| status.into_response())?;
+
+        let package_record = PackageModel::find_by_name(
+            &state.pool,
+            namespace_record.namespace_id,
+            &package,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
 
-                verify_signature(record.signature, &body)
-                    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+        // A bundle counts as a single fetch of the package
+        if let Some(limiter) = &state.fetch_limiter {
+            if let Err(retry_after) =
+                limiter.acquire(package_record.package_id)
+            {
+                return Err((
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(RETRY_AFTER, retry_after.to_string())],
+                )
+                    .into_response());
+            }
+        }
 
-                let mut headers = HeaderMap::new();
-                headers.insert("content-type", mime_type.parse().unwrap());
+        let mut pager: Pager = Default::default();
+        let mut records = Vec::new();
+        loop {
+            let result = if let Some(range) = &query.range {
+                PackageModel::find_versions(
+                    &state.pool,
+                    &namespace,
+                    &package,
+                    range,
+                    &pager,
+                    true,
+                )
+                .await
+            } else {
+                PackageModel::list_versions(
+                    &state.pool,
+                    &namespace,
+                    &package,
+                    &pager,
+                    true,
+                )
+                .await
+            };
 
-                if let Some(hooks) = state.config.webhooks.clone() {
-                    let body = WebHookBody { inner: record };
-                    let packet = WebHookPacket {
-                        event: WebHookEvent::Fetch,
-                        body,
-                    };
-                    tokio::spawn(execute_webhooks(hooks, packet));
+            let page = result.map_err(|e| {
+                match e {
+                    DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
                 }
+                .into_response()
+            })?;
 
-                Ok((headers, Bytes::from(body)))
+            // The range query is an approximation in SQL so check
+            // each record against the requested range
+            let is_last = (page.records.len() as i64) < pager.limit;
+            records.extend(page.records.into_iter().filter(|record| {
+                query
+                    .range
+                    .as_ref()
+                    .map(|range| range.matches(&record.version))
+                    .unwrap_or(true)
+            }));
+            if is_last {
+                break;
             }
-            Err(e) => Err(match e {
-                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            }),
+            pager.offset += pager.limit;
         }
+
+        let manifest = serde_json::to_vec_pretty(&records)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+        let manifest = tar_entry(BUNDLE_MANIFEST, &manifest)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+        // Artifacts are fetched lazily as the response is streamed,
+        // versions purged by the retention policy only appear
+        // in the manifest
+        let records = records
+            .into_iter()
+            .filter(|record| record.purged_at.is_none());
+        let entries = stream::iter(records).then(move |record| {
+            let state = Arc::clone(&state);
+            let package = package.clone();
+            async move { bundle_entry(&state, &package, &record).await }
+        });
+
+        let body = stream::once(future::ready(Ok(manifest)))
+            .chain(entries)
+            .chain(stream::once(future::ready(Ok(Bytes::from(vec![
+                0u8;
+                BLOCK_SIZE
+                    * 2
+            ])))));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/x-tar".parse().unwrap());
+
+        Ok((headers, StreamBody::new(body)))
     }
 
     /// Publish a new package.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn publish(
         Extension(state): Extension<ServerState>,
         TypedHeader(mime): TypedHeader<ContentType>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
+        expected: Option<TypedHeader<Checksum>>,
         Path(namespace): Path<Namespace>,
+        Query(query): Query<PublishQuery>,
+        headers: HeaderMap,
         body: Bytes,
-    ) -> std::result::Result<Json<Receipt>, StatusCode> {
+    ) -> std::result::Result<Json<Receipt>, PublishRejection> {
         //let encoded_signature = base64::encode(signature.as_ref());
 
-        // Verify the signature header against the payload bytes
-        let address = verify_signature(signature.clone().into(), &body)
-            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        // Remove any HTTP content encoding so the artifact
+        // is the decoded payload
+        let body = decode_body(
+            headers.get(CONTENT_ENCODING),
+            body,
+            state.config.registry.body_limit,
+        )?;
+
+        let checksum = Sha3_256::digest(&body);
+
+        // When a checksum header is given the signature is over
+        // the checksum otherwise it is over the payload bytes
+        let scheme = if let Some(TypedHeader(expected)) = &expected {
+            if expected.as_ref() != checksum.as_slice() {
+                return Err(StatusCode::BAD_REQUEST.into());
+            }
+            SignatureScheme::Secp256k1Checksum
+        } else {
+            SignatureScheme::Secp256k1
+        };
+
+        let address = verify_signature(
+            signature.clone().into(),
+            scheme.message(&body, checksum.as_slice()),
+        )
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
         // Check if the author is denied
         if let Some(deny) = &state.config.registry.deny {
             if deny.contains(&address) {
-                return Err(StatusCode::UNAUTHORIZED);
+                return Err(StatusCode::UNAUTHORIZED.into());
             }
         }
 
         // Check if the author is allowed
         if let Some(allow) = &state.config.registry.allow {
             if !allow.contains(&address) {
-                return Err(StatusCode::UNAUTHORIZED);
+                return Err(StatusCode::UNAUTHORIZED.into());
+            }
+        }
+
+        // Create a missing namespace for a registered publisher,
+        // not available when namespaces require a proof of work
+        if state.config.registry.auto_create_namespace
+            && state.config.namespace_difficulty.is_none()
+        {
+            let record =
+                NamespaceModel::find_by_name_shallow(&state.pool, &namespace)
+                    .await
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            if record.is_none() {
+                let publisher =
+                    PublisherModel::find_by_address(&state.pool, &address)
+                        .await
+                        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                if let Some(publisher) = publisher {
+                    match NamespaceModel::insert_fetch(
+                        &state.pool,
+                        &namespace,
+                        publisher.publisher_id,
+                        None,
+                    )
+                    .await
+                    {
+                        Ok(_) => {
+                            tracing::info!(
+                                address = %address,
+                                namespace = %namespace,
+                                "namespace created on publish");
+                        }
+                        // Created by a concurrent request, access
+                        // to the namespace is checked below
+                        Err(DatabaseError::NamespaceExists(_)) => {}
+                        Err(_) => {
+                            return Err(
+                                StatusCode::INTERNAL_SERVER_ERROR.into()
+                            )
+                        }
+                    }
+                }
             }
         }
 
         // Check the publisher and namespace exist and this address
         // is allowed to publish to the target namespace
-        match NamespaceModel::can_access_namespace(
-            &state.pool,
-            &address,
-            &namespace,
-        )
-        .await
-        {
+        match state.can_access_namespace(&address, &namespace).await {
             Ok((publisher_record, namespace_record)) => {
                 let mime_type = state.config.registry.mime.clone();
-                let kind = state.config.registry.kind;
+                let kind = namespace_record
+                    .kind
+                    .unwrap_or(state.config.registry.kind);
 
                 tracing::debug!(mime = ?mime_type);
 
                 // TODO: ensure approval signatures
 
-                // Check MIME type is correct
+                let mime = mime::Mime::from(mime);
+
+                // Check the archive is the kind for the namespace
+                if state.config.registry.reject_wrong_kind {
+                    PackageReader::check_kind(
+                        kind,
+                        mime.essence_str(),
+                        &body,
+                        state.config.registry.max_decompressed_size,
+                        state.config.registry.max_archive_entries,
+                    )
+                    .map_err(|e| match e {
+                        CoreError::WrongKind(_, _) => {
+                            tracing::warn!("{}", e);
+                            StatusCode::UNSUPPORTED_MEDIA_TYPE
+                        }
+                        CoreError::DecompressedSize(_)
+                        | CoreError::ArchiveEntries(_) => {
+                            StatusCode::PAYLOAD_TOO_LARGE
+                        }
+                        _ => StatusCode::BAD_REQUEST,
+                    })?;
+                }
+
+                // Check MIME type is correct, parameters such as
+                // charset are ignored
                 let gzip: mime::Mime = mime_type
                     .parse()
                     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-                let gzip_ct = ContentType::from(gzip);
-                if mime != gzip_ct {
-                    return Err(StatusCode::BAD_REQUEST);
+                // Python wheels are zip archives
+                let wheel = kind == RegistryKind::Pypi
+                    && mime.essence_str() == kind.mime_type();
+                if mime.essence_str() != gzip.essence_str() && !wheel {
+                    return Err(StatusCode::BAD_REQUEST.into());
                 }
 
-                let (package, package_meta) =
-                    PackageReader::read(kind, &body)
-                        .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-                // Check the package does not already exist
-                match PackageModel::can_publish_package(
-                    &state.pool,
-                    &address,
-                    &namespace_record,
-                    &package.name,
-                    Some(&package.version),
+                let (package, package_meta) = PackageReader::read_with_limit(
+                    kind,
+                    mime.essence_str(),
+                    &body,
+                    state.config.registry.max_decompressed_size,
+                    state.config.registry.max_archive_entries,
                 )
-                .await
+                .map_err(|e| match e {
+                    CoreError::DecompressedSize(_)
+                    | CoreError::ArchiveEntries(_) => {
+                        StatusCode::PAYLOAD_TOO_LARGE
+                    }
+                    _ => StatusCode::BAD_REQUEST,
+                })?;
+
+                // Check the file extensions of the archive entries
+                let registry = &state.config.registry;
+                if registry.allowed_extensions.is_some()
+                    || registry.denied_extensions.is_some()
                 {
-                    Ok(_) => {
+                    PackageReader::check_extensions(
+                        mime.essence_str(),
+                        &body,
+                        registry.max_decompressed_size,
+                        registry.max_archive_entries,
+                        registry.allowed_extensions.as_ref(),
+                        registry.denied_extensions.as_ref(),
+                    )
+                    .map_err(|e| match e {
+                        CoreError::DisallowedEntry(_) => {
+                            tracing::warn!("{}", e);
+                            StatusCode::UNPROCESSABLE_ENTITY
+                        }
+                        CoreError::DecompressedSize(_)
+                        | CoreError::ArchiveEntries(_) => {
+                            StatusCode::PAYLOAD_TOO_LARGE
+                        }
+                        _ => StatusCode::BAD_REQUEST,
+                    })?;
+                }
+
+                // Check the manifest has the required fields
+                if state.config.registry.validate_manifest {
+                    PackageReader::validate(kind, &package_meta).map_err(
+                        |e| {
+                            tracing::warn!("{}", e);
+                            match e {
+                                CoreError::ManifestField(field) => {
+                                    PublishRejection::Manifest(
+                                        InvalidManifest::new(field),
+                                    )
+                                }
+                                _ => StatusCode::UNPROCESSABLE_ENTITY.into(),
+                            }
+                        },
+                    )?;
+                }
+
+                // Check the version satisfies the version rules
+                state
+                    .config
+                    .registry
+                    .check_version(&package.version)
+                    .map_err(|e| {
+                        tracing::warn!("{}", e);
+                        StatusCode::UNPROCESSABLE_ENTITY
+                    })?;
+
+                // Check the package does not already exist unless
+                // the owner is forcing a publish within the window
+                let permission = if query.force {
+                    let registry = &state.config.registry;
+                    let window = registry
+                        .allow_force_window
+                        .filter(|_| !registry.immutable_versions)
+                        .ok_or(StatusCode::FORBIDDEN)?;
+                    PackageModel::can_overwrite_version(
+                        &state.pool,
+                        &address,
+                        &namespace_record,
+                        &package.name,
+                        &package.version,
+                        time::Duration::seconds(window as i64),
+                    )
+                    .await
+                } else if state.config.registry.allow_yanked_overwrite {
+                    PackageModel::can_replace_yanked(
+                        &state.pool,
+                        &address,
+                        &namespace_record,
+                        &package.name,
+                        &package.version,
+                    )
+                    .await
+                } else {
+                    PackageModel::can_publish_package(
+                        &state.pool,
+                        &address,
+                        &namespace_record,
+                        &package.name,
+                        Some(&package.version),
+                    )
+                    .await
+                    .map(|_| None)
+                };
+
+                match permission {
+                    Ok(existing) => {
+                        // Scan the artifact before it is stored
+                        if let Some(command) =
+                            &state.config.registry.scan_command
+                        {
+                            let outcome =
+                                scan(command, &body).await.map_err(|e| {
+                                    tracing::error!("{}", e);
+                                    StatusCode::INTERNAL_SERVER_ERROR
+                                })?;
+                            if let ScanOutcome::Threat(report) = outcome {
+                                tracing::warn!(
+                                    address = %address,
+                                    name = %package.name,
+                                    version = %package.version,
+                                    report = %report,
+                                    "scanner rejected package");
+                                return Err(
+                                    StatusCode::UNPROCESSABLE_ENTITY.into()
+                                );
+                            }
+                        }
+
                         let descriptor = Artifact {
                             kind,
                             namespace,
@@ -401,10 +2069,54 @@ impl PackageHandler {
                         };
 
                         let artifact = descriptor.clone();
+                        let archive_size = body.len() as u64;
+
+                        // Details for a verbose receipt
+                        let (manifest, size, readme) = if query.verbose {
+                            let readme = PackageReader::read_readme(
+                                mime.essence_str(),
+                                &body,
+                                state.config.registry.max_decompressed_size,
+                                state.config.registry.max_archive_entries,
+                            )
+                            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                            (
+                                Some(package_meta.clone()),
+                                Some(archive_size),
+                                readme,
+                            )
+                        } else {
+                            (None, None, None)
+                        };
 
-                        let checksum = Sha3_256::digest(&body);
+                        // Keep the artifact being replaced so it can be
+                        // restored when the overwrite is not recorded
+                        let previous = match existing
+                            .as_ref()
+                            .filter(|v| v.purged_at.is_none())
+                        {
+                            Some(version_record) => match state
+                                .layers
+                                .fetch(
+                                    &version_record.pointer_id,
+                                    version_record.content_id.as_ref(),
+                                    &version_record.objects,
+                                    Some(&version_record.checksum),
+                                )
+                                .await
+                            {
+                                Ok(data) => Some(data),
+                                Err(e) => {
+                                    tracing::warn!(
+                                        error = %e,
+                                        "artifact to overwrite is unavailable");
+                                    None
+                                }
+                            },
+                            None => None,
+                        };
 
-                        let objects = state
+                        let (stored, retries) = state
                             .layers
                             .publish(body, &descriptor)
                             .await
@@ -413,7 +2125,37 @@ impl PackageHandler {
                                 StatusCode::INTERNAL_SERVER_ERROR
                             })?;
 
-                        tracing::debug!(id = ?objects, "added package");
+                        tracing::debug!(id = ?stored, "added package");
+
+                        if let Some(policy) = &state.config.registry.cid {
+                            let rejected = stored.iter().find_map(|o| {
+                                if let ObjectKey::Cid(value) = &o.key {
+                                    policy.check(value).err()
+                                } else {
+                                    None
+                                }
+                            });
+                            if let Some(e) = rejected {
+                                tracing::error!("{}", e);
+                                if existing.is_some() {
+                                    restore_artifact(
+                                        &state, &artifact, previous,
+                                    )
+                                    .await;
+                                } else {
+                                    remove_orphaned_artifact(
+                                        &state, &artifact, &stored,
+                                    )
+                                    .await;
+                                }
+                                return Err(
+                                    StatusCode::INTERNAL_SERVER_ERROR.into(),
+                                );
+                            }
+                        }
+
+                        let objects: Vec<ObjectKey> =
+                            stored.iter().map(|o| o.key.clone()).collect();
 
                         // Direct key for the publish receipt
                         let key = objects.iter().find_map(|o| {
@@ -436,21 +2178,71 @@ impl PackageHandler {
                                 signature: PackageSignature {
                                     signer: address,
                                     value: signature.into(),
+                                    scheme,
                                 },
                                 checksum,
+                                size: Some(archive_size),
                             },
                             package: package_meta,
                         };
 
-                        PackageModel::insert(
+                        if let Some(version_record) = &existing {
+                            let yanked = version_record.yanked.is_some();
+                            let result = if yanked {
+                                PackageModel::replace_yanked(
+                                    &state.pool,
+                                    &publisher_record,
+                                    version_record,
+                                    &doc,
+                                    &stored,
+                                )
+                                .await
+                            } else {
+                                PackageModel::overwrite(
+                                    &state.pool,
+                                    &publisher_record,
+                                    version_record,
+                                    &doc,
+                                    &stored,
+                                )
+                                .await
+                            };
+                            if let Err(e) = result {
+                                tracing::error!("{}", e);
+                                restore_artifact(&state, &artifact, previous)
+                                    .await;
+                                return Err(
+                                    StatusCode::INTERNAL_SERVER_ERROR.into(),
+                                );
+                            }
+                            tracing::warn!(
+                                address = %address,
+                                namespace = %artifact.namespace,
+                                package = %artifact.package.name,
+                                version = %artifact.package.version,
+                                yanked,
+                                "version overwritten");
+                        } else if let Err(e) = PackageModel::insert(
                             &state.pool,
                             &publisher_record,
                             &namespace_record,
                             &address,
                             &doc,
+                            &stored,
                         )
                         .await
-                        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                        {
+                            tracing::error!("{}", e);
+                            remove_orphaned_artifact(
+                                &state, &artifact, &stored,
+                            )
+                            .await;
+                            return Err(
+                                StatusCode::INTERNAL_SERVER_ERROR.into()
+                            );
+                        }
+
+                        retry_writes(&state, retries);
 
                         let id = PackageKey::Pointer(
                             artifact.namespace.clone(),
@@ -463,6 +2255,9 @@ impl PackageHandler {
                             artifact,
                             key,
                             checksum,
+                            package: manifest,
+                            size,
+                            readme,
                         };
 
                         if let Some(hooks) = state.config.webhooks.clone() {
@@ -471,17 +2266,31 @@ impl PackageHandler {
                                 event: WebHookEvent::Publish,
                                 body,
                             };
-                            tokio::spawn(execute_webhooks(hooks, packet));
+                            tokio::spawn(execute_webhooks(
+                                hooks,
+                                Some(receipt.artifact.namespace.clone()),
+                                packet,
+                            ));
                         }
 
                         Ok(Json(receipt))
                     }
                     Err(e) => Err(match e {
+                        DatabaseError::Unauthorized(_) => {
+                            StatusCode::UNAUTHORIZED.into()
+                        }
+                        DatabaseError::VersionYanked(_, _, _) => {
+                            tracing::warn!("{}", e);
+                            PublishRejection::Yanked(YankedConflict::new(
+                                e.to_string(),
+                            ))
+                        }
                         DatabaseError::PackageExists(_, _, _)
-                        | DatabaseError::VersionNotAhead(_, _) => {
-                            StatusCode::CONFLICT
+                        | DatabaseError::VersionNotAhead(_, _)
+                        | DatabaseError::OverwriteWindow(_, _, _) => {
+                            StatusCode::CONFLICT.into()
                         }
-                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                        _ => StatusCode::INTERNAL_SERVER_ERROR.into(),
                     }),
                 }
             }
@@ -489,7 +2298,8 @@ impl PackageHandler {
                 DatabaseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
                 DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
-            }),
+            }
+            .into()),
         }
     }
 }
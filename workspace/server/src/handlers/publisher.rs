@@ -1,6 +1,6 @@
 use axum::{
-    extract::{Extension, TypedHeader},
-    http::StatusCode,
+    extract::{Extension, Path},
+    http::{HeaderMap, StatusCode, Uri},
     Json,
 };
 
@@ -8,10 +8,18 @@ use axum::{
 
 use ipfs_registry_core::WELL_KNOWN_MESSAGE;
 
-use ipfs_registry_database::{PublisherModel, PublisherRecord};
+use ipfs_registry_database::{
+    Error as DatabaseError, Pager, PublisherModel, PublisherPackageRecord,
+    PublisherRecord, ResultSet,
+};
+use web3_address::ethereum::Address;
 
 use crate::{
-    handlers::verify_signature, headers::Signature, server::ServerState,
+    encoding::{Encoded, Encoding},
+    handlers::{link_headers, verify_signature},
+    headers::Signature,
+    query::ValidQuery,
+    server::ServerState,
 };
 
 pub(crate) struct PublisherHandler;
@@ -20,7 +28,7 @@ impl PublisherHandler {
     /// Create a new publisher.
     pub(crate) async fn signup(
         Extension(state): Extension<ServerState>,
-        TypedHeader(signature): TypedHeader<Signature>,
+        signature: Signature,
     ) -> std::result::Result<Json<PublisherRecord>, StatusCode> {
         // Verify the signature header against the well known message
         let address = verify_signature(signature.into(), WELL_KNOWN_MESSAGE)
@@ -41,4 +49,29 @@ impl PublisherHandler {
 
         Ok(Json(publisher_record))
     }
+
+    /// List the packages a publisher is allowed to publish to.
+    pub(crate) async fn list_packages(
+        Extension(state): Extension<ServerState>,
+        encoding: Encoding,
+        uri: Uri,
+        Path(address): Path<Address>,
+        ValidQuery(pager): ValidQuery<Pager>,
+    ) -> std::result::Result<
+        (HeaderMap, Encoded<ResultSet<PublisherPackageRecord>>),
+        StatusCode,
+    > {
+        match PublisherModel::list_packages(&state.pool, &address, &pager)
+            .await
+        {
+            Ok(records) => Ok((
+                link_headers(&uri, &pager, &records)?,
+                encoding.encode(records),
+            )),
+            Err(e) => Err(match e {
+                DatabaseError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            }),
+        }
+    }
 }
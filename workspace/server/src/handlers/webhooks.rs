@@ -1,14 +1,21 @@
 use bytes::Bytes;
+use hmac::{Hmac, Mac};
 use k256::ecdsa::{recoverable, signature::Signer};
-use reqwest::Client;
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client,
+};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use std::time::Duration;
 use url::Url;
 
-use ipfs_registry_core::X_SIGNATURE;
+use ipfs_registry_core::{Namespace, X_HUB_SIGNATURE, X_SIGNATURE};
 
 use crate::{config::WebHookConfig, Result};
 
+type HmacSha256 = Hmac<Sha256>;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged, rename_all = "lowercase")]
 pub enum WebHookEvent {
@@ -31,24 +38,73 @@ pub struct WebHookPacket<T> {
 }
 
 /// Execute the configured webhooks.
+///
+/// Events that pertain to a namespace are also sent to the
+/// endpoints configured for the namespace.
 pub async fn execute_webhooks<T: Serialize>(
     hooks: WebHookConfig,
+    namespace: Option<Namespace>,
     packet: WebHookPacket<T>,
 ) {
-    match execute(hooks, packet).await {
+    match execute(hooks, namespace, packet).await {
         Ok(_) => {}
         Err(e) => tracing::error!("{}", e),
     }
 }
 
+/// Compute the value of the HMAC signature header for a payload.
+fn hub_signature(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret)
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
 async fn execute<T: Serialize>(
     hooks: WebHookConfig,
+    namespace: Option<Namespace>,
     packet: WebHookPacket<T>,
 ) -> Result<()> {
     let signing_key = hooks.signing_key.unwrap();
-    let body = Bytes::from(serde_json::to_vec(&packet)?);
+    let body = Bytes::from(hooks.format.to_vec(&packet)?);
     let signature: recoverable::Signature = signing_key.sign(&body);
+
+    let mut requests: Vec<(Url, HeaderMap)> = Vec::new();
     for url in hooks.endpoints {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            X_SIGNATURE,
+            HeaderValue::from_str(&base64::encode(signature))?,
+        );
+        requests.push((url, headers));
+    }
+
+    if let Some(config) = namespace
+        .as_ref()
+        .and_then(|name| hooks.namespaces.get(name))
+    {
+        for url in &config.endpoints {
+            let mut headers = HeaderMap::new();
+            if config.sign {
+                headers.insert(
+                    X_SIGNATURE,
+                    HeaderValue::from_str(&base64::encode(signature))?,
+                );
+            }
+            if let Some(secret) = &config.secret {
+                headers.insert(
+                    X_HUB_SIGNATURE,
+                    HeaderValue::from_str(&hub_signature(
+                        secret.as_bytes(),
+                        &body,
+                    ))?,
+                );
+            }
+            requests.push((url.clone(), headers));
+        }
+    }
+
+    for (url, headers) in requests {
         tracing::debug!(
             url = %url,
             event = ?packet.event,
@@ -61,7 +117,7 @@ async fn execute<T: Serialize>(
             hooks.backoff_seconds,
             url,
             body.clone(),
-            signature.clone(),
+            headers,
         ));
     }
     Ok(())
@@ -72,11 +128,11 @@ async fn request_with_retry(
     backoff_seconds: u64,
     url: Url,
     body: Bytes,
-    signature: recoverable::Signature,
+    headers: HeaderMap,
 ) -> Result<bool> {
     let mut backoff_millis = backoff_seconds * 1000;
     for _ in 0..retry_limit {
-        match request(url.clone(), body.clone(), signature).await {
+        match request(url.clone(), body.clone(), headers.clone()).await {
             Ok(success) => {
                 if success {
                     return Ok(true);
@@ -93,17 +149,116 @@ async fn request_with_retry(
     Ok(false)
 }
 
-async fn request(
-    url: Url,
-    body: Bytes,
-    signature: recoverable::Signature,
-) -> Result<bool> {
+async fn request(url: Url, body: Bytes, headers: HeaderMap) -> Result<bool> {
     let client = Client::new();
-    let response = client
-        .post(url)
-        .body(body.clone())
-        .header(X_SIGNATURE, base64::encode(&signature))
-        .send()
-        .await?;
+    let response =
+        client.post(url).body(body).headers(headers).send().await?;
     Ok(response.status().is_success())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NamespaceWebHookConfig;
+    use axum::{
+        body::Bytes as BodyBytes, extract::Extension, http::HeaderMap,
+        routing::post, Router,
+    };
+    use k256::ecdsa::SigningKey;
+    use serde_json::json;
+    use std::{collections::HashMap, net::TcpListener};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn hub_signature_hmac() {
+        assert_eq!(
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+            hub_signature(
+                b"key",
+                b"The quick brown fox jumps over the lazy dog"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn namespace_webhook_hmac() -> Result<()> {
+        type Received = mpsc::UnboundedSender<(HeaderMap, BodyBytes)>;
+        let (tx, mut rx) =
+            mpsc::unbounded_channel::<(HeaderMap, BodyBytes)>();
+        let app = Router::new()
+            .route(
+                "/",
+                post(
+                    |Extension(tx): Extension<Received>,
+                     headers: HeaderMap,
+                     body: BodyBytes| async move {
+                        tx.send((headers, body)).unwrap();
+                    },
+                ),
+            )
+            .layer(Extension(tx));
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = Url::parse(&format!("http://{}", listener.local_addr()?))?;
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        let secret = "mock-namespace-secret";
+        let namespace = Namespace::new_unchecked("mock-namespace");
+        let hooks = WebHookConfig {
+            retry_limit: 1,
+            namespaces: HashMap::from([(
+                namespace.clone(),
+                NamespaceWebHookConfig {
+                    endpoints: vec![url],
+                    secret: Some(secret.to_owned()),
+                    sign: false,
+                },
+            )]),
+            signing_key: Some(SigningKey::from_bytes(&[1u8; 32])?),
+            ..Default::default()
+        };
+
+        // Events for other namespaces are not sent
+        let packet = WebHookPacket {
+            event: WebHookEvent::Publish,
+            body: WebHookBody {
+                inner: json!({"package": "other"}),
+            },
+        };
+        execute(
+            hooks.clone(),
+            Some(Namespace::new_unchecked("other-namespace")),
+            packet,
+        )
+        .await?;
+
+        let packet = WebHookPacket {
+            event: WebHookEvent::Publish,
+            body: WebHookBody {
+                inner: json!({"package": "mock"}),
+            },
+        };
+        execute(hooks, Some(namespace), packet).await?;
+
+        let (headers, body) = rx.recv().await.unwrap();
+        let received: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(json!("mock"), received["body"]["package"]);
+        assert!(headers.get(X_SIGNATURE).is_none());
+
+        let header = headers.get(X_HUB_SIGNATURE).unwrap().to_str().unwrap();
+        let digest =
+            hex::decode(header.strip_prefix("sha256=").unwrap()).unwrap();
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(&body);
+        assert!(mac.verify_slice(&digest).is_ok());
+
+        let mut mac = HmacSha256::new_from_slice(b"other-secret").unwrap();
+        mac.update(&body);
+        assert!(mac.verify_slice(&digest).is_err());
+        Ok(())
+    }
+}
@@ -1,11 +1,33 @@
 //! Custom typed headers.
-use axum::headers::{self, Header, HeaderName, HeaderValue};
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequest, RequestParts},
+    headers::{self, Header, HeaderName, HeaderValue},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
 
 use once_cell::sync::Lazy;
 
 pub static X_SIGNATURE: Lazy<HeaderName> =
     Lazy::new(|| HeaderName::from_static(ipfs_registry_core::X_SIGNATURE));
 
+pub static X_CHECKSUM: Lazy<HeaderName> =
+    Lazy::new(|| HeaderName::from_static(ipfs_registry_core::X_CHECKSUM));
+
+pub static X_NONCE: Lazy<HeaderName> =
+    Lazy::new(|| HeaderName::from_static(ipfs_registry_core::X_NONCE));
+
+pub static X_SIGNATURE_EXPIRES: Lazy<HeaderName> = Lazy::new(|| {
+    HeaderName::from_static(ipfs_registry_core::X_SIGNATURE_EXPIRES)
+});
+
+pub static X_TARGET_SIGNATURE: Lazy<HeaderName> = Lazy::new(|| {
+    HeaderName::from_static(ipfs_registry_core::X_TARGET_SIGNATURE)
+});
+
 /// Represents the `x-signature` header.
 #[derive(Clone)]
 pub struct Signature([u8; 65]);
@@ -16,6 +38,22 @@ impl AsRef<[u8]> for Signature {
     }
 }
 
+impl Signature {
+    /// Parse a signature from a header value.
+    fn parse(value: &HeaderValue) -> Result<Self, SignatureRejection> {
+        let value = value
+            .to_str()
+            .map_err(|_| SignatureRejection::InvalidEncoding)?;
+        let value = base64::decode(value)
+            .map_err(|_| SignatureRejection::InvalidEncoding)?;
+        let value: [u8; 65] = value
+            .as_slice()
+            .try_into()
+            .map_err(|_| SignatureRejection::InvalidLength(value.len()))?;
+        Ok(Signature(value))
+    }
+}
+
 impl Header for Signature {
     fn name() -> &'static HeaderName {
         &X_SIGNATURE
@@ -26,15 +64,7 @@ impl Header for Signature {
         I: Iterator<Item = &'i HeaderValue>,
     {
         let value = values.next().ok_or_else(headers::Error::invalid)?;
-        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
-
-        let value =
-            base64::decode(value).map_err(|_| headers::Error::invalid())?;
-        let value: [u8; 65] = value
-            .as_slice()
-            .try_into()
-            .map_err(|_| headers::Error::invalid())?;
-        Ok(Signature(value))
+        Signature::parse(value).map_err(|_| headers::Error::invalid())
     }
 
     fn encode<E>(&self, values: &mut E)
@@ -53,3 +83,220 @@ impl From<Signature> for [u8; 65] {
         value.0
     }
 }
+
+#[async_trait]
+impl<B: Send> FromRequest<B> for Signature {
+    type Rejection = SignatureRejection;
+
+    async fn from_request(
+        req: &mut RequestParts<B>,
+    ) -> Result<Self, Self::Rejection> {
+        let value = req
+            .headers()
+            .get(&*X_SIGNATURE)
+            .ok_or(SignatureRejection::Missing)?;
+        Signature::parse(value)
+    }
+}
+
+/// Represents the `x-target-signature` header.
+pub struct TargetSignature(Signature);
+
+impl Header for TargetSignature {
+    fn name() -> &'static HeaderName {
+        &X_TARGET_SIGNATURE
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        Signature::parse(value)
+            .map(TargetSignature)
+            .map_err(|_| headers::Error::invalid())
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        self.0.encode(values)
+    }
+}
+
+impl From<TargetSignature> for [u8; 65] {
+    fn from(value: TargetSignature) -> Self {
+        value.0.into()
+    }
+}
+
+/// Extract a signature that may be omitted.
+///
+/// A signature that is present but malformed is rejected
+/// rather than being treated as missing.
+pub struct OptionalSignature(pub Option<Signature>);
+
+#[async_trait]
+impl<B: Send> FromRequest<B> for OptionalSignature {
+    type Rejection = SignatureRejection;
+
+    async fn from_request(
+        req: &mut RequestParts<B>,
+    ) -> Result<Self, Self::Rejection> {
+        match Signature::from_request(req).await {
+            Ok(signature) => Ok(Self(Some(signature))),
+            Err(SignatureRejection::Missing) => Ok(Self(None)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Rejection for a missing or malformed `x-signature` header.
+///
+/// Responds with `400 Bad Request` and a JSON body describing
+/// the problem.
+#[derive(Debug)]
+pub enum SignatureRejection {
+    /// The header was not sent.
+    Missing,
+    /// The header is not valid base64.
+    InvalidEncoding,
+    /// The decoded signature is not 65 bytes.
+    InvalidLength(usize),
+}
+
+impl IntoResponse for SignatureRejection {
+    fn into_response(self) -> Response {
+        let (error, message) = match self {
+            Self::Missing => (
+                "missing_signature",
+                format!("missing {} header", *X_SIGNATURE),
+            ),
+            Self::InvalidEncoding => (
+                "invalid_signature_encoding",
+                format!("{} header is not valid base64", *X_SIGNATURE),
+            ),
+            Self::InvalidLength(length) => (
+                "invalid_signature_length",
+                format!(
+                    "{} header must decode to 65 bytes, got {}",
+                    *X_SIGNATURE, length
+                ),
+            ),
+        };
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": error, "message": message})),
+        )
+            .into_response()
+    }
+}
+
+/// Represents the `x-checksum` header.
+#[derive(Clone)]
+pub struct Checksum([u8; 32]);
+
+impl AsRef<[u8]> for Checksum {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Header for Checksum {
+    fn name() -> &'static HeaderName {
+        &X_CHECKSUM
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+        let value =
+            hex::decode(value).map_err(|_| headers::Error::invalid())?;
+        let value: [u8; 32] = value
+            .as_slice()
+            .try_into()
+            .map_err(|_| headers::Error::invalid())?;
+        Ok(Checksum(value))
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        let value = HeaderValue::from_str(&hex::encode(self.0))
+            .expect("failed to create checksum header");
+        values.extend(std::iter::once(value));
+    }
+}
+
+/// Represents the `x-nonce` header.
+#[derive(Clone, Copy)]
+pub struct Nonce(u64);
+
+impl Header for Nonce {
+    fn name() -> &'static HeaderName {
+        &X_NONCE
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+        let value: u64 =
+            value.parse().map_err(|_| headers::Error::invalid())?;
+        Ok(Nonce(value))
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        values.extend(std::iter::once(HeaderValue::from(self.0)));
+    }
+}
+
+impl From<Nonce> for u64 {
+    fn from(value: Nonce) -> Self {
+        value.0
+    }
+}
+
+/// Represents the `x-signature-expires` header.
+#[derive(Clone, Copy)]
+pub struct SignatureExpires(i64);
+
+impl Header for SignatureExpires {
+    fn name() -> &'static HeaderName {
+        &X_SIGNATURE_EXPIRES
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+        let value: i64 =
+            value.parse().map_err(|_| headers::Error::invalid())?;
+        Ok(SignatureExpires(value))
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        values.extend(std::iter::once(HeaderValue::from(self.0)));
+    }
+}
+
+impl From<SignatureExpires> for i64 {
+    fn from(value: SignatureExpires) -> Self {
+        value.0
+    }
+}
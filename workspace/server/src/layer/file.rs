@@ -2,12 +2,17 @@ use std::path::PathBuf;
 
 use async_trait::async_trait;
 use axum::body::Bytes;
+use sha3::{Digest, Sha3_256};
+use tokio::io::AsyncReadExt;
 
 use ipfs_registry_core::{Artifact, ObjectKey};
 
 use super::Layer;
 use crate::{Error, Result};
 
+/// Size of the chunks read when computing a checksum.
+const CHUNK_SIZE: usize = 64 * 1024;
+
 pub struct FileLayer {
     directory: PathBuf,
 }
@@ -49,4 +54,44 @@ impl Layer for FileLayer {
             Err(Error::BadObjectKey)
         }
     }
+
+    async fn get_artifact_checksum(
+        &self,
+        id: &ObjectKey,
+    ) -> Result<(Vec<u8>, [u8; 32])> {
+        if let ObjectKey::Pointer(key) = id {
+            let path = self.directory.join(key.clone());
+            if !path.exists() {
+                return Err(Error::NotFile(path));
+            }
+            let mut file = tokio::fs::File::open(path).await?;
+            let size = file.metadata().await?.len() as usize;
+            let mut data = Vec::with_capacity(size);
+            let mut hasher = Sha3_256::new();
+            let mut chunk = vec![0; CHUNK_SIZE];
+            loop {
+                let read = file.read(&mut chunk).await?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&chunk[..read]);
+                data.extend_from_slice(&chunk[..read]);
+            }
+            Ok((data, hasher.finalize().into()))
+        } else {
+            Err(Error::BadObjectKey)
+        }
+    }
+
+    async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+        if let ObjectKey::Pointer(key) = id {
+            let path = self.directory.join(key.clone());
+            if path.exists() {
+                tokio::fs::remove_file(path).await?;
+            }
+            Ok(())
+        } else {
+            Err(Error::BadObjectKey)
+        }
+    }
 }
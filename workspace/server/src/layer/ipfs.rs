@@ -1,9 +1,13 @@
 //! IPFS backed storage layer.
 use async_trait::async_trait;
 use axum::{body::Bytes, http::uri::Scheme};
+use cid::Cid;
 use futures::TryStreamExt;
 use ipfs_api_backend_hyper::{IpfsApi, IpfsClient, TryFromUri};
+use reqwest::{Client, StatusCode};
+use sha3::{Digest, Sha3_256};
 use std::io::Cursor;
+use tokio::sync::Semaphore;
 use url::Url;
 
 use hyper::client::HttpConnector;
@@ -16,15 +20,49 @@ use super::Layer;
 use crate::{Error, Result};
 
 /// Layer for IPFS backed storage.
-pub struct IpfsLayer {
-    client: IpfsClient<HttpsConnector<HttpConnector>>,
+pub struct IpfsLayer<C = IpfsClient<HttpsConnector<HttpConnector>>> {
+    client: C,
+    gateway: Option<(Client, Url)>,
+    pin: bool,
+    permits: Option<Semaphore>,
 }
 
 impl IpfsLayer {
     /// Create a new IPFS storage layer.
-    pub fn new(url: &Url) -> Result<Self> {
+    ///
+    /// When a gateway is given artifacts are read from the
+    /// gateway and written using the node API.
+    ///
+    /// When `pin` is not set artifacts are not pinned after they
+    /// are added and `concurrency` limits the number of add and
+    /// pin operations in flight.
+    pub fn new(
+        url: &Url,
+        gateway: Option<&Url>,
+        pin: bool,
+        concurrency: Option<usize>,
+    ) -> Result<Self> {
         let client = IpfsLayer::new_client(url)?;
-        Ok(Self { client })
+        let gateway = gateway.map(|url| {
+            tracing::info!(gateway = %url);
+            (Client::new(), url.clone())
+        });
+        Ok(IpfsLayer::with_client(client, gateway, pin, concurrency))
+    }
+    /// Fetch an artifact from the gateway.
+    async fn get_gateway(
+        client: &Client,
+        gateway: &Url,
+        id: &str,
+    ) -> Result<Vec<u8>> {
+        let url = gateway.join(&format!("ipfs/{}", id))?;
+        let response = client.get(url).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(Error::ObjectMissing(id.to_owned()));
+        } else if !response.status().is_success() {
+            return Err(Error::GatewayResponse(response.status().into()));
+        }
+        Ok(response.bytes().await?.to_vec())
     }
 
     /// Create a new IPFS client from the configuration URL.
@@ -55,8 +93,29 @@ impl IpfsLayer {
     }
 }
 
+impl<C> IpfsLayer<C> {
+    /// Create a storage layer using an existing client.
+    fn with_client(
+        client: C,
+        gateway: Option<(Client, Url)>,
+        pin: bool,
+        concurrency: Option<usize>,
+    ) -> Self {
+        Self {
+            client,
+            gateway,
+            pin,
+            permits: concurrency.map(Semaphore::new),
+        }
+    }
+}
+
 #[async_trait]
-impl Layer for IpfsLayer {
+impl<C> Layer for IpfsLayer<C>
+where
+    C: IpfsApi + Send + Sync,
+    Error: From<C::Error>,
+{
     fn supports_content_id(&self) -> bool {
         true
     }
@@ -66,14 +125,24 @@ impl Layer for IpfsLayer {
         data: Bytes,
         _descriptor: &Artifact,
     ) -> Result<ObjectKey> {
+        let _permit = if let Some(permits) = &self.permits {
+            Some(permits.acquire().await?)
+        } else {
+            None
+        };
         let data = Cursor::new(data);
         let add_res = self.client.add(data).await?;
-        self.client.pin_add(&add_res.hash, true).await?;
-        Ok(ObjectKey::Cid(add_res.hash.try_into()?))
+        if self.pin {
+            self.client.pin_add(&add_res.hash, true).await?;
+        }
+        Ok(ObjectKey::Cid(Cid::try_from(add_res.hash)?))
     }
 
     async fn get_artifact(&self, id: &ObjectKey) -> Result<Vec<u8>> {
         let id = id.to_string();
+        if let Some((client, gateway)) = &self.gateway {
+            return IpfsLayer::get_gateway(client, gateway, &id).await;
+        }
         let res = self
             .client
             .cat(&id)
@@ -82,4 +151,178 @@ impl Layer for IpfsLayer {
             .await?;
         Ok(res)
     }
+
+    async fn get_artifact_checksum(
+        &self,
+        id: &ObjectKey,
+    ) -> Result<(Vec<u8>, [u8; 32])> {
+        let id = id.to_string();
+        if let Some((client, gateway)) = &self.gateway {
+            let data = IpfsLayer::get_gateway(client, gateway, &id).await?;
+            let checksum = Sha3_256::digest(&data).into();
+            return Ok((data, checksum));
+        }
+        let (data, hasher) = self
+            .client
+            .cat(&id)
+            .try_fold(
+                (Vec::new(), Sha3_256::new()),
+                |(mut data, mut hasher), chunk| async move {
+                    hasher.update(&chunk);
+                    data.extend_from_slice(&chunk);
+                    Ok((data, hasher))
+                },
+            )
+            .await?;
+        Ok((data, hasher.finalize().into()))
+    }
+
+    async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+        // Unpinned content is reclaimed by the node garbage collector
+        let id = id.to_string();
+        let pins = self.client.pin_ls(Some(&id), None).await;
+        if pins.is_ok() {
+            self.client.pin_rm(&id, true).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::{HeaderName, HeaderValue};
+    use ipfs_api_backend_hyper::Form;
+    use ipfs_api_prelude::{ApiRequest, Backend, BoxStream};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        time::Duration,
+    };
+
+    const MOCK_CID: &str = "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb";
+
+    /// Backend that records the API paths requested and the
+    /// maximum number of concurrent requests.
+    #[derive(Default)]
+    struct MockBackend {
+        paths: Mutex<Vec<&'static str>>,
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Backend for MockBackend {
+        type HttpRequest = ();
+        type HttpResponse = ();
+        type Error = ipfs_api_backend_hyper::Error;
+
+        fn build_base_request<Req: ApiRequest>(
+            &self,
+            _req: Req,
+            _form: Option<Form<'static>>,
+        ) -> std::result::Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn get_header(_res: &(), _key: HeaderName) -> Option<&HeaderValue> {
+            None
+        }
+
+        async fn request_raw<Req: ApiRequest>(
+            &self,
+            _req: Req,
+            _form: Option<Form<'static>>,
+        ) -> std::result::Result<(StatusCode, Bytes), Self::Error> {
+            self.paths.lock().unwrap().push(Req::PATH);
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+
+            let body = match Req::PATH {
+                "/add" => format!(
+                    r#"{{"Name":"{0}","Hash":"{0}","Size":"0"}}"#,
+                    MOCK_CID
+                ),
+                "/pin/add" => format!(r#"{{"Pins":["{}"]}}"#, MOCK_CID),
+                _ => String::from("{}"),
+            };
+            Ok((StatusCode::OK, Bytes::from(body)))
+        }
+
+        fn response_to_byte_stream(
+            _res: (),
+        ) -> BoxStream<Bytes, Self::Error> {
+            Box::new(futures::stream::empty())
+        }
+
+        fn request_stream<Res, F>(
+            &self,
+            _req: (),
+            _process: F,
+        ) -> BoxStream<Res, Self::Error>
+        where
+            F: 'static + Send + Fn(()) -> BoxStream<Res, Self::Error>,
+        {
+            unimplemented!("streaming requests are not used")
+        }
+    }
+
+    fn mock_artifact() -> Artifact {
+        serde_json::from_str(
+            r#"{
+                "namespace": "mock-namespace",
+                "package": {"name": "mock-package", "version": "1.0.0"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn ipfs_layer_pin() -> Result<()> {
+        let layer =
+            IpfsLayer::with_client(MockBackend::default(), None, true, None);
+        let key = layer
+            .add_artifact(Bytes::from_static(b"mock"), &mock_artifact())
+            .await?;
+        assert_eq!(MOCK_CID, key.to_string());
+        assert_eq!(
+            vec!["/add", "/pin/add"],
+            *layer.client.paths.lock().unwrap()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ipfs_layer_pin_disabled() -> Result<()> {
+        let layer =
+            IpfsLayer::with_client(MockBackend::default(), None, false, None);
+        let key = layer
+            .add_artifact(Bytes::from_static(b"mock"), &mock_artifact())
+            .await?;
+        assert_eq!(MOCK_CID, key.to_string());
+        assert_eq!(vec!["/add"], *layer.client.paths.lock().unwrap());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ipfs_layer_concurrency() -> Result<()> {
+        let layer = IpfsLayer::with_client(
+            MockBackend::default(),
+            None,
+            true,
+            Some(2),
+        );
+        let artifact = mock_artifact();
+        let adds = (0..8).map(|_| {
+            layer.add_artifact(Bytes::from_static(b"mock"), &artifact)
+        });
+        futures::future::try_join_all(adds).await?;
+        assert_eq!(16, layer.client.paths.lock().unwrap().len());
+        assert_eq!(2, layer.client.peak.load(Ordering::SeqCst));
+        Ok(())
+    }
 }
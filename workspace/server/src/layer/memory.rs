@@ -50,4 +50,14 @@ impl Layer for MemoryLayer {
             Err(Error::BadObjectKey)
         }
     }
+
+    async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+        if let ObjectKey::Pointer(key) = id {
+            let mut writer = self.files.write().await;
+            writer.remove(key);
+            Ok(())
+        } else {
+            Err(Error::BadObjectKey)
+        }
+    }
 }
@@ -2,8 +2,19 @@
 use async_trait::async_trait;
 use axum::body::Bytes;
 use cid::Cid;
+use futures::future::BoxFuture;
+use sha3::{Digest, Sha3_256};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
-use ipfs_registry_core::{Artifact, ObjectKey};
+use ipfs_registry_core::{Artifact, ObjectKey, StoredObject};
 
 use crate::{
     config::{LayerConfig, RegistryConfig, ServerConfig},
@@ -13,21 +24,50 @@ use crate::{
 pub(crate) mod file;
 pub(crate) mod ipfs;
 pub(crate) mod memory;
+pub(crate) mod oci;
 pub(crate) mod s3;
 
+/// Number of times a write to a storage layer that failed
+/// when publishing under a write quorum is retried.
+const WRITE_RETRY_LIMIT: u32 = 5;
+
+/// Initial backoff before retrying a failed write.
+const WRITE_RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Type for a storage layer implementation.
+type BoxedLayer = Arc<dyn Layer + Send + Sync + 'static>;
+
+/// Cancellation flags for the write retries running for
+/// each pointer identifier.
+type ActiveRetries = Arc<Mutex<HashMap<String, Vec<Arc<AtomicBool>>>>>;
+
+/// Callback for an object stored by a successful write retry.
+pub type RetryCallback =
+    Arc<dyn Fn(StoredObject) -> BoxFuture<'static, ()> + Send + Sync>;
+
 /// Convert a configuration into a layer implementation.
 fn get_layer(
     config: &LayerConfig,
     registry: &RegistryConfig,
-) -> Result<Box<dyn Layer + Send + Sync + 'static>> {
+) -> Result<BoxedLayer> {
     match config {
-        LayerConfig::Ipfs { url } => Ok(Box::new(ipfs::IpfsLayer::new(url)?)),
+        LayerConfig::Ipfs {
+            url,
+            gateway,
+            pin,
+            concurrency,
+        } => Ok(Arc::new(ipfs::IpfsLayer::new(
+            url,
+            gateway.as_ref(),
+            *pin,
+            *concurrency,
+        )?)),
         LayerConfig::Aws {
             profile,
             region,
             bucket,
             prefix,
-        } => Ok(Box::new(s3::S3Layer::new(
+        } => Ok(Arc::new(s3::S3Layer::new(
             profile.to_string(),
             region.to_string(),
             bucket.to_string(),
@@ -35,11 +75,21 @@ fn get_layer(
             prefix.clone(),
         )?)),
         LayerConfig::Memory { .. } => {
-            Ok(Box::new(memory::MemoryLayer::new()))
+            Ok(Arc::new(memory::MemoryLayer::new()))
         }
         LayerConfig::File { directory } => {
-            Ok(Box::new(file::FileLayer::new(directory.clone())))
+            Ok(Arc::new(file::FileLayer::new(directory.clone())))
         }
+        LayerConfig::Oci {
+            registry: url,
+            repository,
+            credentials,
+        } => Ok(Arc::new(oci::OciLayer::new(
+            url.clone(),
+            repository.clone(),
+            credentials.clone(),
+            registry.mime.clone(),
+        )?)),
     }
 }
 
@@ -50,64 +100,252 @@ pub fn build(config: &ServerConfig) -> Result<Layers> {
         storage.push(get_layer(layer, &config.registry)?);
     }
 
-    Ok(Layers { storage })
+    let timeout = config
+        .timeout
+        .as_ref()
+        .and_then(|timeout| timeout.storage)
+        .map(Duration::from_secs);
+
+    let write_quorum = config
+        .storage
+        .write_quorum
+        .unwrap_or(config.storage.layers.len());
+
+    Ok(Layers {
+        storage,
+        timeout,
+        write_quorum,
+        retry_backoff: WRITE_RETRY_BACKOFF,
+        retries: Default::default(),
+    })
+}
+
+/// Limit the duration of a storage layer operation.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    if let Some(duration) = timeout {
+        tokio::time::timeout(duration, operation)
+            .await
+            .map_err(|_| Error::StorageTimeout(duration))?
+    } else {
+        operation.await
+    }
 }
 
 /// Type for a collection of storage layer implementations.
 pub struct Layers {
-    storage: Vec<Box<dyn Layer + Send + Sync + 'static>>,
+    storage: Vec<BoxedLayer>,
+    timeout: Option<Duration>,
+    /// Number of layers that must accept an artifact
+    /// for a publish to succeed.
+    write_quorum: usize,
+    /// Initial backoff for retrying failed writes.
+    retry_backoff: Duration,
+    /// Write retries running in the background.
+    retries: ActiveRetries,
 }
 
 impl Layers {
-    /// Publish an artifact to all storage layers.
+    /// Add a storage layer.
+    #[cfg(test)]
+    pub(crate) fn push(&mut self, layer: BoxedLayer) {
+        self.storage.push(layer);
+    }
+
+    /// Limit the duration of a storage layer operation.
+    async fn with_timeout<T>(
+        &self,
+        operation: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        with_timeout(self.timeout, operation).await
+    }
+
+    /// Publish an artifact to the storage layers.
+    ///
+    /// Succeeds once the write quorum of layers have accepted the
+    /// artifact and returns the identifiers from those layers with
+    /// the index of each layer and the writes to the remaining
+    /// layers to retry once the publish has been recorded.
+    ///
+    /// Retries still running for an earlier artifact with the same
+    /// pointer identifier are cancelled.
     pub async fn publish(
         &self,
         data: Bytes,
         artifact: &Artifact,
-    ) -> Result<Vec<ObjectKey>> {
+    ) -> Result<(Vec<StoredObject>, PendingWrites)> {
+        if self.storage.is_empty() {
+            return Err(Error::NoStorageLayers);
+        }
+
+        self.cancel_retries(&artifact.pointer_id());
+
         // Do it like this to avoid an unnecessary clone() on the
         // buffer when only a single storage layer is configured
         let has_mirrors = self.storage.len() > 1;
         if has_mirrors {
             let mut keys = Vec::new();
-            for layer in self.storage.iter() {
-                let id = layer.add_artifact(data.clone(), artifact).await?;
-                keys.push(id);
+            let mut failed = Vec::new();
+            for (index, layer) in self.storage.iter().enumerate() {
+                match self
+                    .with_timeout(layer.add_artifact(data.clone(), artifact))
+                    .await
+                {
+                    Ok(key) => keys.push(StoredObject { layer: index, key }),
+                    Err(e) => {
+                        tracing::error!(layer = index, "{}", e);
+                        failed.push(index);
+                        // Quorum can no longer be reached
+                        if self.storage.len() - failed.len()
+                            < self.write_quorum
+                        {
+                            return Err(e);
+                        }
+                    }
+                }
             }
-            Ok(keys)
+
+            if !failed.is_empty() {
+                tracing::warn!(
+                    accepted = keys.len(),
+                    failed = ?failed,
+                    "publish reached write quorum, pending retry of failed layers");
+            }
+
+            Ok((keys, self.pending_writes(failed, data, artifact)))
         } else {
-            let primary = self
-                .storage
-                .get(0)
-                .expect("failed to get primary storage layer");
-            Ok(vec![primary.add_artifact(data, artifact).await?])
+            let primary =
+                self.storage.first().ok_or(Error::NoStorageLayers)?;
+            let key = self
+                .with_timeout(primary.add_artifact(data, artifact))
+                .await?;
+            Ok((
+                vec![StoredObject { layer: 0, key }],
+                self.pending_writes(vec![], Bytes::new(), artifact),
+            ))
+        }
+    }
+
+    /// Writes to retry for the layers that failed to
+    /// accept an artifact.
+    fn pending_writes(
+        &self,
+        failed: Vec<usize>,
+        data: Bytes,
+        artifact: &Artifact,
+    ) -> PendingWrites {
+        PendingWrites {
+            layers: failed
+                .into_iter()
+                .map(|index| (index, Arc::clone(&self.storage[index])))
+                .collect(),
+            data,
+            artifact: artifact.clone(),
+            timeout: self.timeout,
+            backoff: self.retry_backoff,
+            retries: Arc::clone(&self.retries),
+        }
+    }
+
+    /// Cancel the write retries running for a pointer identifier.
+    fn cancel_retries(&self, pointer_id: &str) {
+        if let Some(flags) = self.retries.lock().unwrap().remove(pointer_id) {
+            for flag in flags {
+                flag.store(true, Ordering::SeqCst);
+            }
         }
     }
 
+    /// Key for an artifact in the storage layer at an index.
+    ///
+    /// Layers keyed by content identifier use the key recorded for
+    /// the layer when the artifact was stored as each layer derives
+    /// its own content identifier; artifacts stored before the keys
+    /// were recorded fall back to the content identifier. Layers
+    /// keyed by content identifier are skipped when there is no
+    /// content identifier.
+    fn object_key(
+        index: usize,
+        layer: &BoxedLayer,
+        pointer_id: &ObjectKey,
+        content_id: Option<&ObjectKey>,
+        objects: &[StoredObject],
+    ) -> Option<ObjectKey> {
+        if !layer.supports_content_id() {
+            return Some(pointer_id.clone());
+        }
+        let content_id = content_id?;
+        Some(
+            objects
+                .iter()
+                .find(|o| o.layer == index)
+                .map(|o| o.key.clone())
+                .unwrap_or_else(|| content_id.clone()),
+        )
+    }
+
     /// Fetch an artifact from the storage layers.
+    ///
+    /// When a checksum is given the artifact returned by each layer
+    /// is verified and a layer that returns corrupt data is skipped
+    /// so the next mirror is tried; the checksum is computed while
+    /// the artifact is read from the layer.
     pub async fn fetch(
         &self,
         pointer_id: &str,
         content_id: Option<&Cid>,
+        objects: &[StoredObject],
+        checksum: Option<&[u8]>,
     ) -> Result<Vec<u8>> {
         let pointer_id = ObjectKey::Pointer(pointer_id.to_string());
-        let content_id = content_id.map(|c| ObjectKey::Cid(c.clone()));
+        let content_id = content_id.map(|c| ObjectKey::Cid(*c));
+
+        if self.storage.is_empty() {
+            return Err(Error::NoStorageLayers);
+        }
 
         let len = self.storage.len();
         for (index, layer) in self.storage.iter().enumerate() {
-            let is_last = index == len - 1;
-            let result = if layer.supports_content_id() {
-                if let Some(content_id) = &content_id {
-                    layer.get_artifact(content_id).await
-                } else {
-                    continue;
-                }
+            let is_last = index + 1 == len;
+            let id = if let Some(id) = Self::object_key(
+                index,
+                layer,
+                &pointer_id,
+                content_id.as_ref(),
+                objects,
+            ) {
+                id
             } else {
-                layer.get_artifact(&pointer_id).await
+                continue;
+            };
+
+            let result = if let Some(checksum) = checksum {
+                self.with_timeout(layer.get_artifact_checksum(&id))
+                    .await
+                    .map(|(data, digest)| {
+                        (data, digest.as_slice() == checksum)
+                    })
+            } else {
+                self.with_timeout(layer.get_artifact(&id))
+                    .await
+                    .map(|data| (data, true))
             };
 
             match result {
-                Ok(result) => return Ok(result),
+                Ok((result, verified)) => {
+                    if verified {
+                        return Ok(result);
+                    }
+                    tracing::warn!(
+                        layer = index,
+                        key = %pointer_id,
+                        "artifact checksum mismatch");
+                    if is_last {
+                        return Err(Error::Checksum(pointer_id.to_string()));
+                    }
+                }
                 Err(e) => {
                     tracing::error!("{}", e);
                     if is_last {
@@ -122,6 +360,188 @@ impl Layers {
             content_id.map(|c| c.to_string()),
         ))
     }
+
+    /// Remove an artifact from all storage layers.
+    ///
+    /// Write retries for the artifact are cancelled and every
+    /// layer is attempted; if any layer fails the last error
+    /// is returned.
+    ///
+    /// Layers keyed by content identifier are only attempted when
+    /// a content identifier is given so shared content can be kept.
+    pub async fn remove(
+        &self,
+        pointer_id: &str,
+        content_id: Option<&Cid>,
+        objects: &[StoredObject],
+    ) -> Result<()> {
+        self.cancel_retries(pointer_id);
+
+        let pointer_id = ObjectKey::Pointer(pointer_id.to_string());
+        let content_id = content_id.map(|c| ObjectKey::Cid(*c));
+
+        if self.storage.is_empty() {
+            return Err(Error::NoStorageLayers);
+        }
+
+        let mut result = Ok(());
+        for (index, layer) in self.storage.iter().enumerate() {
+            let id = if let Some(id) = Self::object_key(
+                index,
+                layer,
+                &pointer_id,
+                content_id.as_ref(),
+                objects,
+            ) {
+                id
+            } else {
+                continue;
+            };
+
+            if let Err(e) =
+                self.with_timeout(layer.remove_artifact(&id)).await
+            {
+                tracing::error!("{}", e);
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+/// Writes to storage layers that failed when publishing an
+/// artifact under a write quorum.
+///
+/// Nothing is retried until `retry()` is called so a publish that
+/// fails after the artifact was stored drops the pending writes
+/// rather than writing an orphaned artifact.
+#[must_use]
+pub struct PendingWrites {
+    layers: Vec<(usize, BoxedLayer)>,
+    data: Bytes,
+    artifact: Artifact,
+    timeout: Option<Duration>,
+    backoff: Duration,
+    retries: ActiveRetries,
+}
+
+impl PendingWrites {
+    /// Pointer identifier of the artifact.
+    pub fn pointer_id(&self) -> String {
+        self.artifact.pointer_id()
+    }
+
+    /// Retry the failed writes in the background using an
+    /// exponential backoff.
+    ///
+    /// The callback receives the object stored by each retry that
+    /// succeeds; a retry is abandoned when the artifact is removed
+    /// or published again.
+    pub fn retry(self, on_success: RetryCallback) {
+        let pointer_id = self.artifact.pointer_id();
+        for (index, layer) in self.layers {
+            let cancelled = Arc::new(AtomicBool::new(false));
+            self.retries
+                .lock()
+                .unwrap()
+                .entry(pointer_id.clone())
+                .or_default()
+                .push(Arc::clone(&cancelled));
+
+            let data = self.data.clone();
+            let artifact = self.artifact.clone();
+            let timeout = self.timeout;
+            let backoff = self.backoff;
+            let retries = Arc::clone(&self.retries);
+            let pointer_id = pointer_id.clone();
+            let on_success = Arc::clone(&on_success);
+            tokio::spawn(async move {
+                retry_write(
+                    index, layer, data, &artifact, timeout, backoff,
+                    &cancelled, on_success,
+                )
+                .await;
+
+                let mut retries = retries.lock().unwrap();
+                if let Some(flags) = retries.get_mut(&pointer_id) {
+                    flags.retain(|flag| !Arc::ptr_eq(flag, &cancelled));
+                    if flags.is_empty() {
+                        retries.remove(&pointer_id);
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Retry writing an artifact to a layer until it succeeds, the
+/// retries are exhausted or the retry is cancelled.
+#[allow(clippy::too_many_arguments)]
+async fn retry_write(
+    index: usize,
+    layer: BoxedLayer,
+    data: Bytes,
+    artifact: &Artifact,
+    timeout: Option<Duration>,
+    backoff: Duration,
+    cancelled: &AtomicBool,
+    on_success: RetryCallback,
+) {
+    let pointer_id = artifact.pointer_id();
+    for attempt in 0..WRITE_RETRY_LIMIT {
+        tokio::time::sleep(backoff * 2u32.pow(attempt)).await;
+        if cancelled.load(Ordering::SeqCst) {
+            tracing::info!(
+                layer = index,
+                pointer_id = %pointer_id,
+                "write retry cancelled");
+            return;
+        }
+
+        match with_timeout(
+            timeout,
+            layer.add_artifact(data.clone(), artifact),
+        )
+        .await
+        {
+            Ok(key) => {
+                // Artifact was removed while the write was in
+                // flight; content identifiers may be shared so
+                // only pointer objects are removed
+                if cancelled.load(Ordering::SeqCst) {
+                    if let ObjectKey::Pointer(_) = &key {
+                        if let Err(e) = layer.remove_artifact(&key).await {
+                            tracing::error!(
+                                layer = index,
+                                pointer_id = %pointer_id,
+                                error = %e,
+                                "failed to remove cancelled write");
+                        }
+                    }
+                    return;
+                }
+
+                tracing::info!(
+                    layer = index,
+                    pointer_id = %pointer_id,
+                    "write retry succeeded");
+                on_success(StoredObject { layer: index, key }).await;
+                return;
+            }
+            Err(e) => {
+                tracing::error!(
+                    layer = index,
+                    attempt = attempt + 1,
+                    "write retry failed: {}",
+                    e
+                );
+            }
+        }
+    }
+    tracing::error!(
+        layer = index,
+        pointer_id = %pointer_id,
+        "write retries exhausted");
 }
 
 /// Trait for a storage layer.
@@ -139,4 +559,504 @@ pub trait Layer {
 
     /// Get an artifact from storage by identifier.
     async fn get_artifact(&self, id: &ObjectKey) -> Result<Vec<u8>>;
+
+    /// Get an artifact from storage by identifier and compute the
+    /// checksum of the artifact.
+    ///
+    /// Layers that read an artifact in chunks should feed each chunk
+    /// to the hasher so the artifact is only read once.
+    async fn get_artifact_checksum(
+        &self,
+        id: &ObjectKey,
+    ) -> Result<(Vec<u8>, [u8; 32])>
+    where
+        Self: Sync,
+    {
+        let data = self.get_artifact(id).await?;
+        let checksum = Sha3_256::digest(&data).into();
+        Ok((data, checksum))
+    }
+
+    /// Remove an artifact from storage by identifier.
+    ///
+    /// Removing an artifact that does not exist is not an error.
+    async fn remove_artifact(&self, id: &ObjectKey) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ipfs_registry_core::{Namespace, PackageMeta, PackageName};
+    use semver::Version;
+
+    fn mock_layers(
+        storage: Vec<BoxedLayer>,
+        timeout: Option<Duration>,
+    ) -> Layers {
+        let write_quorum = storage.len();
+        Layers {
+            storage,
+            timeout,
+            write_quorum,
+            retry_backoff: Duration::from_millis(10),
+            retries: Default::default(),
+        }
+    }
+
+    fn mock_artifact() -> Artifact {
+        Artifact {
+            kind: Default::default(),
+            namespace: Namespace::new_unchecked("mock-namespace"),
+            package: PackageMeta {
+                name: PackageName::new_unchecked("mock-package"),
+                version: Version::new(1, 0, 0),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_layers_publish() {
+        let layers = mock_layers(vec![], None);
+        let result = layers
+            .publish(Bytes::from_static(b"mock"), &mock_artifact())
+            .await;
+        assert!(matches!(result, Err(Error::NoStorageLayers)));
+    }
+
+    #[tokio::test]
+    async fn empty_layers_fetch() {
+        let layers = mock_layers(vec![], None);
+        let result = layers
+            .fetch(&mock_artifact().pointer_id(), None, &[], None)
+            .await;
+        assert!(matches!(result, Err(Error::NoStorageLayers)));
+    }
+
+    /// Memory layer that is slow to return artifacts.
+    struct SlowLayer {
+        inner: memory::MemoryLayer,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Layer for SlowLayer {
+        fn supports_content_id(&self) -> bool {
+            false
+        }
+
+        async fn add_artifact(
+            &self,
+            data: Bytes,
+            artifact: &Artifact,
+        ) -> Result<ObjectKey> {
+            self.inner.add_artifact(data, artifact).await
+        }
+
+        async fn get_artifact(&self, id: &ObjectKey) -> Result<Vec<u8>> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.get_artifact(id).await
+        }
+
+        async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+            self.inner.remove_artifact(id).await
+        }
+    }
+
+    fn slow_layer() -> BoxedLayer {
+        Arc::new(SlowLayer {
+            inner: memory::MemoryLayer::new(),
+            delay: Duration::from_secs(5),
+        })
+    }
+
+    #[tokio::test]
+    async fn slow_layer_fetch_timeout() {
+        let layers =
+            mock_layers(vec![slow_layer()], Some(Duration::from_millis(50)));
+        let artifact = mock_artifact();
+        let _ = layers
+            .publish(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+        let result =
+            layers.fetch(&artifact.pointer_id(), None, &[], None).await;
+        assert!(matches!(result, Err(Error::StorageTimeout(_))));
+    }
+
+    #[tokio::test]
+    async fn slow_layer_fetch_mirror() {
+        let layers = mock_layers(
+            vec![slow_layer(), Arc::new(memory::MemoryLayer::new())],
+            Some(Duration::from_millis(50)),
+        );
+        let artifact = mock_artifact();
+        let _ = layers
+            .publish(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+        let result = layers
+            .fetch(&artifact.pointer_id(), None, &[], None)
+            .await
+            .unwrap();
+        assert_eq!(b"mock".to_vec(), result);
+    }
+
+    #[tokio::test]
+    async fn corrupt_layer_fetch_mirror() {
+        let corrupt = memory::MemoryLayer::new();
+        let mirror = memory::MemoryLayer::new();
+        let artifact = mock_artifact();
+        corrupt
+            .add_artifact(Bytes::from_static(b"corrupt"), &artifact)
+            .await
+            .unwrap();
+        mirror
+            .add_artifact(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+
+        let checksum = Sha3_256::digest(b"mock");
+        let layers =
+            mock_layers(vec![Arc::new(corrupt), Arc::new(mirror)], None);
+
+        // Without a checksum the corrupt bytes are returned
+        let result = layers
+            .fetch(&artifact.pointer_id(), None, &[], None)
+            .await
+            .unwrap();
+        assert_eq!(b"corrupt".to_vec(), result);
+
+        // With a checksum the mirror is used
+        let result = layers
+            .fetch(
+                &artifact.pointer_id(),
+                None,
+                &[],
+                Some(checksum.as_slice()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(b"mock".to_vec(), result);
+
+        // Every layer is corrupt
+        let layers = mock_layers(vec![layers.storage[0].clone()], None);
+        let result = layers
+            .fetch(
+                &artifact.pointer_id(),
+                None,
+                &[],
+                Some(checksum.as_slice()),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Checksum(_))));
+    }
+
+    /// Layer keyed by a content identifier that only accepts
+    /// the content identifier it derives for artifacts.
+    struct ContentLayer {
+        key: Cid,
+        files: Mutex<HashMap<Cid, Vec<u8>>>,
+    }
+
+    impl ContentLayer {
+        fn new(key: Cid) -> Self {
+            Self {
+                key,
+                files: Mutex::new(HashMap::new()),
+            }
+        }
+
+        fn cid(id: &ObjectKey) -> Result<Cid> {
+            match id {
+                ObjectKey::Cid(cid) => Ok(*cid),
+                ObjectKey::Pointer(_) => Err(Error::BadObjectKey),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Layer for ContentLayer {
+        fn supports_content_id(&self) -> bool {
+            true
+        }
+
+        async fn add_artifact(
+            &self,
+            data: Bytes,
+            _artifact: &Artifact,
+        ) -> Result<ObjectKey> {
+            self.files.lock().unwrap().insert(self.key, data.to_vec());
+            Ok(ObjectKey::Cid(self.key))
+        }
+
+        async fn get_artifact(&self, id: &ObjectKey) -> Result<Vec<u8>> {
+            let cid = Self::cid(id)?;
+            if cid != self.key {
+                return Err(Error::BadObjectKey);
+            }
+            self.files
+                .lock()
+                .unwrap()
+                .get(&cid)
+                .cloned()
+                .ok_or_else(|| Error::ObjectMissing(cid.to_string()))
+        }
+
+        async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+            let cid = Self::cid(id)?;
+            if cid != self.key {
+                return Err(Error::BadObjectKey);
+            }
+            self.files.lock().unwrap().remove(&cid);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn mixed_content_layers() {
+        // Same content with a different codec for each layer,
+        // like the dag-pb and raw identifiers of IPFS and OCI
+        let dag: Cid = "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb"
+            .try_into()
+            .unwrap();
+        let raw = Cid::new_v1(0x55, *dag.hash());
+        let first = Arc::new(ContentLayer::new(dag));
+        let second = Arc::new(ContentLayer::new(raw));
+        let layers = mock_layers(vec![first.clone(), second.clone()], None);
+
+        let artifact = mock_artifact();
+        let (objects, _) = layers
+            .publish(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+        let content_id = dag;
+        let checksum = Sha3_256::digest(b"mock");
+
+        // Mirror is fetched using the key for that layer
+        first.files.lock().unwrap().clear();
+        let result = layers
+            .fetch(
+                &artifact.pointer_id(),
+                Some(&content_id),
+                &objects,
+                Some(checksum.as_slice()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(b"mock".to_vec(), result);
+
+        // Every layer is removed using the key for that layer
+        layers
+            .remove(&artifact.pointer_id(), Some(&content_id), &objects)
+            .await
+            .unwrap();
+        assert!(second.files.lock().unwrap().is_empty());
+
+        // Artifacts stored before the keys were recorded
+        // use the content identifier for every layer
+        let result = layers
+            .remove(&artifact.pointer_id(), Some(&content_id), &[])
+            .await;
+        assert!(matches!(result, Err(Error::BadObjectKey)));
+    }
+
+    #[tokio::test]
+    async fn file_layer_streaming_checksum() {
+        let directory = std::env::temp_dir()
+            .join(format!("ipfs-registry-checksum-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // Larger than a single chunk
+        let data: Vec<u8> = (0..200_000).map(|i| i as u8).collect();
+        let artifact = mock_artifact();
+        let layer = file::FileLayer::new(directory.clone());
+        let id = layer
+            .add_artifact(Bytes::from(data.clone()), &artifact)
+            .await
+            .unwrap();
+
+        let (result, checksum) =
+            layer.get_artifact_checksum(&id).await.unwrap();
+        assert_eq!(data, result);
+        assert_eq!(Sha3_256::digest(&data).as_slice(), checksum.as_slice());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    /// Memory layer that fails to add the first `failures` artifacts.
+    struct FlakyLayer {
+        inner: memory::MemoryLayer,
+        failures: std::sync::atomic::AtomicU32,
+    }
+
+    impl FlakyLayer {
+        fn new(failures: u32) -> Self {
+            Self {
+                inner: memory::MemoryLayer::new(),
+                failures: std::sync::atomic::AtomicU32::new(failures),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Layer for FlakyLayer {
+        fn supports_content_id(&self) -> bool {
+            false
+        }
+
+        async fn add_artifact(
+            &self,
+            data: Bytes,
+            artifact: &Artifact,
+        ) -> Result<ObjectKey> {
+            use std::sync::atomic::Ordering;
+            let failures = self.failures.load(Ordering::SeqCst);
+            if failures > 0 {
+                self.failures.store(failures - 1, Ordering::SeqCst);
+                return Err(Error::StorageTimeout(Duration::from_secs(0)));
+            }
+            self.inner.add_artifact(data, artifact).await
+        }
+
+        async fn get_artifact(&self, id: &ObjectKey) -> Result<Vec<u8>> {
+            self.inner.get_artifact(id).await
+        }
+
+        async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+            self.inner.remove_artifact(id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn write_quorum_all_failing_mirror() {
+        let layers = mock_layers(
+            vec![
+                Arc::new(memory::MemoryLayer::new()),
+                Arc::new(FlakyLayer::new(u32::MAX)),
+            ],
+            None,
+        );
+        let result = layers
+            .publish(Bytes::from_static(b"mock"), &mock_artifact())
+            .await;
+        assert!(matches!(result, Err(Error::StorageTimeout(_))));
+    }
+
+    #[tokio::test]
+    async fn write_quorum_majority_failing_mirror() {
+        let mut layers = mock_layers(
+            vec![
+                Arc::new(memory::MemoryLayer::new()),
+                Arc::new(FlakyLayer::new(u32::MAX)),
+                Arc::new(memory::MemoryLayer::new()),
+            ],
+            None,
+        );
+        layers.write_quorum = 2;
+        let (keys, _) = layers
+            .publish(Bytes::from_static(b"mock"), &mock_artifact())
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![0, 2],
+            keys.iter().map(|o| o.layer).collect::<Vec<_>>()
+        );
+
+        // Two failing mirrors cannot reach the quorum
+        layers.storage[2] = Arc::new(FlakyLayer::new(u32::MAX));
+        let result = layers
+            .publish(Bytes::from_static(b"mock"), &mock_artifact())
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn write_quorum_retry() {
+        let flaky = Arc::new(FlakyLayer::new(2));
+        let mut layers = mock_layers(
+            vec![Arc::new(memory::MemoryLayer::new()), flaky.clone()],
+            None,
+        );
+        layers.write_quorum = 1;
+        let artifact = mock_artifact();
+        let (keys, pending) = layers
+            .publish(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+        assert_eq!(1, keys.len());
+        assert_eq!(0, keys[0].layer);
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        pending.retry(Arc::new(move |object| {
+            let tx = tx.clone();
+            Box::pin(async move {
+                tx.send(object).unwrap();
+            })
+        }));
+
+        // Retried with a backoff of 10ms then 20ms
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let id = ObjectKey::Pointer(artifact.pointer_id());
+        assert_eq!(b"mock".to_vec(), flaky.get_artifact(&id).await.unwrap());
+
+        // Layer the retry succeeded on is reported
+        let object = rx.try_recv().unwrap();
+        assert_eq!(1, object.layer);
+        assert!(layers.retries.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn write_quorum_retry_dropped() {
+        let flaky = Arc::new(FlakyLayer::new(1));
+        let mut layers = mock_layers(
+            vec![Arc::new(memory::MemoryLayer::new()), flaky.clone()],
+            None,
+        );
+        layers.write_quorum = 1;
+        let artifact = mock_artifact();
+
+        // Publish failed to be recorded so the writes are not retried
+        let (_, pending) = layers
+            .publish(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+        drop(pending);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let id = ObjectKey::Pointer(artifact.pointer_id());
+        assert!(flaky.get_artifact(&id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn write_quorum_retry_cancelled() {
+        let flaky = Arc::new(FlakyLayer::new(2));
+        let mut layers = mock_layers(
+            vec![Arc::new(memory::MemoryLayer::new()), flaky.clone()],
+            None,
+        );
+        layers.write_quorum = 1;
+        let artifact = mock_artifact();
+        let (_, pending) = layers
+            .publish(Bytes::from_static(b"mock"), &artifact)
+            .await
+            .unwrap();
+        let succeeded = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&succeeded);
+        pending.retry(Arc::new(move |_| {
+            flag.store(true, Ordering::SeqCst);
+            Box::pin(async {})
+        }));
+
+        // Removing the artifact cancels the retry
+        layers
+            .remove(&artifact.pointer_id(), None, &[])
+            .await
+            .unwrap();
+        assert!(layers.retries.lock().unwrap().is_empty());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let id = ObjectKey::Pointer(artifact.pointer_id());
+        assert!(flaky.get_artifact(&id).await.is_err());
+        assert!(!succeeded.load(Ordering::SeqCst));
+    }
 }
@@ -0,0 +1,277 @@
+//! OCI registry backed storage layer.
+//!
+//! Artifacts are pushed as blobs and referenced by an image manifest
+//! tagged with the pointer id. Blob digests are represented as raw
+//! CIDv1 content identifiers so they can be stored and fetched in the
+//! same way as IPFS content identifiers.
+use async_trait::async_trait;
+use axum::body::Bytes;
+use cid::{multihash::MultihashGeneric, Cid};
+use reqwest::{
+    header::{CONTENT_TYPE, LOCATION},
+    Client, RequestBuilder, StatusCode,
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use ipfs_registry_core::{Artifact, ObjectKey};
+
+use super::Layer;
+use crate::{config::OciCredentials, Error, Result};
+
+/// Multicodec for raw binary data.
+const RAW_CODEC: u64 = 0x55;
+
+/// Multihash code for SHA2-256.
+const SHA2_256: u64 = 0x12;
+
+/// Media type for image manifests.
+const MANIFEST_MIME: &str = "application/vnd.oci.image.manifest.v1+json";
+
+/// Media type for the empty config blob.
+const EMPTY_MIME: &str = "application/vnd.oci.empty.v1+json";
+
+/// Content of the empty config blob.
+const EMPTY_CONFIG: &[u8] = b"{}";
+
+/// Compute the SHA2-256 hash of a buffer.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Format a hash as an OCI digest.
+fn digest(hash: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(hash))
+}
+
+/// Compute the OCI digest of a buffer.
+fn digest_of(data: &[u8]) -> String {
+    digest(&sha256(data))
+}
+
+/// Convert a hash to a raw content identifier.
+fn hash_to_cid(hash: &[u8]) -> Result<Cid> {
+    let multihash = MultihashGeneric::<64>::wrap(SHA2_256, hash)
+        .map_err(cid::Error::from)?;
+    Ok(Cid::new_v1(RAW_CODEC, multihash))
+}
+
+/// Convert a raw content identifier to an OCI digest.
+fn cid_to_digest(cid: &Cid) -> Result<String> {
+    if cid.codec() != RAW_CODEC || cid.hash().code() != SHA2_256 {
+        return Err(Error::BadObjectKey);
+    }
+    Ok(digest(cid.hash().digest()))
+}
+
+/// Layer for OCI registry backed storage.
+pub struct OciLayer {
+    client: Client,
+    registry: Url,
+    repository: String,
+    credentials: Option<OciCredentials>,
+    content_type: String,
+}
+
+impl OciLayer {
+    /// Create a new OCI storage layer.
+    pub fn new(
+        registry: Url,
+        repository: String,
+        credentials: Option<OciCredentials>,
+        content_type: String,
+    ) -> Result<Self> {
+        if registry.host_str().is_none() {
+            return Err(Error::InvalidHost(registry));
+        }
+
+        tracing::info!(registry = %registry, repository = %repository);
+
+        Ok(Self {
+            client: Client::new(),
+            registry,
+            repository,
+            credentials,
+            content_type,
+        })
+    }
+
+    /// Get a URL for a path in the repository.
+    fn url(&self, path: &str) -> Result<Url> {
+        Ok(self
+            .registry
+            .join(&format!("v2/{}/{}", self.repository, path))?)
+    }
+
+    /// Add credentials to a request.
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        if let Some(credentials) = &self.credentials {
+            request.basic_auth(
+                &credentials.username,
+                Some(&credentials.password),
+            )
+        } else {
+            request
+        }
+    }
+
+    /// Push a blob to the repository.
+    async fn push_blob(&self, data: Bytes, digest: &str) -> Result<()> {
+        let request = self.client.post(self.url("blobs/uploads/")?);
+        let response = self.authorize(request).send().await?;
+        if response.status() != StatusCode::ACCEPTED {
+            return Err(Error::OciResponse(response.status().into()));
+        }
+
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(Error::OciUploadLocation)?;
+
+        let mut url = self.registry.join(location)?;
+        url.query_pairs_mut().append_pair("digest", digest);
+
+        let request = self
+            .client
+            .put(url)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(data);
+        let response = self.authorize(request).send().await?;
+        if response.status() != StatusCode::CREATED {
+            return Err(Error::OciResponse(response.status().into()));
+        }
+
+        Ok(())
+    }
+
+    /// Push an image manifest referencing an artifact blob.
+    async fn push_manifest(
+        &self,
+        artifact: &Artifact,
+        digest: &str,
+        size: usize,
+    ) -> Result<()> {
+        let config = digest_of(EMPTY_CONFIG);
+        self.push_blob(Bytes::from_static(EMPTY_CONFIG), &config)
+            .await?;
+
+        let manifest = json!({
+            "schemaVersion": 2,
+            "mediaType": MANIFEST_MIME,
+            "artifactType": self.content_type,
+            "config": {
+                "mediaType": EMPTY_MIME,
+                "digest": config,
+                "size": EMPTY_CONFIG.len(),
+            },
+            "layers": [{
+                "mediaType": self.content_type,
+                "digest": digest,
+                "size": size,
+                "annotations": {
+                    "org.opencontainers.image.title":
+                        artifact.package.name.to_string(),
+                },
+            }],
+            "annotations": {
+                "org.opencontainers.image.title":
+                    format!("{}/{}", artifact.namespace, artifact.package.name),
+                "org.opencontainers.image.version":
+                    artifact.package.version.to_string(),
+            },
+        });
+
+        let request = self
+            .client
+            .put(self.url(&format!("manifests/{}", artifact.pointer_id()))?)
+            .header(CONTENT_TYPE, MANIFEST_MIME)
+            .body(serde_json::to_vec(&manifest)?);
+        let response = self.authorize(request).send().await?;
+        if response.status() != StatusCode::CREATED {
+            return Err(Error::OciResponse(response.status().into()));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Layer for OciLayer {
+    fn supports_content_id(&self) -> bool {
+        true
+    }
+
+    async fn add_artifact(
+        &self,
+        data: Bytes,
+        artifact: &Artifact,
+    ) -> Result<ObjectKey> {
+        let hash = sha256(&data);
+        let digest = digest(&hash);
+        let size = data.len();
+        self.push_blob(data, &digest).await?;
+        self.push_manifest(artifact, &digest, size).await?;
+        Ok(ObjectKey::Cid(hash_to_cid(&hash)?))
+    }
+
+    async fn get_artifact(&self, id: &ObjectKey) -> Result<Vec<u8>> {
+        if let ObjectKey::Cid(cid) = id {
+            let digest = cid_to_digest(cid)?;
+            let request =
+                self.client.get(self.url(&format!("blobs/{}", digest))?);
+            let response = self.authorize(request).send().await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Err(Error::ObjectMissing(digest));
+            } else if !response.status().is_success() {
+                return Err(Error::OciResponse(response.status().into()));
+            }
+
+            let data = response.bytes().await?;
+            if digest_of(&data) != digest {
+                return Err(Error::Checksum(digest));
+            }
+            Ok(data.to_vec())
+        } else {
+            Err(Error::BadObjectKey)
+        }
+    }
+
+    async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+        if let ObjectKey::Cid(cid) = id {
+            let digest = cid_to_digest(cid)?;
+            let request =
+                self.client.delete(self.url(&format!("blobs/{}", digest))?);
+            let response = self.authorize(request).send().await?;
+            if response.status() != StatusCode::NOT_FOUND
+                && !response.status().is_success()
+            {
+                return Err(Error::OciResponse(response.status().into()));
+            }
+            Ok(())
+        } else {
+            Err(Error::BadObjectKey)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_content_id() {
+        let hash = sha256(b"mock");
+        let cid = hash_to_cid(&hash).unwrap();
+        assert_eq!(digest(&hash), cid_to_digest(&cid).unwrap());
+    }
+
+    #[test]
+    fn ipfs_content_id() {
+        let cid: Cid = "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb"
+            .try_into()
+            .unwrap();
+        assert!(matches!(cid_to_digest(&cid), Err(Error::BadObjectKey)));
+    }
+}
@@ -9,8 +9,8 @@ use rusoto_core::{
     credential, request::HttpClient, ByteStream, Region, RusotoError,
 };
 use rusoto_s3::{
-    GetObjectError, GetObjectRequest, PutObjectOutput, PutObjectRequest,
-    S3Client, S3,
+    DeleteObjectRequest, GetObjectError, GetObjectRequest, PutObjectOutput,
+    PutObjectRequest, S3Client, S3,
 };
 
 use ipfs_registry_core::{Artifact, ObjectKey};
@@ -18,11 +18,31 @@ use ipfs_registry_core::{Artifact, ObjectKey};
 use super::Layer;
 use crate::{Error, Result};
 
+/// Validate and normalize a prefix for object keys.
+///
+/// A non-empty prefix is terminated with a slash so that
+/// object keys are always nested below the prefix.
+pub(crate) fn normalize_prefix(prefix: &str) -> Result<String> {
+    if prefix.starts_with('/')
+        || prefix.contains('\\')
+        || prefix.chars().any(char::is_control)
+    {
+        return Err(Error::InvalidPrefix(prefix.to_owned()));
+    }
+
+    Ok(if prefix.is_empty() || prefix.ends_with('/') {
+        prefix.to_owned()
+    } else {
+        format!("{}/", prefix)
+    })
+}
+
 /// Layer for S3 backed storage.
 pub struct S3Layer {
     client: S3Client,
     bucket: String,
     content_type: String,
+    /// Normalized prefix for object keys.
     prefix: String,
 }
 
@@ -35,6 +55,7 @@ impl S3Layer {
         content_type: String,
         prefix: String,
     ) -> Result<Self> {
+        let prefix = normalize_prefix(&prefix)?;
         let region: Region = region.parse()?;
         let client = S3Layer::new_client(&profile, &region)?;
         Ok(Self {
@@ -109,14 +130,19 @@ impl S3Layer {
         }
     }
 
+    async fn delete_object(&self, key: String) -> Result<()> {
+        let req = DeleteObjectRequest {
+            bucket: self.bucket.clone(),
+            key,
+            ..Default::default()
+        };
+        self.client.delete_object(req).await?;
+        Ok(())
+    }
+
     /// Get the key for an object in a bucket.
     fn get_bucket_key(&self, key: &str) -> String {
-        let prefix = if self.prefix == "" || self.prefix.ends_with('/') {
-            self.prefix.clone()
-        } else {
-            format!("{}/", self.prefix)
-        };
-        format!("{}{}", prefix, key)
+        format!("{}{}", self.prefix, key)
     }
 }
 
@@ -149,4 +175,37 @@ impl Layer for S3Layer {
             Err(Error::BadObjectKey)
         }
     }
+
+    async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+        if let ObjectKey::Pointer(key) = id {
+            let bucket_key = self.get_bucket_key(key);
+            self.delete_object(bucket_key).await
+        } else {
+            Err(Error::BadObjectKey)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn s3_prefix_normalize() -> Result<()> {
+        assert_eq!("", normalize_prefix("")?);
+        assert_eq!("a/", normalize_prefix("a")?);
+        assert_eq!("a/", normalize_prefix("a/")?);
+        assert_eq!("a/b/", normalize_prefix("a/b")?);
+        Ok(())
+    }
+
+    #[test]
+    fn s3_prefix_invalid() {
+        for prefix in ["/", "/a", "a\\b", "a\nb", "a\0"] {
+            assert!(matches!(
+                normalize_prefix(prefix),
+                Err(Error::InvalidPrefix(_))
+            ));
+        }
+    }
 }
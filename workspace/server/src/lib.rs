@@ -1,23 +1,41 @@
 //! Package registry server.
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
+// Existing code predates these lints
+#![allow(
+    clippy::needless_borrow,
+    clippy::assign_op_pattern
+)]
 
 use axum_server::Handle;
+use ipfs_registry_database::{
+    CleanupReport, MaintenanceModel, ReindexReport,
+};
+use sqlx::SqlitePool;
 use std::{net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
 
+mod cache;
 pub mod config;
+mod decode;
+mod encoding;
 mod error;
 mod handlers;
 mod headers;
 mod layer;
+mod limit;
+mod query;
+mod redirect;
+mod retention;
+mod scan;
 mod server;
+mod signed_url;
 
 /// Result type for the server library.
 pub type Result<T> = std::result::Result<T, error::Error>;
 
 pub use error::Error;
 pub use layer::build as build_layers;
-pub use server::{Server, ServerInfo, State};
+pub use server::{BuildInfo, Server, ServerFeatures, ServerInfo, State};
 
 /// Start a server using the given bind address and configuration.
 pub async fn start(bind: String, config: PathBuf) -> Result<()> {
@@ -26,11 +44,34 @@ pub async fn start(bind: String, config: PathBuf) -> Result<()> {
     let config = config::ServerConfig::load(&config)?;
     let layers = layer::build(&config)?;
     let handle = Handle::new();
-    let state = Arc::new(
-        server::State::new(config, ServerInfo { name, version }, layers)
-            .await?,
-    );
+    let info = ServerInfo::new(name, version, &config);
+    let state = Arc::new(server::State::new(config, info, layers).await?);
     let addr = SocketAddr::from_str(&bind)?;
     Server.start(addr, state, handle).await?;
     Ok(())
 }
+
+/// Recompute the confusable skeletons for all namespaces and
+/// packages in the database for the given configuration.
+pub async fn reindex(config: PathBuf) -> Result<ReindexReport> {
+    let config = config::ServerConfig::load(&config)?;
+    let url = std::env::var("DATABASE_URL")
+        .ok()
+        .unwrap_or_else(|| config.database.url.clone());
+    let pool = SqlitePool::connect(&url).await?;
+    Ok(MaintenanceModel::reindex_skeletons(&pool).await?)
+}
+
+/// Remove packages without any versions from the database for
+/// the given configuration.
+pub async fn cleanup(
+    config: PathBuf,
+    dry_run: bool,
+) -> Result<CleanupReport> {
+    let config = config::ServerConfig::load(&config)?;
+    let url = std::env::var("DATABASE_URL")
+        .ok()
+        .unwrap_or_else(|| config.database.url.clone());
+    let pool = SqlitePool::connect(&url).await?;
+    Ok(MaintenanceModel::remove_empty_packages(&pool, dry_run).await?)
+}
@@ -0,0 +1,83 @@
+//! Per-package fetch limits.
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::config::FetchLimitConfig;
+
+/// Limits the number of fetches for each package within
+/// a fixed window of time.
+pub(crate) struct FetchLimiter {
+    requests: u32,
+    window: Duration,
+    packages: Mutex<HashMap<i64, (Instant, u32)>>,
+}
+
+impl FetchLimiter {
+    /// Create a new fetch limiter.
+    pub(crate) fn new(config: &FetchLimitConfig) -> Self {
+        Self {
+            requests: config.requests,
+            window: Duration::from_secs(config.window),
+            packages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a fetch for a package.
+    ///
+    /// When the package has exhausted its budget for the current
+    /// window the number of seconds until the window ends is
+    /// returned as an error.
+    pub(crate) fn acquire(&self, package_id: i64) -> Result<(), u64> {
+        self.acquire_at(package_id, Instant::now())
+    }
+
+    fn acquire_at(&self, package_id: i64, now: Instant) -> Result<(), u64> {
+        let mut packages = self.packages.lock().unwrap();
+        if !packages.contains_key(&package_id) {
+            let window = self.window;
+            packages.retain(|_, (start, _)| now < *start + window);
+        }
+
+        let (start, count) = packages.entry(package_id).or_insert((now, 0));
+        if now >= *start + self.window {
+            *start = now;
+            *count = 0;
+        }
+
+        if *count >= self.requests {
+            let remaining = (*start + self.window) - now;
+            Err(remaining.as_secs().max(1))
+        } else {
+            *count += 1;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fetch_limit_window() {
+        let limiter = FetchLimiter::new(&FetchLimitConfig {
+            requests: 2,
+            window: 60,
+        });
+        let now = Instant::now();
+
+        assert!(limiter.acquire_at(1, now).is_ok());
+        assert!(limiter.acquire_at(1, now).is_ok());
+        assert_eq!(Err(60), limiter.acquire_at(1, now));
+
+        // Other packages have their own budget
+        assert!(limiter.acquire_at(2, now).is_ok());
+
+        // Budget is restored when the window ends
+        let later = now + Duration::from_secs(60);
+        assert!(limiter.acquire_at(1, later).is_ok());
+    }
+}
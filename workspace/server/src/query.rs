@@ -0,0 +1,72 @@
+//! Validated query string extraction.
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequest, Query, RequestParts},
+    http::StatusCode,
+};
+use serde::de::DeserializeOwned;
+
+use ipfs_registry_database::{Cursor, Pager};
+
+/// Validation for query parameters once deserialized.
+pub(crate) trait Validate {
+    /// Check the query parameters are acceptable.
+    fn validate(&self) -> std::result::Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Ensure pagination parameters are in range.
+pub(crate) fn validate_pager(
+    offset: i64,
+    limit: i64,
+) -> std::result::Result<(), String> {
+    if offset < 0 {
+        Err(format!("offset must not be negative, got {}", offset))
+    } else if limit < 1 {
+        Err(format!("limit must be greater than zero, got {}", limit))
+    } else {
+        Ok(())
+    }
+}
+
+/// Ensure a pagination cursor can be decoded.
+pub(crate) fn validate_cursor(
+    cursor: Option<&str>,
+) -> std::result::Result<(), String> {
+    if let Some(cursor) = cursor {
+        cursor.parse::<Cursor>().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+impl Validate for Pager {
+    fn validate(&self) -> std::result::Result<(), String> {
+        validate_pager(self.offset, self.limit)
+    }
+}
+
+/// Query string extractor that rejects parameters which are
+/// present but invalid with `400 Bad Request` and a message.
+///
+/// Omitted parameters use the defaults for the query type.
+pub(crate) struct ValidQuery<T>(pub T);
+
+#[async_trait]
+impl<T, B> FromRequest<B> for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    B: Send,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request(
+        req: &mut RequestParts<B>,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request(req)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        value.validate().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        Ok(Self(value))
+    }
+}
@@ -0,0 +1,175 @@
+//! Redirect requests to the canonical host, to HTTPS or to the API.
+use axum::{
+    body::Body,
+    http::{
+        header::{HOST, LOCATION},
+        uri::Authority,
+        Method, Request, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use url::Url;
+
+use crate::{config::CanonicalHostConfig, server::ServerState};
+
+/// Paths that are never redirected so that health checks
+/// and metrics scrapers may use any host.
+const EXEMPT_PATHS: [&str; 3] = ["/api", "/api/health", "/api/metrics"];
+
+/// Redirect requests for a host other than the canonical host.
+pub(crate) async fn canonical_host<B>(
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let location = req
+        .extensions()
+        .get::<ServerState>()
+        .and_then(|state| state.config.canonical_host.as_ref())
+        .and_then(|config| redirect_location(config, &req));
+
+    if let Some(location) = location {
+        (StatusCode::PERMANENT_REDIRECT, [(LOCATION, location)])
+            .into_response()
+    } else {
+        next.run(req).await
+    }
+}
+
+/// Determine if a request is for metadata rather than
+/// a package archive.
+fn is_metadata(method: &Method, path: &str) -> bool {
+    method == Method::GET
+        && path != "/api/package"
+        && !path.ends_with("/bundle")
+        && !path.ends_with("/download")
+}
+
+/// Get the host and optional port for a URL.
+fn authority(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    Some(if let Some(port) = url.port() {
+        format!("{}:{}", host, port)
+    } else {
+        host.to_owned()
+    })
+}
+
+/// Compute the redirect location for a request or `None` if
+/// the request should not be redirected.
+fn redirect_location<B>(
+    config: &CanonicalHostConfig,
+    req: &Request<B>,
+) -> Option<String> {
+    let path = req.uri().path();
+    if EXEMPT_PATHS.contains(&path) {
+        return None;
+    }
+
+    if config.metadata_only && !is_metadata(req.method(), path) {
+        return None;
+    }
+
+    let host = req
+        .headers()
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| req.uri().authority().map(|value| value.as_str()))?;
+
+    let expected = authority(&config.url)?;
+    if host.eq_ignore_ascii_case(&expected) {
+        return None;
+    }
+
+    let mut location = config.url.clone();
+    location.set_path(path);
+    location.set_query(req.uri().query());
+    Some(location.to_string())
+}
+
+/// Redirect a plaintext request to HTTPS.
+///
+/// Only `GET` and `HEAD` requests are redirected, other requests
+/// are rejected as the body has already been sent in plaintext.
+pub(crate) async fn https_redirect(
+    port: u16,
+    req: Request<Body>,
+) -> Response {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let host = req
+        .headers()
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| req.uri().authority().map(|value| value.as_str()));
+
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|value| value.as_str())
+        .unwrap_or("/");
+
+    if let Some(location) =
+        host.and_then(|host| https_location(host, port, path))
+    {
+        (StatusCode::PERMANENT_REDIRECT, [(LOCATION, location)])
+            .into_response()
+    } else {
+        StatusCode::BAD_REQUEST.into_response()
+    }
+}
+
+/// Redirect a request for the root path to the API.
+pub(crate) async fn api_redirect() -> Response {
+    (StatusCode::TEMPORARY_REDIRECT, [(LOCATION, "/api")]).into_response()
+}
+
+/// Compute the HTTPS location for a host, port and path.
+fn https_location(host: &str, port: u16, path: &str) -> Option<String> {
+    let authority: Authority = host.parse().ok()?;
+    Some(if port == 443 {
+        format!("https://{}{}", authority.host(), path)
+    } else {
+        format!("https://{}:{}{}", authority.host(), port, path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_routes() {
+        assert!(is_metadata(&Method::GET, "/api/package/mock/mock/latest"));
+        assert!(!is_metadata(&Method::GET, "/api/package"));
+        assert!(!is_metadata(&Method::GET, "/api/package/mock/mock/bundle"));
+        assert!(!is_metadata(
+            &Method::GET,
+            "/api/package/mock/mock/1.0.0/download"
+        ));
+        assert!(!is_metadata(&Method::POST, "/api/package/mock"));
+    }
+
+    #[test]
+    fn url_authority() {
+        let url: Url = "https://example.com".parse().unwrap();
+        assert_eq!(Some(String::from("example.com")), authority(&url));
+        let url: Url = "http://localhost:9009".parse().unwrap();
+        assert_eq!(Some(String::from("localhost:9009")), authority(&url));
+    }
+
+    #[test]
+    fn https_locations() {
+        assert_eq!(
+            Some(String::from("https://example.com/api?id=1")),
+            https_location("example.com:80", 443, "/api?id=1")
+        );
+        assert_eq!(
+            Some(String::from("https://localhost:9009/api")),
+            https_location("localhost", 9009, "/api")
+        );
+        assert_eq!(None, https_location("", 443, "/"));
+    }
+}
@@ -0,0 +1,529 @@
+//! Retention policy for yanked artifacts.
+use std::time::Duration;
+use time::OffsetDateTime;
+
+use ipfs_registry_database::PackageModel;
+
+use crate::{
+    config::RetentionConfig,
+    server::{ServerState, State},
+    Result,
+};
+
+/// Source of the current time.
+pub(crate) trait Clock {
+    /// Get the current date and time.
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// Clock that reads the system time.
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// Remove artifacts from storage for versions that were yanked
+/// before the grace period and return the number purged.
+///
+/// Database rows are kept so that metadata still resolves and
+/// content identifiers shared with a version that has not been
+/// purged are not removed.
+pub(crate) async fn purge_yanked(
+    state: &State,
+    config: &RetentionConfig,
+    clock: &impl Clock,
+) -> Result<usize> {
+    let cutoff =
+        clock.now() - time::Duration::days(config.yanked_after_days as i64);
+    let records = PackageModel::find_purgeable(&state.pool, &cutoff).await?;
+
+    let mut purged = 0;
+    for record in records {
+        // Mark the version before removing the artifact so a
+        // version restored or overwritten since it was found is
+        // skipped and cannot be restored during the removal
+        if !PackageModel::set_purged(&state.pool, &record, &cutoff).await? {
+            continue;
+        }
+
+        // Content shared with another version that has not been
+        // purged must stay pinned for that version
+        let mut content_id = record.content_id.as_ref();
+        if let Some(cid) = content_id {
+            if PackageModel::is_content_referenced(
+                &state.pool,
+                cid,
+                Some(record.version_id),
+            )
+            .await?
+            {
+                content_id = None;
+            }
+        }
+
+        // Failures are retried on the next run
+        if state
+            .layers
+            .remove(&record.pointer_id, content_id, &record.objects)
+            .await
+            .is_err()
+        {
+            PackageModel::clear_purged(&state.pool, record.version_id)
+                .await?;
+            continue;
+        }
+
+        tracing::info!(pointer_id = %record.pointer_id, "purged artifact");
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+/// Run the retention policy periodically in the background.
+pub(crate) fn spawn(state: ServerState, config: RetentionConfig) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config.interval));
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge_yanked(&state, &config, &SystemClock).await
+            {
+                tracing::error!("{}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{LayerConfig, ServerConfig},
+        layer, ServerInfo,
+    };
+    use async_trait::async_trait;
+    use axum::body::Bytes;
+    use ipfs_registry_core::{Artifact, ObjectKey, PackageKey, Pointer};
+    use ipfs_registry_database::{
+        NamespaceModel, PublisherModel, PublisherRecord,
+    };
+    use semver::Version;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use web3_address::ethereum::Address;
+
+    const MOCK_CID: &str = "QmSYVWjXh5GCZpxhCSHMa89X9VHnPpaxafkBAR9rjfCenb";
+
+    /// Layer addressed by content identifier that records the
+    /// identifiers of removed artifacts.
+    #[derive(Default)]
+    struct CidLayer {
+        removed: Mutex<Vec<ObjectKey>>,
+    }
+
+    #[async_trait]
+    impl crate::layer::Layer for CidLayer {
+        fn supports_content_id(&self) -> bool {
+            true
+        }
+
+        async fn add_artifact(
+            &self,
+            _data: Bytes,
+            _artifact: &Artifact,
+        ) -> Result<ObjectKey> {
+            Ok(ObjectKey::Cid(MOCK_CID.try_into().unwrap()))
+        }
+
+        async fn get_artifact(&self, _id: &ObjectKey) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn remove_artifact(&self, id: &ObjectKey) -> Result<()> {
+            self.removed.lock().unwrap().push(id.clone());
+            Ok(())
+        }
+    }
+
+    /// Layer that fails to remove artifacts.
+    struct FailingLayer;
+
+    #[async_trait]
+    impl crate::layer::Layer for FailingLayer {
+        fn supports_content_id(&self) -> bool {
+            false
+        }
+
+        async fn add_artifact(
+            &self,
+            _data: Bytes,
+            artifact: &Artifact,
+        ) -> Result<ObjectKey> {
+            Ok(ObjectKey::Pointer(artifact.pointer_id()))
+        }
+
+        async fn get_artifact(&self, _id: &ObjectKey) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn remove_artifact(&self, _id: &ObjectKey) -> Result<()> {
+            Err(crate::Error::BadObjectKey)
+        }
+    }
+
+    struct MockClock(OffsetDateTime);
+
+    impl Clock for MockClock {
+        fn now(&self) -> OffsetDateTime {
+            self.0
+        }
+    }
+
+    async fn mock_state() -> State {
+        let config =
+            ServerConfig::new(LayerConfig::Memory { memory: true }.into());
+        let layers = layer::build(&config).unwrap();
+        let info = ServerInfo::new(
+            String::from("retention-test"),
+            String::from("0.0.0"),
+            &config,
+        );
+        State::new(config, info, layers).await.unwrap()
+    }
+
+    fn mock_pointer() -> Pointer {
+        serde_json::from_value(json!({
+            "definition": {
+                "objects": ["mock-namespace/mock-package/1.0.0"],
+                "artifact": {
+                    "namespace": "mock-namespace",
+                    "package": {
+                        "name": "mock-package",
+                        "version": "1.0.0"
+                    }
+                },
+                "signature": {
+                    "signer": "0x1fc770ac21067a04f83101ebf19a670db9e3eb21",
+                    "value": "mgtkUNH0I4D4JqhvLYEG1snbBByRLZCmBj5r+KKJiTAVUdBFj7Sm9JtGczTX0dk2jjtBH0wbLOcFIWesQiwVAwE="
+                },
+                "checksum": "4ad90a2c2e08374f8ccec2b604915a0ab7e97fcca983b12a6857d20df3fca9c0"
+            },
+            "package": {}
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn purge_after_retention_window() {
+        let state = mock_state().await;
+        let config = RetentionConfig {
+            yanked_after_days: 30,
+            interval: 3600,
+        };
+
+        let address = Address::from([1u8; 20]);
+        let pointer = mock_pointer();
+        let artifact = &pointer.definition.artifact;
+        let id = PackageKey::Pointer(
+            artifact.namespace.clone(),
+            artifact.package.name.clone(),
+            artifact.package.version.clone(),
+        );
+
+        let publisher_id =
+            PublisherModel::insert(&state.pool, &address).await.unwrap();
+        NamespaceModel::insert(
+            &state.pool,
+            &artifact.namespace,
+            publisher_id,
+            None,
+        )
+        .await
+        .unwrap();
+        let (publisher_record, namespace_record) =
+            NamespaceModel::can_access_namespace(
+                &state.pool,
+                &address,
+                &artifact.namespace,
+            )
+            .await
+            .unwrap();
+
+        let _ = state
+            .layers
+            .publish(Bytes::from_static(b"mock"), artifact)
+            .await
+            .unwrap();
+        PackageModel::insert(
+            &state.pool,
+            &publisher_record,
+            &namespace_record,
+            &address,
+            &pointer,
+            &[],
+        )
+        .await
+        .unwrap();
+        PackageModel::yank(&state.pool, &address, &id, "mock yank")
+            .await
+            .unwrap();
+
+        // Still within the grace period
+        let now = OffsetDateTime::now_utc();
+        let purged = purge_yanked(&state, &config, &MockClock(now))
+            .await
+            .unwrap();
+        assert_eq!(0, purged);
+        assert!(state
+            .layers
+            .fetch(&artifact.pointer_id(), None, &[], None)
+            .await
+            .is_ok());
+
+        // After the grace period the artifact is removed
+        let later = now + time::Duration::days(31);
+        let purged = purge_yanked(&state, &config, &MockClock(later))
+            .await
+            .unwrap();
+        assert_eq!(1, purged);
+        assert!(state
+            .layers
+            .fetch(&artifact.pointer_id(), None, &[], None)
+            .await
+            .is_err());
+
+        // Metadata survives and is marked as purged
+        let (_, _, record) =
+            PackageModel::find_by_key(&state.pool, &id, Default::default())
+                .await
+                .unwrap();
+        let record = record.unwrap();
+        assert_eq!(Some(String::from("mock yank")), record.yanked);
+        assert!(record.purged_at.is_some());
+
+        // Purged versions are not purged again
+        let purged = purge_yanked(&state, &config, &MockClock(later))
+            .await
+            .unwrap();
+        assert_eq!(0, purged);
+    }
+
+    #[tokio::test]
+    async fn purge_keeps_shared_content() {
+        let mut state = mock_state().await;
+        let layer = Arc::new(CidLayer::default());
+        state.layers.push(layer.clone());
+        let config = RetentionConfig {
+            yanked_after_days: 30,
+            interval: 3600,
+        };
+
+        let address = Address::from([1u8; 20]);
+        let namespace = mock_pointer().definition.artifact.namespace;
+        let publisher_id =
+            PublisherModel::insert(&state.pool, &address).await.unwrap();
+        NamespaceModel::insert(&state.pool, &namespace, publisher_id, None)
+            .await
+            .unwrap();
+        let (publisher_record, namespace_record) =
+            NamespaceModel::can_access_namespace(
+                &state.pool,
+                &address,
+                &namespace,
+            )
+            .await
+            .unwrap();
+
+        // Two versions share the same content identifier
+        let mut ids = Vec::new();
+        for version in [Version::new(1, 0, 0), Version::new(1, 1, 0)] {
+            let mut pointer = mock_pointer();
+            pointer.definition.objects =
+                vec![ObjectKey::Cid(MOCK_CID.try_into().unwrap())];
+            pointer.definition.artifact.package.version = version;
+            let artifact = &pointer.definition.artifact;
+            ids.push(PackageKey::Pointer(
+                artifact.namespace.clone(),
+                artifact.package.name.clone(),
+                artifact.package.version.clone(),
+            ));
+            PackageModel::insert(
+                &state.pool,
+                &publisher_record,
+                &namespace_record,
+                &address,
+                &pointer,
+                &[],
+            )
+            .await
+            .unwrap();
+        }
+
+        let later = OffsetDateTime::now_utc() + time::Duration::days(31);
+
+        // Content is kept while the other version is live
+        PackageModel::yank(&state.pool, &address, &ids[0], "mock yank")
+            .await
+            .unwrap();
+        let purged = purge_yanked(&state, &config, &MockClock(later))
+            .await
+            .unwrap();
+        assert_eq!(1, purged);
+        assert!(layer.removed.lock().unwrap().is_empty());
+
+        // Content is removed once no version references it
+        PackageModel::yank(&state.pool, &address, &ids[1], "mock yank")
+            .await
+            .unwrap();
+        let purged = purge_yanked(&state, &config, &MockClock(later))
+            .await
+            .unwrap();
+        assert_eq!(1, purged);
+        let removed = layer.removed.lock().unwrap();
+        assert_eq!(1, removed.len());
+        assert!(matches!(removed.first(), Some(ObjectKey::Cid(_))));
+    }
+
+    /// Publish and yank the mock package.
+    async fn mock_yanked(
+        state: &State,
+    ) -> (Address, PackageKey, PublisherRecord) {
+        let address = Address::from([1u8; 20]);
+        let pointer = mock_pointer();
+        let artifact = &pointer.definition.artifact;
+        let id = PackageKey::Pointer(
+            artifact.namespace.clone(),
+            artifact.package.name.clone(),
+            artifact.package.version.clone(),
+        );
+
+        let publisher_id =
+            PublisherModel::insert(&state.pool, &address).await.unwrap();
+        NamespaceModel::insert(
+            &state.pool,
+            &artifact.namespace,
+            publisher_id,
+            None,
+        )
+        .await
+        .unwrap();
+        let (publisher_record, namespace_record) =
+            NamespaceModel::can_access_namespace(
+                &state.pool,
+                &address,
+                &artifact.namespace,
+            )
+            .await
+            .unwrap();
+
+        let _ = state
+            .layers
+            .publish(Bytes::from_static(b"mock"), artifact)
+            .await
+            .unwrap();
+        PackageModel::insert(
+            &state.pool,
+            &publisher_record,
+            &namespace_record,
+            &address,
+            &pointer,
+            &[],
+        )
+        .await
+        .unwrap();
+        PackageModel::yank(&state.pool, &address, &id, "mock yank")
+            .await
+            .unwrap();
+
+        (address, id, publisher_record)
+    }
+
+    #[tokio::test]
+    async fn purge_skips_restored_versions() {
+        let state = mock_state().await;
+        let (address, id, publisher_record) = mock_yanked(&state).await;
+        let cutoff = OffsetDateTime::now_utc() + time::Duration::days(1);
+
+        // Restored after the version was found
+        let records = PackageModel::find_purgeable(&state.pool, &cutoff)
+            .await
+            .unwrap();
+        assert_eq!(1, records.len());
+        PackageModel::unyank(&state.pool, &address, &id)
+            .await
+            .unwrap();
+        assert!(!PackageModel::set_purged(&state.pool, &records[0], &cutoff)
+            .await
+            .unwrap());
+
+        // Overwritten after the version was found
+        PackageModel::yank(&state.pool, &address, &id, "mock yank")
+            .await
+            .unwrap();
+        let records = PackageModel::find_purgeable(&state.pool, &cutoff)
+            .await
+            .unwrap();
+        assert_eq!(1, records.len());
+        let mut pointer = mock_pointer();
+        pointer.definition.checksum = [1; 32];
+        PackageModel::replace_yanked(
+            &state.pool,
+            &publisher_record,
+            &records[0],
+            &pointer,
+            &[],
+        )
+        .await
+        .unwrap();
+        assert!(!PackageModel::set_purged(&state.pool, &records[0], &cutoff)
+            .await
+            .unwrap());
+
+        let (_, _, record) =
+            PackageModel::find_by_key(&state.pool, &id, Default::default())
+                .await
+                .unwrap();
+        assert!(record.unwrap().purged_at.is_none());
+        assert!(state
+            .layers
+            .fetch(&pointer.definition.artifact.pointer_id(), None, &[], None)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn purge_retries_failed_removal() {
+        let mut state = mock_state().await;
+        state.layers.push(Arc::new(FailingLayer));
+        let config = RetentionConfig {
+            yanked_after_days: 30,
+            interval: 3600,
+        };
+        let (address, id, _) = mock_yanked(&state).await;
+
+        let later = OffsetDateTime::now_utc() + time::Duration::days(31);
+        let purged = purge_yanked(&state, &config, &MockClock(later))
+            .await
+            .unwrap();
+        assert_eq!(0, purged);
+
+        // Version is not marked as purged so it may be restored
+        // and is found again on the next run
+        let (_, _, record) =
+            PackageModel::find_by_key(&state.pool, &id, Default::default())
+                .await
+                .unwrap();
+        assert!(record.unwrap().purged_at.is_none());
+        let cutoff = later - time::Duration::days(30);
+        let records = PackageModel::find_purgeable(&state.pool, &cutoff)
+            .await
+            .unwrap();
+        assert_eq!(1, records.len());
+        PackageModel::unyank(&state.pool, &address, &id)
+            .await
+            .unwrap();
+    }
+}
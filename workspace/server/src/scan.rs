@@ -0,0 +1,83 @@
+//! Scan artifacts with an external command before publishing.
+use std::process::Stdio;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{Error, Result};
+
+/// Exit code a scanner uses to report a threat.
+const THREAT_EXIT_CODE: i32 = 1;
+
+/// Outcome of scanning an artifact.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum ScanOutcome {
+    /// No threat was reported.
+    Clean,
+    /// A threat was reported, includes the scanner output.
+    Threat(String),
+}
+
+/// Pipe the artifact bytes to a shell command.
+///
+/// An exit code of zero indicates the artifact is clean and
+/// an exit code of one indicates a threat was found which
+/// matches the convention used by ClamAV; any other status
+/// is an error so that a broken scanner does not accept
+/// packages.
+pub(crate) async fn scan(command: &str, body: &[u8]) -> Result<ScanOutcome> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().ok_or_else(|| {
+        Error::ScanCommand(command.to_owned(), "no stdin".to_owned())
+    })?;
+
+    // Write concurrently with reading the output so a scanner
+    // that writes before consuming all input cannot deadlock
+    let write = async move {
+        match stdin.write_all(body).await {
+            // Scanner is allowed to exit before reading everything
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        }
+    };
+    let (written, output) = tokio::join!(write, child.wait_with_output());
+    written?;
+    let output = output?;
+
+    match output.status.code() {
+        Some(0) => Ok(ScanOutcome::Clean),
+        Some(THREAT_EXIT_CODE) => Ok(ScanOutcome::Threat(
+            String::from_utf8_lossy(&output.stdout).trim().to_owned(),
+        )),
+        _ => Err(Error::ScanCommand(
+            command.to_owned(),
+            output.status.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scan_outcome() -> Result<()> {
+        let command = "if grep -q threat; then echo found; exit 1; fi";
+        assert_eq!(ScanOutcome::Clean, scan(command, b"clean").await?);
+        assert_eq!(
+            ScanOutcome::Threat("found".to_owned()),
+            scan(command, b"a threat").await?
+        );
+        assert!(matches!(
+            scan("exit 2", b"").await,
+            Err(Error::ScanCommand(_, _))
+        ));
+        Ok(())
+    }
+}
@@ -1,31 +1,48 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 use axum::{
+    body::boxed,
+    error_handling::HandleErrorLayer,
     extract::Extension,
+    handler::Handler,
     http::{
-        header::{AUTHORIZATION, CONTENT_TYPE},
-        HeaderValue, Method,
+        header::{AUTHORIZATION, CONTENT_TYPE, STRICT_TRANSPORT_SECURITY},
+        HeaderValue, Method, Request, StatusCode, Uri,
     },
-    response::IntoResponse,
-    routing::{get, post},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
     Json, Router,
 };
 use axum_server::{tls_rustls::RustlsConfig, Handle};
 use serde::Serialize;
 use serde_json::json;
+use tower::{BoxError, ServiceBuilder, ServiceExt};
 use tower_http::{
-    cors::CorsLayer, limit::RequestBodyLimitLayer, trace::TraceLayer,
+    cors::CorsLayer, limit::RequestBodyLimitLayer, services::ServeDir,
+    set_header::SetResponseHeaderLayer, trace::TraceLayer,
 };
 
 use sqlx::SqlitePool;
+use web3_address::ethereum::Address;
+
+use ipfs_registry_core::{Namespace, RegistryKind};
+use ipfs_registry_database::{
+    Error as DatabaseError, MaintenanceModel, NamespaceModel,
+    NamespaceRecord, PublisherRecord,
+};
 
 use crate::{
-    config::ServerConfig,
-    config::TlsConfig,
-    handlers::{NamespaceHandler, PackageHandler, PublisherHandler},
-    headers::X_SIGNATURE,
+    cache::RecordCache,
+    config::{RootBehavior, ServerConfig, TlsConfig},
+    handlers::{
+        AdminHandler, NamespaceHandler, PackageHandler, PublisherHandler,
+    },
+    headers::{X_CHECKSUM, X_NONCE, X_SIGNATURE, X_SIGNATURE_EXPIRES},
     layer::Layers,
-    Result,
+    limit::FetchLimiter,
+    redirect::{api_redirect, canonical_host, https_redirect},
+    retention, Result,
 };
 
 /// Type alias for the server state.
@@ -41,6 +58,10 @@ pub struct State {
     pub(crate) layers: Layers,
     /// Connection pool.
     pub(crate) pool: SqlitePool,
+    /// Limits fetches for each package.
+    pub(crate) fetch_limiter: Option<FetchLimiter>,
+    /// Cache of publisher and namespace records.
+    pub(crate) cache: Option<RecordCache>,
 }
 
 impl State {
@@ -62,13 +83,55 @@ impl State {
             sqlx::migrate!("../../migrations").run(&pool).await?;
         }
 
+        let fetch_limiter =
+            config.registry.fetch_limit.as_ref().map(FetchLimiter::new);
+        let cache = config.registry.cache_ttl.map(RecordCache::new);
+
         Ok(State {
             config,
             info,
             layers,
             pool,
+            fetch_limiter,
+            cache,
         })
     }
+
+    /// Verify an address can access a namespace using the
+    /// record cache when enabled.
+    pub(crate) async fn can_access_namespace(
+        &self,
+        publisher: &Address,
+        namespace: &Namespace,
+    ) -> std::result::Result<(PublisherRecord, NamespaceRecord), DatabaseError>
+    {
+        if let Some(cache) = &self.cache {
+            cache
+                .can_access_namespace(&self.pool, publisher, namespace)
+                .await
+        } else {
+            NamespaceModel::can_access_namespace(
+                &self.pool, publisher, namespace,
+            )
+            .await
+        }
+    }
+
+    /// Remove a namespace from the record cache after the
+    /// namespace has been changed.
+    pub(crate) fn invalidate_namespace(&self, namespace: &Namespace) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_namespace(namespace);
+        }
+    }
+
+    /// Remove every record from the record cache after the
+    /// address of a publisher has changed.
+    pub(crate) fn invalidate_all(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
 }
 
 /// Server information.
@@ -78,6 +141,72 @@ pub struct ServerInfo {
     pub name: String,
     /// Version of the crate.
     pub version: String,
+    /// Information about the server build.
+    pub build: BuildInfo,
+    /// Features enabled by the server configuration.
+    pub features: ServerFeatures,
+}
+
+impl ServerInfo {
+    /// Create server information for a configuration.
+    pub fn new(name: String, version: String, config: &ServerConfig) -> Self {
+        Self {
+            name,
+            version,
+            build: Default::default(),
+            features: ServerFeatures::new(config),
+        }
+    }
+}
+
+/// Information about the server build.
+#[derive(Serialize)]
+pub struct BuildInfo {
+    /// Commit hash set using the `IPKG_BUILD_COMMIT` environment
+    /// variable when the server was compiled.
+    pub commit: Option<String>,
+    /// Version of the compiler used to build the server.
+    pub rustc: Option<String>,
+}
+
+impl Default for BuildInfo {
+    fn default() -> Self {
+        Self {
+            commit: option_env!("IPKG_BUILD_COMMIT").map(String::from),
+            rustc: option_env!("IPKG_RUSTC_VERSION").map(String::from),
+        }
+    }
+}
+
+/// Features enabled by the server configuration.
+#[derive(Serialize)]
+pub struct ServerFeatures {
+    /// Whether webhooks are configured.
+    pub webhooks: bool,
+    /// Whether the server is running over HTTPS.
+    pub tls: bool,
+    /// Whether clients may request MessagePack encoded metadata.
+    pub msgpack: bool,
+    /// Whether signed download URLs may be created.
+    pub signed_urls: bool,
+    /// Default kind for packages published to the registry.
+    pub kind: RegistryKind,
+    /// Kinds that namespaces may be registered with.
+    pub kinds: Vec<RegistryKind>,
+}
+
+impl ServerFeatures {
+    /// Determine the enabled features from a configuration.
+    pub fn new(config: &ServerConfig) -> Self {
+        Self {
+            webhooks: config.webhooks.is_some(),
+            tls: config.tls.is_some(),
+            msgpack: config.registry.msgpack,
+            signed_urls: config.signed_urls.is_some(),
+            kind: config.registry.kind,
+            kinds: RegistryKind::ALL.to_vec(),
+        }
+    }
 }
 
 /// Server implementation.
@@ -96,6 +225,10 @@ impl Server {
         let limit = state.config.registry.body_limit;
         let tls = state.config.tls.as_ref().cloned();
 
+        if let Some(retention) = state.config.registry.retention.clone() {
+            retention::spawn(Arc::clone(&state), retention);
+        }
+
         //sqlx::migrate!("../../migrations").run(&pool).await?;
 
         if let Some(tls) = tls {
@@ -115,13 +248,27 @@ impl Server {
         limit: usize,
         tls: TlsConfig,
     ) -> Result<()> {
+        let redirect = tls.redirect;
         let tls = RustlsConfig::from_pem_file(&tls.cert, &tls.key).await?;
         let app = Server::router(state, origins, limit)?;
         tracing::info!("listening on {}", addr);
-        axum_server::bind_rustls(addr, tls)
-            .handle(handle)
-            .serve(app.into_make_service())
-            .await?;
+        let server = axum_server::bind_rustls(addr, tls)
+            .handle(handle.clone())
+            .serve(app.into_make_service());
+
+        if let Some(redirect) = redirect {
+            let port = addr.port();
+            let app = Router::new().fallback(
+                (move |req| https_redirect(port, req)).into_service(),
+            );
+            tracing::info!("redirecting plaintext requests on {}", redirect);
+            let plaintext = axum_server::bind(redirect)
+                .handle(handle)
+                .serve(app.into_make_service());
+            tokio::try_join!(server, plaintext)?;
+        } else {
+            server.await?;
+        }
         Ok(())
     }
 
@@ -170,25 +317,90 @@ impl Server {
                 .allow_methods(vec![
                     Method::GET,
                     Method::POST,
+                    Method::PUT,
                     Method::DELETE,
                 ])
                 .allow_headers(vec![
                     AUTHORIZATION,
                     CONTENT_TYPE,
                     X_SIGNATURE.clone(),
+                    X_SIGNATURE_EXPIRES.clone(),
+                    X_NONCE.clone(),
+                    X_CHECKSUM.clone(),
                 ])
                 .allow_origin(origins)
         } else {
             CorsLayer::very_permissive()
         };
 
-        let app = Router::new()
+        let hsts = state
+            .config
+            .tls
+            .as_ref()
+            .and_then(|tls| tls.hsts.as_ref())
+            .map(|hsts| HeaderValue::from_str(&hsts.header_value()))
+            .transpose()?;
+
+        let timeout = state
+            .config
+            .timeout
+            .as_ref()
+            .and_then(|timeout| timeout.request)
+            .map(Duration::from_secs);
+
+        let routes = Router::new()
             .route("/api", get(ApiHandler::get))
+            .route("/api/health", get(ApiHandler::health))
+            .route("/api/metrics", get(ApiHandler::metrics))
             .route("/api/signup", post(PublisherHandler::signup))
+            .route(
+                "/api/admin/namespace/resolve",
+                post(AdminHandler::resolve_namespace),
+            )
+            .route("/api/admin/audit", get(AdminHandler::list_audit))
+            .route(
+                "/api/admin/publisher/rotate",
+                post(AdminHandler::rotate_key),
+            )
+            .route(
+                "/api/admin/signatures/rotated",
+                get(AdminHandler::list_rotated_signatures),
+            )
+            .route(
+                "/api/publisher/:address/packages",
+                get(PublisherHandler::list_packages),
+            )
             .route(
                 "/api/register/:namespace",
                 post(NamespaceHandler::register),
             )
+            .route(
+                "/api/namespace/:namespace",
+                put(NamespaceHandler::set_description),
+            )
+            .route(
+                "/api/namespace/:namespace/access",
+                get(NamespaceHandler::list_access),
+            )
+            .route(
+                "/api/namespace/:namespace/deprecate",
+                post(NamespaceHandler::deprecate)
+                    .delete(NamespaceHandler::undeprecate),
+            )
+            .route(
+                "/api/namespace/:namespace/private",
+                post(NamespaceHandler::set_private)
+                    .delete(NamespaceHandler::set_public),
+            )
+            .route(
+                "/api/namespace/:namespace/restricted",
+                post(NamespaceHandler::set_restricted)
+                    .delete(NamespaceHandler::set_unrestricted),
+            )
+            .route(
+                "/api/namespace/:namespace/transfer/:address",
+                post(NamespaceHandler::transfer),
+            )
             .route(
                 "/api/namespace/:namespace/user/:address",
                 post(NamespaceHandler::add_user)
@@ -199,6 +411,7 @@ impl Server {
                 post(NamespaceHandler::grant_access)
                     .delete(NamespaceHandler::revoke_access),
             )
+            .route("/api/search", get(PackageHandler::search))
             .route("/api/package", get(PackageHandler::fetch))
             .route(
                 "/api/package/:namespace",
@@ -211,28 +424,173 @@ impl Server {
             )
             .route(
                 "/api/package/:namespace/:package",
-                get(PackageHandler::get_package),
+                get(PackageHandler::get_package)
+                    .put(PackageHandler::set_description),
             )
             .route(
                 "/api/package/:namespace/:package/versions",
                 get(PackageHandler::list_versions),
             )
+            .route(
+                "/api/package/:namespace/:package/dependents",
+                get(PackageHandler::list_dependents),
+            )
             .route(
                 "/api/package/:namespace/:package/latest",
                 get(PackageHandler::latest_version),
             )
+            .route(
+                "/api/package/:namespace/:package/bundle",
+                get(PackageHandler::bundle),
+            )
+            .route(
+                "/api/package/:namespace/:package/diff",
+                get(PackageHandler::diff),
+            )
             .route(
                 "/api/package/:namespace/:package/deprecate",
                 post(PackageHandler::deprecate),
             )
+            .route(
+                "/api/package/:namespace/:package/transfer/:target",
+                post(PackageHandler::transfer),
+            )
+            .route(
+                "/api/package/:namespace/:package/can-publish",
+                get(PackageHandler::can_publish),
+            )
+            .route(
+                "/api/package/:namespace/:package/trusted",
+                get(PackageHandler::list_trusted_keys),
+            )
+            .route(
+                "/api/package/:namespace/:package/trusted/:address",
+                post(PackageHandler::add_trusted_key)
+                    .delete(PackageHandler::remove_trusted_key),
+            )
+            .route(
+                "/api/package/:namespace/:package/:version/download",
+                get(PackageHandler::download),
+            )
             .route("/api/package/version", get(PackageHandler::exact_version))
+            .route(
+                "/api/package/version/manifest",
+                get(PackageHandler::manifest),
+            )
+            .route(
+                "/api/package/version/objects",
+                get(PackageHandler::objects),
+            )
+            .route(
+                "/api/package/version/sign-url",
+                post(PackageHandler::sign_url),
+            )
+            .route(
+                "/api/package/version/by-checksum",
+                get(PackageHandler::find_by_checksum),
+            )
             .route("/api/package/yank", post(PackageHandler::yank))
+            .route("/api/package/unyank", post(PackageHandler::unyank));
+
+        let routes = match &state.config.root_behavior {
+            RootBehavior::Info => routes
+                .route("/", get(ApiHandler::get))
+                .fallback(not_found.into_service()),
+            RootBehavior::Redirect => routes
+                .route("/", get(api_redirect))
+                .fallback(not_found.into_service()),
+            RootBehavior::Static(directory) => {
+                let directory = directory.clone();
+                routes.fallback(
+                    (move |req| landing_page(directory.clone(), req))
+                        .into_service(),
+                )
+            }
+        };
+
+        let mut app = routes
+            .layer(middleware::from_fn(canonical_host))
             .layer(RequestBodyLimitLayer::new(limit))
             .layer(cors)
             .layer(TraceLayer::new_for_http())
             .layer(Extension(state));
 
-        Ok(app)
+        if let Some(timeout) = timeout {
+            app = app.layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(handle_timeout))
+                    .timeout(timeout),
+            );
+        }
+
+        if let Some(hsts) = hsts {
+            app = app.layer(SetResponseHeaderLayer::overriding(
+                STRICT_TRANSPORT_SECURITY,
+                hsts,
+            ));
+        }
+
+        // Must be the outermost layer, see `method_not_allowed`
+        Ok(app.layer(middleware::from_fn(method_not_allowed)))
+    }
+}
+
+/// Convert errors from the timeout middleware into a response.
+async fn handle_timeout(error: BoxError) -> StatusCode {
+    if error.is::<tower::timeout::error::Elapsed>() {
+        StatusCode::GATEWAY_TIMEOUT
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// Respond to requests for unknown routes.
+async fn not_found(uri: Uri) -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "not_found", "path": uri.path()})),
+    )
+}
+
+/// Serve files from the landing page directory.
+///
+/// Requests for the API are never served from the directory
+/// so unknown API routes respond as not found.
+async fn landing_page<B: Send + 'static>(
+    directory: PathBuf,
+    req: Request<B>,
+) -> Response {
+    let path = req.uri().path();
+    if path == "/api" || path.starts_with("/api/") {
+        return not_found(req.uri().clone()).await.into_response();
+    }
+    match ServeDir::new(directory).oneshot(req).await {
+        Ok(response) => response.map(boxed).into_response(),
+        Err(e) => {
+            tracing::error!("{}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Add a body to responses for known routes requested with
+/// a method that is not allowed.
+///
+/// The router only sets the `Allow` header on a response that
+/// has not already passed through a layered route so this must
+/// be the outermost layer for the header to be set on the new
+/// response.
+async fn method_not_allowed<B>(req: Request<B>, next: Next<B>) -> Response {
+    let path = req.uri().path().to_owned();
+    let response = next.run(req).await;
+    if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+        (
+            StatusCode::METHOD_NOT_ALLOWED,
+            Json(json!({"error": "method_not_allowed", "path": path})),
+        )
+            .into_response()
+    } else {
+        response
     }
 }
 
@@ -245,4 +603,109 @@ impl ApiHandler {
     ) -> impl IntoResponse {
         Json(json!(&state.info))
     }
+
+    /// Check the server can reach the database.
+    pub(crate) async fn health(
+        Extension(state): Extension<ServerState>,
+    ) -> StatusCode {
+        match MaintenanceModel::ping(&state.pool).await {
+            Ok(_) => StatusCode::OK,
+            Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Serve registry metrics in the Prometheus text format.
+    pub(crate) async fn metrics(
+        Extension(state): Extension<ServerState>,
+    ) -> std::result::Result<Response, StatusCode> {
+        let counts = MaintenanceModel::count_records(&state.pool)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        let mut body = String::new();
+        for (name, value) in [
+            ("publishers", counts.publishers),
+            ("namespaces", counts.namespaces),
+            ("packages", counts.packages),
+            ("versions", counts.versions),
+        ] {
+            body.push_str(&format!(
+                "# HELP ipkg_{name} Number of {name}.\n\
+                 # TYPE ipkg_{name} gauge\n\
+                 ipkg_{name} {value}\n",
+                name = name,
+                value = value,
+            ));
+        }
+
+        Ok(([(CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+            .into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build_layers,
+        config::{LayerConfig, TimeoutConfig},
+    };
+    use axum::{body::Body, http::Request};
+    use ipfs_registry_database::PublisherModel;
+    use k256::ecdsa::{recoverable, signature::Signer, SigningKey};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn request_timeout() {
+        let mut config =
+            ServerConfig::new(LayerConfig::Memory { memory: true }.into());
+        config.timeout = Some(TimeoutConfig {
+            request: Some(1),
+            storage: None,
+        });
+        // Scanning the artifact makes the publish handler slow
+        config.registry.scan_command = Some(String::from("sleep 5"));
+
+        let layers = build_layers(&config).unwrap();
+        let info = ServerInfo::new(
+            String::from("test"),
+            String::from("0.0.0"),
+            &config,
+        );
+        let state = Arc::new(State::new(config, info, layers).await.unwrap());
+
+        let signing_key = SigningKey::from_bytes(&[1; 32]).unwrap();
+        let address: Address = signing_key.verifying_key().into();
+        let namespace = Namespace::new_unchecked("mock-namespace");
+        let publisher_id =
+            PublisherModel::insert(&state.pool, &address).await.unwrap();
+        NamespaceModel::insert(&state.pool, &namespace, publisher_id, None)
+            .await
+            .unwrap();
+
+        let limit = state.config.registry.body_limit;
+        let app = Server::router(state, None, limit).unwrap();
+
+        // Fast requests complete within the timeout
+        let request =
+            Request::builder().uri("/api").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+
+        let body = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../fixtures/mock-package-1.0.0.tgz"
+        ))
+        .unwrap();
+        let signature: recoverable::Signature = signing_key.sign(&body);
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/package/mock-namespace")
+            .header(&*X_SIGNATURE, base64::encode(signature))
+            .header(CONTENT_TYPE, "application/gzip")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::GATEWAY_TIMEOUT, response.status());
+    }
 }
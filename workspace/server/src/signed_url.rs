@@ -0,0 +1,57 @@
+//! Signed download URLs that expire.
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use ipfs_registry_core::PackageKey;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Compute the message authentication code for a package key
+/// that expires at a unix timestamp.
+fn mac(secret: &[u8], key: &PackageKey, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret)
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}", key, expires).as_bytes());
+    mac
+}
+
+/// Create the hex encoded token for a signed URL.
+pub(crate) fn sign(secret: &[u8], key: &PackageKey, expires: i64) -> String {
+    hex::encode(mac(secret, key, expires).finalize().into_bytes())
+}
+
+/// Verify the hex encoded token for a signed URL in constant time.
+///
+/// The expiry is checked by the caller.
+pub(crate) fn verify(
+    secret: &[u8],
+    key: &PackageKey,
+    expires: i64,
+    token: &str,
+) -> bool {
+    match hex::decode(token) {
+        Ok(token) => mac(secret, key, expires).verify_slice(&token).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_url_token() {
+        let secret = b"mock-secret-for-signed-download-urls";
+        let key: PackageKey =
+            "mock-namespace/mock-package/1.0.0".parse().unwrap();
+        let other: PackageKey =
+            "mock-namespace/mock-package/1.1.0".parse().unwrap();
+
+        let token = sign(secret, &key, 1_700_000_000);
+        assert!(verify(secret, &key, 1_700_000_000, &token));
+        assert!(!verify(secret, &key, 1_700_000_001, &token));
+        assert!(!verify(secret, &other, 1_700_000_000, &token));
+        assert!(!verify(b"other-secret", &key, 1_700_000_000, &token));
+        assert!(!verify(secret, &key, 1_700_000_000, "not hex"));
+    }
+}